@@ -0,0 +1,5 @@
+CREATE TABLE category_cache (
+    community TEXT NOT NULL PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content BLOB NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+DROP COLUMN runtime_data;
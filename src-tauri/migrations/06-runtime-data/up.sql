@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN runtime_data JSON;
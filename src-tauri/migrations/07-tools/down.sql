@@ -0,0 +1,2 @@
+ALTER TABLE managed_games
+DROP COLUMN tools;
@@ -0,0 +1,2 @@
+ALTER TABLE managed_games
+ADD COLUMN tools JSON;
@@ -0,0 +1 @@
+DROP TABLE modpack_stats;
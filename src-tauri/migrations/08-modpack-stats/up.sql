@@ -0,0 +1,7 @@
+CREATE TABLE modpack_stats (
+    package TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    downloads INTEGER NOT NULL,
+    rating_score INTEGER NOT NULL,
+    PRIMARY KEY (package, recorded_at)
+);
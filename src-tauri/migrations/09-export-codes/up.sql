@@ -0,0 +1,6 @@
+CREATE TABLE export_codes (
+    code BLOB PRIMARY KEY NOT NULL,
+    profile_id INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    fingerprint TEXT NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE profile_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL,
+    game_slug TEXT NOT NULL,
+    include_config BOOLEAN NOT NULL,
+    mod_count INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    data BLOB NOT NULL
+);
@@ -0,0 +1,5 @@
+ALTER TABLE profiles
+DROP COLUMN profile_group;
+
+ALTER TABLE managed_games
+DROP COLUMN profile_groups;
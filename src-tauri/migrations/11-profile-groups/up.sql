@@ -0,0 +1,5 @@
+ALTER TABLE profiles
+ADD COLUMN profile_group TEXT;
+
+ALTER TABLE managed_games
+ADD COLUMN profile_groups JSON;
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+DROP COLUMN launch_overrides;
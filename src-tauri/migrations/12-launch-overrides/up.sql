@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN launch_overrides JSON;
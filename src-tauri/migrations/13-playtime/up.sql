@@ -0,0 +1,9 @@
+CREATE TABLE play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    profile_id INTEGER NOT NULL,
+    game_slug TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    duration_secs INTEGER NOT NULL
+);
+
+CREATE INDEX play_sessions_profile_id ON play_sessions (profile_id);
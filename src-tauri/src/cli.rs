@@ -3,12 +3,18 @@ use std::{path::PathBuf, process};
 use clap::Parser;
 use eyre::{eyre, Context, OptionExt, Result};
 use tauri::AppHandle;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     game::{self},
-    profile::{self, install::InstallOptions, ModManager},
+    profile::{
+        self,
+        install::{self, InstallOptions, ModInstall},
+        launch::hooks,
+        reconcile, Profile,
+    },
     state::ManagerExt,
+    thunderstore::{self, Thunderstore},
 };
 
 pub fn run(args: Vec<String>, app: &AppHandle) {
@@ -32,6 +38,11 @@ struct Cli {
     #[arg(short, long, value_name = "PATH")]
     install: Option<PathBuf>,
 
+    /// Installs, updates, removes and toggles mods so the profile matches a
+    /// manifest or exported profile.
+    #[arg(long, value_name = "PATH")]
+    sync: Option<PathBuf>,
+
     #[arg(short, long)]
     launch: bool,
 
@@ -47,6 +58,7 @@ impl Cli {
             game,
             profile,
             install,
+            sync,
             launch,
             no_gui,
         } = self;
@@ -76,24 +88,39 @@ impl Cli {
             manager.save_all(app.db())?;
         }
 
-        if let Some(path) = install {
+        if install.is_some() || sync.is_some() {
             drop(manager);
 
             let handle = app.to_owned();
             tauri::async_runtime::spawn(async move {
-                if let Err(err) = install_local_mod(path, &handle).await {
-                    error!("failed to install mod from cli: {:#}", err)
+                if let Some(path) = install {
+                    if let Err(err) = install_local_mod(path, &handle).await {
+                        error!("failed to install mod from cli: {:#}", err)
+                    }
+                }
+
+                if let Some(path) = sync {
+                    if let Err(err) = sync_profile(path, &handle).await {
+                        error!("failed to sync profile from cli: {:#}", err)
+                    }
                 }
 
-                let manager = handle.lock_manager();
-                if let Err(err) =
-                    handle_launch_and_no_gui(launch, no_gui, from_args, &manager, &handle)
-                {
+                // the pre-launch hook blocks until it exits
+                let result = tauri::async_runtime::spawn_blocking(move || {
+                    handle_launch_and_no_gui(launch, no_gui, from_args, &handle)
+                })
+                .await
+                .map_err(eyre::Error::from)
+                .and_then(|result| result);
+
+                if let Err(err) = result {
                     error!("{:#}", err);
                 }
             });
         } else {
-            handle_launch_and_no_gui(launch, no_gui, from_args, &manager, app)?;
+            drop(manager);
+
+            handle_launch_and_no_gui(launch, no_gui, from_args, app)?;
         }
 
         debug!("cli finished");
@@ -103,13 +130,21 @@ impl Cli {
             launch: bool,
             no_gui: bool,
             from_args: bool,
-            manager: &ModManager,
             app: &AppHandle,
         ) -> Result<()> {
             if launch {
+                // the hook can take a while or hang, so run it without holding any locks
+                let hook = hooks::pre_launch(app.read_manager().active_profile());
+                if let Some(hook) = hook {
+                    hook.run().context("pre-launch hook failed")?;
+                }
+
+                let prefs = app.lock_prefs();
+                let manager = app.read_manager();
+
                 manager
                     .active_game()
-                    .launch(&app.lock_prefs(), app)
+                    .launch(&prefs, app)
                     .context("failed to launch game")?;
             }
 
@@ -123,18 +158,63 @@ impl Cli {
 }
 
 async fn install_local_mod(path: PathBuf, app: &AppHandle) -> Result<()> {
-    profile::import::import_local_mod(
-        path,
-        None,
-        app,
-        InstallOptions::default().on_progress(Box::new(|progress, _| {
-            info!(
-                "{} {} ({}%)",
-                progress.task,
-                progress.current_name,
-                (progress.total_progress * 100.0).round()
-            )
-        })),
-    )
-    .await
+    profile::import::import_local_mod(path, None, app, install_options()).await
+}
+
+async fn sync_profile(path: PathBuf, app: &AppHandle) -> Result<()> {
+    let mods = reconcile::read_lockfile(&path)?;
+    let plan = reconcile::reconcile(&mods, &AppHost(app)).await?;
+
+    for ident in &plan.missing {
+        warn!("{} was not found on thunderstore, skipping", ident);
+    }
+
+    Ok(())
+}
+
+/// Reconciles the app's active profile.
+struct AppHost<'a>(&'a AppHandle);
+
+impl reconcile::Host for AppHost<'_> {
+    async fn read<R>(&self, f: impl FnOnce(&Profile, &Thunderstore) -> R) -> R {
+        thunderstore::wait_for_fetch(self.0).await;
+
+        let manager = self.0.read_manager();
+        let thunderstore = self.0.lock_thunderstore();
+
+        f(manager.active_profile(), &thunderstore)
+    }
+
+    async fn install(&self, mods: Vec<ModInstall>) -> Result<()> {
+        let options = install_options().before_install(Box::new(|install, profile, _| {
+            if profile.has_mod(install.uuid()) {
+                profile
+                    .force_remove_mod(install.uuid())
+                    .context("failed to remove existing version")?;
+            }
+
+            Ok(())
+        }));
+
+        install::install_mods(mods, options, self.0).await
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Profile) -> Result<()>) -> Result<()> {
+        let mut manager = self.0.lock_manager();
+        let profile = manager.active_profile_mut();
+
+        f(profile)?;
+        profile.save(self.0.db())
+    }
+}
+
+fn install_options() -> InstallOptions {
+    InstallOptions::default().on_progress(Box::new(|progress, _| {
+        info!(
+            "{} {} ({}%)",
+            progress.task,
+            progress.current_name,
+            (progress.total_progress * 100.0).round()
+        )
+    }))
 }
@@ -1,6 +1,10 @@
-use std::io::{BufRead, Write};
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    ops::Range,
+};
 
-use eyre::{eyre, OptionExt, Result};
+use eyre::{bail, ensure, eyre, OptionExt, Result};
 
 use super::frontend::{self, Num};
 
@@ -119,9 +123,12 @@ impl EntryKind {
         }
     }
 
-    pub fn set(&mut self, value: frontend::Value) -> Result<()> {
-        self.as_normal_mut()?.value = value.into();
-        Ok(())
+    /// Sets the value, checked against the acceptable range or values of the entry.
+    ///
+    /// Numbers outside of the range are clamped if `clamp` is set, otherwise
+    /// they're rejected. Returns the new value, with the entry's constraints.
+    pub fn set(&mut self, value: frontend::Value, clamp: bool) -> Result<frontend::Value> {
+        self.as_normal_mut()?.set(value, clamp)
     }
 
     pub fn reset(&mut self) -> Result<frontend::Value> {
@@ -163,6 +170,56 @@ pub enum Value {
 }
 
 impl Entry {
+    fn set(&mut self, value: frontend::Value, clamp: bool) -> Result<frontend::Value> {
+        use frontend::Value as In;
+
+        let new_value = match (&self.value, value) {
+            (Value::Boolean(_), In::Bool(bool)) => Value::Boolean(bool),
+            (Value::String(_), In::String(str)) => Value::String(str),
+            (Value::Other(_), In::String(str)) => Value::Other(str),
+            (Value::Int32(num), In::Int(new)) => Value::Int32(Num {
+                value: constrain(new.value, num.range.as_ref(), clamp)?,
+                range: num.range.clone(),
+            }),
+            (Value::Single(num), In::Float(new)) => Value::Single(Num {
+                value: constrain(new.value, num.range.as_ref(), clamp)?,
+                range: num.range.clone(),
+            }),
+            (Value::Double(num), In::Float(new)) => Value::Double(Num {
+                value: constrain(new.value as f64, num.range.as_ref(), clamp)?,
+                range: num.range.clone(),
+            }),
+            (Value::Enum { options, .. }, In::Enum { index, .. }) => {
+                ensure!(
+                    index < options.len(),
+                    "value must be one of {}",
+                    options.join(", ")
+                );
+
+                Value::Enum {
+                    index,
+                    options: options.clone(),
+                }
+            }
+            (Value::Flags { options, .. }, In::Flags { indicies, .. }) => {
+                ensure!(
+                    indicies.iter().all(|index| *index < options.len()),
+                    "values must be some of {}",
+                    options.join(", ")
+                );
+
+                Value::Flags {
+                    indicies,
+                    options: options.clone(),
+                }
+            }
+            _ => bail!("expected a value of type {}", self.type_name),
+        };
+
+        self.value = new_value;
+        Ok(self.value.clone().into())
+    }
+
     fn reset(&mut self) -> Result<frontend::Value> {
         self.value = self.default_value.clone().ok_or_eyre("no default value")?;
         Ok(self.value.clone().into())
@@ -178,6 +235,38 @@ impl Entry {
     }
 }
 
+/// Checks that `value` is within the inclusive `range`, or clamps it into it.
+fn constrain<T>(value: T, range: Option<&Range<T>>, clamp: bool) -> Result<T>
+where
+    T: PartialOrd + Copy + Display,
+{
+    let Some(range) = range else {
+        return Ok(value);
+    };
+
+    if value < range.start {
+        ensure!(
+            clamp,
+            "{} is below the acceptable range of {} to {}",
+            value,
+            range.start,
+            range.end
+        );
+        Ok(range.start)
+    } else if value > range.end {
+        ensure!(
+            clamp,
+            "{} is above the acceptable range of {} to {}",
+            value,
+            range.start,
+            range.end
+        );
+        Ok(range.end)
+    } else {
+        Ok(value)
+    }
+}
+
 impl Value {
     fn options(&self) -> Option<&[String]> {
         match self {
@@ -194,3 +194,40 @@ fn check_from_string() {
 
     assert_eq!(left, right);
 }
+
+#[test]
+fn set_checks_range() {
+    let mut file = test_file();
+    let entry = file.find_entry("Section2", "Entry4").unwrap();
+
+    let int = |value| frontend::Value::Int(Num { value, range: None });
+
+    assert!(entry.set(int(11), false).is_err());
+
+    let frontend::Value::Int(num) = entry.set(int(11), true).unwrap() else {
+        panic!("expected an int");
+    };
+    assert_eq!(num.value, 10);
+    assert_eq!(num.range, Some(0..10));
+
+    assert!(entry.set(frontend::Value::Bool(true), true).is_err());
+}
+
+#[test]
+fn set_checks_options() {
+    let mut file = test_file();
+    let entry = file.find_entry("Section1", "Entry3").unwrap();
+
+    let value = |index| frontend::Value::Enum {
+        index,
+        options: Vec::new(),
+    };
+
+    assert!(entry.set(value(3), false).is_err());
+
+    let frontend::Value::Enum { index, options } = entry.set(value(2), false).unwrap() else {
+        panic!("expected an enum");
+    };
+    assert_eq!(index, 2);
+    assert_eq!(options, ["Easy", "Medium", "Hard"]);
+}
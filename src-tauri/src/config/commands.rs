@@ -1,21 +1,53 @@
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use eyre::{eyre, Context};
 use tauri::{command, AppHandle};
 
-use super::{frontend, AnyFileKind};
-use crate::{state::ManagerExt, util::cmd::Result};
+use super::{
+    copy::{self, CopyConfigResult},
+    diff::{self, ConfigDiff},
+    frontend,
+    history::{self, ConfigVersion},
+    search::{self, ConfigMatch},
+    shared, AnyFileKind,
+};
+use crate::{
+    profile::undo,
+    state::ManagerExt,
+    util::{self, cmd::Result, error::IoResultExt},
+};
 
 #[command]
-pub fn get_config_files(app: AppHandle) -> Result<Vec<frontend::File>> {
+pub async fn get_config_files(app: AppHandle) -> Result<Vec<frontend::File>> {
+    let files = tauri::async_runtime::spawn_blocking(move || {
+        let mut manager = app.lock_manager();
+        let profile = manager.active_profile_mut();
+
+        profile.refresh_config();
+
+        profile.config_cache.to_frontend()
+    })
+    .await?;
+
+    Ok(files)
+}
+
+#[command]
+pub fn search_config(query: &str, app: AppHandle) -> Result<Vec<ConfigMatch>> {
     let mut manager = app.lock_manager();
     let profile = manager.active_profile_mut();
 
     profile.refresh_config();
 
-    Ok(profile.config_cache.to_frontend())
+    let files = profile.config_cache.to_frontend();
+    Ok(search::search(&files, query))
 }
 
+/// Returns the value that was written, which differs from `value` if it was
+/// clamped into the entry's acceptable range.
 #[command]
 pub fn set_config_entry(
     file: &Path,
@@ -23,20 +55,26 @@ pub fn set_config_entry(
     entry: &str,
     value: frontend::Value,
     app: AppHandle,
-) -> Result<()> {
+) -> Result<frontend::Value> {
+    let clamp = app.lock_prefs().clamp_config_values;
     let mut manager = app.lock_manager();
 
     let profile = manager.active_profile_mut();
     let file = profile.config_cache.find_file(file)?;
 
-    match &mut file.kind {
-        AnyFileKind::BepInEx(file) => file.find_entry(section, entry)?.set(value),
-        AnyFileKind::GDWeave(file) => file.set(entry, value),
+    let value = match &mut file.kind {
+        AnyFileKind::BepInEx(file) => file.find_entry(section, entry)?.set(value, clamp),
+        AnyFileKind::GDWeave(file) => file.set(entry, value.clone()).map(|_| value),
+        AnyFileKind::Yaml(file) => file.set(section, entry, value.clone()).map(|_| value),
+        AnyFileKind::Toml(file) => file.set(section, entry, value.clone()).map(|_| value),
+        AnyFileKind::Ini(file) => file.set(section, entry, value.clone()).map(|_| value),
         _ => return Err(eyre!("unsupported for this format").into()),
     }?;
 
+    history::record(&profile.path, &file.relative_path)
+        .context("failed to save previous version")?;
     file.write(&profile.path).context("failed to write file")?;
-    Ok(())
+    Ok(value)
 }
 
 #[command]
@@ -56,13 +94,153 @@ pub fn reset_config_entry(
         _ => return Err(eyre!("unsupported for this format").into()),
     }?;
 
+    history::record(&profile.path, &file.relative_path)
+        .context("failed to save previous version")?;
     file.write(&profile.path).context("failed to write file")?;
     Ok(value)
 }
 
+#[command]
+pub fn get_config_history(file: &Path, app: AppHandle) -> Result<Vec<ConfigVersion>> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    let file = profile.config_cache.find_file(file)?;
+
+    let versions = history::list(&profile.path, &file.relative_path)?;
+    Ok(versions)
+}
+
+#[command]
+pub fn revert_config_file(file: &Path, version: i64, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.config_cache.find_file(file)?;
+
+    history::revert(&profile.path, file, version).context("failed to revert file")?;
+
+    // make sure the file is read again, even if the modified time didn't change
+    profile.config_cache.0.retain(|f| f.relative_path != file);
+
+    Ok(())
+}
+
+#[command]
+pub fn read_config_file_raw(file: &Path, app: AppHandle) -> Result<String> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.config_cache.find_file(file)?;
+
+    let path = profile.path.join(file);
+    let content = fs::read_to_string(&path).fs_context("reading config file", &path)?;
+
+    Ok(content)
+}
+
+/// Overwrites a config file with `content` and parses it again.
+///
+/// Returns the updated file, which may have become supported or failed to parse.
+#[command]
+pub fn write_config_file_raw(file: &Path, content: &str, app: AppHandle) -> Result<frontend::File> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.config_cache.find_file(file)?;
+
+    history::record(&profile.path, file).context("failed to save previous version")?;
+
+    let path = profile.path.join(file);
+    fs::write(&path, content).fs_context("writing config file", &path)?;
+
+    // make sure the file is read again, even if the modified time and size didn't change
+    profile.config_cache.0.retain(|f| f.relative_path != file);
+    profile.refresh_config();
+
+    let file = profile.config_cache.find_file(file)?.to_frontend();
+    Ok(file)
+}
+
+/// Copies config files from one profile of the active game to another.
+///
+/// Unless `overwrite` is set, nothing is copied if any of the files already
+/// exist in the target profile with different contents, and those are returned instead.
+#[command]
+pub fn copy_config_files(
+    from_profile: i64,
+    to_profile: i64,
+    files: Vec<PathBuf>,
+    overwrite: bool,
+    app: AppHandle,
+) -> Result<CopyConfigResult> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game_mut();
+
+    let src = game.find_profile(from_profile)?.path.clone();
+    let dest = game.find_profile(to_profile)?.path.clone();
+
+    if !overwrite {
+        let conflicts = copy::find_conflicts(&src, &dest, &files)?;
+
+        if !conflicts.is_empty() {
+            return Ok(CopyConfigResult::Conflicts { files: conflicts });
+        }
+    }
+
+    let copied = copy::copy(&src, &dest, &files).context("failed to copy config")?;
+
+    // the copies might keep the modified time of the originals
+    game.find_profile_mut(to_profile)?
+        .config_cache
+        .forget(&files);
+
+    Ok(CopyConfigResult::Done { copied })
+}
+
+/// Compares the config of two profiles of the active game.
+#[command]
+pub fn diff_config(profile_a: i64, profile_b: i64, app: AppHandle) -> Result<ConfigDiff> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game_mut();
+
+    let mut read = |id: i64| -> eyre::Result<_> {
+        let profile = game.find_profile_mut(id)?;
+        profile.refresh_config();
+        Ok(profile.config_cache.to_frontend())
+    };
+
+    let a = read(profile_a)?;
+    let b = read(profile_b)?;
+
+    Ok(diff::diff(&a, &b))
+}
+
+/// Lists the config files shared between every profile of the active game.
+#[command]
+pub fn get_shared_config(app: AppHandle) -> Vec<PathBuf> {
+    shared::list(app.lock_manager().active_game())
+}
+
+/// Starts or stops sharing a config file between every profile of the active game.
+///
+/// When starting, the version in the active profile is used.
+#[command]
+pub fn set_config_shared(file: &Path, shared: bool, app: AppHandle) -> Result<()> {
+    let manager = app.read_manager();
+    let game = manager.active_game();
+
+    match shared {
+        true => shared::share(game, &game.active_profile().path, file)?,
+        false => shared::unshare(game, file)?,
+    }
+
+    Ok(())
+}
+
 #[command]
 pub fn open_config_file(file: &Path, app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let profile = manager.active_profile();
     let path = profile.path.join(file);
@@ -87,10 +265,14 @@ pub fn delete_config_file(file: &Path, app: AppHandle) -> Result<()> {
         return Ok(()); // ignore if the file is not in the list
     };
 
+    let entry = undo::record_config_deletion(profile, file)?;
+
     profile.config_cache.0.remove(index);
 
     let path = profile.path.join(file);
-    trash::delete(path).context("failed to move file to recycle bin")?;
+    util::fs::delete(path)?;
+
+    app.lock_undo_stack().push(entry);
 
     Ok(())
 }
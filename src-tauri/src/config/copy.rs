@@ -0,0 +1,91 @@
+//! Copying config files from one profile to another.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use eyre::{ensure, Result};
+use serde::Serialize;
+use tracing::info;
+
+use super::history;
+use crate::util::error::IoResultExt;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CopyConfigResult {
+    /// Nothing was copied, since these files already exist in the target
+    /// profile with different contents.
+    Conflicts {
+        files: Vec<PathBuf>,
+    },
+    Done {
+        copied: usize,
+    },
+}
+
+/// Finds the files that would overwrite a different version in `dest`.
+pub fn find_conflicts(src: &Path, dest: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut conflicts = Vec::new();
+
+    for file in files {
+        validate(file)?;
+
+        let target = dest.join(file);
+        if !target.exists() {
+            continue;
+        }
+
+        let source = src.join(file);
+        let differs = fs::read(&source).fs_context("reading config file", &source)?
+            != fs::read(&target).fs_context("reading config file", &target)?;
+
+        if differs {
+            conflicts.push(file.clone());
+        }
+    }
+
+    Ok(conflicts)
+}
+
+/// Copies config files, relative to the profile directories, from `src` to `dest`.
+///
+/// Files that are overwritten are saved to the config history of `dest` first.
+/// Returns the number of files that were copied.
+pub fn copy(src: &Path, dest: &Path, files: &[PathBuf]) -> Result<usize> {
+    ensure!(src != dest, "cannot copy config to the same profile");
+
+    for file in files {
+        validate(file)?;
+
+        let source = src.join(file);
+        let target = dest.join(file);
+
+        history::record(dest, file)?;
+
+        fs::create_dir_all(target.parent().unwrap()).fs_context("creating directory", &target)?;
+        fs::copy(&source, &target).fs_context("copying config file", &target)?;
+    }
+
+    info!(
+        "copied {} config files from {} to {}",
+        files.len(),
+        src.display(),
+        dest.display()
+    );
+
+    Ok(files.len())
+}
+
+/// Makes sure the frontend can't reach outside of the profiles.
+pub(super) fn validate(file: &Path) -> Result<()> {
+    ensure!(
+        file.components()
+            .all(|component| matches!(component, Component::Normal(_))),
+        "invalid config file path: {}",
+        file.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Comparing the config of two profiles.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::Serialize;
+
+use super::{
+    frontend::{self, FileKind},
+    search::value_text,
+};
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub only_in_a: Vec<DiffEntry>,
+    pub only_in_b: Vec<DiffEntry>,
+    pub different: Vec<DiffEntry>,
+}
+
+/// An entry that differs between the profiles, with its value in each of them.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffEntry {
+    pub relative_path: PathBuf,
+    pub section: String,
+    pub entry: String,
+    pub value_a: Option<String>,
+    pub value_b: Option<String>,
+}
+
+type EntryKey = (PathBuf, String, String);
+
+/// Compares the entries of two sets of config files, matched by their
+/// path, section and name. Files that couldn't be read are ignored.
+///
+/// Every list is sorted by path, then section, then name.
+pub fn diff(a: &[frontend::File], b: &[frontend::File]) -> ConfigDiff {
+    let mut a = entries(a);
+    let b = entries(b);
+
+    let mut diff = ConfigDiff::default();
+
+    for (key, value_b) in b {
+        match a.remove(&key) {
+            Some(value_a) if value_a == value_b => (),
+            Some(value_a) => diff
+                .different
+                .push(DiffEntry::new(key, Some(value_a), Some(value_b))),
+            None => diff
+                .only_in_b
+                .push(DiffEntry::new(key, None, Some(value_b))),
+        }
+    }
+
+    diff.only_in_a = a
+        .into_iter()
+        .map(|(key, value_a)| DiffEntry::new(key, Some(value_a), None))
+        .collect();
+
+    diff
+}
+
+fn entries(files: &[frontend::File]) -> BTreeMap<EntryKey, String> {
+    let mut entries = BTreeMap::new();
+
+    for file in files {
+        let FileKind::Ok(data) = &file.kind else {
+            continue;
+        };
+
+        for section in &data.sections {
+            for entry in &section.entries {
+                let Some(value) = value_text(&entry.value) else {
+                    continue;
+                };
+
+                let key = (
+                    file.relative_path.clone(),
+                    section.name.clone(),
+                    entry.name.clone(),
+                );
+                entries.insert(key, value);
+            }
+        }
+    }
+
+    entries
+}
+
+impl DiffEntry {
+    fn new(
+        (relative_path, section, entry): EntryKey,
+        value_a: Option<String>,
+        value_b: Option<String>,
+    ) -> Self {
+        Self {
+            relative_path,
+            section,
+            entry,
+            value_a,
+            value_b,
+        }
+    }
+}
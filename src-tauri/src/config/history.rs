@@ -0,0 +1,134 @@
+//! Previous versions of config files, saved before they're changed through Gale.
+//!
+//! Versions are stored in `snapshots/config` in the profile, mirroring the
+//! layout of the profile, as one file per version named after the time
+//! (in milliseconds) it was saved.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use eyre::{OptionExt, Result};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::util::error::IoResultExt;
+
+/// The number of versions kept for each file.
+const MAX_VERSIONS: usize = 10;
+
+/// Changes made within this time of the last saved version are treated as part
+/// of the same edit, so that dragging a slider doesn't flush out the history.
+const MIN_INTERVAL: TimeDelta = TimeDelta::seconds(60);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigVersion {
+    pub id: i64,
+    pub saved_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+fn history_dir(profile_dir: &Path, relative_path: &Path) -> PathBuf {
+    profile_dir
+        .join("snapshots")
+        .join("config")
+        .join(relative_path)
+}
+
+/// Saves the current contents of a config file before it's written to.
+pub fn record(profile_dir: &Path, relative_path: &Path) -> Result<()> {
+    save_version(profile_dir, relative_path, false)
+}
+
+/// Lists the saved versions of a config file, newest first.
+pub fn list(profile_dir: &Path, relative_path: &Path) -> Result<Vec<ConfigVersion>> {
+    let mut versions = read_versions(&history_dir(profile_dir, relative_path))?;
+    versions.reverse();
+
+    Ok(versions)
+}
+
+/// Restores a config file to a saved version.
+///
+/// The current contents are saved first, so the revert can be undone the same way.
+pub fn revert(profile_dir: &Path, relative_path: &Path, id: i64) -> Result<()> {
+    let source = history_dir(profile_dir, relative_path).join(id.to_string());
+    let contents = fs::read(&source).fs_context("reading saved version", &source)?;
+
+    save_version(profile_dir, relative_path, true)?;
+
+    let path = profile_dir.join(relative_path);
+    fs::write(&path, contents).fs_context("writing config file", &path)?;
+
+    debug!("reverted {} to version {}", relative_path.display(), id);
+
+    Ok(())
+}
+
+fn save_version(profile_dir: &Path, relative_path: &Path, always: bool) -> Result<()> {
+    let path = profile_dir.join(relative_path);
+    let Ok(contents) = fs::read(&path) else {
+        return Ok(()); // nothing to save yet
+    };
+
+    let dir = history_dir(profile_dir, relative_path);
+    fs::create_dir_all(&dir).fs_context("creating history directory", &dir)?;
+
+    let versions = read_versions(&dir)?;
+    let now = Utc::now();
+
+    if let Some(latest) = versions.last() {
+        let latest_contents = fs::read(dir.join(latest.id.to_string())).ok();
+
+        if latest_contents.as_ref() == Some(&contents)
+            || (!always && now - latest.saved_at < MIN_INTERVAL)
+        {
+            return Ok(());
+        }
+    }
+
+    let version_path = dir.join(now.timestamp_millis().to_string());
+    fs::write(&version_path, contents).fs_context("saving config version", &version_path)?;
+
+    // the new version isn't in the list, so keep one less
+    for old in versions.iter().rev().skip(MAX_VERSIONS - 1) {
+        let old_path = dir.join(old.id.to_string());
+        fs::remove_file(&old_path).fs_context("removing old config version", &old_path)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the versions in `dir`, oldest first.
+fn read_versions(dir: &Path) -> Result<Vec<ConfigVersion>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+
+    for entry in fs::read_dir(dir).fs_context("reading history directory", dir)? {
+        let entry = entry?;
+
+        let Some(id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<i64>().ok())
+        else {
+            continue;
+        };
+
+        versions.push(ConfigVersion {
+            id,
+            saved_at: DateTime::from_timestamp_millis(id).ok_or_eyre("invalid version time")?,
+            size: entry.metadata()?.len(),
+        });
+    }
+
+    versions.sort_by_key(|version| version.id);
+
+    Ok(versions)
+}
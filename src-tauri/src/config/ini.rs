@@ -0,0 +1,140 @@
+use std::io::{BufRead, Write};
+
+use eyre::{bail, ensure, OptionExt, Result};
+
+use super::frontend::{self, Num};
+
+/// A generic INI file.
+///
+/// The original lines are kept so comments and formatting
+/// survive when entries are changed.
+#[derive(Debug)]
+pub struct File {
+    lines: Vec<String>,
+    sections: Vec<Section>,
+}
+
+#[derive(Debug)]
+struct Section {
+    name: String,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    value: String,
+    line: usize,
+}
+
+impl File {
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+        let mut sections = vec![Section {
+            name: "Default".into(),
+            entries: Vec::new(),
+        }];
+
+        for (index, line) in lines.iter().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with([';', '#']) {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                sections.push(Section {
+                    name: name.trim().to_owned(),
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_eyre(format!("invalid line {}: expected key = value", index + 1))?;
+
+            sections.last_mut().unwrap().entries.push(Entry {
+                name: name.trim().to_owned(),
+                value: value.trim().to_owned(),
+                line: index,
+            });
+        }
+
+        sections.retain(|section| !section.entries.is_empty());
+        ensure!(!sections.is_empty(), "file has no entries");
+
+        Ok(File { lines, sections })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        for line in &self.lines {
+            writeln!(writer, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set(&mut self, section: &str, name: &str, value: frontend::Value) -> Result<()> {
+        let entry = self
+            .sections
+            .iter_mut()
+            .find(|s| s.name == section)
+            .and_then(|s| s.entries.iter_mut().find(|e| e.name == name))
+            .ok_or_eyre("entry not found")?;
+
+        entry.value = match value {
+            frontend::Value::Bool(bool) => bool.to_string(),
+            frontend::Value::String(str) => str,
+            frontend::Value::Int(num) => num.value.to_string(),
+            frontend::Value::Float(num) => num.value.to_string(),
+            _ => bail!("unsupported config value"),
+        };
+
+        self.lines[entry.line] = format!("{} = {}", entry.name, entry.value);
+
+        Ok(())
+    }
+
+    pub fn to_frontend(&self) -> frontend::FileData {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| frontend::Section {
+                name: section.name.clone(),
+                entries: section
+                    .entries
+                    .iter()
+                    .map(|entry| frontend::Entry {
+                        name: entry.name.clone(),
+                        value: parse_value(&entry.value),
+                        description: None,
+                        default: None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        frontend::FileData {
+            metadata: None,
+            sections,
+        }
+    }
+}
+
+/// INI values are untyped, so guess the type from the value itself.
+fn parse_value(value: &str) -> frontend::Value {
+    if let Ok(bool) = value.to_lowercase().parse() {
+        return frontend::Value::Bool(bool);
+    }
+
+    if let Ok(value) = value.parse() {
+        return frontend::Value::Int(Num { value, range: None });
+    }
+
+    if let Ok(value) = value.parse() {
+        return frontend::Value::Float(Num { value, range: None });
+    }
+
+    frontend::Value::String(value.to_owned())
+}
@@ -2,26 +2,37 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     fs::{self},
-    io::{BufReader, BufWriter},
+    io::BufWriter,
     path::{Path, PathBuf},
     time::SystemTime,
 };
 
-use eyre::{Context, OptionExt, Result};
+use eyre::{OptionExt, Result};
 use tracing::debug;
 use rayon::prelude::*;
 use walkdir::WalkDir;
 
 use crate::{
-    game::{ModLoader, ModLoaderKind},
+    game::{GameModLoader, ModLoaderKind},
     profile::Profile,
     util::error::IoResultExt,
 };
 
 mod bepinex;
 pub mod commands;
+mod copy;
+mod diff;
 mod frontend;
 mod gd_weave;
+pub mod history;
+mod ini;
+mod search;
+pub mod shared;
+mod toml;
+mod yaml;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, Default)]
 pub struct ConfigCache(Vec<AnyFile>);
@@ -30,14 +41,38 @@ pub struct ConfigCache(Vec<AnyFile>);
 struct AnyFile {
     display_name: String,
     relative_path: PathBuf,
-    read_time: SystemTime,
+    /// The state of the file on disk when it was last read or written.
+    stamp: Option<FileStamp>,
     kind: AnyFileKind,
 }
 
+/// Identifies a version of a file on disk, to tell if it needs to be parsed again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    size: u64,
+}
+
+impl FileStamp {
+    fn of(metadata: &fs::Metadata) -> Option<Self> {
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            size: metadata.len(),
+        })
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        fs::metadata(path).ok().as_ref().and_then(Self::of)
+    }
+}
+
 #[derive(Debug)]
 enum AnyFileKind {
     BepInEx(bepinex::File),
     GDWeave(gd_weave::File),
+    Yaml(yaml::File),
+    Toml(toml::File),
+    Ini(ini::File),
     Err(eyre::Error),
     Unsupported,
 }
@@ -50,7 +85,36 @@ impl AnyFile {
             .to_string_lossy()
     }
 
-    fn write(&self, profile_dir: &Path) -> Result<()> {
+    fn to_frontend(&self) -> frontend::File {
+        use frontend::FileKind;
+
+        let kind = match &self.kind {
+            AnyFileKind::BepInEx(file) => FileKind::Ok(file.to_frontend()),
+            AnyFileKind::GDWeave(file) => match file.to_frontend() {
+                Ok(file) => FileKind::Ok(file),
+                Err(err) => FileKind::err(err),
+            },
+            AnyFileKind::Yaml(file) => match file.to_frontend() {
+                Ok(file) => FileKind::Ok(file),
+                Err(err) => FileKind::err(err),
+            },
+            AnyFileKind::Toml(file) => match file.to_frontend() {
+                Ok(file) => FileKind::Ok(file),
+                Err(err) => FileKind::err(err),
+            },
+            AnyFileKind::Ini(file) => FileKind::Ok(file.to_frontend()),
+            AnyFileKind::Err(err) => FileKind::err(err),
+            AnyFileKind::Unsupported => FileKind::Unsupported,
+        };
+
+        frontend::File {
+            display_name: self.display_name.clone(),
+            relative_path: self.relative_path.clone(),
+            kind,
+        }
+    }
+
+    fn write(&mut self, profile_dir: &Path) -> Result<()> {
         debug!("writing config file to {}", self.relative_path.display());
 
         let path = profile_dir.join(&self.relative_path);
@@ -61,13 +125,148 @@ impl AnyFile {
         match &self.kind {
             AnyFileKind::BepInEx(file) => file.write(writer),
             AnyFileKind::GDWeave(file) => file.write(writer),
+            AnyFileKind::Yaml(file) => file.write(writer),
+            AnyFileKind::Toml(file) => file.write(writer),
+            AnyFileKind::Ini(file) => file.write(writer),
             AnyFileKind::Err(_) => Ok(()),
             AnyFileKind::Unsupported => Ok(()),
+        }?;
+
+        // the parsed contents already match what was written
+        self.stamp = FileStamp::read(&path);
+
+        Ok(())
+    }
+}
+
+/// The config formats that have a dedicated handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    BepInEx,
+    /// A flat JSON object, like the configs of GDWeave mods.
+    Json,
+    Yaml,
+    Toml,
+    Ini,
+}
+
+impl Format {
+    fn from_extension(mod_loader: &GameModLoader, extension: &str) -> Option<Self> {
+        match (&mod_loader.kind, extension) {
+            (ModLoaderKind::BepInEx(_), "cfg") => Some(Self::BepInEx),
+            (ModLoaderKind::MelonLoader(_), "cfg") => Some(Self::Toml),
+            (ModLoaderKind::GDWeave(_), "json") => Some(Self::Json),
+            (_, "yml" | "yaml") => Some(Self::Yaml),
+            (_, "toml") => Some(Self::Toml),
+            (_, "ini") => Some(Self::Ini),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the contents of a file, regardless of its extension.
+    fn sniff(content: &str) -> Option<Self> {
+        let lines = content
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim_start().is_empty())
+            .collect::<Vec<_>>();
+
+        let first = lines.first()?.trim_start();
+
+        if first.starts_with('{') {
+            return Some(Self::Json);
+        }
+
+        if lines.iter().any(|line| {
+            line.starts_with("## Settings file was created by plugin")
+                || line.starts_with("# Setting type:")
+        }) {
+            return Some(Self::BepInEx);
+        }
+
+        let lines = lines
+            .into_iter()
+            .filter(|line| !line.trim_start().starts_with(['#', ';']))
+            .collect::<Vec<_>>();
+
+        let is_section = |line: &str| line.starts_with('[') && line.ends_with(']');
+        if lines.iter().any(|line| line.contains('='))
+            && lines
+                .iter()
+                .all(|line| is_section(line.trim_start()) || line.contains('='))
+        {
+            return Some(Self::Ini);
+        }
+
+        let is_yaml_key = |line: &str| {
+            line.split_once(':').is_some_and(|(key, value)| {
+                !key.is_empty()
+                    && !key.contains(char::is_whitespace)
+                    && (value.is_empty() || value.starts_with(' '))
+            })
+        };
+
+        if first == "---"
+            || (lines.iter().any(|line| is_yaml_key(line))
+                && lines.iter().all(|line| {
+                    line.starts_with([' ', '-']) || *line == "---" || is_yaml_key(line)
+                }))
+        {
+            return Some(Self::Yaml);
         }
+
+        None
+    }
+
+    fn read(self, content: &str) -> Result<AnyFileKind> {
+        let reader = content.as_bytes();
+
+        Ok(match self {
+            Self::BepInEx => AnyFileKind::BepInEx(bepinex::File::read(reader)?),
+            Self::Json => AnyFileKind::GDWeave(gd_weave::File::read(reader)?),
+            Self::Yaml => AnyFileKind::Yaml(yaml::File::read(reader)?),
+            Self::Toml => AnyFileKind::Toml(toml::File::read(reader)?),
+            Self::Ini => AnyFileKind::Ini(ini::File::read(reader)?),
+        })
     }
 }
 
 impl AnyFileKind {
+    /// Reads a file, preferring the format detected from its contents over its extension.
+    ///
+    /// Parse errors are only reported if the extension matches the failed format,
+    /// otherwise the file is considered unsupported.
+    fn read(content: &str, extension: &str, mod_loader: &GameModLoader) -> Self {
+        let expected = Format::from_extension(mod_loader, extension);
+        // TOML can't be told apart from INI by its contents, so trust the extension
+        let sniffed = match (Format::sniff(content), expected) {
+            (Some(Format::Ini), Some(Format::Toml)) => expected,
+            // other loaders' JSON files are usually data, not flat configs like GDWeave's
+            (Some(Format::Json), _) if !matches!(mod_loader.kind, ModLoaderKind::GDWeave(_)) => {
+                None
+            }
+            (sniffed, _) => sniffed,
+        };
+
+        let mut error = None;
+
+        for format in sniffed
+            .into_iter()
+            .chain(expected.filter(|f| Some(*f) != sniffed))
+        {
+            match format.read(content) {
+                Ok(kind) => return kind,
+                Err(err) if Some(format) == expected => error = Some(err),
+                Err(err) => debug!("failed to read file as {:?}: {:#}", format, err),
+            }
+        }
+
+        match error {
+            Some(err) => Self::Err(err.wrap_err("failed to parse file")),
+            None => Self::Unsupported,
+        }
+    }
+
     fn mod_name(&self) -> Option<&str> {
         match self {
             Self::BepInEx(file) => file.mod_name(),
@@ -112,125 +311,59 @@ impl Profile {
 }
 
 impl ConfigCache {
-    pub fn refresh(&mut self, root: &Path, mod_loader: &ModLoader) {
+    /// Brings the cache up to date with the config directory of the profile at `root`.
+    ///
+    /// Only files whose modified time or size changed since they were last read
+    /// are parsed again, in parallel. Files that no longer exist are removed.
+    pub fn refresh(&mut self, root: &Path, mod_loader: &'static GameModLoader) {
         let config_dir = root.join(mod_loader.config_path());
 
-        let files = WalkDir::new(&config_dir)
-            .into_iter()
-            .par_bridge()
-            .filter_map(Result::ok)
-            .filter_map(|entry| self.read_file(entry, root, &config_dir, mod_loader))
-            .collect_vec_list()
-            .into_iter()
-            .flatten();
-
-        for (file, index) in files {
-            match index {
-                Some(index) => self.0[index] = file,
-                None => self.0.push(file),
-            };
-        }
-
-        self.resolve_duplicate_names();
-    }
-
-    fn read_file(
-        &self,
-        entry: walkdir::DirEntry,
-        root: &Path,
-        config_dir: &Path,
-        mod_loader: &ModLoader,
-    ) -> Option<(AnyFile, Option<usize>)> {
-        const EXTENSIONS: &[&str] = &["cfg", "txt", "json", "yml", "yaml", "ini", "xml"];
-
-        let extension = entry.path().extension().and_then(|ext| ext.to_str())?;
-
-        let relative_path = entry
-            .path()
-            .strip_prefix(root)
-            .expect("file path should be a child of root")
-            .to_path_buf();
-
-        let curr_index = self
+        let mut cached = self
             .0
-            .iter()
-            .position(|file| file.relative_path == relative_path);
+            .drain(..)
+            .map(|file| (file.relative_path.clone(), file))
+            .collect::<HashMap<_, _>>();
 
-        if !self.needs_refresh(curr_index, &entry) {
-            return None;
-        }
+        let mut files = Vec::new();
+        let mut stale = Vec::new();
 
-        let kind = match (&mod_loader.kind, extension) {
-            (ModLoaderKind::BepInEx { .. }, "cfg") => {
-                read_file(&entry, bepinex::File::read, AnyFileKind::BepInEx)
+        for entry in WalkDir::new(&config_dir).into_iter().filter_map(Result::ok) {
+            if !entry.file_type().is_file() {
+                continue;
             }
-            (ModLoaderKind::GDWeave {}, "json") => {
-                read_file(&entry, gd_weave::File::read, AnyFileKind::GDWeave)
-            }
-            (_, ext) if EXTENSIONS.contains(&ext) => AnyFileKind::Unsupported,
-            _ => return None,
-        };
-
-        let display_name = match kind.mod_name() {
-            Some(name) => Cow::Borrowed(name),
-            None => match &kind {
-                AnyFileKind::BepInEx(_) | AnyFileKind::GDWeave(_) => {
-                    relative_path.file_stem().unwrap().to_string_lossy()
-                }
-                AnyFileKind::Unsupported | AnyFileKind::Err(_) => entry
-                    .path()
-                    .strip_prefix(config_dir)
-                    .unwrap()
-                    .to_string_lossy(),
-            },
-        }
-        .replace('-', "")
-        .replace('_', " ");
 
-        let file = AnyFile {
-            display_name,
-            relative_path,
-            read_time: SystemTime::now(),
-            kind,
-        };
+            let relative_path = entry
+                .path()
+                .strip_prefix(root)
+                .expect("file path should be a child of root")
+                .to_path_buf();
 
-        return Some((file, curr_index));
+            let stamp = entry.metadata().ok().as_ref().and_then(FileStamp::of);
 
-        fn read_file<T, F, G>(entry: &walkdir::DirEntry, f: F, g: G) -> AnyFileKind
-        where
-            F: FnOnce(BufReader<fs::File>) -> Result<T>,
-            G: FnOnce(T) -> AnyFileKind,
-        {
-            let file = fs::File::open(entry.path())
-                .map(BufReader::new)
-                .context("failed to open file")
-                .and_then(f);
-
-            match file {
-                Ok(file) => g(file),
-                Err(err) => AnyFileKind::Err(err),
+            match cached.remove(&relative_path) {
+                Some(file) if stamp.is_some() && file.stamp == stamp => files.push(file),
+                _ => stale.push((entry, relative_path, stamp)),
             }
         }
-    }
 
-    fn needs_refresh(&self, curr_index: Option<usize>, entry: &walkdir::DirEntry) -> bool {
-        let Some(curr_index) = curr_index else {
-            return true;
-        };
-        let Some(curr_file) = self.0.get(curr_index) else {
-            return true;
-        };
-        let Ok(metadata) = entry.metadata() else {
-            return true;
-        };
-        let Ok(modified) = metadata.modified() else {
-            return true;
-        };
-        if modified > curr_file.read_time {
-            return true;
-        };
+        debug!(
+            "parsing {} config files, {} unchanged",
+            stale.len(),
+            files.len()
+        );
+
+        files.par_extend(
+            stale
+                .into_par_iter()
+                .filter_map(|(entry, relative_path, stamp)| {
+                    read_file(&entry, relative_path, stamp, &config_dir, mod_loader)
+                }),
+        );
 
-        false
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+        self.0 = files;
+        self.resolve_duplicate_names();
     }
 
     fn resolve_duplicate_names(&mut self) {
@@ -277,28 +410,12 @@ impl ConfigCache {
     }
 
     fn to_frontend(&self) -> Vec<frontend::File> {
-        use frontend::FileKind;
+        self.0.iter().map(AnyFile::to_frontend).collect()
+    }
 
-        self.0
-            .iter()
-            .map(|file| {
-                let kind = match &file.kind {
-                    AnyFileKind::BepInEx(file) => FileKind::Ok(file.to_frontend()),
-                    AnyFileKind::GDWeave(file) => match file.to_frontend() {
-                        Ok(file) => FileKind::Ok(file),
-                        Err(err) => FileKind::err(err),
-                    },
-                    AnyFileKind::Err(err) => FileKind::err(err),
-                    AnyFileKind::Unsupported => FileKind::Unsupported,
-                };
-
-                frontend::File {
-                    display_name: file.display_name.clone(),
-                    relative_path: file.relative_path.clone(),
-                    kind,
-                }
-            })
-            .collect()
+    /// Removes files from the cache, so they're read again on the next refresh.
+    pub fn forget(&mut self, files: &[PathBuf]) {
+        self.0.retain(|file| !files.contains(&file.relative_path));
     }
 
     fn find_file(&mut self, file: &Path) -> Result<&mut AnyFile> {
@@ -308,3 +425,48 @@ impl ConfigCache {
             .ok_or_eyre("file not found")
     }
 }
+
+fn read_file(
+    entry: &walkdir::DirEntry,
+    relative_path: PathBuf,
+    stamp: Option<FileStamp>,
+    config_dir: &Path,
+    mod_loader: &GameModLoader,
+) -> Option<AnyFile> {
+    const EXTENSIONS: &[&str] = &["cfg", "txt", "json", "yml", "yaml", "toml", "ini", "xml"];
+
+    let extension = entry.path().extension().and_then(|ext| ext.to_str())?;
+    if !EXTENSIONS.contains(&extension) {
+        return None;
+    }
+
+    let kind = match fs::read_to_string(entry.path()) {
+        Ok(content) => AnyFileKind::read(&content, extension, mod_loader),
+        Err(err) => AnyFileKind::Err(eyre::Report::new(err).wrap_err("failed to open file")),
+    };
+
+    let display_name = match kind.mod_name() {
+        Some(name) => Cow::Borrowed(name),
+        None => match &kind {
+            AnyFileKind::BepInEx(_)
+            | AnyFileKind::GDWeave(_)
+            | AnyFileKind::Yaml(_)
+            | AnyFileKind::Toml(_)
+            | AnyFileKind::Ini(_) => relative_path.file_stem().unwrap().to_string_lossy(),
+            AnyFileKind::Unsupported | AnyFileKind::Err(_) => entry
+                .path()
+                .strip_prefix(config_dir)
+                .unwrap()
+                .to_string_lossy(),
+        },
+    }
+    .replace('-', "")
+    .replace('_', " ");
+
+    Some(AnyFile {
+        display_name,
+        relative_path,
+        stamp,
+        kind,
+    })
+}
@@ -0,0 +1,160 @@
+//! Searching for entries across every config file of a profile.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::frontend::{self, FileKind, Value};
+
+/// The maximum number of matches returned by [`search`].
+const MAX_MATCHES: usize = 100;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigMatch {
+    pub relative_path: PathBuf,
+    pub display_name: String,
+    pub section: String,
+    /// The matched entry, or `None` if only the section name matched.
+    pub entry: Option<String>,
+    pub field: MatchField,
+    #[serde(skip)]
+    score: u32,
+}
+
+/// Which part of an entry matched the query, from most to least relevant.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchField {
+    Name,
+    Section,
+    Description,
+    Value,
+}
+
+/// Finds entries whose name, section, description or current value contain
+/// `query`, ignoring case. The best matches come first.
+pub fn search(files: &[frontend::File], query: &str) -> Vec<ConfigMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+
+    for file in files {
+        let FileKind::Ok(data) = &file.kind else {
+            continue;
+        };
+
+        for section in &data.sections {
+            let section_score = score(&section.name, &query);
+
+            if let Some(score) = section_score {
+                matches.push(ConfigMatch::new(
+                    file,
+                    section,
+                    None,
+                    MatchField::Section,
+                    score,
+                ));
+            }
+
+            for entry in &section.entries {
+                let found = [
+                    (MatchField::Name, score(&entry.name, &query)),
+                    (
+                        MatchField::Description,
+                        entry
+                            .description
+                            .as_deref()
+                            .and_then(|description| score(description, &query)),
+                    ),
+                    (
+                        MatchField::Value,
+                        value_text(&entry.value).and_then(|value| score(&value, &query)),
+                    ),
+                ]
+                .into_iter()
+                .find_map(|(field, score)| Some((field, score?)));
+
+                if let Some((field, score)) = found {
+                    matches.push(ConfigMatch::new(
+                        file,
+                        section,
+                        Some(&entry.name),
+                        field,
+                        score,
+                    ));
+                }
+            }
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        a.field
+            .cmp(&b.field)
+            .then(b.score.cmp(&a.score))
+            .then_with(|| a.display_name.cmp(&b.display_name))
+    });
+    matches.truncate(MAX_MATCHES);
+
+    matches
+}
+
+impl ConfigMatch {
+    fn new(
+        file: &frontend::File,
+        section: &frontend::Section,
+        entry: Option<&str>,
+        field: MatchField,
+        score: u32,
+    ) -> Self {
+        Self {
+            relative_path: file.relative_path.clone(),
+            display_name: file.display_name.clone(),
+            section: section.name.clone(),
+            entry: entry.map(str::to_owned),
+            field,
+            score,
+        }
+    }
+}
+
+/// Scores how well `text` matches the lowercase `query`, if at all.
+///
+/// Exact matches score highest, then matches at the start of the text or
+/// of a word, then matches anywhere.
+fn score(text: &str, query: &str) -> Option<u32> {
+    let text = text.to_lowercase();
+    let index = text.find(query)?;
+
+    let score = if text == query {
+        3
+    } else if index == 0 {
+        2
+    } else if !text[..index].ends_with(char::is_alphanumeric) {
+        1
+    } else {
+        0
+    };
+
+    Some(score)
+}
+
+pub(super) fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(bool) => Some(bool.to_string()),
+        Value::String(str) => Some(str.clone()),
+        Value::Int(num) => Some(num.value.to_string()),
+        Value::Float(num) => Some(num.value.to_string()),
+        Value::Enum { index, options } => options.get(*index).cloned(),
+        Value::Flags { indicies, options } => Some(
+            indicies
+                .iter()
+                .filter_map(|index| options.get(*index).map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    }
+}
@@ -0,0 +1,126 @@
+//! Config files shared between every profile of a game.
+//!
+//! Shared files are stored in the `shared_config` directory of the game and
+//! copied into whichever profile is launched. The newest version of each file
+//! is picked from every profile first, so changes made through Gale or by the
+//! game itself follow the user to the next profile they launch.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use eyre::{ensure, Result};
+use tracing::{debug, info};
+use walkdir::WalkDir;
+
+use super::copy;
+use crate::{profile::ManagedGame, util::error::IoResultExt};
+
+fn dir(game: &ManagedGame) -> PathBuf {
+    game.path.join("shared_config")
+}
+
+/// Lists the shared files of `game`, relative to the profile directories.
+pub fn list(game: &ManagedGame) -> Vec<PathBuf> {
+    let dir = dir(game);
+
+    WalkDir::new(&dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(&dir)
+                .ok()
+                .map(|path| path.to_path_buf())
+        })
+        .collect()
+}
+
+/// Starts sharing `file`, using the version in `profile_dir`.
+pub fn share(game: &ManagedGame, profile_dir: &Path, file: &Path) -> Result<()> {
+    copy::validate(file)?;
+
+    let source = profile_dir.join(file);
+    ensure!(source.is_file(), "config file {} not found", file.display());
+
+    let target = dir(game).join(file);
+    fs::create_dir_all(target.parent().unwrap()).fs_context("creating directory", &target)?;
+    fs::copy(&source, &target).fs_context("copying config file", &target)?;
+
+    info!("sharing {} between profiles", file.display());
+
+    Ok(())
+}
+
+/// Stops sharing `file`. The profiles keep their current copies.
+pub fn unshare(game: &ManagedGame, file: &Path) -> Result<()> {
+    copy::validate(file)?;
+
+    let path = dir(game).join(file);
+    if path.exists() {
+        fs::remove_file(&path).fs_context("removing shared config file", &path)?;
+    }
+
+    info!("stopped sharing {}", file.display());
+
+    Ok(())
+}
+
+/// Brings the shared files up to date and copies them into the active profile.
+///
+/// Returns the files that were replaced in the active profile.
+pub fn sync(game: &ManagedGame) -> Result<Vec<PathBuf>> {
+    let dir = dir(game);
+    let active = game.active_profile();
+    let mut replaced = Vec::new();
+
+    for file in list(game) {
+        let shared = dir.join(&file);
+
+        let newest = game
+            .profiles
+            .iter()
+            .map(|profile| profile.path.join(&file))
+            .filter_map(|path| modified(&path).map(|time| (path, time)))
+            .max_by_key(|(_, time)| *time);
+
+        if let Some((path, time)) = newest {
+            if modified(&shared).map_or(true, |shared_time| time > shared_time) {
+                debug!("updating shared {} from {}", file.display(), path.display());
+                fs::copy(&path, &shared).fs_context("updating shared config file", &shared)?;
+            }
+        }
+
+        let content = fs::read(&shared).fs_context("reading shared config file", &shared)?;
+        let target = active.path.join(&file);
+
+        if fs::read(&target).is_ok_and(|current| current == content) {
+            continue;
+        }
+
+        fs::create_dir_all(target.parent().unwrap()).fs_context("creating directory", &target)?;
+        fs::write(&target, content).fs_context("writing shared config file", &target)?;
+
+        replaced.push(file);
+    }
+
+    if !replaced.is_empty() {
+        info!(
+            "copied {} shared config files into {}",
+            replaced.len(),
+            active.name
+        );
+    }
+
+    Ok(replaced)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,329 @@
+use super::{frontend::Num, *};
+
+#[test]
+fn sniff_json() {
+    assert_eq!(Format::sniff("\n  {\n\"a\": 1 }"), Some(Format::Json));
+}
+
+#[test]
+fn sniff_bepinex() {
+    let content = "## Settings file was created by plugin Foo v1.0.0\n\
+        ## Plugin GUID: foo\n\n\
+        [General]\n\n\
+        # Setting type: Boolean\n\
+        # Default value: true\n\
+        Enabled = true\n";
+
+    assert_eq!(Format::sniff(content), Some(Format::BepInEx));
+}
+
+#[test]
+fn sniff_ini() {
+    let content = "; comment\n[Section]\nkey = value\nother=1\n";
+
+    assert_eq!(Format::sniff(content), Some(Format::Ini));
+}
+
+#[test]
+fn sniff_yaml() {
+    assert_eq!(
+        Format::sniff("key: value\nlist:\n  - 1\n"),
+        Some(Format::Yaml)
+    );
+    assert_eq!(Format::sniff("---\nfoo: bar\n"), Some(Format::Yaml));
+}
+
+#[test]
+fn sniff_plain_text() {
+    assert_eq!(Format::sniff("Thanks for downloading: enjoy!\n"), None);
+    assert_eq!(Format::sniff(""), None);
+}
+
+#[test]
+fn json_is_only_gd_weave() {
+    let content = "{ \"a\": 1 }";
+
+    let gd_weave = &crate::game::from_slug("webfishing").unwrap().mod_loader;
+    assert!(matches!(
+        AnyFileKind::read(content, "json", gd_weave),
+        AnyFileKind::GDWeave(_)
+    ));
+
+    let bepinex = &crate::game::from_slug("lethal-company").unwrap().mod_loader;
+    assert!(matches!(
+        AnyFileKind::read(content, "json", bepinex),
+        AnyFileKind::Unsupported
+    ));
+}
+
+#[test]
+fn ini_preserves_comments() {
+    let content = "; keep me\n[Section]\nkey = 1\n";
+    let mut file = ini::File::read(content.as_bytes()).unwrap();
+
+    file.set(
+        "Section",
+        "key",
+        frontend::Value::Int(Num {
+            value: 2,
+            range: None,
+        }),
+    )
+    .unwrap();
+
+    let mut out = Vec::new();
+    file.write(&mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "; keep me\n[Section]\nkey = 2\n"
+    );
+}
+
+#[test]
+fn toml_preserves_comments() {
+    let content = "# keep me\nname = \"foo\"\n\n[graphics]\nscale = 1 # inline\n";
+    let mut file = toml::File::read(content.as_bytes()).unwrap();
+
+    let data = file.to_frontend().unwrap();
+    assert_eq!(data.sections.len(), 2);
+    assert_eq!(data.sections[1].name, "graphics");
+
+    file.set(
+        "graphics",
+        "scale",
+        frontend::Value::Int(Num {
+            value: 2,
+            range: None,
+        }),
+    )
+    .unwrap();
+
+    let mut out = Vec::new();
+    file.write(&mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "# keep me\nname = \"foo\"\n\n[graphics]\nscale = 2 # inline\n"
+    );
+}
+
+#[test]
+fn yaml_nested_sections() {
+    let content = "enabled: true\naudio:\n  volume: 0.5\n  tracks:\n    - a\n";
+    let mut file = yaml::File::read(content.as_bytes()).unwrap();
+
+    file.set("audio", "volume", frontend::Value::Bool(false))
+        .unwrap();
+
+    let data = file.to_frontend().unwrap();
+    let names = data
+        .sections
+        .iter()
+        .map(|section| (section.name.as_str(), section.entries.len()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, [("Default", 1), ("audio", 1)]);
+}
+
+#[test]
+fn yaml_preserves_comments() {
+    let content = "# keep me\nenabled: true # inline\naudio:\n  # volume\n  volume: 0.5\n";
+    let mut file = yaml::File::read(content.as_bytes()).unwrap();
+
+    file.set("Default", "enabled", frontend::Value::Bool(false))
+        .unwrap();
+    file.set(
+        "audio",
+        "volume",
+        frontend::Value::Int(Num {
+            value: 1,
+            range: None,
+        }),
+    )
+    .unwrap();
+
+    let mut out = Vec::new();
+    file.write(&mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "# keep me\nenabled: false # inline\naudio:\n  # volume\n  volume: 1\n"
+    );
+}
+
+#[test]
+fn melon_preferences() {
+    let content = "[MyMod]\n# Whether the mod is enabled\nEnabled = true\n\nSpeed = 1.5\nKeys = [ \"A\", \"B\" ]\n";
+    let file = toml::File::read(content.as_bytes()).unwrap();
+
+    let data = file.to_frontend().unwrap();
+    let section = &data.sections[0];
+
+    assert_eq!(section.name, "MyMod");
+    assert_eq!(section.entries.len(), 2);
+    assert_eq!(
+        section.entries[0].description.as_deref(),
+        Some("Whether the mod is enabled")
+    );
+    assert_eq!(section.entries[1].description, None);
+}
+
+/// Reads `(relative_path, content)` pairs of TOML files into frontend files.
+fn frontend_files(files: &[(&str, &str)]) -> Vec<frontend::File> {
+    files
+        .iter()
+        .map(|(path, content)| {
+            let file = toml::File::read(content.as_bytes()).unwrap();
+
+            frontend::File {
+                display_name: path.to_string(),
+                relative_path: path.into(),
+                kind: frontend::FileKind::Ok(file.to_frontend().unwrap()),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn search_ranks_names_first() {
+    let mut files = frontend_files(&[
+        (
+            "game.toml",
+            "[Camera]\nSubFOV = 1\nFOVScale = 1\nFOV = 90\n\n[Other]\nLabel = \"fov\"\n# Scales the fov\nZoom = 1\n",
+        ),
+        ("mod.toml", "[Fov]\nEnabled = true\n"),
+    ]);
+    files.push(frontend::File {
+        display_name: "Broken".into(),
+        relative_path: "broken.toml".into(),
+        kind: frontend::FileKind::err("fov"),
+    });
+
+    let matches = search::search(&files, " fov ")
+        .into_iter()
+        .map(|m| (m.display_name, m.entry.unwrap_or_default(), m.field))
+        .collect::<Vec<_>>();
+
+    let game = || "game.toml".to_owned();
+    assert_eq!(
+        matches,
+        [
+            (game(), "FOV".to_owned(), search::MatchField::Name),
+            (game(), "FOVScale".to_owned(), search::MatchField::Name),
+            (game(), "SubFOV".to_owned(), search::MatchField::Name),
+            (
+                "mod.toml".to_owned(),
+                String::new(),
+                search::MatchField::Section
+            ),
+            (game(), "Zoom".to_owned(), search::MatchField::Description),
+            (game(), "Label".to_owned(), search::MatchField::Value),
+        ]
+    );
+
+    assert!(search::search(&files, "  ").is_empty());
+}
+
+#[test]
+fn copy_reports_conflicts() {
+    let src = tempfile::tempdir().unwrap();
+    let dest = tempfile::tempdir().unwrap();
+
+    let same = PathBuf::from("config/same.cfg");
+    let changed = PathBuf::from("config/changed.cfg");
+    let new = PathBuf::from("config/new.cfg");
+
+    for dir in [src.path(), dest.path()] {
+        fs::create_dir_all(dir.join("config")).unwrap();
+        fs::write(dir.join(&same), "a = 1").unwrap();
+    }
+    fs::write(src.path().join(&changed), "b = 2").unwrap();
+    fs::write(dest.path().join(&changed), "b = 3").unwrap();
+    fs::write(src.path().join(&new), "c = 4").unwrap();
+
+    let files = vec![same, changed.clone(), new.clone()];
+    assert_eq!(
+        copy::find_conflicts(src.path(), dest.path(), &files).unwrap(),
+        vec![changed.clone()]
+    );
+
+    assert_eq!(copy::copy(src.path(), dest.path(), &files).unwrap(), 3);
+    assert_eq!(
+        fs::read_to_string(dest.path().join(&changed)).unwrap(),
+        "b = 2"
+    );
+    assert_eq!(fs::read_to_string(dest.path().join(&new)).unwrap(), "c = 4");
+
+    assert!(copy::find_conflicts(src.path(), dest.path(), &[PathBuf::from("../x")]).is_err());
+}
+
+#[test]
+fn diff_profiles() {
+    let a = frontend_files(&[
+        ("game.toml", "[Camera]\nFOV = 90\nShake = true\n"),
+        ("old.toml", "[Section]\nKey = 1\n"),
+    ]);
+    let mut b = frontend_files(&[
+        ("game.toml", "[Camera]\nFOV = 110\nShake = true\nZoom = 2\n"),
+        ("new.toml", "[Section]\nKey = 1\n"),
+    ]);
+    b.push(frontend::File {
+        display_name: "Broken".into(),
+        relative_path: "old.toml".into(),
+        kind: frontend::FileKind::err("invalid"),
+    });
+
+    fn keys(entries: &[diff::DiffEntry]) -> Vec<(&str, &str)> {
+        entries
+            .iter()
+            .map(|entry| (entry.relative_path.to_str().unwrap(), entry.entry.as_str()))
+            .collect()
+    }
+
+    let diff = diff::diff(&a, &b);
+
+    assert_eq!(keys(&diff.only_in_a), [("old.toml", "Key")]);
+    assert_eq!(
+        keys(&diff.only_in_b),
+        [("game.toml", "Zoom"), ("new.toml", "Key")]
+    );
+    assert_eq!(keys(&diff.different), [("game.toml", "FOV")]);
+    assert_eq!(diff.different[0].value_a.as_deref(), Some("90"));
+    assert_eq!(diff.different[0].value_b.as_deref(), Some("110"));
+
+    let same = diff::diff(&a, &a);
+    assert!(same.only_in_a.is_empty() && same.only_in_b.is_empty() && same.different.is_empty());
+}
+
+#[test]
+fn refresh_reparses_changed_files() {
+    let root = tempfile::tempdir().unwrap();
+    let mod_loader = &crate::game::from_slug("lethal-company").unwrap().mod_loader;
+    let config_dir = root.path().join(mod_loader.config_path());
+    fs::create_dir_all(&config_dir).unwrap();
+
+    fs::write(config_dir.join("a.ini"), "[Section]\nkey = 1\n").unwrap();
+    fs::write(config_dir.join("b.ini"), "[Section]\nkey = 1\n").unwrap();
+
+    let mut cache = ConfigCache::default();
+    cache.refresh(root.path(), mod_loader);
+    assert_eq!(cache.0.len(), 2);
+
+    let stamp = |cache: &ConfigCache, name: &str| {
+        cache
+            .0
+            .iter()
+            .find(|file| file.relative_path.ends_with(name))
+            .and_then(|file| file.stamp)
+    };
+
+    let before = stamp(&cache, "a.ini");
+
+    fs::write(config_dir.join("a.ini"), "[Section]\nkey = 12\n").unwrap();
+    fs::remove_file(config_dir.join("b.ini")).unwrap();
+    cache.refresh(root.path(), mod_loader);
+
+    assert_eq!(cache.0.len(), 1);
+    assert_ne!(stamp(&cache, "a.ini"), before);
+}
@@ -0,0 +1,170 @@
+use std::io::{BufRead, Write};
+
+use eyre::{bail, OptionExt, Result};
+use toml_edit::{DocumentMut, Item, Key, Table, Value};
+
+use super::frontend::{self, Num};
+
+/// A TOML file, which is also the format of MelonLoader's `MelonPreferences.cfg`.
+///
+/// Tables are shown as sections, named by their dotted path, with top-level
+/// keys in the `Default` section. Comments right above an entry are used as its
+/// description, like MelonLoader writes them.
+///
+/// The document is edited in place, so comments and formatting survive when
+/// entries are changed.
+#[derive(Debug)]
+pub struct File {
+    document: DocumentMut,
+}
+
+impl File {
+    pub fn read(mut reader: impl BufRead) -> Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let document = content.parse()?;
+        Ok(File { document })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        write!(writer, "{}", self.document)?;
+        Ok(())
+    }
+
+    pub fn set(&mut self, section: &str, name: &str, value: frontend::Value) -> Result<()> {
+        let path = table_paths(self.document.as_table())
+            .into_iter()
+            .find(|path| section_name(path) == section)
+            .ok_or_eyre("section not found")?;
+
+        let mut table = self.document.as_table_mut();
+        for key in &path {
+            table = table
+                .get_mut(key)
+                .and_then(Item::as_table_mut)
+                .ok_or_eyre("section not found")?;
+        }
+
+        let entry = table
+            .get_mut(name)
+            .and_then(Item::as_value_mut)
+            .ok_or_eyre("entry not found")?;
+
+        let mut new_value = to_toml(value)?;
+        *new_value.decor_mut() = entry.decor().clone();
+        *entry = new_value;
+
+        Ok(())
+    }
+
+    pub fn to_frontend(&self) -> Result<frontend::FileData> {
+        let root = self.document.as_table();
+
+        let sections = table_paths(root)
+            .into_iter()
+            .filter_map(|path| {
+                let table = path
+                    .iter()
+                    .try_fold(root, |table, key| table.get(key).and_then(Item::as_table))?;
+
+                let entries = table
+                    .iter()
+                    .filter_map(|(key, item)| {
+                        Some(frontend::Entry {
+                            name: key.to_owned(),
+                            value: to_frontend(item.as_value()?)?,
+                            description: table.key(key).and_then(description),
+                            default: None,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                (!entries.is_empty()).then(|| frontend::Section {
+                    name: section_name(&path),
+                    entries,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if sections.is_empty() {
+            bail!("file has no editable entries");
+        }
+
+        Ok(frontend::FileData {
+            metadata: None,
+            sections,
+        })
+    }
+}
+
+/// The key paths of every table in the document, including the root, in order.
+fn table_paths(root: &Table) -> Vec<Vec<String>> {
+    fn visit(table: &Table, path: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+        paths.push(path.clone());
+
+        for (key, item) in table {
+            let Some(table) = item.as_table() else {
+                continue;
+            };
+
+            path.push(key.to_owned());
+            visit(table, path, paths);
+            path.pop();
+        }
+    }
+
+    let mut paths = Vec::new();
+    visit(root, &mut Vec::new(), &mut paths);
+    paths
+}
+
+fn section_name(path: &[String]) -> String {
+    match path.is_empty() {
+        true => "Default".into(),
+        false => path.join("."),
+    }
+}
+
+/// Reads the comment lines directly above a key.
+fn description(key: &Key) -> Option<String> {
+    let prefix = key.leaf_decor().prefix()?.as_str()?;
+
+    // only the comments right above, not the ones separated by a blank line
+    let lines = prefix
+        .lines()
+        .map(str::trim)
+        .rev()
+        .take_while(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim())
+        .collect::<Vec<_>>();
+
+    (!lines.is_empty()).then(|| lines.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+fn to_frontend(value: &Value) -> Option<frontend::Value> {
+    match value {
+        Value::Boolean(bool) => Some(frontend::Value::Bool(*bool.value())),
+        Value::String(str) => Some(frontend::Value::String(str.value().to_owned())),
+        Value::Integer(int) => Some(frontend::Value::Int(Num {
+            value: i32::try_from(*int.value()).ok()?,
+            range: None,
+        })),
+        Value::Float(float) => Some(frontend::Value::Float(Num {
+            value: *float.value() as f32,
+            range: None,
+        })),
+        _ => None,
+    }
+}
+
+fn to_toml(value: frontend::Value) -> Result<Value> {
+    Ok(match value {
+        frontend::Value::Bool(bool) => bool.into(),
+        frontend::Value::String(str) => str.into(),
+        frontend::Value::Int(num) => i64::from(num.value).into(),
+        // go through the string representation, so 0.1 isn't written as 0.10000000149011612
+        frontend::Value::Float(num) => num.value.to_string().parse::<f64>()?.into(),
+        _ => bail!("unsupported config value"),
+    })
+}
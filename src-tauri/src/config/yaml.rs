@@ -0,0 +1,255 @@
+use std::io::{BufRead, Write};
+
+use eyre::{bail, OptionExt, Result};
+use serde_yaml::{Mapping, Number, Value};
+
+use super::frontend::{self, Num};
+
+/// A YAML file with a mapping at the top level.
+///
+/// Nested mappings are shown as sections, named by the dotted path to them.
+/// Values that can't be edited, like lists, are kept but not shown.
+///
+/// Like INI files, the original lines are kept and changed values are written
+/// into them, so comments and formatting survive. If a value can't be found on
+/// a single line (for example in a flow mapping), the whole file is serialized again.
+#[derive(Debug)]
+pub struct File {
+    root: Mapping,
+    lines: Vec<String>,
+    reserialize: bool,
+}
+
+impl File {
+    pub fn read(mut reader: impl BufRead) -> Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let root = serde_yaml::from_str(&content)?;
+        let lines = content.lines().map(str::to_owned).collect();
+
+        Ok(File {
+            root,
+            lines,
+            reserialize: false,
+        })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        if self.reserialize {
+            serde_yaml::to_writer(writer, &self.root)?;
+        } else {
+            for line in &self.lines {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set(&mut self, section: &str, name: &str, value: frontend::Value) -> Result<()> {
+        let path = section_paths(&self.root)
+            .into_iter()
+            .find(|path| section_name(path) == section)
+            .ok_or_eyre("section not found")?;
+
+        let mut mapping = &mut self.root;
+        for key in &path {
+            mapping = mapping
+                .get_mut(key.as_str())
+                .and_then(Value::as_mapping_mut)
+                .ok_or_eyre("section not found")?;
+        }
+
+        let entry = mapping.get_mut(name).ok_or_eyre("entry not found")?;
+        *entry = to_yaml(value)?;
+
+        if !self.reserialize {
+            let keys = path.iter().map(String::as_str).chain([name]);
+            let replaced = find_line(&self.lines, keys).and_then(|index| {
+                replace_value(&self.lines[index], entry).map(|line| (index, line))
+            });
+
+            match replaced {
+                Some((index, line)) => self.lines[index] = line,
+                None => self.reserialize = true,
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_frontend(&self) -> Result<frontend::FileData> {
+        let sections = section_paths(&self.root)
+            .into_iter()
+            .filter_map(|path| {
+                let mapping = path.iter().try_fold(&self.root, |mapping, key| {
+                    mapping.get(key.as_str()).and_then(Value::as_mapping)
+                })?;
+
+                let entries = mapping
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        Some(frontend::Entry {
+                            name: key.as_str()?.to_owned(),
+                            value: to_frontend(value)?,
+                            description: None,
+                            default: None,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                (!entries.is_empty()).then(|| frontend::Section {
+                    name: section_name(&path),
+                    entries,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if sections.is_empty() {
+            bail!("file has no editable entries");
+        }
+
+        Ok(frontend::FileData {
+            metadata: None,
+            sections,
+        })
+    }
+}
+
+/// The key paths of every mapping in the file, including the root, in order.
+fn section_paths(root: &Mapping) -> Vec<Vec<String>> {
+    fn visit(mapping: &Mapping, path: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+        paths.push(path.clone());
+
+        for (key, value) in mapping {
+            let (Some(key), Some(value)) = (key.as_str(), value.as_mapping()) else {
+                continue;
+            };
+
+            path.push(key.to_owned());
+            visit(value, path, paths);
+            path.pop();
+        }
+    }
+
+    let mut paths = Vec::new();
+    visit(root, &mut Vec::new(), &mut paths);
+    paths
+}
+
+/// Finds the line that defines the entry at the given key path,
+/// by following the indentation of block mappings.
+fn find_line<'a>(lines: &[String], keys: impl IntoIterator<Item = &'a str>) -> Option<usize> {
+    let mut start = 0;
+    let mut parent_indent = None;
+    let mut found = None;
+
+    for key in keys {
+        let mut block_indent = None;
+        found = None;
+
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+                continue;
+            }
+
+            let indent = line.len() - trimmed.len();
+
+            if parent_indent.is_some_and(|parent| indent <= parent) {
+                // left the parent mapping
+                break;
+            }
+
+            // the first line of a block sets the indentation of its keys
+            if indent != *block_indent.get_or_insert(indent) {
+                continue;
+            }
+
+            if line_key(trimmed) == Some(key) {
+                found = Some(index);
+                start = index + 1;
+                parent_indent = Some(indent);
+                break;
+            }
+        }
+
+        found?;
+    }
+
+    found
+}
+
+fn line_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim().trim_matches(['"', '\'']))
+}
+
+/// Replaces the value of a `key: value` line, keeping a trailing comment.
+///
+/// Returns `None` if the value doesn't fit on the line, like block scalars.
+fn replace_value(line: &str, value: &Value) -> Option<String> {
+    let colon = line.find(':')?;
+    let (prefix, rest) = line.split_at(colon + 1);
+    let old = rest.trim_start();
+
+    if old.is_empty() || old.starts_with(['|', '>', '{', '[', '&', '*', '!']) {
+        return None;
+    }
+
+    let comment = match old.starts_with(['"', '\'']) {
+        true => "",
+        false => rest.find(" #").map_or("", |index| &rest[index..]),
+    };
+
+    let value = serde_yaml::to_string(value).ok()?;
+    let value = value.trim_end();
+
+    if value.contains('\n') {
+        return None;
+    }
+
+    Some(format!("{} {}{}", prefix, value, comment))
+}
+
+fn section_name(path: &[String]) -> String {
+    match path.is_empty() {
+        true => "Default".into(),
+        false => path.join("."),
+    }
+}
+
+fn to_frontend(value: &Value) -> Option<frontend::Value> {
+    match value {
+        Value::Bool(bool) => Some(frontend::Value::Bool(*bool)),
+        Value::String(str) => Some(frontend::Value::String(str.to_owned())),
+        Value::Number(number) => {
+            if let Some(num) = number.as_i64() {
+                return Some(frontend::Value::Int(Num {
+                    value: i32::try_from(num).ok()?,
+                    range: None,
+                }));
+            }
+
+            number.as_f64().map(|num| {
+                frontend::Value::Float(Num {
+                    value: num as f32,
+                    range: None,
+                })
+            })
+        }
+        _ => None,
+    }
+}
+
+fn to_yaml(value: frontend::Value) -> Result<Value> {
+    Ok(match value {
+        frontend::Value::Bool(bool) => Value::Bool(bool),
+        frontend::Value::String(str) => Value::String(str),
+        frontend::Value::Int(num) => Value::Number(num.value.into()),
+        frontend::Value::Float(num) => Value::Number(Number::from(num.value as f64)),
+        _ => bail!("unsupported config value"),
+    })
+}
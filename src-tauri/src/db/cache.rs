@@ -64,4 +64,36 @@ impl Db {
 
         Ok(())
     }
+
+    /// Gets a community's cached categories, if they're younger than `max_age`.
+    pub fn get_cached_categories(
+        &self,
+        community: &str,
+        max_age: Option<Duration>,
+    ) -> Result<Option<String>> {
+        let conn = self.conn();
+
+        // matches everything if there's no max age
+        let cutoff = max_age
+            .map(|max_age| (Utc::now() - max_age).naive_utc().to_string())
+            .unwrap_or_default();
+
+        let content = conn
+            .prepare(
+                "SELECT content FROM category_cache WHERE community = $1 AND created_at >= $2",
+            )?
+            .query_row(params![community, cutoff], |row| row.get(0))
+            .optional()?;
+
+        Ok(content)
+    }
+
+    pub fn insert_cached_categories(&self, community: &str, content: &str) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR REPLACE INTO category_cache (community, content) VALUES ($1, $2)")?
+            .execute(params![community, content])?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use eyre::Context;
+use tauri::{command, AppHandle};
+
+use super::transfer;
+use crate::{state::ManagerExt, util, util::cmd::Result};
+
+/// Exports the database and every game's profiles to a single file, optionally
+/// with the download cache, so it can be restored on another computer.
+#[command]
+pub async fn export_state(path: PathBuf, include_cache: bool, app: AppHandle) -> Result<()> {
+    let (data_dir, dirs) = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        let mut dirs = manager
+            .games
+            .values()
+            .map(|game| game.path.clone())
+            .filter(|path| path.exists())
+            .collect::<Vec<_>>();
+
+        if include_cache && prefs.cache_dir().exists() {
+            dirs.push(prefs.cache_dir());
+        }
+
+        (prefs.data_dir.to_path_buf(), dirs)
+    };
+
+    util::cancel::reset(&app);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        transfer::export(&path, &data_dir, &dirs, handle.db(), &handle)
+    })
+    .await?
+    .context("failed to export state")?;
+
+    Ok(())
+}
+
+/// Replaces the current state with an exported one, then restarts the app to load it.
+#[command]
+pub async fn restore_state(path: PathBuf, app: AppHandle) -> Result<()> {
+    let data_dir = app.lock_prefs().data_dir.to_path_buf();
+
+    util::cancel::reset(&app);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || transfer::restore(&path, &data_dir, &handle))
+        .await?
+        .context("failed to restore state")?;
+
+    app.restart();
+}
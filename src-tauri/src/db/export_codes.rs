@@ -0,0 +1,57 @@
+use chrono::DateTime;
+use eyre::Result;
+use rusqlite::params;
+use uuid::Uuid;
+
+use super::Db;
+use crate::profile::export::codes::ExportCodeRecord;
+
+impl Db {
+    pub fn insert_export_code(&self, record: &ExportCodeRecord) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT OR REPLACE INTO export_codes (code, profile_id, created_at, fingerprint)
+                VALUES ($1, $2, $3, $4)",
+        )?
+        .execute(params![
+            record.code,
+            record.profile_id,
+            record.created_at.timestamp(),
+            record.fingerprint
+        ])?;
+
+        Ok(())
+    }
+
+    /// Gets the export codes of a profile, newest first.
+    pub fn get_export_codes(&self, profile_id: i64) -> Result<Vec<ExportCodeRecord>> {
+        let conn = self.conn();
+
+        let records = conn
+            .prepare(
+                "SELECT code, profile_id, created_at, fingerprint FROM export_codes
+                    WHERE profile_id = $1 ORDER BY created_at DESC",
+            )?
+            .query_map(params![profile_id], |row| {
+                Ok(ExportCodeRecord {
+                    code: row.get(0)?,
+                    profile_id: row.get(1)?,
+                    created_at: DateTime::from_timestamp(row.get(2)?, 0).unwrap_or_default(),
+                    fingerprint: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(records)
+    }
+
+    pub fn delete_export_code(&self, code: Uuid) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("DELETE FROM export_codes WHERE code = ?")?
+            .execute([code])?;
+
+        Ok(())
+    }
+}
@@ -106,6 +106,9 @@ fn read_manager_data(prefs: &Prefs) -> Result<SaveData> {
                 modpack: profile_data.modpack.map(Into::into),
                 ignored_updates: Some(profile_data.ignored_updates),
                 sync_data: None,
+                runtime_data: None,
+                group: None,
+                launch_overrides: None,
             });
 
             if data.active_profile_index == index {
@@ -118,6 +121,8 @@ fn read_manager_data(prefs: &Prefs) -> Result<SaveData> {
             slug: game.slug.to_string(),
             favorite: data.favorite,
             active_profile_id,
+            tools: None,
+            profile_groups: None,
         });
     }
 
@@ -157,6 +162,7 @@ impl From<legacy::GamePrefs> for GamePrefs {
             custom_args: legacy.custom_args,
             launch_mode: legacy.launch_mode.into(),
             platform: legacy.platform.map(Into::into),
+            ..Default::default()
         }
     }
 }
@@ -193,6 +199,10 @@ impl From<legacy::ProfileMod> for ProfileMod {
         ProfileMod {
             enabled: legacy.enabled,
             install_time: legacy.install_time,
+            tags: Vec::new(),
+            note: None,
+            pinned: false,
+            files: Vec::new(),
             kind: legacy.kind.into(),
         }
     }
@@ -236,10 +246,13 @@ impl From<legacy::LocalMod> for LocalMod {
             icon: legacy.icon,
             author: legacy.author,
             description: legacy.description,
+            website_url: None,
             version: legacy.version,
             dependencies: legacy.dependencies,
             uuid: legacy.uuid,
             file_size: legacy.file_size,
+            source: None,
+            latest_update: None,
         }
     }
 }
@@ -259,6 +272,7 @@ impl From<legacy::ModpackArgs> for ModpackArgs {
             website_url: legacy.website_url,
             include_disabled: legacy.include_disabled,
             include_files: legacy.include_files,
+            additional_communities: Default::default(),
         }
     }
 }
@@ -19,7 +19,16 @@ use crate::{
 };
 
 pub mod cache;
+pub mod commands;
+mod export_codes;
 mod migrate;
+mod playtime;
+mod recovery;
+mod stats;
+mod templates;
+pub mod transfer;
+
+pub use recovery::{load, LoadedData, Recovery, BACKUP_FILE_NAME};
 
 pub const FILE_NAME: &str = "data.sqlite3";
 pub const SHM_FILE_NAME: &str = "data.sqlite3-shm";
@@ -100,6 +109,8 @@ pub struct ManagedGameData {
     pub slug: String,
     pub favorite: bool,
     pub active_profile_id: i64,
+    pub tools: Option<Vec<profile::install::tools::Tool>>,
+    pub profile_groups: Option<Vec<String>>,
 }
 
 pub struct ProfileData {
@@ -111,6 +122,9 @@ pub struct ProfileData {
     pub modpack: Option<profile::export::modpack::ModpackArgs>,
     pub ignored_updates: Option<HashSet<Uuid>>,
     pub sync_data: Option<profile::sync::SyncProfileData>,
+    pub runtime_data: Option<profile::RuntimeDataOverrides>,
+    pub group: Option<String>,
+    pub launch_overrides: Option<profile::launch::LaunchOverrides>,
 }
 
 pub struct SaveData {
@@ -212,20 +226,22 @@ impl Db {
             });
 
         let games = conn
-            .prepare("SELECT id, slug, favorite, active_profile_id FROM managed_games")?
+            .prepare("SELECT id, slug, favorite, active_profile_id, tools, profile_groups FROM managed_games")?
             .query_map((), |row| {
                 Ok(ManagedGameData {
                     id: row.get(0)?,
                     slug: row.get(1)?,
                     favorite: row.get(2)?,
                     active_profile_id: row.get(3)?,
+                    tools: map_json_option_row(row, 4)?,
+                    profile_groups: map_json_option_row(row, 5)?,
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
 
         let mut profiles = conn
             .prepare(
-                "SELECT id, name, path, game_slug, mods, modpack, ignored_updates, sync_data FROM profiles",
+                "SELECT id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, runtime_data, profile_group, launch_overrides FROM profiles",
             )?
             .query_map((), |row| {
                 Ok(ProfileData {
@@ -236,7 +252,10 @@ impl Db {
                     mods: map_json_row(row, 4)?,
                     modpack: map_json_option_row(row, 5)?,
                     ignored_updates: map_json_option_row(row, 6)?,
-                    sync_data: map_json_option_row(row, 7)?
+                    sync_data: map_json_option_row(row, 7)?,
+                    runtime_data: map_json_option_row(row, 8)?,
+                    group: row.get(9)?,
+                    launch_overrides: map_json_option_row(row, 10)?
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -272,6 +291,13 @@ impl Db {
             tx.prepare("DELETE FROM profiles WHERE id = ?")?
                 .execute([id])?;
 
+            tx.prepare("DELETE FROM export_codes WHERE profile_id = ?")?
+                .execute([id])?;
+
+            // profile ids are reused, so a new profile would inherit these
+            tx.prepare("DELETE FROM play_sessions WHERE profile_id = ?")?
+                .execute([id])?;
+
             Ok(())
         })
     }
@@ -310,16 +336,21 @@ impl Db {
         games: impl Iterator<Item = &'a ManagedGame>,
     ) -> Result<()> {
         let mut stmt = tx.prepare(
-            "INSERT OR REPLACE INTO managed_games (id, slug, favorite, active_profile_id)
-                VALUES (?, ?, ?, ?)",
+            "INSERT OR REPLACE INTO managed_games (id, slug, favorite, active_profile_id, tools, profile_groups)
+                VALUES (?, ?, ?, ?, ?, ?)",
         )?;
 
         for game in games {
+            let tools = serde_json::to_string(&game.tools)?;
+            let profile_groups = serde_json::to_string(&game.profile_groups)?;
+
             stmt.execute(params![
                 game.id,
                 game.game.slug,
                 game.favorite,
-                game.active_profile_id
+                game.active_profile_id,
+                tools,
+                profile_groups
             ])?;
         }
 
@@ -337,8 +368,8 @@ impl Db {
     ) -> Result<()> {
         let mut stmt = tx.prepare(
             "INSERT OR REPLACE INTO profiles 
-                (id, name, path, game_slug, mods, modpack, ignored_updates, sync_data) 
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                (id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, runtime_data, profile_group, launch_overrides) 
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )?;
 
         for profile in profiles {
@@ -354,6 +385,8 @@ impl Db {
                 .as_ref()
                 .map(serde_json::to_string)
                 .transpose()?;
+            let runtime_data = serde_json::to_string(&profile.runtime_data)?;
+            let launch_overrides = serde_json::to_string(&profile.launch_overrides)?;
 
             stmt.execute(params![
                 profile.id,
@@ -363,7 +396,10 @@ impl Db {
                 mods,
                 modpack,
                 ignored_updates,
-                sync_data
+                sync_data,
+                runtime_data,
+                profile.group,
+                launch_overrides
             ])?;
         }
 
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use rusqlite::params;
+
+use super::Db;
+use crate::profile::launch::playtime::PlaytimeTotals;
+
+impl Db {
+    pub fn insert_play_session(
+        &self,
+        profile_id: i64,
+        game_slug: &str,
+        started_at: DateTime<Utc>,
+        duration_secs: u64,
+    ) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT INTO play_sessions (profile_id, game_slug, started_at, duration_secs)
+                VALUES ($1, $2, $3, $4)",
+        )?
+        .execute(params![
+            profile_id,
+            game_slug,
+            started_at.timestamp(),
+            duration_secs
+        ])?;
+
+        Ok(())
+    }
+
+    /// Sums up the recorded sessions of each profile, counting those started
+    /// after `recent_since` as recent.
+    pub fn get_playtime_totals(&self, recent_since: DateTime<Utc>) -> Result<Vec<PlaytimeTotals>> {
+        let conn = self.conn();
+
+        let totals = conn
+            .prepare(
+                "SELECT
+                    game_slug,
+                    profile_id,
+                    SUM(duration_secs),
+                    SUM(CASE WHEN started_at >= $1 THEN duration_secs ELSE 0 END),
+                    COUNT(*),
+                    MAX(started_at)
+                FROM play_sessions
+                GROUP BY game_slug, profile_id",
+            )?
+            .query_map(params![recent_since.timestamp()], |row| {
+                Ok(PlaytimeTotals {
+                    game_slug: row.get(0)?,
+                    profile_id: row.get(1)?,
+                    total_secs: row.get(2)?,
+                    recent_secs: row.get(3)?,
+                    sessions: row.get(4)?,
+                    last_played: DateTime::from_timestamp(row.get(5)?, 0).unwrap_or_default(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(totals)
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use eyre::{Context, Result};
+use rusqlite::ErrorCode;
+use serde::Serialize;
+use tracing::{error, info};
+
+use super::{Db, SaveData, FILE_NAME, SHM_FILE_NAME, WAL_FILE_NAME};
+use crate::{prefs::Prefs, profile::sync::auth::AuthState, util};
+
+pub const BACKUP_FILE_NAME: &str = "data.sqlite3.bak";
+const BACKUP_TEMP_FILE_NAME: &str = "data.sqlite3.bak.tmp";
+const CORRUPT_EXTENSION: &str = "corrupt";
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryKind {
+    /// The database was replaced with the backup from the last successful launch.
+    RestoredBackup,
+    /// No usable backup was found, so the app started with empty data.
+    Reset,
+}
+
+/// Describes how the app recovered from save data it failed to read on startup.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Recovery {
+    pub kind: RecoveryKind,
+    pub error: String,
+    /// Where the unreadable database was moved to.
+    pub corrupt_path: PathBuf,
+}
+
+pub struct LoadedData {
+    pub db: Db,
+    pub existed: bool,
+    pub data: SaveData,
+    pub prefs: Prefs,
+    pub auth: Option<AuthState>,
+    pub migrated: bool,
+    pub recovery: Option<Recovery>,
+}
+
+/// Opens and reads the database, falling back to the last backup
+/// or an empty database if it is corrupted.
+///
+/// Other errors, like the database being locked by another process, are returned as is.
+pub fn load() -> Result<LoadedData> {
+    let dir = util::path::default_app_data_dir();
+
+    if let Err(err) = super::transfer::apply_pending_restore(&dir) {
+        error!("failed to apply restored state: {:?}", err);
+    }
+
+    let err = match try_load() {
+        Ok(loaded) => return Ok(loaded),
+        Err(err) if is_corrupt(&err) => err,
+        Err(err) => return Err(err),
+    };
+
+    error!("failed to read database, attempting recovery: {:?}", err);
+
+    let corrupt_path = set_aside(&dir, CORRUPT_EXTENSION).context("failed to move database")?;
+
+    let backup_path = dir.join(BACKUP_FILE_NAME);
+    if backup_path.exists() {
+        fs::copy(&backup_path, dir.join(FILE_NAME)).context("failed to copy backup")?;
+
+        match try_load() {
+            Ok(loaded) => {
+                info!("restored database from backup");
+
+                return Ok(loaded.with_recovery(RecoveryKind::RestoredBackup, &err, corrupt_path));
+            }
+            Err(backup_err) => {
+                error!("failed to read database backup: {:?}", backup_err);
+
+                remove_db_files(&dir).context("failed to remove restored backup")?;
+            }
+        }
+    }
+
+    info!("creating new database");
+
+    let loaded = try_load().context("failed to create new database")?;
+    Ok(loaded.with_recovery(RecoveryKind::Reset, &err, corrupt_path))
+}
+
+fn try_load() -> Result<LoadedData> {
+    let (db, existed) = super::init().context("failed to init database")?;
+    let (data, prefs, auth, migrated) = db.read().context("failed to read database")?;
+
+    Ok(LoadedData {
+        db,
+        existed,
+        data,
+        prefs,
+        auth,
+        migrated,
+        recovery: None,
+    })
+}
+
+/// Whether `err` was caused by the database file being corrupted or not a database at all.
+fn is_corrupt(err: &eyre::Report) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(err, _))
+                if matches!(err.code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+        )
+    })
+}
+
+impl LoadedData {
+    fn with_recovery(
+        mut self,
+        kind: RecoveryKind,
+        err: &eyre::Report,
+        corrupt_path: PathBuf,
+    ) -> Self {
+        self.recovery = Some(Recovery {
+            kind,
+            error: format!("{:#}", err),
+            corrupt_path,
+        });
+        self
+    }
+}
+
+/// Renames the database and its journal files by appending `extension` and the current time,
+/// so files set aside earlier are kept. Returns the new database path.
+pub(super) fn set_aside(dir: &Path, extension: &str) -> Result<PathBuf> {
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let new_path = |name: &str| dir.join(format!("{}.{}-{}", name, extension, timestamp));
+
+    for name in [FILE_NAME, SHM_FILE_NAME, WAL_FILE_NAME] {
+        let path = dir.join(name);
+
+        if path.exists() {
+            fs::rename(&path, new_path(name))
+                .with_context(|| format!("failed to move {}", name))?;
+        }
+    }
+
+    Ok(new_path(FILE_NAME))
+}
+
+fn remove_db_files(dir: &Path) -> Result<()> {
+    for name in [FILE_NAME, SHM_FILE_NAME, WAL_FILE_NAME] {
+        let path = dir.join(name);
+
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("failed to remove {}", name))?;
+        }
+    }
+
+    Ok(())
+}
+
+impl Db {
+    /// Writes a compacted copy of the database to [`BACKUP_FILE_NAME`],
+    /// which is restored if the database can't be read on a later launch.
+    pub fn backup(&self) -> Result<()> {
+        let dir = util::path::default_app_data_dir();
+        let temp_path = dir.join(BACKUP_TEMP_FILE_NAME);
+
+        if temp_path.exists() {
+            fs::remove_file(&temp_path).context("failed to remove old temporary backup")?;
+        }
+
+        self.write_copy(&temp_path)
+            .context("failed to write backup")?;
+
+        fs::rename(&temp_path, dir.join(BACKUP_FILE_NAME)).context("failed to replace backup")?;
+
+        Ok(())
+    }
+
+    /// Writes a compacted copy of the database to `path`, which must not exist yet.
+    pub fn write_copy(&self, path: &Path) -> Result<()> {
+        self.conn()
+            .execute("VACUUM INTO ?", [path.to_string_lossy()])?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use chrono::DateTime;
+use eyre::Result;
+use rusqlite::params;
+
+use super::Db;
+use crate::profile::export::stats::StatsPoint;
+
+impl Db {
+    pub fn insert_modpack_stats(&self, package: &str, point: &StatsPoint) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT OR REPLACE INTO modpack_stats (package, recorded_at, downloads, rating_score)
+                VALUES ($1, $2, $3, $4)",
+        )?
+        .execute(params![
+            package,
+            point.recorded_at.timestamp(),
+            point.downloads,
+            point.rating_score
+        ])?;
+
+        Ok(())
+    }
+
+    /// Gets the recorded stats of a package, oldest first.
+    pub fn get_modpack_stats(&self, package: &str) -> Result<Vec<StatsPoint>> {
+        let conn = self.conn();
+
+        let points = conn
+            .prepare(
+                "SELECT recorded_at, downloads, rating_score FROM modpack_stats
+                    WHERE package = $1 ORDER BY recorded_at",
+            )?
+            .query_map(params![package], |row| {
+                Ok(StatsPoint {
+                    recorded_at: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                    downloads: row.get(1)?,
+                    rating_score: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(points)
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::DateTime;
+use eyre::{OptionExt, Result};
+use rusqlite::{params, OptionalExtension};
+
+use super::Db;
+use crate::profile::template::ProfileTemplate;
+
+impl Db {
+    /// Saves a new template and returns its id.
+    pub fn insert_template(
+        &self,
+        template: &ProfileTemplate,
+        game_slug: &str,
+        data: &[u8],
+    ) -> Result<i64> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT INTO profile_templates (name, game_slug, include_config, mod_count, created_at, data)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+        )?
+        .execute(params![
+            template.name,
+            game_slug,
+            template.include_config,
+            template.thunderstore_mod_count,
+            template.created_at.timestamp(),
+            data
+        ])?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Gets the templates of a game, without their data, sorted by name.
+    pub fn get_templates(&self, game_slug: &str) -> Result<Vec<ProfileTemplate>> {
+        let conn = self.conn();
+
+        let templates = conn
+            .prepare(
+                "SELECT id, name, include_config, mod_count, created_at FROM profile_templates
+                    WHERE game_slug = $1 ORDER BY name",
+            )?
+            .query_map(params![game_slug], |row| {
+                Ok(ProfileTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    include_config: row.get(2)?,
+                    thunderstore_mod_count: row.get(3)?,
+                    created_at: DateTime::from_timestamp(row.get(4)?, 0).unwrap_or_default(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(templates)
+    }
+
+    /// Gets the exported profile a template was saved as.
+    pub fn get_template_data(&self, id: i64) -> Result<Vec<u8>> {
+        let conn = self.conn();
+
+        let data = conn
+            .prepare("SELECT data FROM profile_templates WHERE id = ?")?
+            .query_row([id], |row| row.get(0))
+            .optional()?;
+
+        data.ok_or_eyre("template not found")
+    }
+
+    pub fn delete_template(&self, id: i64) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("DELETE FROM profile_templates WHERE id = ?")?
+            .execute([id])?;
+
+        Ok(())
+    }
+}
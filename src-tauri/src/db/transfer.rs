@@ -0,0 +1,369 @@
+//! Exporting and restoring the entire app state, to move it to another computer.
+//!
+//! The archive holds a copy of the database, which includes the prefs, the data
+//! directories of every managed game with their profiles, and optionally the
+//! download cache. Credentials are left out, since the file is likely to be shared.
+//!
+//! The database can't be replaced while it's open, so a restore only stages it
+//! next to the current one, and the data directories in the data directory.
+//! Both are put in place together on the next startup.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use eyre::{ensure, Context, OptionExt, Result};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use super::{recovery, Db, FILE_NAME};
+use crate::util::{self, error::IoResultExt};
+
+/// Where the restored database is staged until the next startup.
+pub const RESTORE_FILE_NAME: &str = "data.sqlite3.restore";
+
+/// Where the restored data directories are staged until the next startup,
+/// inside of the data directory.
+const RESTORE_DIR_NAME: &str = ".restore";
+
+/// What the replaced database is renamed to once a restore is applied.
+const REPLACED_EXTENSION: &str = "replaced";
+
+const MANIFEST_NAME: &str = "gale-state.json";
+const DB_ENTRY_NAME: &str = "data.sqlite3";
+/// The prefix of the data directory entries in the archive.
+const DATA_PREFIX: &str = "data/";
+
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+    created_at: DateTime<Utc>,
+}
+
+/// Writes the database and `dirs`, which must be inside of `data_dir`, to a zip at `dest`.
+pub fn export(
+    dest: &Path,
+    data_dir: &Path,
+    dirs: &[PathBuf],
+    db: &Db,
+    app: &AppHandle,
+) -> Result<()> {
+    info!("exporting app state to {}", dest.display());
+
+    let mut temp_file = util::fs::temp_file_for(dest)?;
+    let mut zip = ZipWriter::new(BufWriter::new(&mut temp_file));
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_owned(),
+        created_at: Utc::now(),
+    };
+
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())?;
+    serde_json::to_writer_pretty(&mut zip, &manifest).context("failed to write manifest")?;
+
+    let db_dir = util::fs::temp_dir_in(data_dir)?;
+    let db_path = db_dir.path().join(FILE_NAME);
+    db.write_copy(&db_path)?;
+    strip_secrets(&db_path).context("failed to remove credentials from database copy")?;
+
+    zip.start_file(DB_ENTRY_NAME, SimpleFileOptions::default())?;
+    io::copy(
+        &mut File::open(&db_path).fs_context("opening database copy", &db_path)?,
+        &mut zip,
+    )?;
+
+    let mut count = 0;
+
+    for dir in dirs {
+        for entry in WalkDir::new(dir) {
+            util::cancel::check(app)?;
+
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative_path = entry
+                .path()
+                .strip_prefix(data_dir)
+                .context("directory is outside of the data directory")?;
+
+            let name = format!(
+                "{}{}",
+                DATA_PREFIX,
+                relative_path.to_string_lossy().replace('\\', "/")
+            );
+
+            zip.start_file(name, SimpleFileOptions::default())?;
+            io::copy(
+                &mut File::open(entry.path()).fs_context("opening file", entry.path())?,
+                &mut zip,
+            )?;
+
+            count += 1;
+        }
+    }
+
+    zip.finish()?.flush()?;
+    temp_file
+        .persist(dest)
+        .context("failed to save exported file")?;
+
+    info!("exported database and {} files", count);
+
+    Ok(())
+}
+
+/// Removes the sync login and the credentials that older prefs still hold
+/// from a copy of the database.
+fn strip_secrets(db_path: &Path) -> Result<()> {
+    let conn = rusqlite::Connection::open(db_path)?;
+
+    conn.execute("DELETE FROM auth", ())?;
+
+    let prefs = conn
+        .query_row("SELECT data FROM prefs", (), |row| row.get::<_, String>(0))
+        .ok();
+
+    if let Some(prefs) = prefs {
+        let mut prefs: serde_json::Value = serde_json::from_str(&prefs)?;
+        if let Some(prefs) = prefs.as_object_mut() {
+            prefs.remove("nexusApiKey");
+            prefs.remove("githubToken");
+        }
+
+        conn.execute("UPDATE prefs SET data = ?", [prefs.to_string()])?;
+    }
+
+    // otherwise the deleted data stays in the file's free pages
+    conn.execute("VACUUM", ())?;
+
+    Ok(())
+}
+
+/// Extracts the data directories of an exported state and stages them along
+/// with its database, to replace the current state once the app is restarted.
+///
+/// The top-level directories in the archive, like the game directories, replace
+/// the existing ones as a whole, so no stray files are left in the profiles.
+/// Nothing in use is touched until [`apply_pending_restore`].
+pub fn restore(src: &Path, data_dir: &Path, app: &AppHandle) -> Result<()> {
+    info!("restoring app state from {}", src.display());
+
+    let file = File::open(src).fs_context("opening file", src)?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).context("failed to read archive")?;
+
+    let manifest: Manifest = serde_json::from_reader(
+        archive
+            .by_name(MANIFEST_NAME)
+            .ok()
+            .ok_or_eyre("file is not an exported Gale state")?,
+    )
+    .context("failed to read manifest")?;
+
+    ensure!(
+        manifest.format_version <= FORMAT_VERSION,
+        "the state was exported from a newer version of Gale ({}), please update first",
+        manifest.app_version
+    );
+
+    let temp_dir = util::fs::temp_dir_in(data_dir)?;
+
+    for i in 0..archive.len() {
+        util::cancel::check(app)?;
+
+        let mut file = archive.by_index(i)?;
+
+        let Some(name) = file.name().strip_prefix(DATA_PREFIX) else {
+            continue;
+        };
+
+        let relative_path = PathBuf::from(name);
+        if file.is_dir() || !util::fs::is_enclosed(&relative_path) {
+            continue;
+        }
+
+        let path = temp_dir.path().join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).fs_context("creating directory", &path)?;
+        io::copy(
+            &mut file,
+            &mut File::create(&path).fs_context("creating file", &path)?,
+        )?;
+    }
+
+    let db_dir = util::path::default_app_data_dir();
+    let db_temp_dir = util::fs::temp_dir_in(&db_dir)?;
+    let db_path = db_temp_dir.path().join(FILE_NAME);
+
+    io::copy(
+        &mut archive
+            .by_name(DB_ENTRY_NAME)
+            .ok()
+            .ok_or_eyre("archive is missing the database")?,
+        &mut File::create(&db_path).fs_context("creating database", &db_path)?,
+    )?;
+
+    relocate(&db_path, data_dir).context("failed to update profile locations")?;
+
+    util::fs::persist_dir(temp_dir, &data_dir.join(RESTORE_DIR_NAME))
+        .context("failed to stage data directories")?;
+
+    // the staged database marks the restore as complete, so it goes last
+    fs::rename(&db_path, db_dir.join(RESTORE_FILE_NAME)).context("failed to stage database")?;
+
+    info!(
+        "restored state exported at {} by version {}, restart to apply",
+        manifest.created_at, manifest.app_version
+    );
+
+    Ok(())
+}
+
+/// Points the profiles and data directory in the database at `data_dir`, since
+/// the state was likely exported from somewhere else.
+fn relocate(db_path: &Path, data_dir: &Path) -> Result<()> {
+    let conn = rusqlite::Connection::open(db_path)?;
+
+    let profiles = conn
+        .prepare("SELECT id, name, game_slug FROM profiles")?
+        .query_map((), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (id, name, game_slug) in profiles {
+        let path = data_dir.join(game_slug).join("profiles").join(name);
+
+        conn.execute(
+            "UPDATE profiles SET path = ? WHERE id = ?",
+            params![path.to_string_lossy(), id],
+        )?;
+    }
+
+    let prefs = conn
+        .query_row("SELECT data FROM prefs", (), |row| row.get::<_, String>(0))
+        .ok();
+
+    if let Some(prefs) = prefs {
+        let mut prefs: serde_json::Value = serde_json::from_str(&prefs)?;
+        prefs["dataDir"] = data_dir.to_string_lossy().into();
+
+        conn.execute("UPDATE prefs SET data = ?", [prefs.to_string()])?;
+    }
+
+    Ok(())
+}
+
+/// Puts the database and data directories staged by [`restore`] in place,
+/// keeping the old database next to the new one.
+///
+/// The data directories are replaced first, and put back if that fails, in
+/// which case the restore is left staged and the current state is kept.
+pub(super) fn apply_pending_restore(dir: &Path) -> Result<()> {
+    let staged_path = dir.join(RESTORE_FILE_NAME);
+    if !staged_path.exists() {
+        return Ok(());
+    }
+
+    info!("applying restored state");
+
+    let data_dir = staged_data_dir(&staged_path)?.unwrap_or_else(|| dir.to_path_buf());
+    let staged_dirs = data_dir.join(RESTORE_DIR_NAME);
+
+    if staged_dirs.exists() {
+        replace_dirs(&staged_dirs, &data_dir).context("failed to move restored directories")?;
+    }
+
+    let replaced_path = recovery::set_aside(dir, REPLACED_EXTENSION)?;
+    fs::rename(&staged_path, dir.join(FILE_NAME)).context("failed to move restored database")?;
+
+    warn!(
+        "replaced the previous database, which was moved to {}",
+        replaced_path.display()
+    );
+
+    Ok(())
+}
+
+/// Reads the data directory from the prefs of a staged database, which
+/// [`relocate`] pointed at where the restore was staged.
+fn staged_data_dir(db_path: &Path) -> Result<Option<PathBuf>> {
+    let conn = rusqlite::Connection::open(db_path)?;
+
+    let prefs = conn
+        .query_row("SELECT data FROM prefs", (), |row| row.get::<_, String>(0))
+        .ok();
+
+    let Some(prefs) = prefs else {
+        return Ok(None);
+    };
+
+    let prefs: serde_json::Value = serde_json::from_str(&prefs)?;
+    Ok(prefs["dataDir"].as_str().map(PathBuf::from))
+}
+
+/// Moves every directory in `staged` into `data_dir`, replacing the existing ones.
+///
+/// If any move fails, the directories already moved are put back.
+fn replace_dirs(staged: &Path, data_dir: &Path) -> Result<()> {
+    // deleted along with the replaced directories when dropped
+    let aside = util::fs::temp_dir_in(data_dir)?;
+    let mut moved = Vec::new();
+
+    let result = staged
+        .read_dir()
+        .fs_context("reading staged directories", staged)?
+        .try_for_each(|entry| -> Result<()> {
+            let entry = entry?;
+            let name = entry.file_name();
+            let dest = data_dir.join(&name);
+
+            info!("replacing {}", dest.display());
+
+            let existed = dest.exists();
+            if existed {
+                fs::rename(&dest, aside.path().join(&name))
+                    .fs_context("moving directory", &dest)?;
+            }
+
+            moved.push((name, existed));
+            fs::rename(entry.path(), &dest).fs_context("moving directory", &dest)?;
+
+            Ok(())
+        });
+
+    if let Err(err) = result {
+        for (name, existed) in moved.into_iter().rev() {
+            let dest = data_dir.join(&name);
+
+            // fails if this is the one that couldn't be moved in
+            fs::rename(&dest, staged.join(&name)).ok();
+
+            if existed {
+                fs::rename(aside.path().join(&name), &dest).ok();
+            }
+        }
+
+        return Err(err);
+    }
+
+    fs::remove_dir_all(staged).fs_context("removing staged directories", staged)?;
+
+    Ok(())
+}
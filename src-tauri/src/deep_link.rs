@@ -6,7 +6,8 @@ use crate::{
     logger,
     profile::{self},
     state::ManagerExt,
-    thunderstore::{BorrowedMod, IntoFrontendMod, Thunderstore},
+    thunderstore::{self, BorrowedMod, IntoFrontendMod, Thunderstore},
+    util,
 };
 
 pub fn handle(app: &AppHandle, args: Vec<String>) -> bool {
@@ -23,17 +24,34 @@ pub fn handle(app: &AppHandle, args: Vec<String>) -> bool {
         .ok();
 
     if url.starts_with("ror2mm://") {
-        let thunderstore = app.lock_thunderstore();
-        let borrowed_mod = match resolve_mod_url(&url, &thunderstore) {
-            Ok(to_install) => to_install,
-            Err(err) => {
-                logger::log_webview_err("Failed to install mod from link", err, app);
-                return true;
+        let handle = app.to_owned();
+        tauri::async_runtime::spawn(async move {
+            // links opened on startup arrive before the packages are loaded
+            thunderstore::wait_for_fetch(&handle).await;
+
+            let thunderstore = handle.lock_thunderstore();
+            match resolve_mod_url(&url, &thunderstore) {
+                Ok(borrowed_mod) => {
+                    let frontend_mod = borrowed_mod.into_frontend(None);
+                    handle.emit("install_mod", frontend_mod).ok();
+                }
+                Err(err) => {
+                    logger::log_webview_err("Failed to install mod from link", err, &handle);
+                }
             }
-        };
+        });
+
+        true
+    } else if url.starts_with("nxm://") {
+        let handle = app.to_owned();
+        tauri::async_runtime::spawn(async move {
+            util::cancel::reset(&handle);
+
+            if let Err(err) = profile::install::nexus::install_from_link(&url, &handle).await {
+                logger::log_webview_err("Failed to install mod from Nexus Mods", err, &handle);
+            }
+        });
 
-        let frontend_mod = borrowed_mod.into_frontend(None);
-        app.emit("install_mod", frontend_mod).ok();
         true
     } else if url.starts_with("gale://auth/callback") {
         let handle = app.to_owned();
@@ -45,7 +63,7 @@ pub fn handle(app: &AppHandle, args: Vec<String>) -> bool {
 
         true
     } else if url.ends_with("r2z") {
-        let import_data = match profile::import::read_file_at_path(url.into()) {
+        let import_data = match profile::import::read_file_at_path(url.into(), app) {
             Ok(data) => data,
             Err(err) => {
                 logger::log_webview_err("Failed to import profile from file", err, app);
@@ -61,15 +79,26 @@ pub fn handle(app: &AppHandle, args: Vec<String>) -> bool {
     }
 }
 
+/// Resolves a link like `ror2mm://v1/install/thunderstore.io/<owner>/<name>/<version>/`.
+///
+/// If the version is left out, the latest one is used.
 fn resolve_mod_url<'a>(url: &str, thunderstore: &'a Thunderstore) -> Result<BorrowedMod<'a>> {
-    let (owner, name, version) = url
+    let mut segments = url
         .strip_prefix("ror2mm://v1/install/thunderstore.io/")
-        .and_then(|path| {
-            let mut split = path.split('/');
+        .ok_or_eyre("invalid package url")?
+        .split('/')
+        .filter(|segment| !segment.is_empty());
 
-            Some((split.next()?, split.next()?, split.next()?))
-        })
+    let (owner, name) = segments
+        .next()
+        .zip(segments.next())
         .ok_or_eyre("invalid package url")?;
 
-    thunderstore.find_mod(owner, name, version)
+    match segments.next() {
+        Some(version) => thunderstore.find_mod(owner, name, version),
+        None => {
+            let package = thunderstore.find_package(&format!("{}-{}", owner, name))?;
+            Ok((package, package.latest()).into())
+        }
+    }
 }
@@ -0,0 +1,302 @@
+use std::{
+    borrow::Cow,
+    hash::{self, Hash},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::LazyLock,
+};
+
+use eyre::Result;
+use heck::{ToKebabCase, ToPascalCase};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::profile::install::PackageInstaller;
+
+mod mod_loader;
+pub mod overrides;
+
+pub use mod_loader::{ModLoader, ModLoaderKind};
+
+const GAMES_JSON: &str = include_str!("../../games.json");
+
+static GAMES: LazyLock<Vec<GameData<'static>>> = LazyLock::new(|| {
+    let mut games: Vec<_> = serde_json::from_str(GAMES_JSON).unwrap();
+    overrides::apply(&mut games);
+    games
+});
+
+pub type Game = &'static GameData<'static>;
+
+pub fn all() -> impl Iterator<Item = Game> {
+    GAMES.iter()
+}
+
+pub fn from_slug(slug: &str) -> Option<Game> {
+    GAMES.iter().find(|game| game.slug == slug)
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonGame<'a> {
+    name: &'a str,
+    #[serde(default)]
+    slug: Option<&'a str>,
+    #[serde(default)]
+    popular: bool,
+    #[serde(default)]
+    server: bool,
+    #[serde(default, rename = "r2dirName")]
+    r2_dir_name: Option<&'a str>,
+    #[serde(default)]
+    nexus_domain: Option<&'a str>,
+    #[serde(default)]
+    save_path: Option<&'a str>,
+    #[serde(borrow)]
+    mod_loader: GameModLoader<'a>,
+    #[serde(borrow, default)]
+    platforms: Platforms<'a>,
+    #[serde(borrow, default)]
+    dedicated_server: Option<DedicatedServer<'a>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Platforms<'a> {
+    pub steam: Option<Steam>,
+    #[serde(borrow)]
+    pub epic_games: Option<EpicGames<'a>>,
+    pub oculus: Option<Oculus>,
+    pub origin: Option<Origin>,
+    #[serde(borrow)]
+    pub xbox_store: Option<XboxStore<'a>>,
+}
+
+impl Platforms<'_> {
+    pub fn has(&self, platform: Platform) -> bool {
+        match platform {
+            Platform::Steam => self.steam.is_some(),
+            Platform::EpicGames => self.epic_games.is_some(),
+            Platform::Oculus => self.oculus.is_some(),
+            Platform::Origin => self.origin.is_some(),
+            Platform::XboxStore => self.xbox_store.is_some(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Platform> + '_ {
+        Platform::iter().filter(|platform| self.has(*platform))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase", from = "JsonGame")]
+pub struct GameData<'a> {
+    pub name: &'a str,
+    pub slug: Cow<'a, str>,
+    pub r2_dir_name: Cow<'a, str>,
+    pub nexus_domain: Cow<'a, str>,
+    /// Where the game keeps its saves, relative to the Windows user directory.
+    pub save_path: Option<&'a str>,
+    pub popular: bool,
+    pub server: bool,
+    pub mod_loader: GameModLoader<'a>,
+    pub platforms: Platforms<'a>,
+    pub dedicated_server: Option<DedicatedServer<'a>>,
+}
+
+impl<'a> From<JsonGame<'a>> for GameData<'a> {
+    fn from(value: JsonGame<'a>) -> Self {
+        let JsonGame {
+            name,
+            slug,
+            popular,
+            server,
+            r2_dir_name,
+            nexus_domain,
+            save_path,
+            mod_loader,
+            platforms,
+            dedicated_server,
+        } = value;
+
+        let slug = match slug {
+            Some(slug) => Cow::Borrowed(slug),
+            None => Cow::Owned(name.to_kebab_case()),
+        };
+
+        let r2_dir_name = match r2_dir_name {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(slug.to_pascal_case()),
+        };
+
+        let nexus_domain = match nexus_domain {
+            Some(domain) => Cow::Borrowed(domain),
+            None => Cow::Owned(slug.replace('-', "")),
+        };
+
+        Self {
+            name,
+            slug,
+            r2_dir_name,
+            nexus_domain,
+            save_path,
+            popular,
+            server,
+            mod_loader,
+            platforms,
+            dedicated_server,
+        }
+    }
+}
+
+impl PartialEq for GameData<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.slug == other.slug
+    }
+}
+
+impl Eq for GameData<'_> {}
+
+impl Hash for GameData<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.slug.hash(state);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Display, EnumIter)]
+#[serde(rename_all = "camelCase")]
+pub enum Platform {
+    #[default]
+    Steam,
+    EpicGames,
+    Oculus,
+    Origin,
+    XboxStore,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Steam {
+    pub id: u32,
+    /// How the game is started through Steam, unless overridden in the prefs.
+    #[serde(default)]
+    pub launch: SteamLaunch,
+}
+
+/// How a game is started through Steam.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SteamLaunch {
+    /// Runs the Steam executable with `-applaunch`.
+    #[default]
+    Applaunch,
+    /// Opens a `steam://run` URL, which hands the launch to the running Steam
+    /// client. Needed by games with anti-tamper that refuse to start otherwise.
+    Protocol,
+}
+
+/// A dedicated server for a game, which can be run with a profile's mods.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DedicatedServer<'a> {
+    /// The Steam app of the server, if it's installed separately from the game.
+    #[serde(default)]
+    pub steam_id: Option<u32>,
+    /// The file names of the server executable in the server's directory, for
+    /// each platform it's available on. The first one that exists is used.
+    #[serde(borrow)]
+    pub exe_names: Vec<&'a str>,
+    /// Arguments that run the server without graphics.
+    #[serde(borrow, default)]
+    pub args: Vec<&'a str>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EpicGames<'a> {
+    #[serde(default)]
+    pub identifier: Option<&'a str>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Oculus {}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Origin {}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct XboxStore<'a> {
+    #[serde(default)]
+    pub identifier: Option<&'a str>,
+}
+
+/// The mod loader a game declares in `games.json`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameModLoader<'a> {
+    /// Overrides the loader's default package name on Thunderstore.
+    #[serde(default)]
+    pub package_name: Option<&'a str>,
+    /// Additional packages to treat as the loader, set by the [`overrides`].
+    #[serde(skip)]
+    pub extra_package_names: Vec<&'a str>,
+    #[serde(flatten, borrow)]
+    pub kind: ModLoaderKind<'a>,
+}
+
+impl GameModLoader<'static> {
+    pub fn loader(&'static self) -> &'static dyn ModLoader {
+        self.kind.loader()
+    }
+
+    pub fn to_str(&'static self) -> &'static str {
+        self.loader().name()
+    }
+
+    /// Checks for the mod loader's own package on Thunderstore.
+    pub fn is_loader_package(&'static self, full_name: &str) -> bool {
+        if self.extra_package_names.contains(&full_name) {
+            return true;
+        }
+
+        match self.package_name {
+            Some(package_name) => full_name == package_name,
+            None => self.loader().is_loader_package(full_name),
+        }
+    }
+
+    pub fn installer_for(&'static self, package_name: &str) -> Box<dyn PackageInstaller> {
+        match self.is_loader_package(package_name) {
+            true => self.loader().loader_installer(),
+            false => self.loader().mod_installer(),
+        }
+    }
+
+    pub fn log_path(&'static self) -> &'static str {
+        self.loader().log_path()
+    }
+
+    pub fn config_path(&'static self) -> PathBuf {
+        self.loader().config_path()
+    }
+
+    pub fn proxy_dll(&'static self) -> Option<&'static str> {
+        self.loader().proxy_dll()
+    }
+
+    pub fn add_launch_args(&'static self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        self.loader().add_launch_args(command, profile_dir)
+    }
+
+    pub fn add_vanilla_args(
+        &'static self,
+        command: &mut Command,
+        profile_dir: &Path,
+    ) -> Result<()> {
+        self.loader().add_vanilla_args(command, profile_dir)
+    }
+}
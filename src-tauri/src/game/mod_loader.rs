@@ -0,0 +1,597 @@
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::{bail, Context, OptionExt, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    profile::{
+        install::{
+            self, BepinexInstaller, ExtractInstaller, FlattenTopLevel, GDWeaveModInstaller,
+            PackageInstaller, ShimloaderInstaller, Subdir, SubdirInstaller,
+        },
+        Profile,
+    },
+    util::{
+        self,
+        error::IoResultExt,
+        fs::{JsonStyle, PathExt},
+    },
+};
+
+/// Describes how a mod loader lays out, installs and launches mods.
+///
+/// Each game declares its loader in `games.json`, which is deserialized into
+/// a [`ModLoaderKind`]. Adding support for a new loader only requires a new
+/// implementation of this trait and a variant in that enum.
+pub trait ModLoader: Debug + Send + Sync {
+    /// The display name of the loader.
+    fn name(&self) -> &'static str;
+
+    /// Checks for the mod loader's own package on Thunderstore.
+    fn is_loader_package(&self, full_name: &str) -> bool;
+
+    /// The installer used for the loader's own package.
+    ///
+    /// The installer is also responsible for toggling and uninstalling the package.
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller>;
+
+    /// The installer used for regular mods.
+    ///
+    /// The installer is also responsible for toggling and uninstalling the mods,
+    /// as well as mapping files in the package to their paths in the profile.
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller>;
+
+    /// Path to the loader's log file, relative to the profile directory.
+    ///
+    /// Empty if the loader doesn't write a log file.
+    fn log_path(&self) -> &str {
+        ""
+    }
+
+    /// Path to the loader's config directory, relative to the profile directory.
+    fn config_path(&self) -> PathBuf {
+        PathBuf::new()
+    }
+
+    /// The name of the dll the loader uses to inject itself, if any.
+    fn proxy_dll(&self) -> Option<&str> {
+        None
+    }
+
+    /// Adds the arguments needed to launch the game with the loader
+    /// pointed at `profile_dir`.
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()>;
+
+    /// Adds the arguments needed to launch the game without any mods.
+    ///
+    /// Only needed by loaders that inject themselves regardless of
+    /// [`ModLoader::add_launch_args`], like through a proxy dll.
+    fn add_vanilla_args(&self, _command: &mut Command, _profile_dir: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    /// Writes the profile's mod order in a format the loader understands.
+    ///
+    /// Called whenever the user reorders mods. Loaders without
+    /// any notion of load order can leave this as a no-op.
+    fn write_load_order(&self, _profile: &Profile) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "name")]
+pub enum ModLoaderKind<'a> {
+    BepInEx(#[serde(borrow)] BepInEx<'a>),
+    MelonLoader(#[serde(borrow)] MelonLoader<'a>),
+    Northstar(Northstar),
+    GDWeave(GDWeave),
+    Shimloader(Shimloader),
+    Lovely(Lovely),
+    ReturnOfModding(#[serde(borrow)] ReturnOfModding<'a>),
+}
+
+impl<'a> ModLoaderKind<'a> {
+    /// The extra subdirs from `games.json`, if the loader supports them.
+    pub fn extra_subdirs_mut(&mut self) -> Option<&mut Vec<Subdir<'a>>> {
+        match self {
+            ModLoaderKind::BepInEx(loader) => Some(&mut loader.extra_subdirs),
+            ModLoaderKind::MelonLoader(loader) => Some(&mut loader.extra_subdirs),
+            _ => None,
+        }
+    }
+}
+
+impl ModLoaderKind<'static> {
+    pub fn loader(&'static self) -> &'static dyn ModLoader {
+        match self {
+            ModLoaderKind::BepInEx(loader) => loader,
+            ModLoaderKind::MelonLoader(loader) => loader,
+            ModLoaderKind::Northstar(loader) => loader,
+            ModLoaderKind::GDWeave(loader) => loader,
+            ModLoaderKind::Shimloader(loader) => loader,
+            ModLoaderKind::Lovely(loader) => loader,
+            ModLoaderKind::ReturnOfModding(loader) => loader,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BepInEx<'a> {
+    #[serde(default, borrow, rename = "subdirs")]
+    pub extra_subdirs: Vec<Subdir<'a>>,
+}
+
+impl ModLoader for BepInEx<'static> {
+    fn name(&self) -> &'static str {
+        "BepInEx"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name.starts_with("BepInEx-BepInExPack")
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        Box::new(BepinexInstaller)
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[
+            Subdir::flat_separated("plugins", "BepInEx/plugins"),
+            Subdir::flat_separated("patchers", "BepInEx/patchers"),
+            Subdir::flat_separated("monomod", "BepInEx/monomod").extension(".mm.dll"),
+            Subdir::flat_separated("core", "BepInEx/core"),
+            Subdir::untracked("config", "BepInEx/config").mutable(),
+            // ASI plugins and native libraries are loaded from the game directory,
+            // the profile root is copied there on launch
+            Subdir::tracked("", "").extension(".asi").native(),
+        ];
+
+        Box::new(
+            SubdirInstaller::new(SUBDIRS)
+                .with_default(0)
+                .with_extras(&self.extra_subdirs),
+        )
+    }
+
+    fn log_path(&self) -> &str {
+        "BepInEx/LogOutput.log"
+    }
+
+    fn config_path(&self) -> PathBuf {
+        ["BepInEx", "config"].iter().collect()
+    }
+
+    fn proxy_dll(&self) -> Option<&str> {
+        Some("winhttp")
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        let (enable_prefix, target_prefix) = doorstop_args(profile_dir)?;
+        let preloader_path = bepinex_preloader_path(profile_dir)?;
+
+        command
+            .args([enable_prefix, "true", target_prefix])
+            .arg(preloader_path);
+
+        Ok(())
+    }
+
+    fn add_vanilla_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        // the proxy dll stays in the game directory, so doorstop has to be told to stand down
+        let (enable_prefix, _) = doorstop_args(profile_dir)?;
+        command.args([enable_prefix, "false"]);
+
+        Ok(())
+    }
+}
+
+fn bepinex_preloader_path(profile_dir: &Path) -> Result<PathBuf> {
+    let mut core_dir = profile_dir.to_path_buf();
+
+    core_dir.push("BepInEx");
+    core_dir.push("core");
+
+    const PRELOADER_NAMES: &[&str] = &[
+        "BepInEx.Unity.Mono.Preloader.dll",
+        "BepInEx.Unity.IL2CPP.dll",
+        "BepInEx.Preloader.dll",
+        "BepInEx.IL2CPP.dll",
+    ];
+
+    let result = core_dir
+        .read_dir()
+        .context("failed to read BepInEx core directory. Is BepInEx installed?")?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            let file_name = entry.file_name();
+            PRELOADER_NAMES.iter().any(|name| file_name == **name)
+        })
+        .ok_or_eyre("BepInEx preloader not found. Is BepInEx installed?")?
+        .path();
+
+    Ok(result)
+}
+
+fn doorstop_args(profile_dir: &Path) -> Result<(&'static str, &'static str)> {
+    let path = profile_dir.join(".doorstop_version");
+
+    let version = if path.exists() {
+        let version = fs::read_to_string(&path)
+            .fs_context("reading version file", &path)?
+            .split('.') // read only the major version number
+            .next()
+            .and_then(|str| str.parse().ok())
+            .ok_or_eyre("invalid version format")?;
+
+        info!("doorstop version read: {}", version);
+        version
+    } else {
+        warn!(".doorstop_version file is missing, defaulting to 3");
+        3
+    };
+
+    match version {
+        3 => Ok(("--doorstop-enable", "--doorstop-target")),
+        4 => Ok(("--doorstop-enabled", "--doorstop-target-assembly")),
+        vers => bail!("unsupported doorstop version: {}", vers),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MelonLoader<'a> {
+    #[serde(default, borrow, rename = "subdirs")]
+    pub extra_subdirs: Vec<Subdir<'a>>,
+}
+
+impl ModLoader for MelonLoader<'static> {
+    fn name(&self) -> &'static str {
+        "MelonLoader"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "LavaGang-MelonLoader"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const FILES: &[&str] = &[
+            "dobby.dll",
+            "version.dll",
+            "MelonLoader/Dependencies",
+            "MelonLoader/Documentation",
+            "MelonLoader/net6",
+            "MelonLoader/net35",
+        ];
+
+        Box::new(ExtractInstaller::new(FILES, FlattenTopLevel::No))
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[
+            Subdir::tracked("UserLibs", "UserLibs")
+                .extension(".lib.dll")
+                .native(),
+            Subdir::tracked("Managed", "MelonLoader/Managed").extension(".managed.dll"),
+            Subdir::tracked("Mods", "Mods").extension(".dll"),
+            Subdir::separated("ModManager", "UserData/ModManager"),
+            Subdir::tracked("MelonLoader", "MelonLoader"),
+            Subdir::tracked("Libs", "MelonLoader/Libs"),
+        ];
+        const IGNORED: &[&str] = &["manifest.json", "icon.png", "README.md"];
+
+        Box::new(
+            SubdirInstaller::new(SUBDIRS)
+                .with_default(2)
+                .with_extras(&self.extra_subdirs)
+                .with_ignored_files(IGNORED),
+        )
+    }
+
+    fn log_path(&self) -> &str {
+        "MelonLoader/Latest.log"
+    }
+
+    fn config_path(&self) -> PathBuf {
+        PathBuf::from("UserData")
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        command.arg("--melonloader.basedir").arg(profile_dir);
+
+        let mono_assembly_exists = profile_dir
+            .join("MelonLoader/Managed/Assembly-CSharp.dll")
+            .exists();
+        let il2cpp_assembly_exists = profile_dir
+            .join("MelonLoader/Il2CppAssemblies/Assembly-CSharp.dll")
+            .exists();
+
+        if !mono_assembly_exists && !il2cpp_assembly_exists {
+            command.arg("--melonloader.agfregenerate");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Northstar {}
+
+impl ModLoader for Northstar {
+    fn name(&self) -> &'static str {
+        "Northstar"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "northstar-Northstar"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const FILES: &[&str] = &[
+            "Northstar.dll",
+            "NorthstarLauncher.exe",
+            "r2ds.bat",
+            "bin",
+            "R2Northstar/plugins",
+            "R2Northstar/mods/Northstar.Client",
+            "R2Northstar/mods/Northstar.Custom",
+            "R2Northstar/mods/Northstar.CustomServers",
+            "R2Northstar/mods/md5sum.text",
+        ];
+
+        Box::new(ExtractInstaller::new(FILES, FlattenTopLevel::Yes))
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[Subdir::tracked("mods", "R2Northstar/mods")];
+        const IGNORED: &[&str] = &["manifest.json", "icon.png", "README.md", "LICENSE"];
+
+        Box::new(SubdirInstaller::new(SUBDIRS).with_ignored_files(IGNORED))
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        let path = profile_dir.join("R2Northstar");
+        let path = path
+            .to_str()
+            .ok_or_eyre("profile path is not valid UTF-8")?;
+
+        command.arg("-northstar").arg(format!("-profile={}", path));
+
+        Ok(())
+    }
+
+    fn write_load_order(&self, profile: &Profile) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct ModJson {
+            name: String,
+        }
+
+        let mods_dir = Path::new("R2Northstar").join("mods");
+
+        // names of every mod in the profile, disabled ones included
+        let mut names = Vec::new();
+        for profile_mod in &profile.mods {
+            for file in install::tracked_files(&profile_mod.full_name(), profile) {
+                let is_manifest = file.file_name().is_some_and(|name| name == "mod.json")
+                    && file.parent().and_then(Path::parent) == Some(&mods_dir);
+
+                if !is_manifest {
+                    continue;
+                }
+
+                let mut path = profile.path.join(&file);
+                if !profile_mod.enabled {
+                    path.add_ext("old");
+                }
+
+                match util::fs::read_json::<ModJson>(&path) {
+                    Ok(manifest) => names.push((manifest.name, profile_mod.enabled)),
+                    Err(err) => warn!("failed to read {}: {:#}", path.display(), err),
+                }
+            }
+        }
+
+        let path = profile.path.join("R2Northstar").join("enabledmods.json");
+
+        // keep the entries of mods we don't manage, like Northstar's own
+        let mut entries = match path.exists() {
+            true => util::fs::read_json::<IndexMap<String, serde_json::Value>>(&path)
+                .unwrap_or_else(|err| {
+                    warn!("failed to read {}: {:#}", path.display(), err);
+                    IndexMap::new()
+                }),
+            false => IndexMap::new(),
+        };
+
+        for (name, _) in &names {
+            entries.shift_remove(name);
+        }
+
+        // disabled mods are hidden from Northstar by renaming their files,
+        // so only list enabled ones here to keep a stale file from disabling anything.
+        // they're appended in profile order, which Northstar loads them in
+        for (name, enabled) in names {
+            if enabled {
+                entries.insert(name, serde_json::Value::Bool(true));
+            }
+        }
+
+        util::fs::write_json(&path, &entries, JsonStyle::Pretty)
+            .context("failed to write enabledmods.json")?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GDWeave {}
+
+impl ModLoader for GDWeave {
+    fn name(&self) -> &'static str {
+        "GDWeave"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "NotNet-GDWeave"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const FILES: &[&str] = &["winmm.dll", "GDWeave/core"];
+
+        Box::new(ExtractInstaller::new(FILES, FlattenTopLevel::No))
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        Box::new(GDWeaveModInstaller)
+    }
+
+    fn log_path(&self) -> &str {
+        "GDWeave/GDWeave.log"
+    }
+
+    fn config_path(&self) -> PathBuf {
+        ["GDWeave", "configs"].iter().collect()
+    }
+
+    fn proxy_dll(&self) -> Option<&str> {
+        Some("winmm")
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        let path = profile_dir.join("GDWeave");
+        let path = path
+            .to_str()
+            .ok_or_eyre("profile path is not valid UTF-8")?;
+
+        command.arg(format!("--gdweave-folder-override={}", path));
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Shimloader {}
+
+impl ModLoader for Shimloader {
+    fn name(&self) -> &'static str {
+        "Shimloader"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "Thunderstore-unreal_shimloader"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        Box::new(ShimloaderInstaller)
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[
+            Subdir::flat_separated("mod", "shimloader/mod"),
+            Subdir::flat_separated("pak", "shimloader/pak"),
+            Subdir::untracked("cfg", "shimloader/cfg").mutable(),
+        ];
+
+        Box::new(SubdirInstaller::new(SUBDIRS).with_default(0))
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        let path = profile_dir.join("shimloader");
+
+        command
+            .arg("--mod-dir")
+            .arg(path.join("mod"))
+            .arg("--pak-dir")
+            .arg(path.join("pak"))
+            .arg("--cfg-dir")
+            .arg(path.join("cfg"));
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lovely {}
+
+impl ModLoader for Lovely {
+    fn name(&self) -> &'static str {
+        "Lovely"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "Thunderstore-lovely"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const FILES: &[&str] = &["version.dll"];
+
+        Box::new(ExtractInstaller::new(FILES, FlattenTopLevel::No))
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[Subdir::separated("", "mods")];
+
+        Box::new(SubdirInstaller::new(SUBDIRS).with_default(0))
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        let path = profile_dir.join("mods");
+        command.arg("--mod-dir").arg(path);
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnOfModding<'a> {
+    #[serde(borrow)]
+    pub files: Vec<&'a str>,
+}
+
+impl ModLoader for ReturnOfModding<'static> {
+    fn name(&self) -> &'static str {
+        "ReturnOfModding"
+    }
+
+    fn is_loader_package(&self, full_name: &str) -> bool {
+        full_name == "ReturnOfModding-ReturnOfModding"
+    }
+
+    fn loader_installer(&'static self) -> Box<dyn PackageInstaller> {
+        Box::new(ExtractInstaller::new(&self.files, FlattenTopLevel::Yes))
+    }
+
+    fn mod_installer(&'static self) -> Box<dyn PackageInstaller> {
+        const SUBDIRS: &[Subdir] = &[
+            Subdir::separated("plugins", "ReturnOfModding/plugins"),
+            Subdir::separated("plugins_data", "ReturnOfModding/plugins_data"),
+            Subdir::separated("config", "ReturnOfModding/config").mutable(),
+        ];
+
+        Box::new(SubdirInstaller::new(SUBDIRS).with_default(0))
+    }
+
+    fn config_path(&self) -> PathBuf {
+        ["ReturnOfModding", "config"].iter().collect()
+    }
+
+    fn proxy_dll(&self) -> Option<&str> {
+        Some(self.files[0])
+    }
+
+    fn add_launch_args(&self, command: &mut Command, profile_dir: &Path) -> Result<()> {
+        command.arg("--rom_modding_root_folder").arg(profile_dir);
+
+        Ok(())
+    }
+}
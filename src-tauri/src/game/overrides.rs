@@ -0,0 +1,210 @@
+//! Overrides for the install rules in `games.json` and additional package fixups,
+//! so packages with unusual layouts can be fixed without a new release.
+//!
+//! Overrides are read from a file in the app data directory, which the user can
+//! edit, and from a downloaded copy of a remote list set in the prefs. Since the
+//! games are only loaded once, changes to either take effect on the next startup.
+//! Local overrides take precedence over the remote list.
+//!
+//! Subdir targets must stay inside of the profile, since the remote list isn't
+//! necessarily trusted. Fixup paths are checked when they're applied.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{ensure, Context, Result};
+use serde::Deserialize;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use super::GameData;
+use crate::{
+    profile::install::{
+        fixups::{self, Fixup},
+        Subdir,
+    },
+    state::ManagerExt,
+    util::{self, error::IoResultExt},
+};
+
+pub const FILE_NAME: &str = "install_overrides.json";
+pub const REMOTE_FILE_NAME: &str = "install_overrides.remote.json";
+
+const TEMPLATE: &str = r#"{
+  "games": {
+    "example-game-slug": {
+      "loaderPackages": [],
+      "subdirs": []
+    }
+  },
+  "packageFixups": []
+}
+"#;
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Overrides<'a> {
+    /// Keyed by game slug.
+    #[serde(borrow, default)]
+    games: HashMap<&'a str, GameOverrides<'a>>,
+    /// Applied after the bundled fixups, see [`fixups`].
+    #[serde(default)]
+    package_fixups: Vec<Fixup>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct GameOverrides<'a> {
+    /// Full names of additional packages to install as the mod loader.
+    #[serde(borrow, default)]
+    loader_packages: Vec<&'a str>,
+    /// Additional subdirs, which take precedence over the loader's own.
+    #[serde(borrow, default)]
+    subdirs: Vec<Subdir<'a>>,
+}
+
+pub fn path() -> PathBuf {
+    util::path::default_app_data_dir().join(FILE_NAME)
+}
+
+/// Creates the overrides file with an example if it doesn't exist yet.
+pub fn ensure_file() -> Result<PathBuf> {
+    let path = path();
+
+    if !path.exists() {
+        fs::create_dir_all(path.parent().unwrap()).fs_context("creating directory", &path)?;
+        fs::write(&path, TEMPLATE).fs_context("writing install overrides", &path)?;
+    }
+
+    Ok(path)
+}
+
+/// Merges the remote and local overrides into `games`.
+pub(super) fn apply(games: &mut [GameData<'static>]) {
+    let dir = util::path::default_app_data_dir();
+
+    for path in [dir.join(REMOTE_FILE_NAME), dir.join(FILE_NAME)] {
+        match read(&path) {
+            Ok(Some(overrides)) => apply_from(games, overrides, &path),
+            Ok(None) => (),
+            Err(err) => warn!(
+                "failed to read install overrides from {}: {:#}",
+                path.display(),
+                err
+            ),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Option<Overrides<'static>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    // the games borrow from their json for the entire lifetime of the app
+    let json: &'static str = fs::read_to_string(path)
+        .fs_context("reading install overrides", path)?
+        .leak();
+
+    let overrides = serde_json::from_str(json).context("failed to parse install overrides")?;
+    Ok(Some(overrides))
+}
+
+impl Overrides<'_> {
+    /// Makes sure no subdir installs files outside of the profile.
+    fn validate(&self) -> Result<()> {
+        for (slug, game_overrides) in &self.games {
+            for subdir in &game_overrides.subdirs {
+                ensure!(
+                    util::fs::is_enclosed(subdir.target),
+                    "subdir target {} of {} is outside of the profile",
+                    subdir.target,
+                    slug
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn apply_from(games: &mut [GameData<'static>], overrides: Overrides<'static>, path: &Path) {
+    if let Err(err) = overrides.validate() {
+        warn!(
+            "install overrides from {} are invalid, skipping: {:#}",
+            path.display(),
+            err
+        );
+        return;
+    }
+
+    for (slug, game_overrides) in overrides.games {
+        let Some(game) = games.iter_mut().find(|game| game.slug == slug) else {
+            warn!("unknown game {} in install overrides, skipping", slug);
+            continue;
+        };
+
+        let mod_loader = &mut game.mod_loader;
+
+        mod_loader
+            .extra_package_names
+            .extend(game_overrides.loader_packages);
+
+        if game_overrides.subdirs.is_empty() {
+            continue;
+        }
+
+        match mod_loader.kind.extra_subdirs_mut() {
+            Some(subdirs) => {
+                subdirs.splice(0..0, game_overrides.subdirs);
+            }
+            None => warn!(
+                "the mod loader of {} does not support extra subdirs, skipping overrides",
+                game.name
+            ),
+        }
+    }
+
+    fixups::extend(overrides.package_fixups);
+
+    info!("applied install overrides from {}", path.display());
+}
+
+/// Downloads the remote list set in the prefs, to be applied on the next startup.
+///
+/// If no list is set, the previously downloaded one is removed.
+pub async fn fetch_remote(app: &AppHandle) -> Result<()> {
+    let url = app.lock_prefs().install_overrides_url.clone();
+    let path = util::path::default_app_data_dir().join(REMOTE_FILE_NAME);
+
+    let Some(url) = url else {
+        if path.exists() {
+            fs::remove_file(&path).fs_context("removing remote install overrides", &path)?;
+        }
+
+        return Ok(());
+    };
+
+    let json = app
+        .http()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    serde_json::from_str::<Overrides>(&json)
+        .map_err(eyre::Error::from)
+        .and_then(|overrides| overrides.validate())
+        .context("remote install overrides are invalid")?;
+
+    fs::write(&path, json).fs_context("writing remote install overrides", &path)?;
+
+    info!("downloaded install overrides from {}", url);
+
+    Ok(())
+}
@@ -1,5 +1,9 @@
-use std::env;
+use std::{
+    env,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
+use eyre::eyre;
 use itertools::Itertools;
 use state::ManagerExt;
 use tauri::{App, AppHandle};
@@ -16,6 +20,7 @@ mod db;
 mod deep_link;
 mod game;
 mod logger;
+mod nexus;
 mod prefs;
 mod profile;
 mod state;
@@ -48,6 +53,10 @@ fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
         warn!("failed to register gale deep link protocol: {:#}", err);
     }
 
+    if let Err(err) = app.deep_link().register("nxm") {
+        warn!("failed to register nxm deep link protocol: {:#}", err);
+    }
+
     let args = env::args().collect_vec();
     if !args.is_empty() && !deep_link::handle(app.handle(), args.clone()) {
         cli::run(args, app.handle())
@@ -56,13 +65,41 @@ fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
     let handle = app.handle().to_owned();
     tauri::async_runtime::spawn(async move { telemetry::send_app_start_event(handle).await });
 
+    let handle = app.handle().to_owned();
+    let warned = AtomicBool::new(false);
+    util::fs::on_trash_fallback(move |path| {
+        // one toast is enough to let the user know, the rest are in the log
+        if !warned.swap(true, Ordering::Relaxed) {
+            logger::log_webview_err(
+                "Deleted permanently",
+                eyre!(
+                    "{} could not be moved to the trash, so it was deleted permanently",
+                    path.display()
+                ),
+                &handle,
+            );
+        }
+    });
+
+    let handle = app.handle().to_owned();
+    tauri::async_runtime::spawn(async move {
+        game::overrides::fetch_remote(&handle)
+            .await
+            .unwrap_or_else(|err| warn!("failed to fetch install overrides: {err:#}"));
+    });
+
+    tauri::async_runtime::spawn(profile::install::locked::retry_in_background());
+
     let handle = app.handle().to_owned();
     tauri::async_runtime::spawn(async move {
         tokio::task::spawn_blocking(move || {
-            handle
-                .db()
-                .evict_outdated_cache()
-                .unwrap_or_else(|err| warn!("failed to evict outdated cache: {err:#}"))
+            let db = handle.db();
+
+            db.evict_outdated_cache()
+                .unwrap_or_else(|err| warn!("failed to evict outdated cache: {err:#}"));
+
+            db.backup()
+                .unwrap_or_else(|err| warn!("failed to back up database: {err:#}"));
         })
         .await
     });
@@ -88,64 +125,147 @@ pub fn run() {
             logger::open_gale_log,
             logger::log_err,
             state::is_first_run,
+            state::get_recovery,
+            db::commands::export_state,
+            db::commands::restore_state,
+            state::cancel_operation,
             thunderstore::commands::query_thunderstore,
             thunderstore::commands::stop_querying_thunderstore,
             thunderstore::commands::get_markdown,
+            thunderstore::commands::get_categories,
+            thunderstore::commands::get_package_credits,
             thunderstore::commands::set_thunderstore_token,
             thunderstore::commands::has_thunderstore_token,
             thunderstore::commands::clear_thunderstore_token,
             thunderstore::commands::trigger_mod_fetch,
             prefs::commands::get_prefs,
             prefs::commands::set_prefs,
+            prefs::commands::set_secret,
+            prefs::commands::is_secret_set,
+            prefs::commands::set_download_source,
+            prefs::commands::set_launch_target,
             prefs::commands::zoom_window,
+            prefs::commands::open_install_overrides,
             profile::commands::get_game_info,
             profile::commands::favorite_game,
             profile::commands::set_active_game,
             profile::commands::get_profile_info,
             profile::commands::set_active_profile,
+            profile::commands::get_profile_disk_usage,
+            profile::commands::restore_last_snapshot,
+            profile::commands::find_orphaned_files,
+            profile::commands::remove_orphaned_files,
+            profile::commands::diagnose_profile,
+            profile::commands::apply_health_fix,
+            profile::commands::verify_profile,
+            profile::commands::get_runtime_data_overrides,
+            profile::commands::set_runtime_data_overrides,
+            profile::commands::repair_mods,
             profile::commands::is_mod_installed,
             profile::commands::query_profile,
             profile::commands::get_dependants,
             profile::commands::create_profile,
             profile::commands::delete_profile,
+            profile::commands::get_profile_templates,
+            profile::commands::save_profile_template,
+            profile::commands::delete_profile_template,
             profile::commands::rename_profile,
             profile::commands::duplicate_profile,
+            profile::commands::get_profile_hash,
+            profile::commands::create_profile_group,
+            profile::commands::rename_profile_group,
+            profile::commands::delete_profile_group,
+            profile::commands::set_profile_group,
             profile::commands::remove_mod,
             profile::commands::force_remove_mods,
             profile::commands::toggle_mod,
             profile::commands::force_toggle_mods,
             profile::commands::set_all_mods_state,
             profile::commands::remove_disabled_mods,
+            profile::commands::undo_last_action,
+            profile::commands::set_mod_index,
+            profile::commands::move_mod,
+            profile::commands::pin_mod,
+            profile::commands::set_mod_note,
+            profile::commands::tag_mods,
+            profile::commands::untag_mods,
+            profile::commands::set_tag_state,
             profile::commands::open_profile_dir,
             profile::commands::open_mod_dir,
             profile::commands::open_game_log,
             profile::commands::create_desktop_shortcut,
             profile::launch::commands::launch_game,
+            profile::launch::commands::is_game_running,
+            profile::launch::commands::stop_game,
+            profile::launch::commands::get_game_log,
+            profile::launch::commands::analyze_game_log,
+            profile::launch::commands::get_playtime_stats,
             profile::launch::commands::get_launch_args,
+            profile::launch::commands::get_launch_overrides,
+            profile::launch::commands::set_launch_overrides,
             profile::launch::commands::open_game_dir,
+            profile::launch::commands::launch_server,
+            profile::launch::commands::export_server_script,
+            profile::launch::commands::open_crash_reports,
+            profile::launch::commands::get_save_backups,
+            profile::launch::commands::backup_saves,
+            profile::launch::commands::restore_save_backup,
             profile::install::commands::install_mod,
-            profile::install::commands::cancel_install,
+            profile::install::commands::scan_conflicts,
+            profile::install::commands::get_tools,
+            profile::install::commands::add_tool,
+            profile::install::commands::remove_tool,
+            profile::install::commands::set_tool_enabled,
+            profile::install::commands::install_github_mod,
+            profile::install::commands::install_dependency_strings,
             profile::install::commands::clear_download_cache,
+            profile::install::commands::get_cache_stats,
+            profile::install::commands::get_cache_gc_report,
+            profile::install::commands::purge_cache,
             profile::install::commands::get_download_size,
+            profile::install::commands::inspect_package,
             profile::update::commands::change_mod_version,
             profile::update::commands::update_mods,
+            profile::update::commands::get_update_counts,
+            profile::update::commands::roll_back_to_date,
             profile::update::commands::ignore_update,
+            profile::update::commands::check_github_updates,
+            profile::update::commands::check_local_updates,
+            profile::update::commands::set_local_mod_source,
             profile::import::commands::import_profile,
+            profile::import::commands::preview_import,
             profile::import::commands::read_profile_code,
             profile::import::commands::read_profile_file,
             profile::import::commands::read_profile_base64,
+            profile::import::commands::import_config,
             profile::import::commands::import_local_mod,
+            profile::import::commands::import_dependency_strings,
+            profile::import::commands::preview_mod_list_text,
+            profile::import::commands::import_mod_list_text,
+            profile::import::commands::install_from_string,
             profile::import::commands::import_local_mod_base64,
             profile::import::commands::get_r2modman_info,
+            profile::import::commands::get_external_manager_info,
             profile::import::commands::import_r2modman,
             profile::export::commands::export_code,
+            profile::export::commands::get_export_codes,
+            profile::export::commands::check_export_code,
+            profile::export::commands::forget_export_code,
             profile::export::commands::export_file,
+            profile::export::commands::export_config,
+            profile::export::commands::export_r2modman,
+            profile::export::commands::export_to_directory,
             profile::export::commands::export_pack,
             profile::export::commands::upload_pack,
+            profile::export::commands::validate_pack,
+            profile::export::commands::get_modpack_stats,
             profile::export::commands::get_pack_args,
             profile::export::commands::set_pack_args,
             profile::export::commands::generate_changelog,
+            profile::export::commands::generate_readme,
             profile::export::commands::copy_dependency_strings,
+            profile::export::commands::export_modlist,
+            profile::export::commands::export_profile_data,
             profile::export::commands::copy_debug_info,
             profile::sync::commands::read_sync_profile,
             profile::sync::commands::create_sync_profile,
@@ -160,10 +280,21 @@ pub fn run() {
             profile::sync::commands::logout,
             profile::sync::commands::get_user,
             config::commands::get_config_files,
+            config::commands::search_config,
             config::commands::set_config_entry,
             config::commands::reset_config_entry,
+            config::commands::copy_config_files,
+            config::commands::diff_config,
             config::commands::open_config_file,
             config::commands::delete_config_file,
+            config::commands::get_config_history,
+            config::commands::revert_config_file,
+            config::commands::read_config_file_raw,
+            config::commands::write_config_file_raw,
+            config::commands::get_shared_config,
+            config::commands::set_config_shared,
+            nexus::commands::install_nexus_mod,
+            nexus::commands::check_nexus_updates,
         ])
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_deep_link::init())
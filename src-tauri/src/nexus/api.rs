@@ -0,0 +1,174 @@
+use bytes::Bytes;
+use eyre::{bail, eyre, OptionExt, Result};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
+use tauri::AppHandle;
+use tracing::debug;
+
+use crate::{
+    state::ManagerExt,
+    util::{http::RequestBuilderExt, secret::Secret},
+};
+
+const BASE_URL: &str = "https://api.nexusmods.com/v1";
+
+/// A minimal client for the Nexus Mods v1 API, scoped to one game.
+pub struct NexusApi {
+    http: reqwest::Client,
+    api_key: String,
+    pub domain: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModInfo {
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileInfo {
+    pub file_id: u64,
+    pub name: String,
+    pub version: Option<String>,
+    pub category_name: Option<String>,
+    pub size_in_bytes: Option<u64>,
+    #[serde(default)]
+    pub size_kb: u64,
+    pub uploaded_timestamp: i64,
+}
+
+impl FileInfo {
+    pub fn size(&self) -> u64 {
+        self.size_in_bytes.unwrap_or(self.size_kb * 1024)
+    }
+
+    fn is_main(&self) -> bool {
+        self.category_name.as_deref() == Some("MAIN")
+    }
+}
+
+#[derive(Deserialize)]
+struct FilesResponse {
+    files: Vec<FileInfo>,
+}
+
+/// Returned when a file is downloaded without a premium account or a key from
+/// a `nxm://` link.
+#[derive(thiserror::Error, Debug)]
+#[error("Nexus Mods only allows direct downloads with a premium account")]
+pub struct PremiumRequired;
+
+/// The one-time key from a `nxm://` link, which lets free accounts download a file.
+#[derive(Debug, Clone)]
+pub struct DownloadKey {
+    pub key: String,
+    pub expires: u64,
+}
+
+#[derive(Deserialize)]
+struct DownloadLink {
+    #[serde(rename = "URI")]
+    uri: String,
+}
+
+impl NexusApi {
+    /// Creates a client for the active game, using the API key from the keyring.
+    pub fn new(app: &AppHandle) -> Result<Self> {
+        let api_key = Secret::NexusApiKey
+            .get()?
+            .filter(|key| !key.is_empty())
+            .ok_or_eyre("no Nexus Mods API key set, add one in the settings")?;
+
+        let domain = app.lock_manager().active_game.nexus_domain.to_string();
+
+        Ok(Self {
+            http: app.http().clone(),
+            api_key,
+            domain,
+        })
+    }
+
+    pub fn for_domain(app: &AppHandle, domain: String) -> Result<Self> {
+        Ok(Self {
+            domain,
+            ..Self::new(app)?
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/games/{}/{}", BASE_URL, self.domain, path);
+
+        debug!("requesting {}", url);
+
+        let response = self
+            .http
+            .get(url)
+            .nexus_headers(&self.api_key)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED => bail!("Nexus Mods API key is invalid"),
+            StatusCode::FORBIDDEN => return Err(PremiumRequired.into()),
+            StatusCode::NOT_FOUND => bail!("not found on Nexus Mods"),
+            StatusCode::TOO_MANY_REQUESTS => {
+                bail!("Nexus Mods rate limit reached, try again later")
+            }
+            _ => (),
+        }
+
+        let result = response.error_for_status()?.json().await?;
+        Ok(result)
+    }
+
+    pub async fn mod_info(&self, mod_id: u64) -> Result<ModInfo> {
+        self.get(&format!("mods/{}.json", mod_id)).await
+    }
+
+    pub async fn files(&self, mod_id: u64) -> Result<Vec<FileInfo>> {
+        let response: FilesResponse = self.get(&format!("mods/{}/files.json", mod_id)).await?;
+        Ok(response.files)
+    }
+
+    /// Finds the most recently uploaded main file of a mod.
+    pub async fn latest_file(&self, mod_id: u64) -> Result<FileInfo> {
+        self.files(mod_id)
+            .await?
+            .into_iter()
+            .filter(FileInfo::is_main)
+            .max_by_key(|file| file.uploaded_timestamp)
+            .ok_or_else(|| eyre!("mod {} has no main files", mod_id))
+    }
+
+    /// Downloads a file. Without a `key`, this fails with [`PremiumRequired`]
+    /// for free accounts.
+    pub async fn download(
+        &self,
+        mod_id: u64,
+        file_id: u64,
+        key: Option<&DownloadKey>,
+    ) -> Result<Bytes> {
+        let mut path = format!("mods/{}/files/{}/download_link.json", mod_id, file_id);
+
+        if let Some(key) = key {
+            path.push_str(&format!("?key={}&expires={}", key.key, key.expires));
+        }
+
+        let links: Vec<DownloadLink> = self.get(&path).await?;
+
+        let link = links.into_iter().next().ok_or_eyre("no download links")?;
+
+        let data = self
+            .http
+            .get(link.uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(data)
+    }
+}
@@ -0,0 +1,22 @@
+use tauri::{command, AppHandle};
+
+use crate::{
+    profile::install::nexus,
+    util::{self, cmd::Result},
+};
+
+#[command]
+pub async fn install_nexus_mod(mod_id: u64, file_id: Option<u64>, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    nexus::install(mod_id, file_id, &app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn check_nexus_updates(app: AppHandle) -> Result<usize> {
+    let count = nexus::check_updates(&app).await?;
+
+    Ok(count)
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{prefs::Prefs, thunderstore::VersionIdent, util};
+
+pub mod api;
+pub mod commands;
+
+/// A mod installed from Nexus Mods.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NexusMod {
+    pub nexus_mod_id: u64,
+    pub file_id: u64,
+    pub game_domain: String,
+    pub name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub file_size: u64,
+    /// The newest main file found during the last update check,
+    /// if it differs from the installed one.
+    #[serde(default)]
+    pub latest_file: Option<NexusFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NexusFile {
+    pub file_id: u64,
+    pub version: Option<String>,
+}
+
+impl NexusMod {
+    pub fn ident(&self) -> VersionIdent {
+        VersionIdent::new(
+            &util::sanitize_ident(self.author.as_deref().unwrap_or("")),
+            &util::sanitize_ident(&self.name),
+            self.version.as_deref().unwrap_or(""),
+        )
+    }
+
+    /// The name used for the mod's directories in the profile.
+    pub fn package_name(&self) -> String {
+        util::sanitize_ident(&self.name)
+    }
+
+    pub fn url(&self) -> String {
+        format!(
+            "https://www.nexusmods.com/{}/mods/{}",
+            self.game_domain, self.nexus_mod_id
+        )
+    }
+
+    pub fn parsed_version(&self) -> Option<semver::Version> {
+        self.version
+            .as_deref()
+            .and_then(util::parse_lenient_version)
+    }
+
+    /// A stable id for the latest available file, used to ignore updates.
+    pub fn latest_file_uuid(&self) -> Option<Uuid> {
+        self.latest_file
+            .as_ref()
+            .map(|file| Uuid::from_u64_pair(self.nexus_mod_id, file.file_id))
+    }
+
+    pub fn cache_path(&self, prefs: &Prefs) -> PathBuf {
+        let mut path = prefs.cache_dir();
+
+        path.push("nexus");
+        path.push(&self.game_domain);
+        path.push(self.nexus_mod_id.to_string());
+        path.push(self.file_id.to_string());
+
+        path
+    }
+}
@@ -1,11 +1,13 @@
-use eyre::anyhow;
+use eyre::{anyhow, Context};
 use serde::Deserialize;
 use tauri::{command, AppHandle, Manager, Window};
 
-use super::Prefs;
+use super::{DownloadSource, Prefs};
 use crate::{
+    game,
+    profile::launch::LaunchTarget,
     state::ManagerExt,
-    util::{cmd::Result, window::WindowExt},
+    util::{cmd::Result, secret::Secret, window::WindowExt},
 };
 
 #[command]
@@ -20,6 +22,60 @@ pub fn set_prefs(value: Prefs, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Stores an API credential in the keyring, or clears it if `value` is empty.
+#[command]
+pub fn set_secret(secret: Secret, value: &str) -> Result<()> {
+    match value.is_empty() {
+        true => secret.clear()?,
+        false => secret.set(value)?,
+    }
+
+    Ok(())
+}
+
+/// Whether an API credential is set, since the value itself is never sent to the frontend.
+#[command]
+pub fn is_secret_set(secret: Secret) -> bool {
+    secret.is_set()
+}
+
+/// Sets where a package is downloaded from, or resets it to Thunderstore if `source` is `None`.
+#[command]
+pub fn set_download_source(
+    full_name: String,
+    source: Option<DownloadSource>,
+    app: AppHandle,
+) -> Result<()> {
+    let mut prefs = app.lock_prefs();
+
+    match source {
+        Some(source) => prefs.download_sources.insert(full_name, source),
+        None => prefs.download_sources.remove(&full_name),
+    };
+
+    prefs.save(app.db())?;
+
+    Ok(())
+}
+
+/// Sets what is launched for the active game, see [`LaunchTarget`].
+#[command]
+pub fn set_launch_target(target: LaunchTarget, app: AppHandle) -> Result<()> {
+    let mut prefs = app.lock_prefs();
+    let manager = app.read_manager();
+
+    let slug = &manager.active_game.slug;
+    prefs
+        .game_prefs
+        .entry(slug.to_string())
+        .or_default()
+        .launch_target = target;
+
+    prefs.save(app.db())?;
+
+    Ok(())
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 pub enum Zoom {
@@ -46,3 +102,12 @@ pub fn zoom_window(value: Zoom, window: Window, app: AppHandle) -> Result<()> {
 
     Ok(())
 }
+
+/// Opens the install overrides file, creating it first if needed.
+#[command]
+pub fn open_install_overrides() -> Result<()> {
+    let path = game::overrides::ensure_file()?;
+    open::that_detached(&path).context("failed to open install overrides")?;
+
+    Ok(())
+}
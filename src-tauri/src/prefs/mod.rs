@@ -12,14 +12,18 @@ use tracing::{debug, info, warn};
 
 use crate::{
     db::{self, Db},
-    game::{self, Platform},
+    game::{self, Platform, SteamLaunch},
     logger,
-    profile::launch::LaunchMode,
+    profile::{
+        install,
+        launch::{LaunchMode, LaunchTarget, WineRunner},
+    },
     state::ManagerExt,
     util::{
         self,
         error::IoResultExt,
         fs::{Overwrite, UseLinks},
+        secret::Secret,
         window::WindowExt,
     },
 };
@@ -187,17 +191,75 @@ pub struct Prefs {
     pub fetch_mods_automatically: bool,
     pub zoom_factor: f32,
     pub pull_before_launch: bool,
+    /// Moved to the OS keyring, only read to migrate older prefs.
+    #[serde(rename = "nexusApiKey", skip_serializing)]
+    pub(crate) legacy_nexus_api_key: Option<String>,
+    /// Moved to the OS keyring, only read to migrate older prefs.
+    #[serde(rename = "githubToken", skip_serializing)]
+    pub(crate) legacy_github_token: Option<String>,
+    /// Replaces the name in the user agent sent with every request.
+    /// The app version and platform are still appended.
+    ///
+    /// Only takes effect after a restart, since the HTTP client is built at startup.
+    pub user_agent: Option<String>,
+    /// Delete files permanently instead of moving them to the trash.
+    pub permanent_delete: bool,
+    /// The maximum size of the download cache in GB, or `None` for no limit.
+    pub max_cache_size: Option<f64>,
+    /// Share files between the cache and profiles with links, instead of copying them.
+    pub link_files: bool,
+    /// Take a snapshot of the profile before updating mods or importing over it.
+    pub auto_snapshots: bool,
+    /// How many automatic snapshots to keep per profile.
+    pub snapshot_retention: usize,
+    /// Clamp config values outside of their acceptable range, instead of rejecting them.
+    pub clamp_config_values: bool,
+    /// A list of install overrides to download, see [`overrides`].
+    ///
+    /// [`overrides`]: crate::game::overrides
+    pub install_overrides_url: Option<String>,
+    /// Where to download specific packages from instead of Thunderstore,
+    /// keyed by the package's full name.
+    pub download_sources: HashMap<String, DownloadSource>,
 
     pub game_prefs: HashMap<String, GamePrefs>,
 }
 
+/// An alternative location to download a package from.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum DownloadSource {
+    /// A mirror of the package's zip.
+    ///
+    /// `{version}` is replaced with the version being installed.
+    Url { url: String },
+    /// A zip of the package on disk.
+    File { path: PathBuf },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub struct GamePrefs {
     pub dir_override: Option<PathBuf>,
     pub custom_args: Option<Vec<String>>,
     pub launch_mode: LaunchMode,
+    pub launch_target: LaunchTarget,
     pub platform: Option<Platform>,
+    /// Overrides how the game is started through Steam, see [`SteamLaunch`].
+    pub steam_launch: Option<SteamLaunch>,
+    /// What runs the game when launching directly on Linux, see [`WineRunner`].
+    pub wine_runner: WineRunner,
+    /// The Proton version used by [`WineRunner`], if not the one Steam uses.
+    pub proton_version: Option<String>,
+    /// Whether to back up the saves before launching with different mods, see [`saves`].
+    ///
+    /// [`saves`]: crate::profile::launch::saves
+    pub backup_saves: bool,
+    pub save_dir_override: Option<PathBuf>,
+    /// Overrides the location of the game's dedicated server.
+    pub server_dir: Option<PathBuf>,
+    /// Added after the server's own arguments when launching it.
+    pub server_args: Option<Vec<String>>,
 }
 
 impl Default for Prefs {
@@ -207,11 +269,27 @@ impl Default for Prefs {
                 .keep(logger::FILE_NAME)
                 .keep(db::FILE_NAME)
                 .keep(db::SHM_FILE_NAME)
-                .keep(db::WAL_FILE_NAME),
+                .keep(db::WAL_FILE_NAME)
+                .keep(db::BACKUP_FILE_NAME)
+                .keep(db::transfer::RESTORE_FILE_NAME)
+                .keep(game::overrides::FILE_NAME)
+                .keep(game::overrides::REMOTE_FILE_NAME)
+                .keep(install::locked::FILE_NAME),
 
             send_telemetry: true,
             fetch_mods_automatically: true,
             pull_before_launch: true,
+            legacy_nexus_api_key: None,
+            legacy_github_token: None,
+            user_agent: None,
+            permanent_delete: false,
+            max_cache_size: None,
+            link_files: true,
+            auto_snapshots: true,
+            snapshot_retention: 5,
+            clamp_config_values: false,
+            install_overrides_url: None,
+            download_sources: HashMap::new(),
 
             zoom_factor: 1.0,
 
@@ -227,11 +305,39 @@ impl Prefs {
             db::FILE_NAME,
             db::SHM_FILE_NAME,
             db::WAL_FILE_NAME,
+            db::BACKUP_FILE_NAME,
+            db::transfer::RESTORE_FILE_NAME,
+            game::overrides::FILE_NAME,
+            game::overrides::REMOTE_FILE_NAME,
+            install::locked::FILE_NAME,
         ]);
 
         let window = app.get_webview_window("main").unwrap();
         window.zoom(self.zoom_factor as f64).ok();
 
+        util::fs::set_use_trash(!self.permanent_delete);
+        install::set_use_links(self.link_files);
+
+        // only left over if the app was closed in the middle of an extraction
+        let staging_dir = self.staging_dir();
+        if staging_dir.exists() {
+            if let Err(err) = fs::remove_dir_all(&staging_dir) {
+                warn!("failed to clean up staging directory: {:#}", err);
+            }
+        }
+
+        for (secret, legacy) in [
+            (Secret::NexusApiKey, &mut self.legacy_nexus_api_key),
+            (Secret::GithubToken, &mut self.legacy_github_token),
+        ] {
+            if let Err(err) = secret.migrate(legacy) {
+                warn!(
+                    "failed to move {:?} to keyring, it has to be set again: {:#}",
+                    secret, err
+                );
+            }
+        }
+
         self.save(db)?;
 
         Ok(())
@@ -281,6 +387,20 @@ impl Prefs {
         self.send_telemetry = value.send_telemetry;
         self.fetch_mods_automatically = value.fetch_mods_automatically;
         self.pull_before_launch = value.pull_before_launch;
+        self.user_agent = value.user_agent;
+
+        self.permanent_delete = value.permanent_delete;
+        util::fs::set_use_trash(!self.permanent_delete);
+
+        self.max_cache_size = value.max_cache_size;
+
+        self.link_files = value.link_files;
+        install::set_use_links(self.link_files);
+
+        self.auto_snapshots = value.auto_snapshots;
+        self.snapshot_retention = value.snapshot_retention;
+        self.clamp_config_values = value.clamp_config_values;
+        self.download_sources = value.download_sources;
 
         self.save(app.db()).context("failed save prefs")
     }
@@ -325,4 +445,21 @@ impl Prefs {
     pub fn cache_dir(&self) -> PathBuf {
         self.data_dir.join("cache")
     }
+
+    /// Where mods are extracted before being moved into the cache, so an
+    /// interrupted extraction doesn't leave anything behind in the cache.
+    pub fn staging_dir(&self) -> PathBuf {
+        self.data_dir.join("staging")
+    }
+
+    pub fn max_cache_size_bytes(&self) -> Option<u64> {
+        self.max_cache_size
+            .filter(|size| *size > 0.0)
+            .map(|size| (size * 1_000_000_000.0) as u64)
+    }
+
+    /// A directory for temporary files, on the same volume as profiles and the cache.
+    pub fn temp_dir(&self) -> PathBuf {
+        self.data_dir.join("temp")
+    }
 }
@@ -5,7 +5,7 @@ use std::{
 };
 
 use eyre::{anyhow, bail, ensure, Context, OptionExt, Result};
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Listener};
 use tracing::{debug, info};
@@ -14,7 +14,7 @@ use uuid::Uuid;
 use super::{
     export::{IncludeExtensions, IncludeGenerated},
     import,
-    install::PackageInstaller,
+    install::{self, PackageInstaller},
     Dependant, ManagedGame, Profile, ProfileMod,
 };
 use crate::{
@@ -104,6 +104,11 @@ impl Profile {
 
         self.installer_for(profile_mod)
             .uninstall(profile_mod, self)?;
+        install::manifest::uninstall(profile_mod, self)?;
+
+        if let Some(local_mod) = profile_mod.kind.as_local() {
+            import::remove_icon(local_mod, self);
+        }
 
         self.mods.remove(index);
 
@@ -131,22 +136,38 @@ impl Profile {
 
         self.installer_for(profile_mod)
             .toggle(enabled, profile_mod, self)?;
+        install::manifest::toggle(enabled, profile_mod, self)?;
 
         self.get_mod_mut(uuid).unwrap().enabled = !enabled;
 
         Ok(())
     }
 
+    /// Finds the mods that would break if `uuid` was disabled or removed.
+    ///
+    /// Every enabled mod counts for the mod loader's package, since the game
+    /// silently launches without mods if it's gone, even if they don't declare
+    /// the dependency.
     fn check_dependants(
         &self,
         uuid: Uuid,
         include_disabled: bool,
         thunderstore: &Thunderstore,
     ) -> Option<Vec<Dependant>> {
-        let dependants = self
-            .dependants(uuid, thunderstore)
+        let is_loader = self.get_mod(uuid).is_ok_and(|profile_mod| {
+            self.game
+                .mod_loader
+                .is_loader_package(&profile_mod.full_name())
+        });
+
+        let dependants = match is_loader {
+            true => Either::Left(self.mods.iter().filter(move |other| other.uuid() != uuid)),
+            false => Either::Right(self.dependants(uuid, thunderstore)),
+        };
+
+        let dependants = dependants
             .filter(|profile_mod| {
-                (include_disabled || profile_mod.enabled)
+                ((include_disabled && !is_loader) || profile_mod.enabled)
                     && profile_mod
                         .as_thunderstore()
                         .and_then(|(ts_mod, _)| {
@@ -208,7 +229,7 @@ impl Profile {
         self.game.mod_loader.installer_for(&profile_mod.full_name())
     }
 
-    fn reorder_mod(&mut self, uuid: Uuid, delta: i32) -> Result<()> {
+    pub fn reorder_mod(&mut self, uuid: Uuid, delta: i32) -> Result<()> {
         let index = self
             .mods
             .iter()
@@ -221,6 +242,105 @@ impl Profile {
 
         Ok(())
     }
+
+    /// Pins or unpins the version of a mod. Pinned mods are skipped when updating.
+    pub fn pin_mod(&mut self, uuid: Uuid, pinned: bool) -> Result<()> {
+        self.get_mod_mut(uuid)?.pinned = pinned;
+
+        Ok(())
+    }
+
+    /// Sets or, if `note` is empty, clears the note of a mod.
+    pub fn set_mod_note(&mut self, uuid: Uuid, note: Option<String>) -> Result<()> {
+        self.get_mod_mut(uuid)?.note = note
+            .map(|note| note.trim().to_owned())
+            .filter(|note| !note.is_empty());
+
+        Ok(())
+    }
+
+    /// Adds `tag` to the given mods, skipping ones that already have it.
+    pub fn tag_mods(&mut self, uuids: &[Uuid], tag: &str) -> Result<()> {
+        let tag = tag.trim();
+        ensure!(!tag.is_empty(), "tag cannot be empty");
+
+        for uuid in uuids {
+            let profile_mod = self.get_mod_mut(*uuid)?;
+
+            if !profile_mod.tags.iter().any(|existing| existing == tag) {
+                profile_mod.tags.push(tag.to_owned());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes `tag` from the given mods.
+    pub fn untag_mods(&mut self, uuids: &[Uuid], tag: &str) -> Result<()> {
+        for uuid in uuids {
+            self.get_mod_mut(*uuid)?
+                .tags
+                .retain(|existing| existing != tag);
+        }
+
+        Ok(())
+    }
+
+    /// Enables or disables every mod with `tag` and returns how many were changed.
+    ///
+    /// Like toggling all mods, this doesn't take dependencies into account.
+    pub fn set_tag_state(&mut self, tag: &str, enable: bool) -> Result<usize> {
+        let uuids = self
+            .mods
+            .iter()
+            .filter(|profile_mod| {
+                profile_mod.enabled != enable && profile_mod.tags.iter().any(|t| t == tag)
+            })
+            .map(|profile_mod| profile_mod.uuid())
+            .collect_vec();
+
+        for uuid in &uuids {
+            self.force_toggle_mod(*uuid)?;
+        }
+
+        Ok(uuids.len())
+    }
+
+    /// All tags used in the profile, sorted alphabetically.
+    pub fn tags(&self) -> Vec<String> {
+        self.mods
+            .iter()
+            .flat_map(|profile_mod| profile_mod.tags.iter().cloned())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Moves a mod to `index` in the mod list, which is clamped to the list's bounds.
+    pub fn set_mod_index(&mut self, uuid: Uuid, index: usize) -> Result<()> {
+        let current = self
+            .mods
+            .iter()
+            .position(|m| m.uuid() == uuid)
+            .ok_or_eyre("mod not found in profile")?;
+
+        let profile_mod = self.mods.remove(current);
+        let index = index.min(self.mods.len());
+        self.mods.insert(index, profile_mod);
+
+        Ok(())
+    }
+
+    /// Writes the order of the mod list to wherever the mod loader reads it from.
+    ///
+    /// This is a no-op for loaders that don't support load ordering.
+    pub fn write_load_order(&self) -> Result<()> {
+        self.game
+            .mod_loader
+            .loader()
+            .write_load_order(self)
+            .context("failed to write load order")
+    }
 }
 
 fn handle_reorder_event(event: tauri::Event, app: &AppHandle) -> Result<()> {
@@ -240,7 +360,10 @@ fn handle_reorder_event(event: tauri::Event, app: &AppHandle) -> Result<()> {
 }
 
 fn handle_finish_reorder_event(app: &AppHandle) -> Result<()> {
-    app.lock_manager().save_active_profile(app.db())
+    let manager = app.read_manager();
+
+    manager.active_profile().write_load_order()?;
+    manager.save_active_profile(app.db())
 }
 
 impl ManagedGame {
@@ -310,6 +433,9 @@ impl ManagedGame {
             linked_config: HashMap::new(),
             modpack: None,
             sync_profile: None,
+            runtime_data: Default::default(),
+            group: None,
+            launch_overrides: Default::default(),
         });
 
         self.active_profile_id = id;
@@ -339,7 +465,7 @@ impl ManagedGame {
         let profile = self.profile_at(index)?;
         let id = profile.id;
 
-        fs::remove_dir_all(&profile.path)?;
+        util::fs::delete(&profile.path)?;
         self.profiles.remove(index);
 
         if !self.profiles.is_empty() {
@@ -382,14 +508,94 @@ impl ManagedGame {
 
         let mods = old_profile.mods.clone();
         let ignored_updates = old_profile.ignored_updates.clone();
+        let group = old_profile.group.clone();
+        let launch_overrides = old_profile.launch_overrides.clone();
 
         let new_profile = self.active_profile_mut();
         new_profile.mods = mods;
         new_profile.ignored_updates = ignored_updates;
+        new_profile.group = group;
+        new_profile.launch_overrides = launch_overrides;
 
         Ok(new_profile)
     }
 
+    pub fn create_profile_group(&mut self, name: String) -> Result<()> {
+        ensure!(!name.trim().is_empty(), "group name cannot be empty");
+        ensure!(
+            !self.profile_groups.contains(&name),
+            "group {} already exists",
+            name
+        );
+
+        self.profile_groups.push(name);
+
+        Ok(())
+    }
+
+    /// Renames a group, keeping its profiles in it.
+    ///
+    /// Returns the profiles that were moved, which need to be saved.
+    pub fn rename_profile_group(&mut self, name: &str, new_name: String) -> Result<Vec<i64>> {
+        ensure!(!new_name.trim().is_empty(), "group name cannot be empty");
+        ensure!(
+            !self.profile_groups.contains(&new_name),
+            "group {} already exists",
+            new_name
+        );
+
+        let group = self
+            .profile_groups
+            .iter_mut()
+            .find(|group| *group == name)
+            .ok_or_eyre("group not found")?;
+
+        *group = new_name.clone();
+
+        Ok(self.move_profiles_in_group(name, Some(new_name)))
+    }
+
+    /// Deletes a group. Its profiles are kept, but aren't in any group anymore.
+    ///
+    /// Returns the profiles that were moved, which need to be saved.
+    pub fn delete_profile_group(&mut self, name: &str) -> Result<Vec<i64>> {
+        let index = self
+            .profile_groups
+            .iter()
+            .position(|group| group == name)
+            .ok_or_eyre("group not found")?;
+
+        self.profile_groups.remove(index);
+
+        Ok(self.move_profiles_in_group(name, None))
+    }
+
+    fn move_profiles_in_group(&mut self, name: &str, new_group: Option<String>) -> Vec<i64> {
+        self.profiles
+            .iter_mut()
+            .filter(|profile| profile.group.as_deref() == Some(name))
+            .map(|profile| {
+                profile.group.clone_from(&new_group);
+                profile.id
+            })
+            .collect()
+    }
+
+    pub fn set_profile_group(&mut self, id: i64, group: Option<String>) -> Result<&Profile> {
+        if let Some(group) = &group {
+            ensure!(
+                self.profile_groups.contains(group),
+                "group {} does not exist",
+                group
+            );
+        }
+
+        let profile = self.find_profile_mut(id)?;
+        profile.group = group;
+
+        Ok(profile)
+    }
+
     pub fn create_desktop_shortcut(&self) -> Result<()> {
         let profile = self.active_profile();
 
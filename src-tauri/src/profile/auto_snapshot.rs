@@ -0,0 +1,212 @@
+//! Snapshots taken automatically before bulk changes, like updating or importing
+//! over a profile, so they can be undone.
+//!
+//! Snapshots are stored as regular profile exports in `snapshots/auto`,
+//! next to the modpack changelog snapshots. Each one has a `.hash` file
+//! with a fingerprint of the mod list and config files, so that nothing is
+//! captured if the profile hasn't changed since the last snapshot.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, OptionExt, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use super::{
+    export::{self, IncludeExtensions, IncludeGenerated},
+    import::{self, ImportOptions},
+    install::InstallOptions,
+    integrity, Profile,
+};
+use crate::{prefs::Prefs, state::ManagerExt, util};
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotReason {
+    Update,
+    Import,
+    Migration,
+}
+
+/// The payload of the `auto_snapshot` event.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoSnapshot {
+    pub profile_id: i64,
+    pub profile_name: String,
+    pub reason: SnapshotReason,
+    pub created_at: DateTime<Utc>,
+}
+
+fn snapshot_dir(profile: &Profile) -> PathBuf {
+    profile.path.join("snapshots").join("auto")
+}
+
+/// Captures a snapshot of `profile`, if enabled in the prefs, and removes
+/// the oldest ones past the retention count.
+///
+/// Emits an `auto_snapshot` event once done.
+pub fn capture(
+    profile: &Profile,
+    reason: SnapshotReason,
+    prefs: &Prefs,
+    app: &AppHandle,
+) -> Result<()> {
+    if !prefs.auto_snapshots || prefs.snapshot_retention == 0 {
+        return Ok(());
+    }
+
+    let dir = snapshot_dir(profile);
+    fs::create_dir_all(&dir).context("failed to create snapshot directory")?;
+
+    let hash = fingerprint(profile).context("failed to hash profile")?;
+
+    if let Some(latest) = find(&dir)?.pop() {
+        let latest_hash = fs::read_to_string(latest.with_extension("hash")).ok();
+
+        if latest_hash.as_deref() == Some(hash.to_hex().as_str()) {
+            info!(
+                "{} is unchanged since its last snapshot, skipping",
+                profile.name
+            );
+            return Ok(());
+        }
+    }
+
+    let created_at = Utc::now();
+    let path = dir
+        .join(created_at.format("%Y-%m-%d_%H-%M-%S%.3f").to_string())
+        .with_extension("r2z");
+
+    let mut temp_file = util::fs::temp_file_for(&path)?;
+    export::export_zip(profile, BufWriter::new(&mut temp_file), profile.game)?;
+    temp_file
+        .persist(&path)
+        .context("failed to save snapshot")?;
+
+    fs::write(path.with_extension("hash"), hash.to_hex().as_str())
+        .context("failed to save snapshot hash")?;
+
+    info!("captured snapshot of {} before {:?}", profile.name, reason);
+
+    if let Err(err) = prune(&dir, prefs.snapshot_retention) {
+        warn!("failed to remove old snapshots: {:#}", err);
+    }
+
+    app.emit(
+        "auto_snapshot",
+        AutoSnapshot {
+            profile_id: profile.id,
+            profile_name: profile.name.clone(),
+            reason,
+            created_at,
+        },
+    )
+    .ok();
+
+    Ok(())
+}
+
+/// Lists the snapshots in `dir`, oldest first.
+fn find(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = dir
+        .read_dir()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "r2z"))
+        .collect::<Vec<_>>();
+
+    // file names are timestamps, so they sort chronologically
+    paths.sort();
+
+    Ok(paths)
+}
+
+fn prune(dir: &Path, retention: usize) -> Result<()> {
+    let paths = find(dir)?;
+    let excess = paths.len().saturating_sub(retention);
+
+    for path in &paths[..excess] {
+        remove(path)?;
+    }
+
+    Ok(())
+}
+
+fn remove(path: &Path) -> Result<()> {
+    fs::remove_file(path)?;
+
+    let hash_path = path.with_extension("hash");
+    if hash_path.exists() {
+        fs::remove_file(hash_path)?;
+    }
+
+    Ok(())
+}
+
+/// Hashes the mod list and the config files that would be included in a snapshot.
+fn fingerprint(profile: &Profile) -> Result<blake3::Hash> {
+    let mut hasher = integrity::fingerprint(profile);
+
+    let mut config = export::find_config(
+        &profile.path,
+        IncludeExtensions::Default,
+        IncludeGenerated::No,
+    )
+    .filter(|path| !profile.runtime_data.matches(path))
+    .collect::<Vec<_>>();
+
+    config.sort();
+
+    for relative_path in config {
+        hasher.update(relative_path.to_string_lossy().as_bytes());
+
+        let mut file = File::open(profile.path.join(&relative_path))?;
+        io::copy(&mut file, &mut hasher)?;
+    }
+
+    Ok(hasher.finalize())
+}
+
+/// Restores the active profile to its latest automatic snapshot, then removes it.
+pub async fn restore_latest(app: &AppHandle) -> Result<()> {
+    let (name, path) = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        let path = find(&snapshot_dir(profile))?
+            .pop()
+            .ok_or_eyre("profile has no snapshots to restore")?;
+
+        (profile.name.clone(), path)
+    };
+
+    info!("restoring {} from {}", name, path.display());
+
+    let mut data = import::read_file_at_path(path.clone(), app)?;
+    // the profile may have been renamed since
+    data.manifest.name = name;
+
+    import::import_profile(
+        data,
+        InstallOptions::default(),
+        ImportOptions::default(),
+        app,
+    )
+    .await
+    .context("failed to restore snapshot")?;
+
+    remove(&path).context("failed to remove restored snapshot")?;
+
+    Ok(())
+}
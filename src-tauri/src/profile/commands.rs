@@ -7,13 +7,25 @@ use tauri::{command, AppHandle};
 use tracing::warn;
 use uuid::Uuid;
 
-use super::{actions::ActionResult, Dependant, Profile};
+use super::{
+    actions::ActionResult,
+    auto_snapshot,
+    disk_usage::{self, DiskUsage, DiskUsageTargets},
+    health::{self, HealthFix, HealthIssue, HealthTargets},
+    integrity,
+    orphans::{self, OrphanTargets, OrphanedFile},
+    template::{self, ProfileTemplate},
+    undo,
+    verify::{self, BrokenMod, VerifyTargets},
+    Dependant, ManagedGame, Profile, RuntimeDataOverrides,
+};
 use crate::{
+    db::Db,
     game::{self, Game, Platform},
     profile::{self},
     state::ManagerExt,
     thunderstore::{query::QueryModsArgs, FrontendProfileMod, Thunderstore, VersionIdent},
-    util::cmd::Result,
+    util::{self, cmd::Result},
 };
 
 #[derive(Serialize)]
@@ -24,6 +36,7 @@ pub struct FrontendGame {
     popular: bool,
     mod_loader: &'static str,
     platforms: Vec<Platform>,
+    dedicated_server: bool,
 }
 
 impl From<Game> for FrontendGame {
@@ -36,6 +49,7 @@ impl From<Game> for FrontendGame {
             popular: value.popular,
             mod_loader: value.mod_loader.to_str(),
             platforms,
+            dedicated_server: value.dedicated_server.is_some(),
         }
     }
 }
@@ -50,7 +64,7 @@ pub struct GameInfo {
 
 #[command]
 pub fn get_game_info(app: AppHandle) -> GameInfo {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let favorites = manager
         .games
@@ -101,6 +115,7 @@ pub fn set_active_game(slug: &str, app: AppHandle) -> Result<()> {
 pub struct ProfilesInfo {
     profiles: Vec<ProfileInfo>,
     active_id: i64,
+    groups: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -110,11 +125,12 @@ pub struct ProfileInfo {
     name: String,
     mod_count: usize,
     sync: Option<profile::sync::SyncProfileData>,
+    group: Option<String>,
 }
 
 #[command]
 pub fn get_profile_info(app: AppHandle) -> ProfilesInfo {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let game = manager.active_game();
 
     ProfilesInfo {
@@ -126,9 +142,11 @@ pub fn get_profile_info(app: AppHandle) -> ProfilesInfo {
                 name: profile.name.clone(),
                 mod_count: profile.mods.len(),
                 sync: profile.sync_profile.clone(),
+                group: profile.group.clone(),
             })
             .collect(),
         active_id: game.active_profile_id,
+        groups: game.profile_groups.clone(),
     }
 }
 
@@ -144,6 +162,141 @@ pub async fn set_active_profile(index: usize, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub async fn get_profile_disk_usage(app: AppHandle) -> Result<DiskUsage> {
+    let targets = DiskUsageTargets::for_active_profile(&app.read_manager());
+
+    let usage = tauri::async_runtime::spawn_blocking(move || disk_usage::measure(targets))
+        .await?
+        .context("failed to measure disk usage")?;
+
+    Ok(usage)
+}
+
+#[command]
+pub fn get_runtime_data_overrides(app: AppHandle) -> RuntimeDataOverrides {
+    app.read_manager().active_profile().runtime_data.clone()
+}
+
+/// Sets which paths in the active profile are treated as runtime data,
+/// which is left out of exports and import conflicts.
+#[command]
+pub fn set_runtime_data_overrides(overrides: RuntimeDataOverrides, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.runtime_data = overrides;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+/// Finds mods in the active profile with missing files.
+#[command]
+pub async fn verify_profile(app: AppHandle) -> Result<Vec<BrokenMod>> {
+    let targets = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        VerifyTargets::for_active_profile(&prefs, &manager)
+    };
+
+    let broken = tauri::async_runtime::spawn_blocking(move || verify::verify(targets))
+        .await?
+        .context("failed to verify profile")?;
+
+    Ok(broken)
+}
+
+/// Reinstalls mods found by `verify_profile`.
+#[command]
+pub async fn repair_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    verify::repair(uuids, &app).await?;
+
+    Ok(())
+}
+
+/// Checks the active profile for missing or disabled dependencies, outdated
+/// dependencies, broken links and empty cached packages.
+#[command]
+pub async fn diagnose_profile(app: AppHandle) -> Result<Vec<HealthIssue>> {
+    let (mut issues, targets) = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        (
+            health::check_mods(&manager, &thunderstore),
+            HealthTargets::for_active_profile(&prefs, &manager),
+        )
+    };
+
+    let file_issues = tauri::async_runtime::spawn_blocking(move || health::check_files(&targets))
+        .await?
+        .context("failed to check profile files")?;
+
+    issues.extend(file_issues);
+
+    Ok(issues)
+}
+
+/// Applies a fix for an issue found by `diagnose_profile`.
+#[command]
+pub async fn apply_health_fix(fix: HealthFix, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    health::apply(fix, &app).await?;
+
+    Ok(())
+}
+
+/// Finds files in the active profile that aren't claimed by any mod.
+#[command]
+pub async fn find_orphaned_files(app: AppHandle) -> Result<Vec<OrphanedFile>> {
+    let targets = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        OrphanTargets::for_active_profile(&prefs, &manager)
+    };
+
+    let orphans = tauri::async_runtime::spawn_blocking(move || orphans::find(&targets))
+        .await?
+        .context("failed to find orphaned files")?;
+
+    Ok(orphans)
+}
+
+/// Moves files found by `find_orphaned_files` to the trash.
+#[command]
+pub async fn remove_orphaned_files(paths: Vec<PathBuf>, app: AppHandle) -> Result<usize> {
+    let targets = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        OrphanTargets::for_active_profile(&prefs, &manager)
+    };
+
+    let removed = tauri::async_runtime::spawn_blocking(move || orphans::remove(&paths, &targets))
+        .await?
+        .context("failed to remove orphaned files")?;
+
+    Ok(removed)
+}
+
+/// Undoes the last update, import or r2modman migration of the active profile,
+/// using the snapshot that was taken before it.
+#[command]
+pub async fn restore_last_snapshot(app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    auto_snapshot::restore_latest(&app).await?;
+
+    Ok(())
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FrontendAvailableUpdate {
@@ -160,13 +313,14 @@ pub struct FrontendAvailableUpdate {
 pub struct ProfileQuery {
     mods: Vec<FrontendProfileMod>,
     total_mod_count: usize,
+    tags: Vec<String>,
     updates: Vec<FrontendAvailableUpdate>,
     unknown_mods: Vec<Dependant>,
 }
 
 #[command]
 pub fn query_profile(args: QueryModsArgs, app: AppHandle) -> Result<ProfileQuery> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let thunderstore = app.lock_thunderstore();
 
     let profile = manager.active_profile();
@@ -198,11 +352,70 @@ pub fn query_profile(args: QueryModsArgs, app: AppHandle) -> Result<ProfileQuery
         .unwrap_or_else(|err| {
             warn!("failed to check for updates: {:#}", err);
             Vec::new()
-        });
+        })
+        .into_iter()
+        .chain(profile.nexus_mods().filter_map(|(nexus_mod, _)| {
+            let latest = nexus_mod.latest_file.as_ref()?;
+            let version_uuid = nexus_mod.latest_file_uuid()?;
+
+            let parse = |version: Option<&str>| {
+                version
+                    .and_then(util::parse_lenient_version)
+                    .unwrap_or(semver::Version::new(0, 0, 0))
+            };
+
+            Some(FrontendAvailableUpdate {
+                full_name: nexus_mod.ident(),
+                package_uuid: nexus_mod.uuid,
+                version_uuid,
+                old: parse(nexus_mod.version.as_deref()),
+                new: parse(latest.version.as_deref()),
+                ignore: profile.ignored_updates.contains(&version_uuid),
+            })
+        }))
+        .chain(profile.local_mods().filter_map(|(local_mod, _)| {
+            let latest = local_mod.latest_update.as_ref()?;
+
+            Some(FrontendAvailableUpdate {
+                full_name: local_mod.ident(),
+                package_uuid: local_mod.uuid,
+                version_uuid: latest.uuid,
+                old: local_mod
+                    .version
+                    .clone()
+                    .unwrap_or(semver::Version::new(0, 0, 0)),
+                new: latest.version.clone(),
+                ignore: profile.ignored_updates.contains(&latest.uuid),
+            })
+        }))
+        .chain(profile.github_mods().filter_map(|(github_mod, _)| {
+            let latest = github_mod.latest_release.as_ref()?;
+
+            let parse = |tag: &str| {
+                util::parse_lenient_version(tag).unwrap_or(semver::Version::new(0, 0, 0))
+            };
+
+            Some(FrontendAvailableUpdate {
+                full_name: github_mod.ident(),
+                package_uuid: github_mod.uuid,
+                version_uuid: latest.uuid,
+                old: parse(&github_mod.tag),
+                new: parse(&latest.tag),
+                ignore: profile.ignored_updates.contains(&latest.uuid),
+            })
+        }))
+        .filter(|update| {
+            // thunderstore updates are already filtered by `check_update`
+            !profile
+                .get_mod(update.package_uuid)
+                .is_ok_and(|profile_mod| profile_mod.pinned)
+        })
+        .collect();
 
     Ok(ProfileQuery {
         mods,
         total_mod_count,
+        tags: profile.tags(),
         updates,
         unknown_mods,
     })
@@ -210,7 +423,7 @@ pub fn query_profile(args: QueryModsArgs, app: AppHandle) -> Result<ProfileQuery
 
 #[command]
 pub fn is_mod_installed(uuid: Uuid, app: AppHandle) -> Result<bool> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let result = manager.active_profile().has_mod(uuid);
 
@@ -218,7 +431,19 @@ pub fn is_mod_installed(uuid: Uuid, app: AppHandle) -> Result<bool> {
 }
 
 #[command]
-pub fn create_profile(name: String, override_path: Option<PathBuf>, app: AppHandle) -> Result<()> {
+pub async fn create_profile(
+    name: String,
+    override_path: Option<PathBuf>,
+    template_id: Option<i64>,
+    app: AppHandle,
+) -> Result<()> {
+    util::cancel::reset(&app);
+
+    if let Some(id) = template_id {
+        template::create_profile(id, name, override_path, &app).await?;
+        return Ok(());
+    }
+
     let mut manager = app.lock_manager();
     let game = manager.active_game_mut();
 
@@ -230,6 +455,31 @@ pub fn create_profile(name: String, override_path: Option<PathBuf>, app: AppHand
     Ok(())
 }
 
+#[command]
+pub fn get_profile_templates(app: AppHandle) -> Result<Vec<ProfileTemplate>> {
+    let templates = template::list(&app)?;
+
+    Ok(templates)
+}
+
+#[command]
+pub fn save_profile_template(
+    name: String,
+    include_config: bool,
+    app: AppHandle,
+) -> Result<ProfileTemplate> {
+    let template = template::save(name, include_config, &app)?;
+
+    Ok(template)
+}
+
+#[command]
+pub fn delete_profile_template(id: i64, app: AppHandle) -> Result<()> {
+    app.db().delete_template(id)?;
+
+    Ok(())
+}
+
 #[command]
 pub fn delete_profile(index: usize, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
@@ -265,11 +515,113 @@ pub fn duplicate_profile(name: String, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Returns the [`integrity::hash`] of the active profile.
+#[command]
+pub fn get_profile_hash(app: AppHandle) -> String {
+    let manager = app.read_manager();
+
+    integrity::hash(manager.active_profile())
+}
+
+#[command]
+pub fn create_profile_group(name: String, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game_mut();
+
+    game.create_profile_group(name)?;
+    game.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn rename_profile_group(name: String, new_name: String, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game_mut();
+
+    let moved = game.rename_profile_group(&name, new_name)?;
+    save_group_change(game, &moved, app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn delete_profile_group(name: String, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game_mut();
+
+    let moved = game.delete_profile_group(&name)?;
+    save_group_change(game, &moved, app.db())?;
+
+    Ok(())
+}
+
+fn save_group_change(game: &ManagedGame, moved: &[i64], db: &Db) -> eyre::Result<()> {
+    for id in moved {
+        game.find_profile(*id)?.save(db)?;
+    }
+
+    game.save(db)
+}
+
+#[command]
+pub fn set_profile_group(id: i64, group: Option<String>, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_game_mut().set_profile_group(id, group)?;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
 #[command]
 pub fn remove_mod(uuid: Uuid, app: AppHandle) -> Result<ActionResult> {
-    mod_action_command(app, |profile, thunderstore| {
-        profile.remove_mod(uuid, thunderstore)
-    })
+    let mut manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let profile = manager.active_profile_mut();
+    let description = match profile.get_mod(uuid) {
+        Ok(profile_mod) => format!("remove {}", profile_mod.full_name()),
+        Err(_) => "remove mod".to_owned(),
+    };
+
+    let (response, entry) = undo::record_removal(profile, &[uuid], description, |profile| {
+        profile.remove_mod(uuid, &thunderstore)
+    })?;
+
+    if let ActionResult::Done = response {
+        profile.save(app.db())?;
+    }
+
+    if let Some(entry) = entry {
+        app.lock_undo_stack().push(entry);
+    }
+
+    Ok(response)
+}
+
+#[command]
+pub fn set_mod_index(uuid: Uuid, index: usize, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.set_mod_index(uuid, index)?;
+    profile.write_load_order()?;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn move_mod(uuid: Uuid, delta: i32, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.reorder_mod(uuid, delta)?;
+    profile.write_load_order()?;
+    profile.save(app.db())?;
+
+    Ok(())
 }
 
 #[command]
@@ -301,15 +653,79 @@ pub fn force_remove_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
 
     let profile = manager.active_profile_mut();
-    for package_uuid in uuids {
-        profile.force_remove_mod(package_uuid)?;
+    let description = format!("remove {} mods", uuids.len());
+
+    let ((), entry) = undo::record_removal(profile, &uuids, description, |profile| {
+        for &package_uuid in &uuids {
+            profile.force_remove_mod(package_uuid)?;
+        }
+        Ok(())
+    })?;
+
+    profile.save(app.db())?;
+
+    if let Some(entry) = entry {
+        app.lock_undo_stack().push(entry);
     }
 
+    Ok(())
+}
+
+#[command]
+pub fn pin_mod(uuid: Uuid, pinned: bool, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.pin_mod(uuid, pinned)?;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn set_mod_note(uuid: Uuid, note: Option<String>, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.set_mod_note(uuid, note)?;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn tag_mods(uuids: Vec<Uuid>, tag: String, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.tag_mods(&uuids, &tag)?;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn untag_mods(uuids: Vec<Uuid>, tag: String, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.untag_mods(&uuids, &tag)?;
     profile.save(app.db())?;
 
     Ok(())
 }
 
+#[command]
+pub fn set_tag_state(tag: String, enable: bool, app: AppHandle) -> Result<usize> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    let count = profile.set_tag_state(&tag, enable)?;
+    profile.save(app.db())?;
+
+    Ok(count)
+}
+
 #[command]
 pub fn set_all_mods_state(enable: bool, app: AppHandle) -> Result<usize> {
     let mut manager = app.lock_manager();
@@ -347,15 +763,31 @@ pub fn remove_disabled_mods(app: AppHandle) -> Result<usize> {
 
     let len = uuids.len();
 
-    for uuid in uuids {
-        profile.force_remove_mod(uuid)?;
-    }
+    let ((), entry) = undo::record_removal(profile, &uuids, "remove disabled mods", |profile| {
+        for &uuid in &uuids {
+            profile.force_remove_mod(uuid)?;
+        }
+        Ok(())
+    })?;
 
     profile.save(app.db())?;
 
+    if let Some(entry) = entry {
+        app.lock_undo_stack().push(entry);
+    }
+
     Ok(len)
 }
 
+#[command]
+pub fn undo_last_action(app: AppHandle) -> Result<String> {
+    let mut manager = app.lock_manager();
+
+    let description = app.lock_undo_stack().undo(&mut manager, app.db())?;
+
+    Ok(description)
+}
+
 #[command]
 pub fn force_toggle_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
@@ -372,7 +804,7 @@ pub fn force_toggle_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn get_dependants(uuid: Uuid, app: AppHandle) -> Result<Vec<VersionIdent>> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let thunderstore = app.lock_thunderstore();
 
     let dependants = manager
@@ -386,7 +818,7 @@ pub fn get_dependants(uuid: Uuid, app: AppHandle) -> Result<Vec<VersionIdent>> {
 
 #[command]
 pub fn open_profile_dir(app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let path = &manager.active_profile().path;
     open::that(path).context("failed to open directory")?;
@@ -396,7 +828,7 @@ pub fn open_profile_dir(app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn open_mod_dir(uuid: Uuid, app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     manager.active_profile().open_mod_dir(uuid)?;
 
@@ -405,7 +837,7 @@ pub fn open_mod_dir(uuid: Uuid, app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn open_game_log(app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let path = manager.active_profile().log_path()?;
     open::that_detached(path).context("failed to open log file")?;
@@ -415,7 +847,7 @@ pub fn open_game_log(app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn create_desktop_shortcut(app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     manager.active_game().create_desktop_shortcut()?;
 
@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::ModManager;
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    pub total: u64,
+    pub config: u64,
+    /// Files that don't belong to any mod folder or the config directory.
+    pub other: u64,
+    /// Sorted by size in descending order.
+    pub mods: Vec<ModDiskUsage>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModDiskUsage {
+    pub uuid: Uuid,
+    pub full_name: String,
+    pub size: u64,
+}
+
+/// The directories to measure in a profile, collected while the manager is locked.
+pub struct DiskUsageTargets {
+    root: PathBuf,
+    config_dir: PathBuf,
+    mod_dirs: Vec<(Uuid, String, PathBuf)>,
+}
+
+impl DiskUsageTargets {
+    pub fn for_active_profile(manager: &ModManager) -> Self {
+        let mod_loader = manager.active_mod_loader();
+        let profile = manager.active_profile();
+
+        let mod_dirs = profile
+            .mods
+            .iter()
+            .filter_map(|profile_mod| {
+                let package_name = profile_mod.full_name();
+                let dir = mod_loader
+                    .installer_for(&package_name)
+                    .mod_dir(&package_name, profile)?;
+
+                Some((profile_mod.uuid(), package_name.into_owned(), dir))
+            })
+            .collect();
+
+        Self {
+            root: profile.path.clone(),
+            config_dir: profile.path.join(mod_loader.config_path()),
+            mod_dirs,
+        }
+    }
+}
+
+/// Walks the profile directory and sums up the size of each part.
+///
+/// This can take a while for large profiles, so avoid calling it on the main thread.
+pub fn measure(targets: DiskUsageTargets) -> Result<DiskUsage> {
+    let mut usage = DiskUsage::default();
+
+    let mod_indices = targets
+        .mod_dirs
+        .iter()
+        .enumerate()
+        .map(|(index, (_, _, dir))| (dir.as_path(), index))
+        .collect::<HashMap<&Path, _>>();
+
+    let mut mod_sizes = vec![0; targets.mod_dirs.len()];
+
+    for entry in WalkDir::new(&targets.root) {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let size = entry.metadata()?.len();
+        usage.total += size;
+
+        let path = entry.path();
+
+        let mod_index = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| *dir != targets.root)
+            .find_map(|dir| mod_indices.get(dir));
+
+        if let Some(&index) = mod_index {
+            mod_sizes[index] += size;
+        } else if path.starts_with(&targets.config_dir) {
+            usage.config += size;
+        } else {
+            usage.other += size;
+        }
+    }
+
+    usage.mods = targets
+        .mod_dirs
+        .into_iter()
+        .zip(mod_sizes)
+        .map(|((uuid, full_name, _), size)| ModDiskUsage {
+            uuid,
+            full_name,
+            size,
+        })
+        .collect();
+
+    usage
+        .mods
+        .sort_by_key(|usage| std::cmp::Reverse(usage.size));
+
+    Ok(usage)
+}
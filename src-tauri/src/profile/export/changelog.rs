@@ -103,19 +103,23 @@ pub(super) fn generate_latest(
         .parse()
         .context("invalid version number")?;
 
-    let latest_snapshot = profile
-        .find_snapshots()?
-        .filter(|(_, v)| *v < version)
-        .max_by(|(_, a), (_, b)| a.cmp(b))
-        .map(|(entry, _)| util::fs::read_json::<Vec<ModId>>(entry.path()))
-        .transpose()?;
-
-    let latest_snapshot = match latest_snapshot {
-        Some(snapshot) => snapshot,
-        None => bail!("no previous version found to compare against"),
+    let old_mods = match published_mods(args, &version, thunderstore) {
+        Some(mods) => mods,
+        None => {
+            let latest_snapshot = profile
+                .find_snapshots()?
+                .filter(|(_, v)| *v < version)
+                .max_by(|(_, a), (_, b)| a.cmp(b))
+                .map(|(entry, _)| util::fs::read_json::<Vec<ModId>>(entry.path()))
+                .transpose()?;
+
+            match latest_snapshot {
+                Some(snapshot) => borrow_mods(snapshot, thunderstore),
+                None => bail!("no previous version found to compare against"),
+            }
+        }
     };
 
-    let old_mods = borrow_mods(latest_snapshot, thunderstore);
     let current_mods = borrow_mods(profile.mods_to_pack(args).cloned(), thunderstore);
 
     let version_header = format!("## {}", args.version_number);
@@ -159,6 +163,31 @@ pub(super) fn generate_latest(
     Ok(())
 }
 
+/// Finds the dependencies of the newest published version of the modpack
+/// that's older than `version`, if the modpack is on Thunderstore.
+fn published_mods<'a>(
+    args: &ModpackArgs,
+    version: &semver::Version,
+    thunderstore: &'a Thunderstore,
+) -> Option<Vec<BorrowedMod<'a>>> {
+    let full_name = format!("{}-{}", args.author, args.name);
+    let package = thunderstore.find_package(&full_name).ok()?;
+
+    let previous = package
+        .versions
+        .iter()
+        .filter(|pkg_version| pkg_version.parsed_version() < *version)
+        .max_by_key(|pkg_version| pkg_version.parsed_version())?;
+
+    let mods = previous
+        .dependencies
+        .iter()
+        .filter_map(|ident| thunderstore.find_ident(ident).ok())
+        .collect();
+
+    Some(mods)
+}
+
 fn borrow_mods<T>(mods: T, thunderstore: &Thunderstore) -> Vec<BorrowedMod<'_>>
 where
     T: IntoIterator<Item = ModId>,
@@ -190,23 +219,28 @@ impl Profile {
             return Ok(Box::new(iter::empty()));
         }
 
-        let iter = path.read_dir()?.filter_map(Result::ok).filter_map(|entry| {
-            match entry
-                .file_name()
-                .to_string_lossy()
-                .trim_end_matches(".json")
-                .parse::<semver::Version>()
-            {
-                Ok(version) => Some((entry, version)),
-                _ => {
-                    warn!(
-                        "snapshot file is not a valid version (at {})",
-                        entry.path().display()
-                    );
-                    None
+        let iter = path
+            .read_dir()?
+            .filter_map(Result::ok)
+            // automatic snapshots are stored in a subdirectory
+            .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_file()))
+            .filter_map(|entry| {
+                match entry
+                    .file_name()
+                    .to_string_lossy()
+                    .trim_end_matches(".json")
+                    .parse::<semver::Version>()
+                {
+                    Ok(version) => Some((entry, version)),
+                    _ => {
+                        warn!(
+                            "snapshot file is not a valid version (at {})",
+                            entry.path().display()
+                        );
+                        None
+                    }
                 }
-            }
-        });
+            });
 
         Ok(Box::new(iter))
     }
@@ -258,10 +292,11 @@ fn generate_diff(old: &[BorrowedMod<'_>], new: &[BorrowedMod<'_>], game: Game) -
         updated.into_iter(),
         |(old, new)| {
             format!(
-                "{} {} ⇒ {}",
+                "{} {} ⇒ {} ({})",
                 package_link(old.package, game),
                 old.ident().version(),
-                new.ident().version()
+                new.ident().version(),
+                markdown_link(format!("{}changelog/", old.package.url(game)), "changelog")
             )
         },
     );
@@ -0,0 +1,86 @@
+//! Codes created by exporting profiles, remembered so users can tell which code
+//! matches which version of a profile.
+//!
+//! Thunderstore has no way to delete a code, so forgetting one only removes it
+//! from the list. Codes still expire on their own after a while.
+
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use reqwest::StatusCode;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::Db,
+    profile::{integrity, ManagedGame},
+};
+
+#[derive(Debug, Clone)]
+pub struct ExportCodeRecord {
+    pub code: Uuid,
+    pub profile_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub fingerprint: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCode {
+    pub code: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub profile_name: String,
+    /// Whether the profile's mods have changed since the code was created.
+    pub outdated: bool,
+}
+
+/// Remembers a code that was just created, with the [`integrity::hash`] of the
+/// profile at the time it was exported.
+pub fn record(code: Uuid, profile_id: i64, fingerprint: String, db: &Db) -> Result<()> {
+    db.insert_export_code(&ExportCodeRecord {
+        code,
+        profile_id,
+        created_at: Utc::now(),
+        fingerprint,
+    })
+}
+
+/// Lists the codes of every profile of `game`, newest first.
+pub fn list(game: &ManagedGame, db: &Db) -> Result<Vec<ExportCode>> {
+    let mut codes = Vec::new();
+
+    for profile in &game.profiles {
+        let fingerprint = integrity::hash(profile);
+
+        codes.extend(
+            db.get_export_codes(profile.id)?
+                .into_iter()
+                .map(|record| ExportCode {
+                    code: record.code,
+                    created_at: record.created_at,
+                    profile_name: profile.name.clone(),
+                    outdated: record.fingerprint != fingerprint,
+                }),
+        );
+    }
+
+    codes.sort_by_key(|code| std::cmp::Reverse(code.created_at));
+
+    Ok(codes)
+}
+
+/// Checks if a code can still be imported, or if it has expired.
+pub async fn is_available(code: Uuid, client: &reqwest::Client) -> Result<bool> {
+    let response = client
+        .get(format!(
+            "https://thunderstore.io/api/experimental/legacyprofile/get/{code}/"
+        ))
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+
+    response.error_for_status()?;
+    Ok(true)
+}
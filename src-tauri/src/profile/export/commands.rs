@@ -13,13 +13,20 @@ use uuid::Uuid;
 
 use super::{
     changelog,
+    codes::{self, ExportCode},
+    data::{self, DataFormat},
+    modlist::{self, ModListOptions},
     modpack::{self, ModpackArgs},
+    r2modman::{R2Export, R2ExportResult},
+    readme,
+    stats::{self, ModpackStats},
+    validate::{self, Issues, PackIssue},
 };
 use crate::{
     profile::ProfileModKind,
     state::ManagerExt,
     thunderstore::{self},
-    util::{cmd::Result, fs::PathExt},
+    util::{self, cmd::Result, fs::PathExt},
 };
 
 #[command]
@@ -29,26 +36,132 @@ pub async fn export_code(app: AppHandle) -> Result<Uuid> {
     Ok(key)
 }
 
+/// Lists the codes previously exported from the profiles of the active game.
 #[command]
-pub fn export_file(dir: PathBuf, app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+pub fn get_export_codes(app: AppHandle) -> Result<Vec<ExportCode>> {
+    let manager = app.read_manager();
 
-    let game = manager.active_game().game;
-    let profile = manager.active_profile();
+    let codes = codes::list(manager.active_game(), app.db())?;
+    Ok(codes)
+}
 
-    let mut path = dir;
-    path.push(&profile.name);
-    path.set_extension("r2z");
+/// Checks if an export code can still be imported.
+#[command]
+pub async fn check_export_code(code: Uuid, app: AppHandle) -> Result<bool> {
+    let available = codes::is_available(code, app.http()).await?;
+
+    Ok(available)
+}
+
+/// Removes an export code from the list. The code itself stays valid until it expires.
+#[command]
+pub fn forget_export_code(code: Uuid, app: AppHandle) -> Result<()> {
+    app.db().delete_export_code(code)?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn export_file(dir: PathBuf, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    let handle = app.clone();
+    let path = tauri::async_runtime::spawn_blocking(move || -> eyre::Result<PathBuf> {
+        let manager = handle.read_manager();
+
+        let game = manager.active_game().game;
+        let profile = manager.active_profile();
+
+        let mut path = dir;
+        path.push(&profile.name);
+        path.set_extension("r2z");
+
+        let mut temp_file = util::fs::temp_file_for(&path)?;
+        super::export_zip(
+            profile,
+            util::cancel::Writer::new(BufWriter::new(&mut temp_file), &handle),
+            game,
+        )?;
+        temp_file
+            .persist(&path)
+            .context("failed to save exported file")?;
+
+        Ok(path)
+    })
+    .await??;
+
+    open::that(path.parent().unwrap()).ok();
+
+    Ok(())
+}
+
+/// Exports only the config files of the active profile to a zip in `dir`.
+#[command]
+pub fn export_config(dir: PathBuf, app: AppHandle) -> Result<()> {
+    let manager = app.read_manager();
 
-    let file = fs::File::create(&path).map_err(|err| anyhow!(err))?;
-    let writer = BufWriter::new(file);
-    super::export_zip(manager.active_profile(), writer, game)?;
+    let profile = manager.active_profile();
+    let path = dir.join(format!("{} config.zip", profile.name));
+
+    let mut temp_file = util::fs::temp_file_for(&path)?;
+    super::export_config_zip(profile, BufWriter::new(&mut temp_file))?;
+    temp_file
+        .persist(&path)
+        .context("failed to save exported file")?;
 
     open::that(path.parent().unwrap()).ok();
 
     Ok(())
 }
 
+/// Copies the active profile's mods and config into `dir`, for use without Gale.
+///
+/// Returns the number of files that were copied.
+#[command]
+pub async fn export_to_directory(dir: PathBuf, app: AppHandle) -> Result<usize> {
+    let (src, runtime_data) = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        (profile.path.clone(), profile.runtime_data.clone())
+    };
+
+    util::cancel::reset(&app);
+
+    let handle = app.clone();
+    let count = tauri::async_runtime::spawn_blocking(move || {
+        super::directory::export(&src, &dir, &runtime_data, &handle)
+    })
+    .await?
+    .context("failed to export profile")?;
+
+    Ok(count)
+}
+
+/// Exports the active profile into an r2modman or Thunderstore Mod Manager data directory,
+/// so that it shows up in that manager. Returns the path of the exported profile
+/// and the mods that had to be left out of its mod list.
+#[command]
+pub async fn export_r2modman(data_dir: PathBuf, app: AppHandle) -> Result<R2ExportResult> {
+    let export = {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        R2Export::new(
+            manager.active_profile(),
+            manager.active_game().game,
+            &data_dir,
+            &thunderstore,
+        )?
+    };
+
+    let result = tauri::async_runtime::spawn_blocking(move || export.write())
+        .await?
+        .context("failed to export profile")?;
+
+    Ok(result)
+}
+
 #[command]
 pub fn get_pack_args(app: AppHandle) -> Result<Option<ModpackArgs>> {
     let mut manager = app.lock_manager();
@@ -71,22 +184,35 @@ pub fn set_pack_args(args: ModpackArgs, app: AppHandle) -> Result<()> {
 }
 
 #[command]
-pub fn export_pack(dir: PathBuf, args: ModpackArgs, app: AppHandle) -> Result<()> {
+pub async fn export_pack(dir: PathBuf, mut args: ModpackArgs, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+    prepare_icon(&args, &app).await?;
+
+    let prefs = app.lock_prefs();
     let mut manager = app.lock_manager();
     let thunderstore = app.lock_thunderstore();
 
+    let game = manager.active_game().game;
     let profile = manager.active_profile_mut();
 
+    readme::refresh(&mut args, profile, game, &thunderstore);
+
     let mut path = dir;
     path.push(format!("{}-{}", args.name, args.version_number));
     path.add_ext("zip");
 
     debug!("exporting pack to {:?}", path);
 
-    let file = fs::File::create(&path)
-        .map(BufWriter::new)
-        .context("failed to create file")?;
-    profile.export_pack(&args, file, &thunderstore)?;
+    let mut temp_file = util::fs::temp_file_for(&path)?;
+    profile.export_pack(
+        &args,
+        util::cancel::Writer::new(BufWriter::new(&mut temp_file), &app),
+        &thunderstore,
+        &prefs,
+    )?;
+    temp_file
+        .persist(&path)
+        .context("failed to save exported file")?;
 
     debug!("taking snapshot of profile");
 
@@ -100,9 +226,26 @@ pub fn export_pack(dir: PathBuf, args: ModpackArgs, app: AppHandle) -> Result<()
 }
 
 #[command]
-pub async fn upload_pack(args: ModpackArgs, app: AppHandle) -> Result<()> {
+pub async fn upload_pack(mut args: ModpackArgs, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    let game = app.lock_manager().active_game;
+
+    for (community, categories) in args.community_categories(game) {
+        match thunderstore::categories::get(community, &app).await {
+            Ok(valid) => thunderstore::categories::validate(community, categories, &valid)?,
+            Err(err) => warn!(
+                "failed to get categories for {}, skipping validation: {:#}",
+                community, err
+            ),
+        }
+    }
+
+    prepare_icon(&args, &app).await?;
+
     let (data, game, args, token) = {
-        let manager = app.lock_manager();
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
         let thunderstore = app.lock_thunderstore();
 
         let token = thunderstore::token::get()
@@ -111,8 +254,21 @@ pub async fn upload_pack(args: ModpackArgs, app: AppHandle) -> Result<()> {
 
         let profile = manager.active_profile();
 
+        readme::refresh(
+            &mut args,
+            profile,
+            manager.active_game().game,
+            &thunderstore,
+        );
+
+        validate::check_args(&args, profile, &thunderstore).ensure_valid()?;
+
         let mut data = Cursor::new(Vec::new());
-        profile.export_pack(&args, &mut data, &thunderstore)?;
+        profile.export_pack(&args, &mut data, &thunderstore, &prefs)?;
+
+        let mut issues = Issues::default();
+        validate::check_size(data.get_ref().len(), &mut issues);
+        issues.ensure_valid()?;
 
         if let Err(err) = profile.take_snapshot(&args) {
             warn!("failed to take profile snapshot: {}", err);
@@ -121,22 +277,83 @@ pub async fn upload_pack(args: ModpackArgs, app: AppHandle) -> Result<()> {
         (data, manager.active_game, args, token)
     };
 
-    let client = app.http().clone();
-    modpack::publish(data.into_inner().into(), game, args, token, client).await?;
+    modpack::publish(data.into_inner().into(), game, args, token, &app).await?;
+
+    Ok(())
+}
+
+/// Checks the modpack against Thunderstore's submission rules without uploading it.
+#[command]
+pub async fn validate_pack(mut args: ModpackArgs, app: AppHandle) -> Result<Vec<PackIssue>> {
+    let game = app.lock_manager().active_game;
+
+    let mut category_errors = Vec::new();
+    for (community, categories) in args.community_categories(game) {
+        if let Ok(valid) = thunderstore::categories::get(community, &app).await {
+            if let Err(err) = thunderstore::categories::validate(community, categories, &valid) {
+                category_errors.push(err.to_string());
+            }
+        }
+    }
+
+    // an invalid icon is reported below
+    prepare_icon(&args, &app).await.ok();
+
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let profile = manager.active_profile();
+
+    readme::refresh(
+        &mut args,
+        profile,
+        manager.active_game().game,
+        &thunderstore,
+    );
+
+    let mut issues = validate::check_args(&args, profile, &thunderstore);
+
+    for message in category_errors {
+        issues.error("categories", message);
+    }
+
+    // building the pack is only worth it if nothing else is wrong
+    if !issues.has_errors() {
+        let mut data = Cursor::new(Vec::new());
+
+        match profile.export_pack(&args, &mut data, &thunderstore, &prefs) {
+            Ok(()) => validate::check_size(data.get_ref().len(), &mut issues),
+            Err(err) => issues.error("includeFileMap", format!("{:#}", err)),
+        }
+    }
+
+    Ok(issues.into_vec())
+}
+
+/// Processes the pack's icon in the background, so exporting only has to read it from the cache.
+async fn prepare_icon(args: &ModpackArgs, app: &AppHandle) -> eyre::Result<()> {
+    let icon_path = args.icon_path.clone();
+    let cache_dir = app.lock_prefs().cache_dir();
+
+    tauri::async_runtime::spawn_blocking(move || super::icon::process(&icon_path, &cache_dir))
+        .await?
+        .context("failed to process icon")?;
 
     Ok(())
 }
 
 #[command]
 pub fn copy_dependency_strings(app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
-    let content = manager
-        .active_profile()
-        .mods
-        .iter()
-        .map(|profile_mod| profile_mod.ident())
-        .join("\n");
+    let content = super::dependency_strings(
+        manager
+            .active_profile()
+            .mods
+            .iter()
+            .map(|profile_mod| profile_mod.ident()),
+    );
 
     app.clipboard()
         .write_text(content)
@@ -145,9 +362,56 @@ pub fn copy_dependency_strings(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Writes a human-readable mod list of the active profile to `path`,
+/// or copies it to the clipboard if no path is given.
+#[command]
+pub fn export_modlist(
+    options: ModListOptions,
+    path: Option<PathBuf>,
+    app: AppHandle,
+) -> Result<()> {
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let content = modlist::generate(
+        manager.active_profile(),
+        manager.active_game,
+        &thunderstore,
+        &options,
+    );
+
+    match path {
+        Some(path) => fs::write(&path, content).context("failed to write mod list")?,
+        None => app
+            .clipboard()
+            .write_text(content)
+            .context("failed to write to clipboard")?,
+    }
+
+    Ok(())
+}
+
+/// Writes the active profile's mods to `path` as JSON or CSV.
+#[command]
+pub fn export_profile_data(format: DataFormat, path: PathBuf, app: AppHandle) -> Result<()> {
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let content = data::generate(
+        manager.active_profile(),
+        manager.active_game,
+        &thunderstore,
+        format,
+    )?;
+
+    fs::write(&path, content).context("failed to write profile data")?;
+
+    Ok(())
+}
+
 #[command]
 pub fn copy_debug_info(app: AppHandle) -> Result<()> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let profile = manager.active_profile();
 
     let log = profile
@@ -166,6 +430,8 @@ pub fn copy_debug_info(app: AppHandle) -> Result<()> {
                 let ty = match &profile_mod.kind {
                     ProfileModKind::Thunderstore(_) => "thunderstore",
                     ProfileModKind::Local(_) => "local",
+                    ProfileModKind::Nexus(_) => "nexus",
+                    ProfileModKind::Github(_) => "github",
                 };
 
                 format!("{} [{}]", profile_mod.ident(), ty)
@@ -186,7 +452,7 @@ pub fn copy_debug_info(app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn generate_changelog(mut args: ModpackArgs, all: bool, app: AppHandle) -> Result<String> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let thunderstore = app.lock_thunderstore();
 
     if all {
@@ -209,3 +475,44 @@ pub fn generate_changelog(mut args: ModpackArgs, all: bool, app: AppHandle) -> R
         Ok(args.changelog)
     }
 }
+
+#[command]
+pub fn generate_readme(mut args: ModpackArgs, app: AppHandle) -> Result<String> {
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    readme::insert(
+        &mut args,
+        manager.active_profile(),
+        manager.active_game().game,
+        &thunderstore,
+    );
+
+    Ok(args.readme)
+}
+
+/// Returns the recorded download and rating stats of the active profile's modpack.
+///
+/// If `refresh` is set, the current stats are fetched from Thunderstore first.
+#[command]
+pub async fn get_modpack_stats(refresh: bool, app: AppHandle) -> Result<ModpackStats> {
+    let package = {
+        let manager = app.read_manager();
+
+        let args = manager
+            .active_profile()
+            .modpack
+            .as_ref()
+            .ok_or_else(|| anyhow!("the profile has no modpack"))?;
+
+        stats::package_name(args)?
+    };
+
+    if refresh {
+        stats::refresh(&package, app.http(), app.db())
+            .await
+            .context("failed to fetch modpack stats")?;
+    }
+
+    Ok(stats::get(package, app.db())?)
+}
@@ -0,0 +1,136 @@
+//! Machine-readable exports of a profile's mods, for spreadsheets and other tools.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    game::Game,
+    profile::{Profile, ProfileModKind},
+    thunderstore::Thunderstore,
+};
+
+const CSV_HEADER: &str = "uuid,full_name,source,owner,name,version,enabled,install_time,file_size";
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DataFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ProfileData<'a> {
+    profile: &'a str,
+    game: &'a str,
+    exported_at: DateTime<Utc>,
+    mods: Vec<ModData>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ModData {
+    uuid: Uuid,
+    full_name: String,
+    /// Where the mod was installed from.
+    source: &'static str,
+    owner: String,
+    name: String,
+    version: String,
+    enabled: bool,
+    install_time: DateTime<Utc>,
+    /// In bytes, or 0 if unknown.
+    file_size: u64,
+}
+
+pub fn generate(
+    profile: &Profile,
+    game: Game,
+    thunderstore: &Thunderstore,
+    format: DataFormat,
+) -> Result<String> {
+    let mods = profile
+        .mods
+        .iter()
+        .map(|profile_mod| {
+            let ident = profile_mod.ident();
+
+            let (source, file_size) = match &profile_mod.kind {
+                ProfileModKind::Thunderstore(ts_mod) => (
+                    "thunderstore",
+                    ts_mod
+                        .id
+                        .borrow(thunderstore)
+                        .map(|borrowed| borrowed.version.file_size)
+                        .unwrap_or(0),
+                ),
+                ProfileModKind::Local(local_mod) => ("local", local_mod.file_size),
+                ProfileModKind::Nexus(nexus_mod) => ("nexus", nexus_mod.file_size),
+                ProfileModKind::Github(github_mod) => ("github", github_mod.file_size),
+            };
+
+            ModData {
+                uuid: profile_mod.uuid(),
+                full_name: ident.to_string(),
+                source,
+                owner: ident.owner().to_owned(),
+                name: ident.name().to_owned(),
+                version: ident.version().to_owned(),
+                enabled: profile_mod.enabled,
+                install_time: profile_mod.install_time,
+                file_size,
+            }
+        })
+        .collect_vec();
+
+    match format {
+        DataFormat::Json => {
+            let data = ProfileData {
+                profile: &profile.name,
+                game: &game.slug,
+                exported_at: Utc::now(),
+                mods,
+            };
+
+            Ok(serde_json::to_string_pretty(&data)?)
+        }
+        DataFormat::Csv => Ok(csv(&mods)),
+    }
+}
+
+fn csv(mods: &[ModData]) -> String {
+    let mut text = String::new();
+    writeln!(text, "{}", CSV_HEADER).unwrap();
+
+    for data in mods {
+        writeln!(
+            text,
+            "{},{},{},{},{},{},{},{},{}",
+            data.uuid,
+            csv_field(&data.full_name),
+            data.source,
+            csv_field(&data.owner),
+            csv_field(&data.name),
+            csv_field(&data.version),
+            data.enabled,
+            data.install_time.to_rfc3339(),
+            data.file_size
+        )
+        .unwrap();
+    }
+
+    text
+}
+
+/// Quotes `str` if it contains anything that would break the row.
+fn csv_field(str: &str) -> String {
+    match str.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", str.replace('"', "\"\"")),
+        false => str.to_owned(),
+    }
+}
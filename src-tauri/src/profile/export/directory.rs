@@ -0,0 +1,87 @@
+use std::{fs, path::Path};
+
+use eyre::{ensure, Result};
+use tauri::AppHandle;
+use tracing::info;
+use walkdir::WalkDir;
+
+use crate::{
+    profile::RuntimeDataOverrides,
+    util::{self, error::IoResultExt},
+};
+
+/// Top-level profile entries that are only needed by Gale.
+const EXCLUDED: &[&str] = &["_state", "snapshots", "profile.json", "mods.yml"];
+
+/// Copies the files needed to run a profile into `dest`, which can be any folder,
+/// like a game or server installation on another machine.
+///
+/// Files of disabled mods and runtime data are skipped, and existing files in `dest` are overwritten.
+/// Everything is copied (never linked), so the result doesn't depend on Gale's cache.
+///
+/// Returns the number of files that were copied. If the export is cancelled,
+/// the files copied so far are left in `dest`.
+pub fn export(
+    src: &Path,
+    dest: &Path,
+    runtime_data: &RuntimeDataOverrides,
+    app: &AppHandle,
+) -> Result<usize> {
+    ensure!(
+        !dest.starts_with(src),
+        "cannot export a profile into its own directory"
+    );
+
+    info!("exporting {} to {}", src.display(), dest.display());
+
+    let entries = WalkDir::new(src)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative_path = entry.path().strip_prefix(src).unwrap();
+
+            (entry.depth() > 1
+                || !EXCLUDED
+                    .iter()
+                    .any(|excluded| entry.file_name() == *excluded))
+                && !runtime_data.matches(relative_path)
+        });
+
+    let mut count = 0;
+
+    for entry in entries {
+        util::cancel::check(app)?;
+
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        // disabled mods have an `.old` extension added to their files
+        if entry.path().extension().is_some_and(|ext| ext == "old") {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(src)
+            .expect("WalkDir should only return paths inside of the root");
+        let target = dest.join(relative_path);
+
+        let parent = target.parent().unwrap();
+        fs::create_dir_all(parent).fs_context("creating directory", parent)?;
+
+        // remove first, in case the target is a hard link to somewhere else
+        if target.exists() {
+            fs::remove_file(&target).fs_context("removing file", &target)?;
+        }
+
+        fs::copy(entry.path(), &target).fs_context("copying file", &target)?;
+        count += 1;
+    }
+
+    info!("exported {} files", count);
+
+    Ok(count)
+}
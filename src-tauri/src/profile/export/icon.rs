@@ -0,0 +1,85 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, Cursor},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, OptionExt, Result};
+use image::{
+    codecs::{gif::GifDecoder, webp::WebPDecoder},
+    imageops::FilterType,
+    AnimationDecoder, DynamicImage, ImageFormat, ImageReader,
+};
+use tracing::debug;
+
+use crate::util::error::IoResultExt;
+
+const ICON_SIZE: u32 = 256;
+
+/// Converts an image to a 256x256 PNG, as required by Thunderstore.
+///
+/// The result is cached in `cache_dir` by the hash of the source file,
+/// so repeated exports with the same icon skip the decoding and resizing.
+/// This can be slow for large images, so avoid calling it on the main thread.
+pub fn process(src: &Path, cache_dir: &Path) -> Result<PathBuf> {
+    let data = fs::read(src).fs_context("reading icon", src)?;
+    let hash = blake3::hash(&data);
+
+    let dir = cache_dir.join("icons");
+    let path = dir.join(format!("{}.png", hash.to_hex()));
+
+    if path.exists() {
+        return Ok(path);
+    }
+
+    debug!("processing icon at {}", src.display());
+
+    let img = decode(data)?.resize_exact(ICON_SIZE, ICON_SIZE, FilterType::Lanczos3);
+
+    fs::create_dir_all(&dir).fs_context("creating icon cache", &dir)?;
+
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    fs::write(&path, bytes).fs_context("writing icon", &path)?;
+
+    Ok(path)
+}
+
+/// Opens the processed version of `src`, processing it first if it isn't cached.
+pub fn open(src: &Path, cache_dir: &Path) -> Result<BufReader<File>> {
+    let path = process(src, cache_dir)?;
+    let file = File::open(&path).fs_context("opening icon", &path)?;
+
+    Ok(BufReader::new(file))
+}
+
+fn decode(data: Vec<u8>) -> Result<DynamicImage> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .context("failed to read image")?;
+
+    // animated images only use their first frame
+    let img = match reader.format() {
+        Some(ImageFormat::Gif) => first_frame(GifDecoder::new(reader.into_inner())?)?,
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(reader.into_inner())?;
+
+            match decoder.has_animation() {
+                true => first_frame(decoder)?,
+                false => DynamicImage::from_decoder(decoder)?,
+            }
+        }
+        _ => reader.decode().context("unsupported image format")?,
+    };
+
+    Ok(img)
+}
+
+fn first_frame<'a>(decoder: impl AnimationDecoder<'a>) -> Result<DynamicImage> {
+    let frame = decoder
+        .into_frames()
+        .next()
+        .ok_or_eyre("image has no frames")??;
+
+    Ok(DynamicImage::ImageRgba8(frame.into_buffer()))
+}
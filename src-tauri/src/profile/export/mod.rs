@@ -1,19 +1,22 @@
 use std::{
     fmt::Display,
-    fs::File,
-    io::{self, Cursor, Seek, Write},
+    fs::{self, File},
+    io::{self, BufWriter, Cursor, Seek, Write},
     path::{Path, PathBuf},
 };
 
 use base64::{prelude::BASE64_STANDARD, Engine};
 use eyre::{eyre, Context};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
+use tempfile::NamedTempFile;
+use tracing::warn;
 use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::{write::SimpleFileOptions, ZipWriter};
 
-use super::{install::ModInstall, Profile, Result};
+use super::{install::ModInstall, integrity, Profile, Result};
 use crate::{
     game::Game,
     state::ManagerExt,
@@ -21,8 +24,18 @@ use crate::{
 };
 
 mod changelog;
+pub mod codes;
 pub mod commands;
+pub mod data;
+mod directory;
+mod icon;
+pub mod modlist;
 pub mod modpack;
+mod pending_upload;
+mod r2modman;
+mod readme;
+pub mod stats;
+pub mod validate;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +47,9 @@ pub struct ProfileManifest {
     pub game: Option<String>,
     #[serde(default)]
     pub ignored_updates: Vec<Uuid>,
+    /// The [`integrity::hash`] of the exported mods, for players to compare.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -47,6 +63,13 @@ pub struct R2Mod {
 
 impl R2Mod {
     pub fn into_install(self, thunderstore: &Thunderstore) -> Result<ModInstall> {
+        let id = self.resolve(thunderstore)?;
+
+        Ok(ModInstall::new(id).with_state(self.enabled))
+    }
+
+    /// Finds the exact package and version on Thunderstore.
+    pub fn resolve(&self, thunderstore: &Thunderstore) -> Result<ModId> {
         let package = thunderstore.find_package(self.full_name.as_str())?;
 
         let version = self.version.to_string();
@@ -58,12 +81,10 @@ impl R2Mod {
             )
         })?;
 
-        let id = ModId {
+        Ok(ModId {
             package_uuid: package.uuid,
             version_uuid: version.uuid,
-        };
-
-        Ok(ModInstall::new(id).with_state(self.enabled))
+        })
     }
 
     pub fn ident(&self) -> VersionIdent {
@@ -100,6 +121,38 @@ pub const PROFILE_DATA_PREFIX: &str = "#r2modman\n";
 pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Game) -> Result<()> {
     let mut zip = ZipWriter::new(writer);
 
+    write_manifest(profile, game, &mut zip)?;
+    write_config(
+        exported_config(profile),
+        &profile.path,
+        &mut zip,
+        SimpleFileOptions::default(),
+    )?;
+
+    zip.finish()?.flush()?;
+
+    Ok(())
+}
+
+/// Writes only the manifest of a profile to a zip, without any config files.
+pub(super) fn export_mods_zip(
+    profile: &Profile,
+    writer: impl Write + Seek,
+    game: Game,
+) -> Result<()> {
+    let mut zip = ZipWriter::new(writer);
+
+    write_manifest(profile, game, &mut zip)?;
+
+    zip.finish()?.flush()?;
+
+    Ok(())
+}
+
+pub(super) fn write_manifest<W>(profile: &Profile, game: Game, zip: &mut ZipWriter<W>) -> Result<()>
+where
+    W: Write + Seek,
+{
     let mods = profile
         .thunderstore_mods()
         .map(|(ts_mod, enabled)| {
@@ -123,27 +176,60 @@ pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Gam
         name: profile.name.clone(),
         ignored_updates: profile.ignored_updates.iter().cloned().collect(),
         game: Some(game.slug.to_string()),
+        hash: Some(integrity::hash(profile)),
         mods,
     };
 
     zip.start_file("export.r2x", SimpleFileOptions::default())?;
-    serde_yaml::to_writer(&mut zip, &manifest).context("failed to write profile manifest")?;
+    serde_yaml::to_writer(zip, &manifest).context("failed to write profile manifest")?;
+
+    Ok(())
+}
+
+/// Writes only the config files of a profile to a zip, the same ones [`export_zip`] includes.
+pub(super) fn export_config_zip(profile: &Profile, writer: impl Write + Seek) -> Result<()> {
+    let mut zip = ZipWriter::new(writer);
 
     write_config(
-        find_config(
-            &profile.path,
-            IncludeExtensions::Default,
-            IncludeGenerated::No,
-        ),
+        exported_config(profile),
         &profile.path,
         &mut zip,
+        SimpleFileOptions::default(),
     )?;
 
+    zip.finish()?.flush()?;
+
     Ok(())
 }
 
+pub(super) fn exported_config(profile: &Profile) -> impl Iterator<Item = PathBuf> + '_ {
+    find_config(
+        &profile.path,
+        IncludeExtensions::Default,
+        IncludeGenerated::No,
+    )
+    .filter(|path| !profile.runtime_data.matches(path))
+}
+
+/// Exports a profile to a temporary file in `temp_dir`.
+///
+/// Use this instead of an in-memory buffer when the archive is sent elsewhere,
+/// since profiles with large config files may not fit in memory.
+pub(super) fn export_zip_to_temp(
+    profile: &Profile,
+    game: Game,
+    temp_dir: &Path,
+) -> Result<NamedTempFile> {
+    fs::create_dir_all(temp_dir).context("failed to create temp directory")?;
+
+    let mut file = NamedTempFile::new_in(temp_dir).context("failed to create temp file")?;
+    export_zip(profile, BufWriter::new(file.as_file_mut()), game)?;
+
+    Ok(file)
+}
+
 async fn export_code(app: &AppHandle) -> Result<Uuid> {
-    let base64 = {
+    let (base64, profile_id, fingerprint) = {
         let mut manager = app.lock_manager();
 
         let game = manager.active_game().game;
@@ -156,7 +242,7 @@ async fn export_code(app: &AppHandle) -> Result<Uuid> {
         let mut base64 = String::from(PROFILE_DATA_PREFIX);
         base64.push_str(&BASE64_STANDARD.encode(data.get_ref()));
 
-        base64
+        (base64, profile.id, integrity::hash(profile))
     };
 
     const URL: &str = "https://thunderstore.io/api/experimental/legacyprofile/create/";
@@ -172,10 +258,19 @@ async fn export_code(app: &AppHandle) -> Result<Uuid> {
         .json::<LegacyProfileCreateResponse>()
         .await?;
 
+    if let Err(err) = codes::record(response.key, profile_id, fingerprint, app.db()) {
+        warn!("failed to remember export code: {:#}", err);
+    }
+
     Ok(response.key)
 }
 
-fn write_config<P, I, W>(files: I, source: &Path, zip: &mut ZipWriter<W>) -> Result<()>
+pub(super) fn write_config<P, I, W>(
+    files: I,
+    source: &Path,
+    zip: &mut ZipWriter<W>,
+    options: SimpleFileOptions,
+) -> Result<()>
 where
     P: AsRef<Path>,
     I: Iterator<Item = P>,
@@ -183,9 +278,13 @@ where
 {
     for file in files {
         let path = file.as_ref().to_string_lossy().replace('\\', "/");
-        zip.start_file(path, SimpleFileOptions::default())?;
 
         let mut reader = File::open(source.join(file))?;
+        let size = reader.metadata()?.len();
+
+        // zip64 is required for entries over 4 GiB
+        let options = options.large_file(size >= u32::MAX as u64);
+        zip.start_file(path, options)?;
 
         io::copy(&mut reader, zip)?;
     }
@@ -193,7 +292,7 @@ where
     Ok(())
 }
 
-const COMMON_EXTENSIONS: &[&str] = &["cfg", "txt", "json", "yml", "yaml", "ini", "xml"];
+const COMMON_EXTENSIONS: &[&str] = &["cfg", "txt", "json", "yml", "yaml", "toml", "ini", "xml"];
 
 const GENERATED_FILES: &[&str] = &[
     "profile.json",
@@ -231,15 +330,29 @@ pub fn find_config(
         .filter(|entry| entry.file_type().is_file())
         .map(move |entry| entry.into_path().strip_prefix(root).unwrap().to_path_buf())
         .filter(move |path| {
-            matches!(include_generated, IncludeGenerated::Yes)
-                || !GENERATED_FILES
-                    .iter()
-                    .any(|exc| path.starts_with(exc) || path.ends_with(exc))
+            matches!(include_generated, IncludeGenerated::Yes) || !is_generated(path)
         })
         .filter(move |path| {
-            matches!(include_extensions, IncludeExtensions::All)
-                || path
-                    .extension()
-                    .is_some_and(|ext| COMMON_EXTENSIONS.iter().any(|inc| *inc == ext))
+            matches!(include_extensions, IncludeExtensions::All) || has_config_extension(path)
         })
 }
+
+/// Formats mods as newline-separated dependency strings, which can be installed
+/// again with [`install::resolve_dependency_strings`](super::install::resolve_dependency_strings).
+pub(crate) fn dependency_strings(idents: impl IntoIterator<Item = impl Display>) -> String {
+    idents.into_iter().join("\n")
+}
+
+/// Whether a path, relative to the profile, is a file generated by a mod manager
+/// (see [`GENERATED_FILES`]).
+pub fn is_generated(relative_path: &Path) -> bool {
+    GENERATED_FILES
+        .iter()
+        .any(|exc| relative_path.starts_with(exc) || relative_path.ends_with(exc))
+}
+
+/// Whether a path has a common config extension (see [`COMMON_EXTENSIONS`]).
+pub fn has_config_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| COMMON_EXTENSIONS.iter().any(|inc| *inc == ext))
+}
@@ -0,0 +1,203 @@
+//! Human-readable mod lists, for posting on forums or in a modpack's docs.
+//!
+//! Unlike the dependency strings, these include names, authors and links,
+//! formatted as either Markdown or a standalone HTML page.
+
+use std::fmt::Write;
+
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::{
+    game::Game,
+    profile::{Profile, ProfileModKind},
+    thunderstore::{FrontendMod, IntoFrontendMod, Thunderstore},
+};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModListFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModListOptions {
+    pub format: ModListFormat,
+    pub include_disabled: bool,
+    pub include_descriptions: bool,
+}
+
+struct Entry {
+    name: String,
+    version: Option<String>,
+    author: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+    enabled: bool,
+}
+
+pub fn generate(
+    profile: &Profile,
+    game: Game,
+    thunderstore: &Thunderstore,
+    options: &ModListOptions,
+) -> String {
+    let entries = entries(profile, game, thunderstore)
+        .into_iter()
+        .filter(|entry| entry.enabled || options.include_disabled)
+        .sorted_by_key(|entry| entry.name.to_lowercase())
+        .collect_vec();
+
+    match options.format {
+        ModListFormat::Markdown => markdown(profile, game, &entries, options),
+        ModListFormat::Html => html(profile, game, &entries, options),
+    }
+}
+
+fn entries(profile: &Profile, game: Game, thunderstore: &Thunderstore) -> Vec<Entry> {
+    profile
+        .mods
+        .iter()
+        .map(|profile_mod| {
+            let (data, url): (FrontendMod, _) = match &profile_mod.kind {
+                ProfileModKind::Thunderstore(ts_mod) => match ts_mod.id.borrow(thunderstore) {
+                    Ok(borrowed) => {
+                        let url = borrowed.package.url(game);
+                        (borrowed.into_frontend(None), Some(url))
+                    }
+                    // removed from thunderstore, so there's nothing to link to
+                    Err(_) => (ts_mod.clone().into(), None),
+                },
+                ProfileModKind::Local(local_mod) => {
+                    let data: FrontendMod = (**local_mod).clone().into();
+                    let url = data.website_url.clone();
+                    (data, url)
+                }
+                ProfileModKind::Nexus(nexus_mod) => {
+                    ((**nexus_mod).clone().into(), Some(nexus_mod.url()))
+                }
+                ProfileModKind::Github(github_mod) => {
+                    ((**github_mod).clone().into(), Some(github_mod.url()))
+                }
+            };
+
+            Entry {
+                name: data.name.replace('_', " "),
+                version: data.version.map(|version| version.to_string()),
+                author: data.author.filter(|author| !author.is_empty()),
+                url,
+                description: data
+                    .description
+                    .filter(|description| !description.trim().is_empty()),
+                enabled: profile_mod.enabled,
+            }
+        })
+        .collect()
+}
+
+fn markdown(profile: &Profile, game: Game, entries: &[Entry], options: &ModListOptions) -> String {
+    let mut text = String::new();
+
+    writeln!(text, "# {}\n", profile.name).unwrap();
+    writeln!(text, "{} mods for {}\n", entries.len(), game.name).unwrap();
+
+    for entry in entries {
+        match &entry.url {
+            Some(url) => write!(text, "- [{}]({})", entry.name, url),
+            None => write!(text, "- {}", entry.name),
+        }
+        .unwrap();
+
+        if let Some(version) = &entry.version {
+            write!(text, " `{}`", version).unwrap();
+        }
+
+        if let Some(author) = &entry.author {
+            write!(text, " by {}", author).unwrap();
+        }
+
+        if !entry.enabled {
+            text.push_str(" *(disabled)*");
+        }
+
+        text.push('\n');
+
+        if let Some(description) = entry
+            .description
+            .as_ref()
+            .filter(|_| options.include_descriptions)
+        {
+            writeln!(text, "  {}", description.trim()).unwrap();
+        }
+    }
+
+    text
+}
+
+fn html(profile: &Profile, game: Game, entries: &[Entry], options: &ModListOptions) -> String {
+    let mut text = String::new();
+
+    writeln!(
+        text,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>",
+        escape(&profile.name)
+    )
+    .unwrap();
+    writeln!(text, "<h1>{}</h1>", escape(&profile.name)).unwrap();
+    writeln!(
+        text,
+        "<p>{} mods for {}</p>\n<ul>",
+        entries.len(),
+        escape(game.name)
+    )
+    .unwrap();
+
+    for entry in entries {
+        text.push_str("<li>");
+
+        match &entry.url {
+            Some(url) => write!(
+                text,
+                "<a href=\"{}\">{}</a>",
+                escape(url),
+                escape(&entry.name)
+            ),
+            None => write!(text, "{}", escape(&entry.name)),
+        }
+        .unwrap();
+
+        if let Some(version) = &entry.version {
+            write!(text, " <code>{}</code>", escape(version)).unwrap();
+        }
+
+        if let Some(author) = &entry.author {
+            write!(text, " by {}", escape(author)).unwrap();
+        }
+
+        if !entry.enabled {
+            text.push_str(" <em>(disabled)</em>");
+        }
+
+        if let Some(description) = entry
+            .description
+            .as_ref()
+            .filter(|_| options.include_descriptions)
+        {
+            write!(text, "<br>{}", escape(description.trim())).unwrap();
+        }
+
+        text.push_str("</li>\n");
+    }
+
+    text.push_str("</ul>\n</body>\n</html>\n");
+    text
+}
+
+fn escape(str: &str) -> String {
+    str.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,25 +1,36 @@
 use std::{
     collections::HashMap,
     fmt::Display,
-    io::{Cursor, Seek, Write},
+    io::{self, Seek, Write},
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use bytes::Bytes;
-use eyre::{anyhow, bail, ensure, eyre, Context, OptionExt, Result};
+use eyre::{bail, ensure, eyre, Context, OptionExt, Result};
 use futures_util::future::try_join_all;
-use image::{imageops::FilterType, ImageFormat};
 use itertools::Itertools;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use tauri::Url;
-use tracing::{debug, info, trace};
+use tauri::{AppHandle, Emitter, Url};
+use tracing::{debug, info, trace, warn};
 use uuid::Uuid;
 use zip::{write::SimpleFileOptions, ZipWriter};
 
-use crate::{game::Game, profile::Profile, thunderstore::*};
+use crate::{
+    game::Game,
+    prefs::Prefs,
+    profile::{install, Profile},
+    state::ManagerExt,
+    thunderstore::{credits::PackageCredits, *},
+    util,
+};
+
+use super::{icon, pending_upload::PendingUpload, IncludeExtensions, IncludeGenerated};
 
-use super::{IncludeExtensions, IncludeGenerated};
+const MAX_CHUNK_RETRIES: usize = 4;
+const CHUNK_RETRY_DELAY: Duration = Duration::from_secs(1);
 
 pub fn refresh_args(profile: &mut Profile) {
     if profile.modpack.is_none() {
@@ -42,7 +53,8 @@ pub fn refresh_args(profile: &mut Profile) {
         IncludeExtensions::Default,
         IncludeGenerated::No,
     ) {
-        includes.entry(path).or_insert(true);
+        let include = !profile.runtime_data.matches(&path);
+        includes.entry(path).or_insert(include);
     }
 }
 
@@ -63,6 +75,27 @@ pub struct ModpackArgs {
     pub include_disabled: bool,
     #[serde(default, rename = "includeFileMap")]
     pub include_files: HashMap<PathBuf, bool>,
+    /// Other communities to publish the modpack to, besides the active game's,
+    /// mapped to the categories to use in each.
+    #[serde(default)]
+    pub additional_communities: HashMap<String, Vec<String>>,
+}
+
+impl ModpackArgs {
+    /// The categories of each community the modpack is published to,
+    /// starting with the active game's.
+    pub fn community_categories(&self, game: Game) -> Vec<(&str, &[String])> {
+        let mut communities = vec![(&*game.slug, self.categories.as_slice())];
+
+        communities.extend(
+            self.additional_communities
+                .iter()
+                .filter(|(slug, _)| **slug != game.slug)
+                .map(|(slug, categories)| (slug.as_str(), categories.as_slice())),
+        );
+
+        communities
+    }
 }
 
 impl Profile {
@@ -80,6 +113,7 @@ impl Profile {
         args: &ModpackArgs,
         writer: impl Write + Seek,
         thunderstore: &Thunderstore,
+        prefs: &Prefs,
     ) -> Result<()> {
         ensure!(!args.name.is_empty(), "name cannot be empty");
         ensure!(!args.description.is_empty(), "description cannot be empty");
@@ -110,46 +144,111 @@ impl Profile {
 
         if !args.readme.is_empty() {
             trace!("writing readme");
-            zip.start_file("README.md", SimpleFileOptions::default())?;
+            zip.start_file("README.md", file_options())?;
             zip.write_all(args.readme.as_bytes())?;
         }
 
         if !args.changelog.is_empty() {
             trace!("writing changelog");
-            zip.start_file("CHANGELOG.md", SimpleFileOptions::default())?;
+            zip.start_file("CHANGELOG.md", file_options())?;
             zip.write_all(args.changelog.as_bytes())?;
         }
 
+        let credits = self.credits(args, thunderstore, prefs)?;
+        if !credits.is_empty() {
+            trace!("writing credits");
+            zip.start_file("CREDITS.md", file_options())?;
+            zip.write_all(credits.as_bytes())?;
+        }
+
         trace!("writing manifest");
-        zip.start_file("manifest.json", SimpleFileOptions::default())?;
+        zip.start_file("manifest.json", file_options())?;
         serde_json::to_writer_pretty(&mut zip, &manifest)?;
 
-        write_icon(&args.icon_path, &mut zip).context("failed to write icon")?;
+        write_icon(&args.icon_path, &mut zip, prefs).context("failed to write icon")?;
 
         super::write_config(
             args.include_files
                 .iter()
                 .filter(|(_, enabled)| **enabled)
-                .map(|(file, _)| file),
+                .map(|(file, _)| file)
+                .sorted(),
             &self.path,
             &mut zip,
+            file_options(),
         )?;
 
         Ok(())
     }
+
+    /// Generates a markdown list of the packed mods with their authors, licenses and sources.
+    ///
+    /// License info is only available for mods in the download cache.
+    fn credits(
+        &self,
+        args: &ModpackArgs,
+        thunderstore: &Thunderstore,
+        prefs: &Prefs,
+    ) -> Result<String> {
+        let mut entries = self
+            .mods_to_pack(args)
+            .map(|mod_ref| mod_ref.borrow(thunderstore))
+            .collect::<Result<Vec<_>>>()?;
+
+        if entries.is_empty() {
+            return Ok(String::new());
+        }
+
+        entries.sort_by(|a, b| util::cmp_ignore_case(a.package.name(), b.package.name()));
+
+        let mut credits = String::from("# Credits\n\n");
+
+        for borrowed in entries {
+            let cache_path = install::cache::path(borrowed.ident(), prefs);
+            let PackageCredits {
+                license,
+                source_url,
+            } = match cache_path.exists() {
+                true => PackageCredits::from_dir(&cache_path, borrowed.version),
+                false => PackageCredits::from_readme(borrowed.version, None),
+            };
+
+            credits.push_str(&format!(
+                "- [{}]({}) by {}",
+                borrowed.package.name(),
+                borrowed.package.url(self.game),
+                borrowed.package.owner()
+            ));
+
+            if let Some(license) = license {
+                credits.push_str(&format!(", licensed under {}", license));
+            }
+
+            if let Some(source_url) = source_url {
+                credits.push_str(&format!(" ([source]({}))", source_url));
+            }
+
+            credits.push('\n');
+        }
+
+        Ok(credits)
+    }
 }
 
-fn write_icon<W>(path: &Path, zip: &mut ZipWriter<W>) -> Result<()>
+/// Entries are stamped with a fixed time instead of the current one, so packing
+/// the same modpack again gives the same zip and a failed upload can be resumed.
+fn file_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().last_modified_time(zip::DateTime::default())
+}
+
+fn write_icon<W>(path: &Path, zip: &mut ZipWriter<W>, prefs: &Prefs) -> Result<()>
 where
     W: Write + Seek,
 {
-    let img = image::ImageReader::open(path)?.decode()?;
-    let img = img.resize_exact(256, 256, FilterType::Lanczos3);
+    let mut icon = icon::open(path, &prefs.cache_dir())?;
 
-    let mut bytes = Vec::new();
-    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
-    zip.start_file("icon.png", SimpleFileOptions::default())?;
-    zip.write_all(&bytes)?;
+    zip.start_file("icon.png", file_options())?;
+    io::copy(&mut icon, zip)?;
 
     Ok(())
 }
@@ -169,7 +268,7 @@ pub async fn publish(
     game: Game,
     args: ModpackArgs,
     token: String,
-    client: reqwest::Client,
+    app: &AppHandle,
 ) -> Result<()> {
     ensure!(args.description.len() <= 250, "description is too long");
     ensure!(!args.readme.is_empty(), "readme cannot be empty");
@@ -181,34 +280,95 @@ pub async fn publish(
 
     info!("publishing modpack");
 
-    let response = initiate_upload(args.name.clone(), data.len() as u64, &token, &client)
-        .await
-        .context("failed to initiate upload")?;
+    let client = app.http().clone();
 
-    let uuid = response.user_media.uuid.ok_or_eyre("no uuid in response")?;
+    let (pending, resumed) = match PendingUpload::load(&data, &token) {
+        Some(pending) => {
+            info!(
+                "resuming upload with {} of {} parts done",
+                pending.completed.len(),
+                pending.upload_urls.len()
+            );
 
-    let tasks = response.upload_urls.into_iter().map(|part| {
-        let data = data.clone();
-        let client = client.clone();
-        tauri::async_runtime::spawn(upload_chunk(part, data, client))
-    });
+            (pending, true)
+        }
+        None => {
+            let response = util::cancel::abortable(
+                initiate_upload(args.name.clone(), data.len() as u64, &token, &client),
+                app,
+            )
+            .await
+            .context("failed to initiate upload")?;
+
+            let pending = PendingUpload {
+                uuid: response.user_media.uuid.ok_or_eyre("no uuid in response")?,
+                hash: PendingUpload::hash(&data),
+                token_hash: PendingUpload::hash(token.as_bytes()),
+                upload_urls: response.upload_urls,
+                completed: Vec::new(),
+            };
+            pending.save();
+
+            (pending, false)
+        }
+    };
+
+    let uuid = pending.uuid;
+    let remaining = pending.remaining().cloned().collect_vec();
+    let attempt = Attempt {
+        started: Instant::now(),
+        resumed_bytes: pending.uploaded_bytes(),
+    };
+    let pending = Arc::new(Mutex::new(pending));
+
+    let mut tasks = remaining
+        .into_iter()
+        .map(|part| {
+            tauri::async_runtime::spawn(upload_chunk(
+                part,
+                data.clone(),
+                client.clone(),
+                pending.clone(),
+                attempt,
+                app.clone(),
+            ))
+        })
+        .collect::<Vec<_>>();
 
-    let parts = match try_join_all(tasks)
+    let result = util::cancel::abortable(try_join_all(tasks.iter_mut()), app)
         .await
-        .map_err(|err| anyhow!(err))
-        .and_then(|parts| parts.into_iter().collect::<Result<Vec<_>>>())
-    {
-        Ok(parts) => parts,
-        Err(err) => {
+        .and_then(|results| results.into_iter().collect::<Result<()>>())
+        // the upload can't be undone once it's finalized
+        .and_then(|_| util::cancel::check(app));
+
+    if let Err(err) = result {
+        // the chunks keep uploading in the background otherwise
+        for task in &tasks {
+            task.abort();
+        }
+
+        // keep the upload around to resume it next time, unless it's
+        // been cancelled or rejected by thunderstore
+        if util::cancel::is_cancel_error(&err) || is_client_error(&err) {
+            PendingUpload::clear();
             tauri::async_runtime::spawn(async move { abort_upload(&uuid, &token, client).await });
-            return Err(err.wrap_err("failed to upload file"));
         }
-    };
+
+        return match resumed {
+            true => Err(err.wrap_err("failed to resume upload")),
+            false => Err(err.wrap_err("failed to upload file")),
+        };
+    }
+
+    let mut parts = pending.lock().unwrap().completed.clone();
+    parts.sort_by_key(|part| part.part_number);
 
     finish_upload(parts, &uuid, &token, &client)
         .await
         .context("failed to finalize upload")?;
 
+    PendingUpload::clear();
+
     submit_package(uuid, game, args, &token, &client)
         .await
         .context("failed to submit package")?;
@@ -243,11 +403,119 @@ async fn initiate_upload(
     Ok(response)
 }
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+enum PartState {
+    Uploading,
+    Retrying { attempt: usize },
+    Done,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UploadProgress {
+    part_number: u32,
+    state: PartState,
+    completed_parts: usize,
+    total_parts: usize,
+    /// Includes the parts uploaded by an earlier attempt that was resumed.
+    uploaded_bytes: u64,
+    /// Only the bytes uploaded by this attempt, to go with `duration_secs`.
+    attempt_bytes: u64,
+    total_bytes: u64,
+    /// Time since this attempt at uploading started.
+    duration_secs: f32,
+}
+
+/// The current attempt at uploading a modpack, which may resume an earlier one.
+#[derive(Clone, Copy)]
+struct Attempt {
+    started: Instant,
+    /// Bytes that were already uploaded when this attempt started.
+    resumed_bytes: u64,
+}
+
+fn emit_progress(
+    part_number: u32,
+    state: PartState,
+    pending: &PendingUpload,
+    attempt: Attempt,
+    app: &AppHandle,
+) {
+    let uploaded_bytes = pending.uploaded_bytes();
+
+    let progress = UploadProgress {
+        part_number,
+        state,
+        completed_parts: pending.completed.len(),
+        total_parts: pending.upload_urls.len(),
+        uploaded_bytes,
+        attempt_bytes: uploaded_bytes.saturating_sub(attempt.resumed_bytes),
+        total_bytes: pending.total_bytes(),
+        duration_secs: attempt.started.elapsed().as_secs_f32(),
+    };
+
+    app.emit("upload_progress", progress).ok();
+}
+
+/// Uploads a part, retrying with exponential backoff if it fails.
 async fn upload_chunk(
     part: UploadPartUrl,
     data: Bytes,
     client: reqwest::Client,
-) -> Result<CompletedPart> {
+    pending: Arc<Mutex<PendingUpload>>,
+    upload_attempt: Attempt,
+    app: AppHandle,
+) -> Result<()> {
+    let mut attempt = 0;
+
+    let tag = loop {
+        let state = match attempt {
+            0 => PartState::Uploading,
+            _ => PartState::Retrying { attempt },
+        };
+        {
+            let pending = pending.lock().unwrap();
+            emit_progress(part.part_number, state, &pending, upload_attempt, &app);
+        }
+
+        match put_chunk(&part, &data, &client).await {
+            Ok(tag) => break tag,
+            Err(err) if attempt < MAX_CHUNK_RETRIES && !is_client_error(&err) => {
+                warn!(
+                    "failed to upload part {}, retrying: {:#}",
+                    part.part_number, err
+                );
+
+                tokio::time::sleep(CHUNK_RETRY_DELAY * 2u32.pow(attempt as u32)).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    debug!("uploaded part {} with tag {}", part.part_number, tag);
+
+    let mut pending = pending.lock().unwrap();
+
+    pending.completed.push(CompletedPart {
+        tag,
+        part_number: part.part_number,
+    });
+    pending.save();
+
+    emit_progress(
+        part.part_number,
+        PartState::Done,
+        &pending,
+        upload_attempt,
+        &app,
+    );
+
+    Ok(())
+}
+
+async fn put_chunk(part: &UploadPartUrl, data: &Bytes, client: &reqwest::Client) -> Result<String> {
     let start = part.offset as usize;
     let end = start + part.length as usize;
     let chunk = data.slice(start..end);
@@ -267,12 +535,14 @@ async fn upload_chunk(
         .context("ETag is not valid utf-8")?
         .to_owned();
 
-    debug!("uploaded part {} with tag {}", part.part_number, tag);
+    Ok(tag)
+}
 
-    Ok(CompletedPart {
-        tag,
-        part_number: part.part_number,
-    })
+/// Whether a request was rejected, in which case retrying won't help.
+fn is_client_error(err: &eyre::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .and_then(|err| err.status())
+        .is_some_and(|status| status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS)
 }
 
 async fn abort_upload(uuid: &Uuid, token: &str, client: reqwest::Client) -> Result<()> {
@@ -311,13 +581,21 @@ async fn submit_package(
     token: &str,
     client: &reqwest::Client,
 ) -> Result<()> {
+    let community_categories = args.community_categories(game);
+
     let metadata = PackageSubmissionMetadata {
-        author_name: args.author,
         has_nsfw_content: args.nsfw,
         upload_uuid: uuid,
         categories: Vec::new(),
-        communities: vec![game.slug.to_string()],
-        community_categories: HashMap::from([(game.slug.to_string(), args.categories)]),
+        communities: community_categories
+            .iter()
+            .map(|(slug, _)| slug.to_string())
+            .collect(),
+        community_categories: community_categories
+            .into_iter()
+            .map(|(slug, categories)| (slug.to_string(), categories.to_vec()))
+            .collect(),
+        author_name: args.author,
     };
 
     debug!("submitting package");
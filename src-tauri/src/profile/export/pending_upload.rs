@@ -0,0 +1,124 @@
+//! Saving the progress of a modpack upload, so that publishing again after a
+//! failed upload only sends the parts that didn't make it the first time.
+//!
+//! Only one upload is kept track of, and only resumed if the modpack zip is
+//! exactly the same as before and is published with the same Thunderstore token.
+
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    thunderstore::{CompletedPart, UploadPartUrl},
+    util::{self, fs::JsonStyle},
+};
+
+const FILE_NAME: &str = "pending_upload.json";
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingUpload {
+    pub uuid: Uuid,
+    /// Hash of the modpack zip being uploaded.
+    pub hash: String,
+    /// Hash of the token the upload was started with, since the upload belongs
+    /// to that account.
+    pub token_hash: String,
+    pub upload_urls: Vec<UploadPartUrl>,
+    pub completed: Vec<CompletedPart>,
+}
+
+fn path() -> PathBuf {
+    util::path::default_app_data_dir().join(FILE_NAME)
+}
+
+impl PendingUpload {
+    /// Hashes the data of a modpack zip or a token, to compare against a saved upload.
+    pub fn hash(data: &[u8]) -> String {
+        blake3::hash(data).to_hex().to_string()
+    }
+
+    /// Reads the saved upload, if there is one for `data` started with `token`.
+    pub fn load(data: &[u8], token: &str) -> Option<Self> {
+        let path = path();
+
+        if !path.exists() {
+            return None;
+        }
+
+        match util::fs::read_json::<Self>(&path) {
+            Ok(upload)
+                if upload.hash == Self::hash(data)
+                    && upload.token_hash == Self::hash(token.as_bytes())
+                    && upload.covers(data.len() as u64) =>
+            {
+                Some(upload)
+            }
+            Ok(_) => None,
+            Err(err) => {
+                warn!("failed to read pending upload: {:#}", err);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = util::fs::write_json(path(), self, JsonStyle::Compact) {
+            warn!("failed to save pending upload: {:#}", err);
+        }
+    }
+
+    /// Forgets the saved upload, after it has been finished or aborted.
+    pub fn clear() {
+        let path = path();
+
+        if path.exists() {
+            if let Err(err) = std::fs::remove_file(&path) {
+                warn!("failed to delete pending upload: {:#}", err);
+            }
+        }
+    }
+
+    /// Whether the parts cover exactly `len` bytes, without gaps or overlaps.
+    fn covers(&self, len: u64) -> bool {
+        let mut offset = 0;
+
+        for part in self.upload_urls.iter().sorted_by_key(|part| part.offset) {
+            if part.offset != offset {
+                return false;
+            }
+
+            offset += part.length;
+        }
+
+        offset == len
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.upload_urls.iter().map(|part| part.length).sum()
+    }
+
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.upload_urls
+            .iter()
+            .filter(|part| self.is_completed(part))
+            .map(|part| part.length)
+            .sum()
+    }
+
+    /// The parts that haven't been uploaded yet.
+    pub fn remaining(&self) -> impl Iterator<Item = &UploadPartUrl> {
+        self.upload_urls
+            .iter()
+            .filter(|part| !self.is_completed(part))
+    }
+
+    fn is_completed(&self, part: &UploadPartUrl) -> bool {
+        self.completed
+            .iter()
+            .any(|completed| completed.part_number == part.part_number)
+    }
+}
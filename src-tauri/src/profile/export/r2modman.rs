@@ -0,0 +1,222 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{ensure, Context, Result};
+use serde::Serialize;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use super::R2Version;
+use crate::{
+    game::Game,
+    profile::{Profile, RuntimeDataOverrides},
+    thunderstore::Thunderstore,
+    util::{self, error::IoResultExt},
+};
+
+/// Top-level profile entries that only Gale uses.
+const EXCLUDED: &[&str] = &["_state", "snapshots"];
+
+/// An entry in r2modman's `mods.yml`.
+///
+/// r2modman expects more metadata than the [`super::R2Mod`]s in exported archives.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    manifest_version: u32,
+    name: String,
+    author_name: String,
+    website_url: String,
+    display_name: String,
+    description: String,
+    game_version: String,
+    network_mode: String,
+    package_type: String,
+    install_mode: String,
+    installed_at_time: i64,
+    loaders: Vec<String>,
+    dependencies: Vec<String>,
+    incompatibilities: Vec<String>,
+    optional_dependencies: Vec<String>,
+    version_number: R2Version,
+    enabled: bool,
+    icon: String,
+}
+
+/// Everything needed to export a profile, collected while the manager is locked.
+pub struct R2Export {
+    name: String,
+    src: PathBuf,
+    dest: PathBuf,
+    manifest: Vec<ManifestEntry>,
+    runtime_data: RuntimeDataOverrides,
+    skipped: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct R2ExportResult {
+    pub path: PathBuf,
+    /// Mods that r2modman can't manage, such as ones from Nexus Mods or GitHub.
+    /// Their files are still copied, but they're left out of `mods.yml`.
+    pub skipped: Vec<String>,
+}
+
+impl R2Export {
+    /// Prepares an export of `profile` into the r2modman data directory at `data_dir`.
+    pub fn new(
+        profile: &Profile,
+        game: Game,
+        data_dir: &Path,
+        thunderstore: &Thunderstore,
+    ) -> Result<Self> {
+        let dest = data_dir
+            .join(&*game.r2_dir_name)
+            .join("profiles")
+            .join(&profile.name);
+
+        ensure!(
+            !dest.exists(),
+            "a profile named '{}' already exists in that directory",
+            profile.name
+        );
+
+        let mut skipped = Vec::new();
+
+        let manifest = profile
+            .mods
+            .iter()
+            .filter_map(|profile_mod| {
+                let Some((ts_mod, version)) =
+                    profile_mod.kind.as_thunderstore().and_then(|ts_mod| {
+                        let version = ts_mod.ident.version().parse::<semver::Version>().ok()?;
+                        Some((ts_mod, version))
+                    })
+                else {
+                    skipped.push(profile_mod.full_name().into_owned());
+                    return None;
+                };
+
+                let ident = &ts_mod.ident;
+                let info = ts_mod.id.borrow(thunderstore).ok();
+
+                // point to where the icon will be after copying, if the loader keeps it
+                let icon = game
+                    .mod_loader
+                    .installer_for(ident.full_name())
+                    .mod_dir(ident.full_name(), profile)
+                    .map(|dir| dir.join("icon.png"))
+                    .filter(|path| path.exists())
+                    .and_then(|path| {
+                        path.strip_prefix(&profile.path)
+                            .ok()
+                            .map(|relative| dest.join(relative))
+                    })
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                Some(ManifestEntry {
+                    manifest_version: 2,
+                    name: ident.full_name().to_owned(),
+                    author_name: ident.owner().to_owned(),
+                    display_name: ident.name().replace('_', " "),
+                    website_url: info
+                        .map(|borrowed| borrowed.version.website_url.clone())
+                        .unwrap_or_default(),
+                    description: info
+                        .map(|borrowed| borrowed.version.description.clone())
+                        .unwrap_or_default(),
+                    dependencies: info
+                        .map(|borrowed| {
+                            borrowed
+                                .version
+                                .dependencies
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    game_version: "0".to_owned(),
+                    network_mode: "both".to_owned(),
+                    package_type: "other".to_owned(),
+                    install_mode: "managed".to_owned(),
+                    installed_at_time: profile_mod.install_time.timestamp_millis(),
+                    loaders: Vec::new(),
+                    incompatibilities: Vec::new(),
+                    optional_dependencies: Vec::new(),
+                    version_number: version.into(),
+                    enabled: profile_mod.enabled,
+                    icon,
+                })
+            })
+            .collect();
+
+        if !skipped.is_empty() {
+            warn!(
+                "{} mods can't be managed by r2modman and are left out of mods.yml: {}",
+                skipped.len(),
+                skipped.join(", ")
+            );
+        }
+
+        Ok(Self {
+            name: profile.name.clone(),
+            src: profile.path.clone(),
+            dest,
+            manifest,
+            runtime_data: profile.runtime_data.clone(),
+            skipped,
+        })
+    }
+
+    /// Copies the profile's files and writes its `mods.yml`.
+    ///
+    /// This copies the whole profile, so avoid calling it on the main thread.
+    pub fn write(self) -> Result<R2ExportResult> {
+        info!("exporting {} to {}", self.name, self.dest.display());
+
+        let temp_dir = util::fs::temp_dir_for(&self.dest)?;
+
+        let entries = WalkDir::new(&self.src)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| {
+                let relative_path = entry.path().strip_prefix(&self.src).unwrap();
+
+                (entry.depth() > 1
+                    || !EXCLUDED
+                        .iter()
+                        .any(|excluded| entry.file_name() == *excluded))
+                    && !self.runtime_data.matches(relative_path)
+            });
+
+        for entry in entries {
+            let entry = entry?;
+
+            let relative_path = entry
+                .path()
+                .strip_prefix(&self.src)
+                .expect("WalkDir should only return paths inside of the root");
+            let target = temp_dir.path().join(relative_path);
+
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&target).fs_context("creating directory", &target)?;
+            } else {
+                fs::copy(entry.path(), &target).fs_context("copying file", &target)?;
+            }
+        }
+
+        let yaml = serde_yaml::to_string(&self.manifest).context("failed to write mods.yml")?;
+        let path = temp_dir.path().join("mods.yml");
+        fs::write(&path, yaml).fs_context("writing mods.yml", &path)?;
+
+        util::fs::persist_dir(temp_dir, &self.dest)?;
+
+        Ok(R2ExportResult {
+            path: self.dest,
+            skipped: self.skipped,
+        })
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use itertools::Itertools;
+
+use super::modpack::ModpackArgs;
+use crate::{
+    game::Game,
+    profile::Profile,
+    thunderstore::{BorrowedMod, Thunderstore},
+};
+
+// the generated part of the readme is wrapped in these,
+// so it can be replaced without touching anything the user wrote
+const START_MARKER: &str = "<!-- gale:generated:start -->";
+const END_MARKER: &str = "<!-- gale:generated:end -->";
+
+const UNCATEGORIZED: &str = "Other";
+
+/// Inserts a freshly generated section into the readme,
+/// replacing the previous one if it exists.
+pub(super) fn insert(
+    args: &mut ModpackArgs,
+    profile: &Profile,
+    game: Game,
+    thunderstore: &Thunderstore,
+) {
+    let section = generate(args, profile, game, thunderstore);
+
+    match find_section(&args.readme) {
+        Some((start, end)) => args.readme.replace_range(start..end, &section),
+        None => {
+            args.readme.truncate(args.readme.trim_end().len());
+
+            if !args.readme.is_empty() {
+                args.readme.push_str("\n\n");
+            }
+
+            args.readme.push_str(&section);
+        }
+    }
+}
+
+/// Regenerates the readme's generated section, if it has one.
+///
+/// Called on export, so the mod list never goes out of date.
+pub(super) fn refresh(
+    args: &mut ModpackArgs,
+    profile: &Profile,
+    game: Game,
+    thunderstore: &Thunderstore,
+) {
+    if find_section(&args.readme).is_some() {
+        insert(args, profile, game, thunderstore);
+    }
+}
+
+fn find_section(readme: &str) -> Option<(usize, usize)> {
+    let start = readme.find(START_MARKER)?;
+    let end = readme[start..].find(END_MARKER)? + start + END_MARKER.len();
+
+    Some((start, end))
+}
+
+fn generate(
+    args: &ModpackArgs,
+    profile: &Profile,
+    game: Game,
+    thunderstore: &Thunderstore,
+) -> String {
+    let mods = profile
+        .mods_to_pack(args)
+        .filter_map(|id| id.borrow(thunderstore).ok())
+        .collect_vec();
+
+    let mut section = String::new();
+
+    section.push_str(START_MARKER);
+    section.push('\n');
+
+    push_install_instructions(&mut section, args, game);
+    push_mod_list(&mut section, &mods, game);
+    push_credits(&mut section, &mods, game);
+
+    section.push_str(END_MARKER);
+    section
+}
+
+fn push_install_instructions(section: &mut String, args: &ModpackArgs, game: Game) {
+    writeln!(
+        section,
+        "## Installation\n\n\
+         1. Install a mod manager, like [Gale](https://github.com/Kesomannen/gale) or [r2modman](https://thunderstore.io/package/ebkr/r2modman/).\n\
+         2. Select {} and search for **{}**.\n\
+         3. Install the modpack and launch the game through the mod manager.\n",
+        game.name, args.name
+    )
+    .unwrap();
+}
+
+fn push_mod_list(section: &mut String, mods: &[BorrowedMod], game: Game) {
+    let mut categories: BTreeMap<&str, Vec<&BorrowedMod>> = BTreeMap::new();
+
+    for borrowed in mods {
+        // list each mod once, under its first category alphabetically
+        let category = borrowed
+            .package
+            .categories
+            .iter()
+            .filter(|category| !category.eq_ignore_ascii_case("mods"))
+            .min()
+            .map(String::as_str)
+            .unwrap_or(UNCATEGORIZED);
+
+        categories.entry(category).or_default().push(borrowed);
+    }
+
+    writeln!(section, "## Mods ({})\n", mods.len()).unwrap();
+
+    // keep the catch-all category at the bottom
+    let uncategorized = categories.remove(UNCATEGORIZED);
+
+    for (category, mods) in categories
+        .into_iter()
+        .chain(uncategorized.map(|mods| (UNCATEGORIZED, mods)))
+    {
+        writeln!(section, "### {}\n", category).unwrap();
+
+        for borrowed in mods
+            .into_iter()
+            .sorted_by_key(|borrowed| borrowed.package.name().to_lowercase())
+        {
+            writeln!(
+                section,
+                "- [{}]({}) `{}` by {}",
+                borrowed.package.name().replace('_', " "),
+                borrowed.package.url(game),
+                borrowed.version.version(),
+                borrowed.package.owner()
+            )
+            .unwrap();
+        }
+
+        section.push('\n');
+    }
+}
+
+fn push_credits(section: &mut String, mods: &[BorrowedMod], game: Game) {
+    let authors = mods
+        .iter()
+        .map(|borrowed| borrowed.package)
+        .unique_by(|package| package.owner())
+        .sorted_by_key(|package| package.owner().to_lowercase())
+        .map(|package| format!("[{}]({})", package.owner(), package.owner_url(game)))
+        .join(", ");
+
+    if authors.is_empty() {
+        return;
+    }
+
+    writeln!(
+        section,
+        "## Credits\n\nThanks to the authors of the included mods: {}.\n",
+        authors
+    )
+    .unwrap();
+}
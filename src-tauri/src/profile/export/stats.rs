@@ -0,0 +1,89 @@
+//! Download and rating stats of published modpacks, recorded over time so that
+//! pack authors can follow their adoption.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use eyre::{ensure, Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::modpack::ModpackArgs;
+use crate::db::Db;
+
+/// New points are only recorded this long after the last one.
+const MIN_INTERVAL: TimeDelta = TimeDelta::hours(1);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsPoint {
+    pub recorded_at: DateTime<Utc>,
+    pub downloads: u64,
+    pub rating_score: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackStats {
+    /// The full name of the package, without a version.
+    pub package: String,
+    /// Oldest first.
+    pub points: Vec<StatsPoint>,
+}
+
+#[derive(Deserialize)]
+struct PackageMetrics {
+    downloads: u64,
+    rating_score: u64,
+}
+
+/// The full name of the package that `args` are published as.
+pub fn package_name(args: &ModpackArgs) -> Result<String> {
+    ensure!(
+        !args.author.is_empty() && !args.name.is_empty(),
+        "the modpack has no author or name"
+    );
+
+    Ok(format!("{}-{}", args.author, args.name))
+}
+
+/// Fetches the current stats of `package` from Thunderstore and records them,
+/// unless the last point is too recent.
+pub async fn refresh(package: &str, client: &reqwest::Client, db: &Db) -> Result<()> {
+    let latest = db.get_modpack_stats(package)?.pop();
+
+    if latest.is_some_and(|point| Utc::now() - point.recorded_at < MIN_INTERVAL) {
+        return Ok(());
+    }
+
+    let (owner, name) = package.split_once('-').ok_or_eyre("invalid package name")?;
+
+    let url = format!(
+        "https://thunderstore.io/api/v1/package-metrics/{}/{}/",
+        owner, name
+    );
+
+    info!("fetching stats of {}", package);
+
+    let metrics: PackageMetrics = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context("the modpack couldn't be found on Thunderstore")?
+        .json()
+        .await?;
+
+    db.insert_modpack_stats(
+        package,
+        &StatsPoint {
+            recorded_at: Utc::now(),
+            downloads: metrics.downloads,
+            rating_score: metrics.rating_score,
+        },
+    )
+}
+
+pub fn get(package: String, db: &Db) -> Result<ModpackStats> {
+    let points = db.get_modpack_stats(&package)?;
+
+    Ok(ModpackStats { package, points })
+}
@@ -0,0 +1,271 @@
+//! Checks a modpack against Thunderstore's submission rules before uploading,
+//! so problems are reported all at once instead of as a failed request.
+
+use std::path::Path;
+
+use eyre::{bail, Result};
+use image::ImageReader;
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::Url;
+
+use super::modpack::ModpackArgs;
+use crate::{profile::Profile, thunderstore::Thunderstore};
+
+const MAX_NAME_LEN: usize = 128;
+const MAX_DESCRIPTION_LEN: usize = 250;
+const MAX_README_SIZE: usize = 100 * 1024;
+const MAX_PACK_SIZE: usize = 500 * 1024 * 1024;
+const ICON_SIZE: u32 = 256;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The upload will be rejected.
+    Error,
+    /// The upload will go through, but probably not as intended.
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackIssue {
+    pub severity: Severity,
+    /// The argument the issue is about, for example `name` or `iconPath`.
+    pub field: &'static str,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct Issues(Vec<PackIssue>);
+
+impl Issues {
+    fn push(&mut self, severity: Severity, field: &'static str, message: impl Into<String>) {
+        self.0.push(PackIssue {
+            severity,
+            field,
+            message: message.into(),
+        });
+    }
+
+    pub fn error(&mut self, field: &'static str, message: impl Into<String>) {
+        self.push(Severity::Error, field, message);
+    }
+
+    fn warning(&mut self, field: &'static str, message: impl Into<String>) {
+        self.push(Severity::Warning, field, message);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.0.iter().any(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Fails with every error message if there are any.
+    pub fn ensure_valid(&self) -> Result<()> {
+        if self.has_errors() {
+            bail!(
+                "modpack is not valid: {}",
+                self.0
+                    .iter()
+                    .filter(|issue| issue.severity == Severity::Error)
+                    .map(|issue| &issue.message)
+                    .join(", ")
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn into_vec(self) -> Vec<PackIssue> {
+        self.0
+    }
+}
+
+/// Checks everything about the modpack that doesn't require building it.
+pub fn check_args(args: &ModpackArgs, profile: &Profile, thunderstore: &Thunderstore) -> Issues {
+    let mut issues = Issues::default();
+
+    check_name(args, &mut issues);
+    check_version(args, thunderstore, &mut issues);
+    check_text(args, &mut issues);
+    check_icon(&args.icon_path, &mut issues);
+    check_dependencies(args, profile, thunderstore, &mut issues);
+
+    issues
+}
+
+/// Checks the size of the built modpack zip.
+pub fn check_size(size: usize, issues: &mut Issues) {
+    if size > MAX_PACK_SIZE {
+        issues.error(
+            "includeFileMap",
+            format!(
+                "modpack is {} MB, the maximum is {} MB",
+                size / 1024 / 1024,
+                MAX_PACK_SIZE / 1024 / 1024
+            ),
+        );
+    }
+}
+
+fn check_name(args: &ModpackArgs, issues: &mut Issues) {
+    if args.name.is_empty() {
+        issues.error("name", "name cannot be empty");
+    } else if !args
+        .name
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || char == '_')
+    {
+        issues.error(
+            "name",
+            "name can only contain letters, numbers and underscores",
+        );
+    } else if args.name.len() > MAX_NAME_LEN {
+        issues.error(
+            "name",
+            format!("name cannot be longer than {} characters", MAX_NAME_LEN),
+        );
+    }
+
+    if args.author.is_empty() {
+        issues.error("author", "author cannot be empty");
+    }
+}
+
+fn check_version(args: &ModpackArgs, thunderstore: &Thunderstore, issues: &mut Issues) {
+    let Ok(version) = args.version_number.parse::<semver::Version>() else {
+        issues.error(
+            "versionNumber",
+            "version number must be in the format 1.2.3",
+        );
+        return;
+    };
+
+    if !version.pre.is_empty() || !version.build.is_empty() {
+        issues.error(
+            "versionNumber",
+            "version number cannot have a pre-release or build suffix",
+        );
+        return;
+    }
+
+    let full_name = format!("{}-{}", args.author, args.name);
+    let Ok(package) = thunderstore.find_package(&full_name) else {
+        return;
+    };
+
+    if package.get_version_with_num(&args.version_number).is_some() {
+        issues.error(
+            "versionNumber",
+            format!("version {} has already been published", version),
+        );
+    } else if package.latest().parsed_version() > version {
+        issues.warning(
+            "versionNumber",
+            format!(
+                "version {} is older than the latest published version ({})",
+                version,
+                package.latest().version()
+            ),
+        );
+    }
+}
+
+fn check_text(args: &ModpackArgs, issues: &mut Issues) {
+    if args.description.is_empty() {
+        issues.error("description", "description cannot be empty");
+    } else if args.description.chars().count() > MAX_DESCRIPTION_LEN {
+        issues.error(
+            "description",
+            format!(
+                "description cannot be longer than {} characters",
+                MAX_DESCRIPTION_LEN
+            ),
+        );
+    }
+
+    if args.readme.trim().is_empty() {
+        issues.error("readme", "readme cannot be empty");
+    } else if args.readme.len() > MAX_README_SIZE {
+        issues.error(
+            "readme",
+            format!("readme cannot be larger than {} KB", MAX_README_SIZE / 1024),
+        );
+    }
+
+    if !args.website_url.is_empty() && Url::parse(&args.website_url).is_err() {
+        issues.error("websiteUrl", "website URL is not valid");
+    }
+}
+
+fn check_icon(path: &Path, issues: &mut Issues) {
+    if path.as_os_str().is_empty() {
+        issues.error("iconPath", "icon is required");
+        return;
+    }
+
+    if !path.exists() {
+        issues.error("iconPath", format!("icon not found at {}", path.display()));
+        return;
+    }
+
+    let dimensions = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(image::ImageError::from)
+        .and_then(|reader| reader.into_dimensions());
+
+    match dimensions {
+        Ok((width, height)) if width != height => issues.warning(
+            "iconPath",
+            format!(
+                "icon is {}x{} and will be stretched to {}x{}",
+                width, height, ICON_SIZE, ICON_SIZE
+            ),
+        ),
+        Ok((width, _)) if width < ICON_SIZE => issues.warning(
+            "iconPath",
+            format!(
+                "icon is smaller than {}x{} and will be upscaled",
+                ICON_SIZE, ICON_SIZE
+            ),
+        ),
+        Ok(_) => (),
+        Err(err) => issues.error("iconPath", format!("icon could not be read: {}", err)),
+    }
+}
+
+fn check_dependencies(
+    args: &ModpackArgs,
+    profile: &Profile,
+    thunderstore: &Thunderstore,
+    issues: &mut Issues,
+) {
+    let mut count = 0;
+
+    for mod_ref in profile.mods_to_pack(args) {
+        count += 1;
+
+        match mod_ref.borrow(thunderstore) {
+            Ok(borrowed) if borrowed.package.is_deprecated => issues.warning(
+                "dependencies",
+                format!("{} is deprecated", borrowed.package.full_name()),
+            ),
+            Ok(_) => (),
+            Err(_) => {
+                let name = profile
+                    .get_mod(mod_ref.package_uuid)
+                    .map(|profile_mod| profile_mod.full_name().into_owned())
+                    .unwrap_or_else(|_| mod_ref.package_uuid.to_string());
+
+                issues.error(
+                    "dependencies",
+                    format!("{} could not be found on Thunderstore", name),
+                );
+            }
+        }
+    }
+
+    if count == 0 {
+        issues.warning("dependencies", "modpack does not contain any mods");
+    }
+}
@@ -0,0 +1,362 @@
+//! Checks for common problems in a profile, each with a fix that can be applied in one click.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::info;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{
+    install::{self, cache, InstallOptions, ModInstall},
+    verify, ModManager,
+};
+use crate::{
+    prefs::Prefs,
+    state::ManagerExt,
+    thunderstore::{ModId, Thunderstore},
+};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    pub message: String,
+    pub fix: HealthFix,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthIssueKind {
+    MissingDependency,
+    DisabledDependency,
+    OutdatedDependency,
+    OutdatedLoader,
+    BrokenLink,
+    EmptyCache,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum HealthFix {
+    InstallMods {
+        mods: Vec<ModId>,
+    },
+    EnableMods {
+        uuids: Vec<Uuid>,
+    },
+    UpdateMods {
+        uuids: Vec<Uuid>,
+    },
+    /// Paths relative to the profile directory.
+    RemoveLinks {
+        paths: Vec<PathBuf>,
+    },
+    /// Clears the cached packages of the mods and installs them again.
+    Redownload {
+        uuids: Vec<Uuid>,
+    },
+}
+
+impl HealthIssue {
+    fn new(kind: HealthIssueKind, message: impl Into<String>, fix: HealthFix) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            fix,
+        }
+    }
+}
+
+/// Files to check in a profile, collected while the manager is locked.
+pub struct HealthTargets {
+    root: PathBuf,
+    /// Pairs of installed mods and the cache paths of their packages.
+    cached: Vec<(Uuid, String, PathBuf)>,
+}
+
+impl HealthTargets {
+    pub fn for_active_profile(prefs: &Prefs, manager: &ModManager) -> Self {
+        let profile = manager.active_profile();
+
+        let cached = profile
+            .thunderstore_mods()
+            .map(|(ts_mod, _)| {
+                (
+                    ts_mod.id.package_uuid,
+                    ts_mod.ident.to_string(),
+                    cache::path(&ts_mod.ident, prefs),
+                )
+            })
+            .collect();
+
+        Self {
+            root: profile.path.clone(),
+            cached,
+        }
+    }
+}
+
+/// Checks the dependencies of the enabled mods in the active profile.
+pub fn check_mods(manager: &ModManager, thunderstore: &Thunderstore) -> Vec<HealthIssue> {
+    let mod_loader = manager.active_mod_loader();
+    let profile = manager.active_profile();
+
+    let mut issues = Vec::new();
+    let mut seen_missing = HashSet::new();
+    let mut seen_outdated = HashSet::new();
+    let mut seen_disabled = HashSet::new();
+
+    for profile_mod in profile
+        .mods
+        .iter()
+        .filter(|profile_mod| profile_mod.enabled)
+    {
+        let dependant = profile_mod.full_name();
+
+        if let Some(borrowed) = profile_mod
+            .kind
+            .as_thunderstore()
+            .and_then(|ts_mod| ts_mod.id.borrow(thunderstore).ok())
+        {
+            for missing in profile.missing_deps(borrowed.dependencies(), thunderstore) {
+                if !seen_missing.insert(missing.package.uuid) {
+                    continue;
+                }
+
+                issues.push(HealthIssue::new(
+                    HealthIssueKind::MissingDependency,
+                    format!(
+                        "{} is required by {}, but isn't installed.",
+                        missing.package.full_name(),
+                        dependant
+                    ),
+                    HealthFix::InstallMods {
+                        mods: vec![missing.into()],
+                    },
+                ));
+            }
+
+            for required in borrowed.dependencies() {
+                let Some((installed, _)) = profile
+                    .thunderstore_mods()
+                    .find(|(ts_mod, _)| ts_mod.ident.full_name() == required.full_name())
+                else {
+                    continue;
+                };
+
+                if !is_older(installed.ident.version(), required.version())
+                    || !seen_outdated.insert(installed.id.package_uuid)
+                {
+                    continue;
+                }
+
+                let kind = match mod_loader.is_loader_package(required.full_name()) {
+                    true => HealthIssueKind::OutdatedLoader,
+                    false => HealthIssueKind::OutdatedDependency,
+                };
+
+                issues.push(HealthIssue::new(
+                    kind,
+                    format!(
+                        "{} is installed, but {} requires version {} or newer.",
+                        installed.ident,
+                        dependant,
+                        required.version()
+                    ),
+                    HealthFix::UpdateMods {
+                        uuids: vec![installed.id.package_uuid],
+                    },
+                ));
+            }
+        }
+
+        for dependency in profile_mod.dependencies(thunderstore) {
+            let uuid = dependency.package.uuid;
+
+            let disabled = profile
+                .get_mod(uuid)
+                .is_ok_and(|dependency| !dependency.enabled);
+
+            if !disabled || !seen_disabled.insert(uuid) {
+                continue;
+            }
+
+            issues.push(HealthIssue::new(
+                HealthIssueKind::DisabledDependency,
+                format!(
+                    "{} is required by {}, but is disabled.",
+                    dependency.package.full_name(),
+                    dependant
+                ),
+                HealthFix::EnableMods { uuids: vec![uuid] },
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Checks for broken links in the profile and empty cached packages.
+///
+/// This reads through the profile, so avoid calling it on the main thread.
+pub fn check_files(targets: &HealthTargets) -> Result<Vec<HealthIssue>> {
+    let mut issues = Vec::new();
+
+    let broken_links = broken_links(&targets.root)?;
+    if !broken_links.is_empty() {
+        let message = match broken_links.len() {
+            1 => format!(
+                "{} points to a file that doesn't exist.",
+                broken_links[0].display()
+            ),
+            n => format!(
+                "{} links in the profile point to files that don't exist.",
+                n
+            ),
+        };
+
+        issues.push(HealthIssue::new(
+            HealthIssueKind::BrokenLink,
+            message,
+            HealthFix::RemoveLinks {
+                paths: broken_links,
+            },
+        ));
+    }
+
+    for (uuid, name, cache_path) in &targets.cached {
+        if !cache_path.exists() || has_files(cache_path) {
+            continue;
+        }
+
+        issues.push(HealthIssue::new(
+            HealthIssueKind::EmptyCache,
+            format!("The cached package of {} is empty.", name),
+            HealthFix::Redownload { uuids: vec![*uuid] },
+        ));
+    }
+
+    info!("found {} issues with the profile", issues.len());
+
+    Ok(issues)
+}
+
+/// Applies a fix returned by [`check_mods`] or [`check_files`] to the active profile.
+pub async fn apply(fix: HealthFix, app: &AppHandle) -> Result<()> {
+    match fix {
+        HealthFix::InstallMods { mods } => {
+            let mods = mods.into_iter().map(ModInstall::new).collect();
+
+            install::install_with_deps(mods, InstallOptions::default(), true, app).await
+        }
+        HealthFix::EnableMods { uuids } => {
+            let mut manager = app.lock_manager();
+            let profile = manager.active_profile_mut();
+
+            for uuid in uuids {
+                if profile
+                    .get_mod(uuid)
+                    .is_ok_and(|profile_mod| !profile_mod.enabled)
+                {
+                    profile.force_toggle_mod(uuid)?;
+                }
+            }
+
+            profile.save(app.db())
+        }
+        HealthFix::UpdateMods { uuids } => super::update::update_mods(uuids, false, app).await,
+        HealthFix::RemoveLinks { paths } => {
+            let root = app.read_manager().active_profile().path.clone();
+
+            for path in paths {
+                // don't let the frontend remove anything outside of the profile
+                if !path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+                {
+                    continue;
+                }
+
+                let path = root.join(path);
+                if is_broken_link(&path) {
+                    fs::remove_file(&path)
+                        .with_context(|| format!("failed to remove {}", path.display()))?;
+                }
+            }
+
+            Ok(())
+        }
+        HealthFix::Redownload { uuids } => {
+            {
+                let prefs = app.lock_prefs();
+                let manager = app.read_manager();
+
+                for (ts_mod, _) in manager.active_profile().thunderstore_mods() {
+                    if !uuids.contains(&ts_mod.id.package_uuid) {
+                        continue;
+                    }
+
+                    let cache_path = cache::path(&ts_mod.ident, &prefs);
+                    if cache_path.exists() {
+                        fs::remove_dir_all(&cache_path)
+                            .context("failed to clear cached package")?;
+                    }
+                }
+            }
+
+            verify::repair(uuids, app).await
+        }
+    }
+}
+
+fn broken_links(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut links = Vec::new();
+
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+
+        if !entry.path_is_symlink() || !is_broken_link(entry.path()) {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(root)
+            .expect("WalkDir should only return paths inside of the root");
+
+        links.push(relative_path.to_path_buf());
+    }
+
+    Ok(links)
+}
+
+fn is_broken_link(path: &Path) -> bool {
+    path.is_symlink() && fs::metadata(path).is_err()
+}
+
+fn has_files(path: &Path) -> bool {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.file_type().is_file())
+}
+
+/// Whether `installed` is an older version than `required`.
+///
+/// Versions that can't be parsed are never considered older.
+fn is_older(installed: &str, required: &str) -> bool {
+    match (
+        semver::Version::parse(installed),
+        semver::Version::parse(required),
+    ) {
+        (Ok(installed), Ok(required)) => installed < required,
+        _ => false,
+    }
+}
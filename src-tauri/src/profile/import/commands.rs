@@ -1,25 +1,59 @@
 use std::path::PathBuf;
 
-use eyre::anyhow;
+use eyre::{anyhow, Context};
 use tauri::{command, AppHandle};
 use uuid::Uuid;
 
 use crate::{
-    profile::install::InstallOptions,
+    profile::{
+        auto_snapshot::{self, SnapshotReason},
+        install::InstallOptions,
+    },
+    state::ManagerExt,
     thunderstore::{self},
-    util::cmd::Result,
+    util::{self, cmd::Result},
 };
 
 use super::{
-    r2modman::{self, ProfileImportData},
-    ImportData,
+    dependency_strings::ModListPreview,
+    preview::ImportPreview,
+    r2modman::{self, ExternalManagerInfo, ProfileImportData},
+    ImportConflict, ImportData, ImportOptions,
 };
 
 #[command]
-pub async fn import_profile(data: ImportData, import_all: bool, app: AppHandle) -> Result<()> {
-    super::import_profile(data, InstallOptions::default(), import_all, &app).await?;
+pub async fn import_profile(
+    data: ImportData,
+    options: ImportOptions,
+    app: AppHandle,
+) -> Result<Vec<ImportConflict>> {
+    util::cancel::reset(&app);
 
-    Ok(())
+    {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+        let game = manager.active_game();
+
+        if let Some(index) = game.profile_index(&data.manifest.name) {
+            auto_snapshot::capture(&game.profiles[index], SnapshotReason::Import, &prefs, &app)?;
+        }
+    }
+
+    let result = super::import_profile(data, InstallOptions::default(), options, &app).await?;
+
+    Ok(result.conflicts)
+}
+
+/// Shows what `import_profile` would change, without applying anything.
+#[command]
+pub fn preview_import(
+    data: ImportData,
+    options: ImportOptions,
+    app: AppHandle,
+) -> Result<ImportPreview> {
+    let preview = super::preview::preview(&data, options, &app)?;
+
+    Ok(preview)
 }
 
 #[command]
@@ -32,21 +66,37 @@ pub async fn read_profile_code(key: &str, app: AppHandle) -> Result<ImportData>
 }
 
 #[command]
-pub async fn read_profile_file(path: PathBuf) -> Result<ImportData> {
-    let data = super::import_file_from_path(path)?;
+pub async fn read_profile_file(path: PathBuf, app: AppHandle) -> Result<ImportData> {
+    let data = super::import_file_from_path(path, &app)?;
 
     Ok(data)
 }
 
 #[command]
-pub async fn read_profile_base64(base64: String) -> Result<ImportData> {
-    let data = super::read_base64(&base64)?;
+pub async fn read_profile_base64(base64: String, app: AppHandle) -> Result<ImportData> {
+    let data = super::read_base64(&base64, &app)?;
 
     Ok(data)
 }
 
+/// Imports the config files of a zip into the active profile. Returns the number of files.
+#[command]
+pub fn import_config(path: PathBuf, app: AppHandle) -> Result<usize> {
+    let mut manager = app.lock_manager();
+    let game = manager.active_game().game;
+    let profile = manager.active_profile_mut();
+
+    let files = super::config::import(&path, &profile.path, &game.mod_loader)
+        .context("failed to import config")?;
+    profile.config_cache.forget(&files);
+
+    Ok(files.len())
+}
+
 #[command]
 pub async fn import_local_mod(path: PathBuf, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     thunderstore::wait_for_fetch(&app).await;
 
     super::import_local_mod(
@@ -62,6 +112,8 @@ pub async fn import_local_mod(path: PathBuf, app: AppHandle) -> Result<()> {
 
 #[command]
 pub async fn import_local_mod_base64(base64: String, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     thunderstore::wait_for_fetch(&app).await;
 
     super::import_local_mod_base64(base64, &app, InstallOptions::default().can_cancel(false))
@@ -70,6 +122,40 @@ pub async fn import_local_mod_base64(base64: String, app: AppHandle) -> Result<(
     Ok(())
 }
 
+#[command]
+pub async fn import_dependency_strings(text: String, app: AppHandle) -> Result<usize> {
+    util::cancel::reset(&app);
+
+    let count = super::dependency_strings::import(&text, &app).await?;
+
+    Ok(count)
+}
+
+#[command]
+pub async fn preview_mod_list_text(text: String, app: AppHandle) -> Result<ModListPreview> {
+    let preview = super::dependency_strings::preview_mod_list(&text, &app).await;
+
+    Ok(preview)
+}
+
+#[command]
+pub async fn import_mod_list_text(text: String, app: AppHandle) -> Result<usize> {
+    util::cancel::reset(&app);
+
+    let count = super::dependency_strings::import_mod_list(&text, &app).await?;
+
+    Ok(count)
+}
+
+#[command]
+pub async fn install_from_string(input: String, app: AppHandle) -> Result<String> {
+    util::cancel::reset(&app);
+
+    let full_name = super::dependency_strings::install_single(&input, &app).await?;
+
+    Ok(full_name)
+}
+
 #[command]
 pub fn get_r2modman_info(
     path: Option<PathBuf>,
@@ -80,8 +166,17 @@ pub fn get_r2modman_info(
     Ok(info)
 }
 
+/// Finds profiles of all supported mod managers, for the active game.
+///
+/// Profiles from any of them can be imported with `import_r2modman`.
+#[command]
+pub fn get_external_manager_info(app: AppHandle) -> Vec<ExternalManagerInfo> {
+    r2modman::gather_external_info(&app)
+}
+
 #[command]
 pub async fn import_r2modman(path: PathBuf, include: Vec<bool>, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
     r2modman::import(path, &include, &app).await?;
 
     Ok(())
@@ -0,0 +1,82 @@
+//! Importing the config files of an archive into a profile, without touching its mods.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, Result};
+use tracing::{info, warn};
+use zip::ZipArchive;
+
+use crate::{
+    config::history,
+    game::GameModLoader,
+    profile::export,
+    util::{self, error::IoResultExt},
+};
+
+/// Copies the config files in the zip at `src` into `profile_dir`.
+///
+/// Only files in the loader's config directory are imported, or if it has none,
+/// files that would be exported as config. Other files, like the manifest of an
+/// exported profile or the mods themselves, are skipped, so full profile exports
+/// work as well. Overwritten files are saved to the config history first.
+/// Returns the imported files, relative to the profile.
+pub fn import(
+    src: &Path,
+    profile_dir: &Path,
+    mod_loader: &'static GameModLoader,
+) -> Result<Vec<PathBuf>> {
+    let config_dir = mod_loader.config_path();
+
+    let file = File::open(src).fs_context("opening file", src)?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).context("failed to read archive")?;
+
+    let mut imported = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+
+        if file.is_dir() {
+            continue;
+        }
+
+        let relative_path = PathBuf::from(file.name().replace('\\', "/"));
+
+        if !util::fs::is_enclosed(&relative_path) {
+            warn!(
+                "file {} escapes the archive root, skipping",
+                relative_path.display()
+            );
+            continue;
+        }
+
+        if export::is_generated(&relative_path)
+            || !export::has_config_extension(&relative_path)
+            || !relative_path.starts_with(&config_dir)
+        {
+            continue;
+        }
+
+        history::record(profile_dir, &relative_path)?;
+
+        let path = profile_dir.join(&relative_path);
+        fs::create_dir_all(path.parent().unwrap()).fs_context("creating directory", &path)?;
+        io::copy(
+            &mut file,
+            &mut File::create(&path).fs_context("creating file", &path)?,
+        )?;
+
+        imported.push(relative_path);
+    }
+
+    info!(
+        "imported {} config files from {}",
+        imported.len(),
+        src.display()
+    );
+
+    Ok(imported)
+}
@@ -0,0 +1,330 @@
+use std::cmp::Reverse;
+
+use eyre::{ensure, Context, OptionExt, Result};
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::{
+    profile::{
+        install::{self, InstallOptions, ModInstall},
+        Profile,
+    },
+    state::ManagerExt,
+    thunderstore::{self, BorrowedMod, Thunderstore, VersionIdent},
+};
+
+/// How many edits a mod name may be off by per character, to still match a package.
+const MAX_EDITS_PER_CHAR: f32 = 0.2;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModListPreview {
+    pub mods: Vec<ResolvedLine>,
+    /// Lines that didn't match any package.
+    pub unresolved: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedLine {
+    pub line: String,
+    pub full_name: String,
+    /// Whether the line was matched by a name similar to the mod's, instead of an exact reference.
+    pub fuzzy: bool,
+    pub installed: bool,
+}
+
+/// Extracts dependency strings from freely formatted text, like a forum post.
+///
+/// Besides `Owner-Mod-1.2.3` strings, Thunderstore package URLs are also
+/// recognized and resolve to the latest version of the package. Any other
+/// words, list markers and punctuation are ignored.
+pub(super) fn parse(text: &str) -> Vec<String> {
+    text.split(|char: char| char.is_whitespace() || matches!(char, ',' | ';'))
+        .filter_map(|word| {
+            if word.contains("thunderstore.io/") {
+                return parse_url(word);
+            }
+
+            let word = word.trim_matches(|char: char| !char.is_alphanumeric());
+
+            // hyphenated words would otherwise parse as well
+            word.parse::<VersionIdent>()
+                .ok()
+                .filter(|ident| semver::Version::parse(ident.version()).is_ok())
+                .map(VersionIdent::into_string)
+        })
+        .unique()
+        .collect()
+}
+
+/// Parses a package URL, like `https://thunderstore.io/c/<game>/p/<owner>/<name>/`.
+///
+/// Version URLs (`.../p/<owner>/<name>/v/<version>/`) resolve to that specific version.
+fn parse_url(url: &str) -> Option<String> {
+    let mut segments = url
+        .split('/')
+        .skip_while(|segment| !matches!(*segment, "p" | "package"))
+        .skip(1)
+        // strip surrounding punctuation, like the end of a markdown link
+        .map(|segment| segment.trim_matches(|char: char| !(char.is_alphanumeric() || char == '_')))
+        .filter(|segment| !segment.is_empty());
+
+    let owner = segments.next()?;
+    let name = segments.next()?;
+
+    match (segments.next(), segments.next()) {
+        (Some("v"), Some(version)) => Some(format!("{}-{}-{}", owner, name, version)),
+        _ => Some(format!("{}-{}", owner, name)),
+    }
+}
+
+/// Installs a single mod from a pasted package URL or dependency string.
+///
+/// If the mod can't be found, the package index is fetched again before giving up.
+/// Returns the full name of the installed mod.
+pub async fn install_single(input: &str, app: &AppHandle) -> Result<String> {
+    thunderstore::wait_for_fetch(app).await;
+
+    let string = parse(input)
+        .into_iter()
+        .next()
+        .ok_or_eyre("no package URL or dependency string found")?;
+
+    let mods = match resolve(&string, app) {
+        Ok(mods) => mods,
+        Err(_) => {
+            info!("{} not found, fetching packages again", string);
+
+            thunderstore::refetch(app)
+                .await
+                .context("failed to fetch packages")?;
+
+            resolve(&string, app)?
+        }
+    };
+
+    ensure!(!mods.is_empty(), "{} is already installed", string);
+
+    install::install_with_deps(mods, InstallOptions::default(), false, app).await?;
+
+    Ok(string)
+}
+
+fn resolve(string: &str, app: &AppHandle) -> Result<Vec<ModInstall>> {
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    install::resolve_dependency_strings([string], manager.active_profile(), &thunderstore)
+}
+
+/// Installs all dependency strings found in `text` into the active profile,
+/// failing if any of them can't be found.
+///
+/// Returns the number of mods that were installed, excluding dependencies.
+pub async fn import(text: &str, app: &AppHandle) -> Result<usize> {
+    install::install_dependency_strings(&parse(text), app).await
+}
+
+/// A line of a pasted mod list and the mod it refers to.
+struct Resolved<'t, 'a> {
+    line: &'t str,
+    borrowed: BorrowedMod<'a>,
+    fuzzy: bool,
+}
+
+/// Resolves a pasted mod list line by line, like one exported from another
+/// manager or copied from a forum post.
+///
+/// Lines without a dependency string or package URL are matched by the names
+/// of packages instead, allowing for typos, see [`name_distance`]. The closest
+/// name wins, then the same owner if the line mentions one (`Mod Name by Owner`),
+/// then the most downloaded package. Blank lines and headings are skipped.
+fn resolve_lines<'t, 'a>(
+    text: &'t str,
+    thunderstore: &'a Thunderstore,
+) -> (Vec<Resolved<'t, 'a>>, Vec<&'t str>) {
+    let mut resolved = Vec::new();
+    let mut unresolved = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let exact = parse(line)
+            .iter()
+            .filter_map(|string| install::find_dependency_string(string, thunderstore).ok())
+            .collect_vec();
+
+        if !exact.is_empty() {
+            resolved.extend(exact.into_iter().map(|borrowed| Resolved {
+                line,
+                borrowed,
+                fuzzy: false,
+            }));
+            continue;
+        }
+
+        match find_by_name(line, thunderstore) {
+            Some(borrowed) => resolved.push(Resolved {
+                line,
+                borrowed,
+                fuzzy: true,
+            }),
+            None => unresolved.push(line),
+        }
+    }
+
+    let resolved = resolved
+        .into_iter()
+        .unique_by(|resolved| resolved.borrowed.package.uuid)
+        .collect();
+
+    (resolved, unresolved)
+}
+
+fn find_by_name<'a>(line: &str, thunderstore: &'a Thunderstore) -> Option<BorrowedMod<'a>> {
+    let (name, owner) = split_line(line);
+
+    // a dependency string without a version
+    if let Ok(borrowed) = install::find_dependency_string(name, thunderstore) {
+        return Some(borrowed);
+    }
+
+    let name = normalize(name);
+    if name.is_empty() {
+        return None;
+    }
+
+    let owner = owner.map(normalize);
+
+    thunderstore
+        .latest()
+        .filter_map(|borrowed| {
+            let distance = name_distance(&name, &normalize(borrowed.package.name()))?;
+            let same_owner = owner
+                .as_ref()
+                .is_some_and(|owner| normalize(borrowed.package.owner()) == *owner);
+
+            Some((borrowed, (Reverse(distance), same_owner)))
+        })
+        .max_by_key(|(borrowed, key)| (*key, borrowed.package.total_downloads()))
+        .map(|(borrowed, _)| borrowed)
+}
+
+/// The number of edits between two normalized names, if they're similar enough
+/// to be the same mod.
+pub(super) fn name_distance(name: &str, package_name: &str) -> Option<usize> {
+    let len = name.chars().count();
+    let max_edits = (len as f32 * MAX_EDITS_PER_CHAR) as usize;
+
+    // the distance is at least the difference in length, which is much cheaper to check
+    if len.abs_diff(package_name.chars().count()) > max_edits {
+        return None;
+    }
+
+    let distance = strsim::levenshtein(name, package_name);
+    (distance <= max_edits).then_some(distance)
+}
+
+/// Splits a line like `- [Mod Name](link) `1.0.0` by Owner` into its name and owner.
+pub(super) fn split_line(line: &str) -> (&str, Option<&str>) {
+    let line = strip_list_marker(line);
+
+    let (line, owner) = match line.rsplit_once(" by ") {
+        Some((line, owner)) => (line, owner.split_whitespace().next()),
+        None => (line, None),
+    };
+
+    let name = match line.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+        Some((name, _)) => name,
+        None => line.split('`').next().unwrap_or(line),
+    };
+
+    (name.trim(), owner)
+}
+
+pub(super) fn strip_list_marker(line: &str) -> &str {
+    if let Some(rest) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        return rest.trim_start();
+    }
+
+    // numbered lists, like `1. ` or `1) `
+    let rest = line.trim_start_matches(|char: char| char.is_ascii_digit());
+    if rest.len() < line.len() {
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest.trim_start();
+        }
+    }
+
+    line
+}
+
+pub(super) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Resolves a pasted mod list without installing anything, see [`resolve_lines`].
+pub async fn preview_mod_list(text: &str, app: &AppHandle) -> ModListPreview {
+    thunderstore::wait_for_fetch(app).await;
+
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+    let profile = manager.active_profile();
+
+    let (resolved, unresolved) = resolve_lines(text, &thunderstore);
+
+    ModListPreview {
+        mods: resolved
+            .into_iter()
+            .map(|resolved| ResolvedLine {
+                line: resolved.line.to_owned(),
+                full_name: resolved.borrowed.ident().to_string(),
+                fuzzy: resolved.fuzzy,
+                installed: profile.has_mod(resolved.borrowed.package.uuid),
+            })
+            .collect(),
+        unresolved: unresolved.into_iter().map(str::to_owned).collect(),
+    }
+}
+
+/// Installs the mods of a pasted mod list that could be resolved,
+/// skipping those that are already installed.
+///
+/// This also covers plain lists of dependency strings or package URLs.
+///
+/// Returns the number of mods that were installed, excluding dependencies.
+pub async fn import_mod_list(text: &str, app: &AppHandle) -> Result<usize> {
+    thunderstore::wait_for_fetch(app).await;
+
+    let mods = {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        resolved_installs(text, manager.active_profile(), &thunderstore)
+    };
+
+    install::install_resolved(mods, app).await
+}
+
+fn resolved_installs(
+    text: &str,
+    profile: &Profile,
+    thunderstore: &Thunderstore,
+) -> Vec<ModInstall> {
+    resolve_lines(text, thunderstore)
+        .0
+        .into_iter()
+        .filter(|resolved| !profile.has_mod(resolved.borrowed.package.uuid))
+        .map(|resolved| ModInstall::new(resolved.borrowed.into()))
+        .collect()
+}
@@ -8,11 +8,12 @@ use base64::{prelude::BASE64_STANDARD, Engine};
 use eyre::{bail, ensure, Context, Result};
 use tauri::AppHandle;
 use tempfile::NamedTempFile;
+use tracing::warn;
 use uuid::Uuid;
 use zip::ZipArchive;
 
 use crate::{
-    game::{ModLoader, ModLoaderKind},
+    game::{GameModLoader, ModLoaderKind},
     prefs::Prefs,
     profile::{
         install::{self, InstallOptions},
@@ -20,7 +21,7 @@ use crate::{
     },
     state::ManagerExt,
     thunderstore::PackageManifest,
-    util::{self, fs::PathExt},
+    util::{self, error::IoResultExt, fs::PathExt},
 };
 
 pub async fn import_local_mod_base64(
@@ -30,7 +31,10 @@ pub async fn import_local_mod_base64(
 ) -> Result<()> {
     let data = BASE64_STANDARD.decode(base64)?;
 
-    let mut file = NamedTempFile::new().context("failed to create temp file")?;
+    let temp_dir = app.lock_prefs().temp_dir();
+    fs::create_dir_all(&temp_dir).context("failed to create temp directory")?;
+
+    let mut file = NamedTempFile::new_in(temp_dir).context("failed to create temp file")?;
     file.write_all(&data).context("failed to write temp file")?;
 
     import_local_mod(
@@ -52,7 +56,7 @@ pub async fn import_local_mod(
 
     if let Some(deps) = &local_mod.dependencies {
         let mods = {
-            let manager = app.lock_manager();
+            let manager = app.read_manager();
             let profile = manager.active_profile();
 
             app.lock_thunderstore()
@@ -79,19 +83,31 @@ pub async fn import_local_mod(
 
     let existing = existing.map(|(LocalMod { uuid, .. }, _)| *uuid);
 
-    if let Some(uuid) = existing {
-        profile
-            .force_remove_mod(uuid)
-            .context("failed to remove existing version")?;
-    }
+    // keep the place, state and user data of the version being replaced
+    let previous = match existing {
+        Some(uuid) => {
+            let index = profile.index_of(uuid)?;
+            let previous = profile.mods[index].clone();
+
+            profile
+                .force_remove_mod(uuid)
+                .context("failed to remove existing version")?;
+
+            Some((index, previous))
+        }
+        None => None,
+    };
 
-    match kind {
+    let files = match kind {
         LocalModKind::Zip => {
-            local_mod.icon = install_from_zip(&path, profile, &local_mod.name, mod_loader, &prefs)
+            let (icon, files) = install_from_zip(&path, profile, &local_mod, mod_loader, &prefs)
                 .context("failed to install")?;
+
+            local_mod.icon = icon;
+            files
         }
         LocalModKind::Dll => match mod_loader.kind {
-            ModLoaderKind::BepInEx { .. } => {
+            ModLoaderKind::BepInEx(_) => {
                 let target: PathBuf = ["BepInEx", "plugins", &local_mod.name, &local_mod.name]
                     .iter()
                     .collect();
@@ -101,13 +117,36 @@ pub async fn import_local_mod(
 
                 fs::create_dir_all(target.parent().unwrap())
                     .context("failed to create plugin directory")?;
-                fs::copy(path, target).context("failed to copy file")?;
+                fs::copy(path, &target).context("failed to copy file")?;
+
+                vec![target.strip_prefix(&profile.path).unwrap().to_path_buf()]
             }
             _ => bail!("currently unsupported"),
         },
-    }
+    };
+
+    match previous {
+        Some((index, previous)) => {
+            if let Some(previous_local) = previous.kind.as_local() {
+                local_mod.source = previous_local.source.clone();
+            }
 
-    profile.mods.push(ProfileMod::new_local(local_mod));
+            let uuid = local_mod.uuid;
+            let profile_mod = ProfileMod::new_local(local_mod)
+                .with_files(files)
+                .with_user_data(previous.user_data());
+
+            let index = index.min(profile.mods.len());
+            profile.mods.insert(index, profile_mod);
+
+            if !previous.enabled {
+                profile.force_toggle_mod(uuid)?;
+            }
+        }
+        None => profile
+            .mods
+            .push(ProfileMod::new_local(local_mod).with_files(files)),
+    }
 
     profile.save(app.db())?;
 
@@ -148,6 +187,7 @@ fn read_local_mod(
             name: manifest.name,
             author: manifest.author,
             description: Some(manifest.description),
+            website_url: Some(manifest.website_url).filter(|url| !url.is_empty()),
             version: Some(manifest.version_number),
             dependencies: Some(manifest.dependencies),
             ..Default::default()
@@ -163,7 +203,7 @@ fn read_local_mod(
     Ok((local_mod, kind))
 }
 
-fn read_zip_manifest(path: &Path) -> Result<Option<PackageManifest>> {
+pub fn read_zip_manifest(path: &Path) -> Result<Option<PackageManifest>> {
     let mut zip = util::fs::open_zip(path).context("failed to open zip archive")?;
 
     let manifest = zip.by_name("manifest.json");
@@ -190,30 +230,76 @@ fn read_zip_manifest(path: &Path) -> Result<Option<PackageManifest>> {
 fn install_from_zip(
     src: &Path,
     profile: &Profile,
-    package_name: &str,
-    mod_loader: &'static ModLoader,
+    local_mod: &LocalMod,
+    mod_loader: &'static GameModLoader,
     prefs: &Prefs,
-) -> Result<Option<PathBuf>> {
-    // dont use tempdir since we need the files on the same drive as the destination
-    // for hard linking to work
+) -> Result<(Option<PathBuf>, Vec<PathBuf>)> {
+    let package_name = local_mod.name.as_str();
 
-    let temp_path = prefs.data_dir.join("temp").join("extract");
-    fs::create_dir_all(&temp_path).context("failed to create temporary directory")?;
+    // the files need to be on the same drive as the destination for hard linking to work
+    let temp_dir = util::fs::temp_dir_in(&prefs.temp_dir())?;
+    let temp_path = temp_dir.path().to_path_buf();
 
     let reader = fs::read(src)
         .map(Cursor::new)
         .context("failed to read file")?;
-    let archive = ZipArchive::new(reader).context("failed to read archive")?;
+    let mut archive = ZipArchive::new(reader).context("failed to read archive")?;
+
+    let icon_data = read_icon(&mut archive);
 
     let mut installer = mod_loader.installer_for(package_name);
     installer.extract(archive, package_name, temp_path.clone())?;
-    installer.install(&temp_path, package_name, profile)?;
+    let files = installer.install(&temp_path, package_name, profile)?;
 
-    fs::remove_dir_all(temp_path).context("failed to remove temporary directory")?;
+    temp_dir
+        .close()
+        .context("failed to remove temporary directory")?;
 
+    // most installers leave out the icon, in which case it's saved separately
     let icon = installer
         .mod_dir(package_name, profile)
-        .and_then(|path| path.join("icon.png").exists_or_none());
+        .and_then(|path| path.join("icon.png").exists_or_none())
+        .or_else(|| {
+            save_icon(&icon_data?, local_mod.uuid, profile)
+                .inspect_err(|err| warn!("failed to save icon of {}: {:#}", package_name, err))
+                .ok()
+        });
+
+    Ok((icon, files))
+}
+
+/// Where icons of local mods are kept, if their installer doesn't keep them.
+fn icon_dir(profile: &Profile) -> PathBuf {
+    profile.path.join("_state").join("icons")
+}
+
+/// Reads the `icon.png` at the root of a local mod's archive.
+fn read_icon(archive: &mut ZipArchive<Cursor<Vec<u8>>>) -> Option<Vec<u8>> {
+    let mut file = archive.by_name("icon.png").ok()?;
+
+    let mut data = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut data).ok()?;
+
+    Some(data)
+}
+
+fn save_icon(data: &[u8], uuid: Uuid, profile: &Profile) -> Result<PathBuf> {
+    let dir = icon_dir(profile);
+    fs::create_dir_all(&dir).fs_context("creating icon directory", &dir)?;
+
+    let path = dir.join(format!("{}.png", uuid));
+    fs::write(&path, data).fs_context("writing icon", &path)?;
+
+    Ok(path)
+}
+
+/// Removes the icon saved for a local mod, if any.
+pub fn remove_icon(local_mod: &LocalMod, profile: &Profile) {
+    let Some(icon) = &local_mod.icon else {
+        return;
+    };
 
-    Ok(icon)
+    if icon.starts_with(icon_dir(profile)) {
+        fs::remove_file(icon).ok();
+    }
 }
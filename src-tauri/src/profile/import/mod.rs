@@ -11,7 +11,6 @@ use itertools::Itertools;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
-use tempfile::tempdir;
 use tracing::{trace, warn};
 use uuid::Uuid;
 
@@ -19,6 +18,7 @@ use crate::{
     profile::{
         export::{ProfileManifest, R2Mod, PROFILE_DATA_PREFIX},
         install::{self, InstallOptions, ModInstall},
+        integrity, RuntimeDataOverrides,
     },
     state::ManagerExt,
     thunderstore::VersionIdent,
@@ -26,17 +26,25 @@ use crate::{
 };
 
 pub mod commands;
+mod config;
+mod dependency_strings;
 mod local;
+mod preview;
 mod r2modman;
 
-pub use local::{import_local_mod, import_local_mod_base64};
+#[cfg(test)]
+mod tests;
+
+pub use local::{
+    import_local_mod, import_local_mod_base64, read_zip_manifest, remove_icon, LocalModKind,
+};
 
 use super::export::{self, IncludeExtensions, IncludeGenerated};
 
-pub fn read_file_at_path(path: PathBuf) -> Result<ImportData> {
+pub fn read_file_at_path(path: PathBuf, app: &AppHandle) -> Result<ImportData> {
     let file = File::open(&path).fs_context("opening file", &path)?;
 
-    read_file(file)
+    read_file(file, app)
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -47,23 +55,28 @@ pub struct ImportData {
     pub delete_after_import: bool,
 }
 
-pub fn import_file_from_path(path: PathBuf) -> Result<ImportData> {
+pub fn import_file_from_path(path: PathBuf, app: &AppHandle) -> Result<ImportData> {
     let file = File::open(&path).fs_context("opening file", &path)?;
 
-    read_file(file)
+    read_file(file, app)
 }
 
-pub(super) fn read_file(source: impl Read + Seek) -> Result<ImportData> {
-    let temp_dir = tempdir().context("failed to create temporary directory")?;
+pub(super) fn read_file(source: impl Read + Seek, app: &AppHandle) -> Result<ImportData> {
+    let temp_dir = util::fs::temp_dir_in(&app.lock_prefs().temp_dir())?;
     util::zip::extract(source, temp_dir.path())?;
 
     let reader = File::open(temp_dir.path().join("export.r2x"))
         .map(BufReader::new)
         .context("failed to open profile manifest")?;
 
-    let manifest: ProfileManifest =
+    let mut manifest: ProfileManifest =
         serde_yaml::from_reader(reader).context("failed to read profile manifest")?;
 
+    // profiles exported before hashes were added, or by other managers
+    manifest
+        .hash
+        .get_or_insert_with(|| integrity::hash_manifest(&manifest.mods));
+
     Ok(ImportData {
         manifest,
         path: temp_dir.into_path(),
@@ -71,12 +84,12 @@ pub(super) fn read_file(source: impl Read + Seek) -> Result<ImportData> {
     })
 }
 
-fn read_base64(base64: &str) -> Result<ImportData> {
+fn read_base64(base64: &str, app: &AppHandle) -> Result<ImportData> {
     let bytes = BASE64_STANDARD
         .decode(base64)
         .context("failed to decode base64 data")?;
 
-    read_file(Cursor::new(bytes))
+    read_file(Cursor::new(bytes), app)
 }
 
 async fn read_code(key: Uuid, app: &AppHandle) -> Result<ImportData> {
@@ -98,17 +111,49 @@ async fn read_code(key: Uuid, app: &AppHandle) -> Result<ImportData> {
         .await?;
 
     match response.strip_prefix(PROFILE_DATA_PREFIX) {
-        Some(str) => read_base64(str),
+        Some(str) => read_base64(str, app),
         None => Err(eyre!("invalid profile data")),
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImportOptions {
+    /// Import all files in the profile, not just well-known config formats.
+    pub import_all: bool,
+    /// Add the imported profile to an existing one with the same name,
+    /// instead of replacing its mods and config.
+    pub merge: bool,
+    /// When merging, replace existing config files that differ from the imported ones.
+    pub overwrite_config: bool,
+}
+
+/// A difference between the imported and existing profile found while merging.
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ImportConflict {
+    /// The profile already has another version of the mod, which was kept.
+    #[serde(rename_all = "camelCase")]
+    ModVersion {
+        name: String,
+        existing: String,
+        imported: String,
+    },
+    /// A config file differs from the imported one.
+    Config { path: PathBuf, overwritten: bool },
+}
+
+pub(super) struct ImportResult {
+    pub index: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
 pub(super) async fn import_profile(
     data: ImportData,
     options: InstallOptions,
-    import_all: bool,
+    import_options: ImportOptions,
     app: &AppHandle,
-) -> Result<usize> {
+) -> Result<ImportResult> {
     let ImportData {
         manifest:
             ProfileManifest {
@@ -121,7 +166,13 @@ pub(super) async fn import_profile(
         delete_after_import,
     } = data;
 
-    let (index, profile_path, to_install) = {
+    let mut conflicts = Vec::new();
+    let mut merging = false;
+    let mut created = false;
+
+    util::cancel::check(app)?;
+
+    let (index, profile_path, runtime_data, to_install) = {
         let (names, installs) = resolve_mods(mods, app)?;
 
         let mut manager = app.lock_manager();
@@ -131,41 +182,78 @@ pub(super) async fn import_profile(
             game.set_active_profile(index)?;
 
             let profile = &mut game.profiles[index];
-            let to_install = incremental_update(installs, &names, profile)?;
+            let to_install = if import_options.merge {
+                merging = true;
+                merge_mods(installs, &names, profile, &mut conflicts)
+            } else {
+                incremental_update(installs, &names, profile)?
+            };
 
             (index, profile, to_install)
         } else {
             let index = game.profiles.len();
             let profile = game.create_profile(name, None, app.db())?;
+            created = true;
 
             (index, profile, installs)
         };
 
-        profile.ignored_updates = ignored_updates.into_iter().collect();
-        (index, profile.path.clone(), to_install)
+        if merging {
+            profile.ignored_updates.extend(ignored_updates);
+        } else {
+            profile.ignored_updates = ignored_updates.into_iter().collect();
+        }
+
+        (
+            index,
+            profile.path.clone(),
+            profile.runtime_data.clone(),
+            to_install,
+        )
     };
 
     install::install_mods(to_install, options, app)
         .await
         .context("error while importing mods")?;
 
-    import_config(
-        &profile_path,
-        &path,
-        if import_all {
-            IncludeExtensions::All
-        } else {
-            IncludeExtensions::Default
-        },
-        IncludeGenerated::No,
-    )
+    // the installer has already removed the mods it installed by now
+    if util::cancel::is_cancelled(app) {
+        if created {
+            let mut manager = app.lock_manager();
+            manager
+                .active_game_mut()
+                .delete_profile(index, true, app.db())
+                .unwrap_or_else(|err| warn!("failed to delete cancelled import: {:#}", err));
+        }
+
+        return Err(util::cancel::Cancelled.into());
+    }
+
+    let extensions = if import_options.import_all {
+        IncludeExtensions::All
+    } else {
+        IncludeExtensions::Default
+    };
+
+    if merging {
+        merge_config(
+            &profile_path,
+            &path,
+            extensions,
+            import_options.overwrite_config,
+            &runtime_data,
+            &mut conflicts,
+        )
+    } else {
+        import_config(&profile_path, &path, extensions, IncludeGenerated::No)
+    }
     .context("failed to import config")?;
 
     if delete_after_import {
         fs::remove_dir_all(path).ok();
     }
 
-    Ok(index)
+    Ok(ImportResult { index, conflicts })
 }
 
 fn resolve_mods(mods: Vec<R2Mod>, app: &AppHandle) -> Result<(Vec<VersionIdent>, Vec<ModInstall>)> {
@@ -241,6 +329,121 @@ fn incremental_update(
     Ok(to_install)
 }
 
+/// Picks the imported mods that the profile doesn't have yet.
+///
+/// Nothing is removed or toggled; mods installed with another version
+/// are kept as they are and reported as conflicts.
+fn merge_mods(
+    installs: Vec<ModInstall>,
+    mod_names: &[VersionIdent],
+    profile: &super::Profile,
+    conflicts: &mut Vec<ImportConflict>,
+) -> Vec<ModInstall> {
+    let existing: HashMap<_, _> = profile
+        .thunderstore_mods()
+        .map(|(ts_mod, _)| (ts_mod.ident.full_name(), ts_mod.ident.version()))
+        .collect();
+
+    mod_names
+        .iter()
+        .zip(installs)
+        .filter_map(|(ident, install)| match existing.get(ident.full_name()) {
+            None => Some(install),
+            Some(version) => {
+                if *version != ident.version() {
+                    warn!(
+                        "kept {} {} instead of the imported version {}",
+                        ident.full_name(),
+                        version,
+                        ident.version()
+                    );
+
+                    conflicts.push(ImportConflict::ModVersion {
+                        name: ident.full_name().to_owned(),
+                        existing: (*version).to_owned(),
+                        imported: ident.version().to_owned(),
+                    });
+                }
+
+                None
+            }
+        })
+        .collect()
+}
+
+/// Copies imported config files into the profile without removing any existing ones.
+///
+/// Files that differ are only overwritten if `overwrite` is set,
+/// but are reported as conflicts either way.
+fn merge_config(
+    dest: &Path,
+    src: &Path,
+    extensions: IncludeExtensions,
+    overwrite: bool,
+    runtime_data: &RuntimeDataOverrides,
+    conflicts: &mut Vec<ImportConflict>,
+) -> Result<()> {
+    for file in export::find_config(src, extensions, IncludeGenerated::No) {
+        let src_path = src.join(&file);
+        let dest_path = config_dest(dest, &file);
+
+        let relative_path = dest_path.strip_prefix(dest).unwrap_or(&file);
+        if runtime_data.matches(relative_path) {
+            continue;
+        }
+
+        if dest_path.exists() {
+            if file_checksum(&src_path)? == file_checksum(&dest_path)? {
+                continue;
+            }
+
+            let action = match overwrite {
+                true => "overwriting it",
+                false => "keeping it",
+            };
+            warn!(
+                "{} differs from the imported config file, {}",
+                relative_path.display(),
+                action
+            );
+
+            conflicts.push(ImportConflict::Config {
+                path: relative_path.to_owned(),
+                overwritten: overwrite,
+            });
+
+            if !overwrite {
+                continue;
+            }
+        }
+
+        trace!("copy {}", file.display());
+        fs::create_dir_all(dest_path.parent().unwrap())?;
+        fs::copy(src_path, dest_path)?;
+    }
+
+    Ok(())
+}
+
+/// Checks whether the existing config `file` is also in the imported profile.
+fn has_source(src: &Path, file: &Path) -> bool {
+    src.join(file).exists()
+        || file
+            .strip_prefix("BepInEx/config")
+            .is_ok_and(|suffix| src.join("config").join(suffix).exists())
+}
+
+/// Maps a config file in an imported profile to its path in `dest`.
+///
+/// Older exports put the `config` directory at the root instead of inside `BepInEx`.
+fn config_dest(dest: &Path, file: &Path) -> PathBuf {
+    if file.starts_with("config") {
+        dest.join("BepInEx").join(file)
+    } else {
+        dest.join(file)
+    }
+}
+
 pub fn import_config(
     dest: &Path,
     src: &Path,
@@ -252,12 +455,7 @@ pub fn import_config(
 
     if extensions != IncludeExtensions::All {
         for file in existing_files {
-            let exists = src.join(&file).exists()
-                || file
-                    .strip_prefix("BepInEx/config")
-                    .is_ok_and(|suffix| src.join("config").join(suffix).exists());
-
-            if !exists {
+            if !has_source(src, &file) {
                 trace!("remove {}", file.display());
                 fs::remove_file(dest.join(&file))?;
             }
@@ -266,11 +464,7 @@ pub fn import_config(
 
     for file in source_files {
         let src_path = src.join(&file);
-        let dest_path = if file.starts_with("config") {
-            dest.join("BepInEx").join(&file)
-        } else {
-            dest.join(&file)
-        };
+        let dest_path = config_dest(dest, &file);
 
         let need_copy = if dest_path.exists() {
             file_checksum(&src_path)? != file_checksum(&dest_path)?
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use eyre::Result;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::{ImportData, ImportOptions};
+use crate::{
+    profile::export::{self, IncludeExtensions, IncludeGenerated},
+    state::ManagerExt,
+};
+
+/// What importing a profile would change, compared to the profile it replaces or merges into.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    /// Whether a profile with the imported name already exists.
+    pub profile_exists: bool,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub upgraded: Vec<VersionChange>,
+    pub downgraded: Vec<VersionChange>,
+    /// Existing config files that would be replaced with different contents.
+    pub config_overwritten: Vec<PathBuf>,
+    /// Existing config files that would be deleted.
+    pub config_removed: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Compares `data` against the profile it would be imported into, without changing anything.
+pub fn preview(
+    data: &ImportData,
+    options: ImportOptions,
+    app: &AppHandle,
+) -> Result<ImportPreview> {
+    let manager = app.read_manager();
+    let game = manager.active_game();
+
+    let Some(profile) = game
+        .profile_index(&data.manifest.name)
+        .map(|index| &game.profiles[index])
+    else {
+        return Ok(ImportPreview {
+            added: data
+                .manifest
+                .mods
+                .iter()
+                .map(|r2_mod| r2_mod.ident().into_string())
+                .collect(),
+            ..Default::default()
+        });
+    };
+
+    let mut preview = ImportPreview {
+        profile_exists: true,
+        ..Default::default()
+    };
+
+    for r2_mod in &data.manifest.mods {
+        let ident = r2_mod.ident();
+
+        let existing = profile
+            .thunderstore_mods()
+            .find(|(ts_mod, _)| ts_mod.ident.full_name() == ident.full_name());
+
+        let Some((ts_mod, _)) = existing else {
+            preview.added.push(ident.into_string());
+            continue;
+        };
+
+        // merging keeps the existing version
+        if options.merge || ts_mod.ident.version() == ident.version() {
+            continue;
+        }
+
+        let change = VersionChange {
+            name: ident.full_name().to_owned(),
+            from: ts_mod.ident.version().to_owned(),
+            to: ident.version().to_owned(),
+        };
+
+        let is_upgrade = match (
+            semver::Version::parse(&change.from),
+            semver::Version::parse(&change.to),
+        ) {
+            (Ok(from), Ok(to)) => to > from,
+            _ => true,
+        };
+
+        if is_upgrade {
+            preview.upgraded.push(change);
+        } else {
+            preview.downgraded.push(change);
+        }
+    }
+
+    if !options.merge {
+        preview.removed = profile
+            .thunderstore_mods()
+            .filter(|(ts_mod, _)| {
+                !data
+                    .manifest
+                    .mods
+                    .iter()
+                    .any(|r2_mod| r2_mod.full_name.as_str() == ts_mod.ident.full_name())
+            })
+            .map(|(ts_mod, _)| ts_mod.ident.to_string())
+            .collect();
+    }
+
+    let extensions = if options.import_all {
+        IncludeExtensions::All
+    } else {
+        IncludeExtensions::Default
+    };
+
+    for file in export::find_config(&data.path, extensions, IncludeGenerated::No) {
+        let src_path = data.path.join(&file);
+        let dest_path = super::config_dest(&profile.path, &file);
+
+        if !dest_path.exists() || (options.merge && !options.overwrite_config) {
+            continue;
+        }
+
+        let relative = dest_path.strip_prefix(&profile.path).unwrap_or(&file);
+        if profile.runtime_data.matches(relative) {
+            continue;
+        }
+
+        if super::file_checksum(&src_path)? != super::file_checksum(&dest_path)? {
+            preview.config_overwritten.push(relative.to_owned());
+        }
+    }
+
+    // mirrors the removal in `import_config`, merging never removes files
+    if !options.merge && extensions != IncludeExtensions::All {
+        preview.config_removed =
+            export::find_config(&profile.path, extensions, IncludeGenerated::No)
+                .filter(|file| {
+                    !super::has_source(&data.path, file) && !profile.runtime_data.matches(file)
+                })
+                .collect();
+    }
+
+    Ok(preview)
+}
@@ -3,14 +3,16 @@ use std::{
     path::PathBuf,
 };
 
-use eyre::{bail, Context, Result};
+use eyre::{bail, Context, OptionExt, Result};
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
+use tempfile::NamedTempFile;
 use tracing::{info, warn};
 
 use crate::{
     logger,
     profile::{
+        auto_snapshot::{self, SnapshotReason},
         export::{ProfileManifest, R2Mod},
         install::InstallOptions,
     },
@@ -19,7 +21,7 @@ use crate::{
     util::{self, error::IoResultExt, fs::PathExt},
 };
 
-use super::ImportData;
+use super::{ImportData, ImportOptions};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +30,78 @@ pub struct ProfileImportData {
     profiles: Vec<String>,
 }
 
+/// A mod manager built on r2modman's data format.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalManager {
+    R2modman,
+    /// The Overwolf app, which keeps its data in a `DataFolder` subdirectory.
+    ThunderstoreModManager,
+}
+
+impl ExternalManager {
+    const ALL: [Self; 2] = [Self::R2modman, Self::ThunderstoreModManager];
+
+    fn label(self) -> &'static str {
+        match self {
+            ExternalManager::R2modman => "r2modman",
+            ExternalManager::ThunderstoreModManager => "Thunderstore Mod Manager",
+        }
+    }
+
+    fn default_path(self) -> Option<PathBuf> {
+        match self {
+            ExternalManager::R2modman => {
+                let parent_dir = match cfg!(target_os = "linux") {
+                    // r2modman uses the config dir instead of the data dir on linux.
+                    true => dirs_next::config_dir(),
+                    false => dirs_next::data_dir(),
+                }?;
+
+                parent_dir.join("r2modmanPlus-local").exists_or_none()
+            }
+            ExternalManager::ThunderstoreModManager => dirs_next::data_dir()?
+                .join("Thunderstore Mod Manager")
+                .join("DataFolder")
+                .exists_or_none(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalManagerInfo {
+    manager: ExternalManager,
+    label: &'static str,
+    #[serde(flatten)]
+    data: ProfileImportData,
+}
+
+/// Finds the data of all installed external managers
+/// that have profiles for the active game.
+pub(super) fn gather_external_info(app: &AppHandle) -> Vec<ExternalManagerInfo> {
+    ExternalManager::ALL
+        .into_iter()
+        .filter_map(|manager| {
+            let path = manager.default_path()?;
+
+            let profiles = match find_profiles(path.clone(), app) {
+                Ok(profiles) => profiles.map(util::fs::file_name_owned).collect(),
+                Err(err) => {
+                    info!("skipping {}: {:#}", manager.label(), err);
+                    return None;
+                }
+            };
+
+            Some(ExternalManagerInfo {
+                manager,
+                label: manager.label(),
+                data: ProfileImportData { path, profiles },
+            })
+        })
+        .collect()
+}
+
 pub(super) fn gather_info(
     path: Option<PathBuf>,
     app: &AppHandle,
@@ -55,8 +129,16 @@ pub(super) async fn import(path: PathBuf, include: &[bool], app: &AppHandle) ->
             continue;
         }
 
+        util::cancel::check(app)?;
+
         let name = profile_dir.file_name().unwrap().to_string_lossy();
 
+        let existed = app
+            .lock_manager()
+            .active_game()
+            .profile_index(&name)
+            .is_some();
+
         let data = match prepare_import(profile_dir.clone(), app) {
             Ok(Some(data)) => data,
             Ok(None) => {
@@ -72,13 +154,33 @@ pub(super) async fn import(path: PathBuf, include: &[bool], app: &AppHandle) ->
             }
         };
 
+        // importing into an existing profile changes it in place, so keep a copy
+        // to roll back to if the import is cancelled halfway through
+        let backup = match existed {
+            true => Some(backup_profile(&name, app)?),
+            false => None,
+        };
+
         if let Err(err) = import_profile(data, app).await {
+            if util::cancel::is_cancel_error(&err) {
+                if let Some(backup) = backup {
+                    roll_back(&name, backup, app).await;
+                }
+
+                return Err(err);
+            }
+
             logger::log_webview_err(
                 "Error while importing from r2modman",
                 err.wrap_err(format!("Failed to import profile '{}'", name)),
                 app,
             );
 
+            if existed {
+                // keep the profile and its snapshot around so the import can be undone
+                continue;
+            }
+
             let mut manager = app.lock_manager();
 
             let game = manager.active_game_mut();
@@ -95,8 +197,59 @@ pub(super) async fn import(path: PathBuf, include: &[bool], app: &AppHandle) ->
     Ok(())
 }
 
+fn backup_profile(name: &str, app: &AppHandle) -> Result<NamedTempFile> {
+    let temp_dir = app.lock_prefs().temp_dir();
+    let manager = app.read_manager();
+    let game = manager.active_game();
+
+    let index = game
+        .profile_index(name)
+        .ok_or_eyre("profile to import into not found")?;
+
+    super::export::export_zip_to_temp(&game.profiles[index], game.game, &temp_dir)
+        .context("failed to back up profile before importing")
+}
+
+/// Restores a profile from the backup taken before a cancelled import into it.
+async fn roll_back(name: &str, backup: NamedTempFile, app: &AppHandle) {
+    info!("rolling back cancelled import of '{}'", name);
+
+    // the cancellation has been handled by now, and would otherwise stop the restore too
+    util::cancel::reset(app);
+
+    let result = async {
+        let mut data = super::read_file_at_path(backup.path().to_path_buf(), app)?;
+        data.manifest.name = name.to_owned();
+
+        super::import_profile(
+            data,
+            InstallOptions::default()
+                .can_cancel(false)
+                .send_progress(false),
+            ImportOptions::default(),
+            app,
+        )
+        .await
+    }
+    .await;
+
+    if let Err(err) = result {
+        logger::log_webview_err(
+            "Error while importing from r2modman",
+            err.wrap_err(format!(
+                "Failed to roll back cancelled import of '{}'",
+                name
+            )),
+            app,
+        );
+    }
+
+    // keep reporting the import as cancelled
+    util::cancel::cancel(app);
+}
+
 fn find_profiles(mut path: PathBuf, app: &AppHandle) -> Result<impl Iterator<Item = PathBuf>> {
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let game = &manager.active_game;
 
@@ -130,7 +283,6 @@ async fn import_profile(data: ImportData, app: &AppHandle) -> Result<()> {
     super::import_profile(
         data,
         InstallOptions::default()
-            .can_cancel(false)
             .send_progress(false)
             .on_progress(Box::new(move |progress, app| {
                 let percentage = (progress.total_progress * 100.0).round();
@@ -139,7 +291,7 @@ async fn import_profile(data: ImportData, app: &AppHandle) -> Result<()> {
                     app,
                 );
             })),
-        false,
+        ImportOptions::default(),
         app,
     )
     .await?;
@@ -148,7 +300,8 @@ async fn import_profile(data: ImportData, app: &AppHandle) -> Result<()> {
 }
 
 fn prepare_import(mut profile_dir: PathBuf, app: &AppHandle) -> Result<Option<ImportData>> {
-    let mut manager = app.lock_manager();
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
 
     let name = util::fs::file_name_owned(&profile_dir);
 
@@ -163,13 +316,16 @@ fn prepare_import(mut profile_dir: PathBuf, app: &AppHandle) -> Result<Option<Im
 
     profile_dir.pop();
 
-    if let Some(index) = manager.active_game().profile_index(&name) {
-        info!("deleting existing profile '{}'", name);
+    let game = manager.active_game();
+    if let Some(index) = game.profile_index(&name) {
+        info!("overwriting existing profile '{}'", name);
 
-        manager
-            .active_game_mut()
-            .delete_profile(index, true, app.db())
-            .context("failed to delete existing profile")?;
+        auto_snapshot::capture(
+            &game.profiles[index],
+            SnapshotReason::Migration,
+            &prefs,
+            app,
+        )?;
     }
 
     let import = ImportData {
@@ -178,6 +334,7 @@ fn prepare_import(mut profile_dir: PathBuf, app: &AppHandle) -> Result<Option<Im
             mods,
             game: None,
             ignored_updates: Vec::new(),
+            hash: None,
         },
         path: profile_dir,
         delete_after_import: false,
@@ -187,22 +344,9 @@ fn prepare_import(mut profile_dir: PathBuf, app: &AppHandle) -> Result<Option<Im
 }
 
 fn find_path() -> Option<PathBuf> {
-    let parent_dir = match cfg!(target_os = "linux") {
-        // r2modman uses the config dir instead of the data dir on linux.
-        true => dirs_next::config_dir(),
-        false => dirs_next::data_dir(),
-    }
-    .unwrap();
-
-    parent_dir
-        .join("r2modmanPlus-local")
-        .exists_or_none()
-        .or_else(|| {
-            parent_dir
-                .join("Thunderstore Mod Manager")
-                .join("DataFolder")
-                .exists_or_none()
-        })
+    ExternalManager::ALL
+        .into_iter()
+        .find_map(ExternalManager::default_path)
 }
 
 fn emit_update(message: &str, app: &AppHandle) {
@@ -0,0 +1,59 @@
+use super::dependency_strings::{name_distance, normalize, parse, split_line, strip_list_marker};
+
+#[test]
+fn parse_finds_strings_and_urls() {
+    let text = "Install BepInEx-BepInExPack-5.4.2100, then \
+        [this](https://thunderstore.io/c/lethal-company/p/Owner/Cool_Mod/) and \
+        https://thunderstore.io/c/lethal-company/p/Owner/Other/v/1.0.0/ (not some-hyphenated-words)";
+
+    assert_eq!(
+        parse(text),
+        [
+            "BepInEx-BepInExPack-5.4.2100",
+            "Owner-Cool_Mod",
+            "Owner-Other-1.0.0"
+        ]
+    );
+}
+
+#[test]
+fn strip_list_markers() {
+    assert_eq!(strip_list_marker("- Mod"), "Mod");
+    assert_eq!(strip_list_marker("*   Mod"), "Mod");
+    assert_eq!(strip_list_marker("12. Mod"), "Mod");
+    assert_eq!(strip_list_marker("3) Mod"), "Mod");
+    assert_eq!(strip_list_marker("3D Mod"), "3D Mod");
+    assert_eq!(strip_list_marker("-Mod"), "-Mod");
+}
+
+#[test]
+fn split_lines() {
+    assert_eq!(split_line("Cool Mod"), ("Cool Mod", None));
+    assert_eq!(
+        split_line("- Cool Mod by Owner (1.0.0)"),
+        ("Cool Mod", Some("Owner"))
+    );
+    assert_eq!(
+        split_line("1. [Cool Mod](https://example.com) `1.0.0` by Owner"),
+        ("Cool Mod", Some("Owner"))
+    );
+    assert_eq!(split_line("* Cool Mod `1.0.0`"), ("Cool Mod", None));
+}
+
+#[test]
+fn normalize_names() {
+    assert_eq!(normalize("Cool_Mod"), "coolmod");
+    assert_eq!(normalize("Cool Mod!"), "coolmod");
+    assert_eq!(normalize("Ünïcode-Mod"), "ünïcodemod");
+}
+
+#[test]
+fn similar_names() {
+    assert_eq!(name_distance("morecompany", "morecompany"), Some(0));
+    assert_eq!(name_distance("morecompny", "morecompany"), Some(1));
+    assert_eq!(name_distance("lategamupgrads", "lategameupgrades"), Some(2));
+
+    // short names need to match more closely
+    assert_eq!(name_distance("abc", "abd"), None);
+    assert_eq!(name_distance("morecompany", "lesscompany"), None);
+}
@@ -0,0 +1,96 @@
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use tauri::AppHandle;
+use zip::ZipArchive;
+
+use crate::{
+    profile::{ModUserData, ProfileMod, ProfileModKind},
+    state::ManagerExt,
+};
+
+/// Where to put a reinstalled mod, so updates keep its position and state.
+pub(super) struct Placement {
+    pub index: usize,
+    pub enabled: bool,
+    pub install_time: DateTime<Utc>,
+    pub user_data: ModUserData,
+}
+
+/// Extracts a downloaded mod archive from outside of Thunderstore into `path`.
+pub(super) async fn extract(
+    data: Bytes,
+    package_name: String,
+    path: PathBuf,
+    app: &AppHandle,
+) -> Result<()> {
+    let staging_dir = app.lock_prefs().staging_dir();
+    let mod_loader = app.read_manager().active_mod_loader();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        super::cache::insert(&path, &staging_dir, |dir| {
+            let archive =
+                ZipArchive::new(Cursor::new(data.to_vec())).context("failed to open archive")?;
+
+            mod_loader.installer_for(&package_name).extract(
+                archive,
+                &package_name,
+                dir.to_path_buf(),
+            )
+        })
+    })
+    .await?
+    .context("error while extracting")
+}
+
+/// Installs an extracted mod into the active profile and saves it.
+///
+/// If `placement` is `None`, the mod is added to the end of the list. Otherwise
+/// it replaces the previous version with the same id, which is only removed now
+/// so that a failed download doesn't leave the profile without the mod.
+pub(super) fn add_to_profile(
+    kind: ProfileModKind,
+    cache_path: &Path,
+    placement: Option<Placement>,
+    app: &AppHandle,
+) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let mod_loader = manager.active_mod_loader();
+    let profile = manager.active_profile_mut();
+
+    let uuid = kind.uuid();
+    let package_name = kind.full_name().into_owned();
+
+    if placement.is_some() && profile.index_of(uuid).is_ok() {
+        profile
+            .force_remove_mod(uuid)
+            .context("failed to remove existing version")?;
+    }
+
+    let mut installer = mod_loader.installer_for(&package_name);
+    let files = installer.install(cache_path, &package_name, profile)?;
+
+    match placement {
+        Some(placement) => {
+            let index = placement.index.min(profile.mods.len());
+            let profile_mod = ProfileMod::new_at(placement.install_time, kind)
+                .with_files(files)
+                .with_user_data(placement.user_data);
+            profile.mods.insert(index, profile_mod);
+
+            if !placement.enabled {
+                profile.force_toggle_mod(uuid)?;
+            }
+        }
+        None => profile.mods.push(ProfileMod::new(kind).with_files(files)),
+    }
+
+    profile.save(app.db())?;
+
+    Ok(())
+}
@@ -1,12 +1,56 @@
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    time::Duration,
+};
 
+use chrono::{DateTime, TimeDelta, Utc};
 use eyre::{Context, Result};
-use tracing::info;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
+use tracing::{info, warn};
 
-use crate::{prefs::Prefs, state::ManagerExt, thunderstore::VersionIdent, util};
+use crate::{
+    prefs::Prefs,
+    profile::ModManager,
+    state::ManagerExt,
+    thunderstore::{self, Thunderstore, VersionIdent},
+    util::{self, error::IoResultExt, fs::JsonStyle},
+};
 
-pub(super) fn path(ident: &VersionIdent, prefs: &Prefs) -> PathBuf {
+/// Records when each cached version was last installed, keyed by `<full_name>/<version>`.
+const USAGE_FILE_NAME: &str = ".usage.json";
+
+/// Top-level cache directories that don't belong to Thunderstore packages.
+const EXTERNAL_DIRS: &[&str] = &["nexus", "github", "icons"];
+
+/// How long [`schedule_evict`] waits after an install before evicting.
+const EVICT_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a version is kept after being used, regardless of the size limit.
+const EVICT_MIN_AGE: TimeDelta = TimeDelta::minutes(10);
+
+/// How long garbage collection waits for the package index, before reporting
+/// uninstalled packages as [`GcCategory::Unknown`].
+const GC_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+static EVICT_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// Locks for the cache paths that are currently being extracted into, see [`insert`].
+static EXTRACTING: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+type Usage = HashMap<String, DateTime<Utc>>;
+
+/// The cache directory of a version. This doesn't depend on the game, so packages
+/// listed in several communities are only downloaded once.
+pub(crate) fn path(ident: &VersionIdent, prefs: &Prefs) -> PathBuf {
     let mut path = prefs.cache_dir();
 
     path.push(ident.full_name());
@@ -15,79 +59,562 @@ pub(super) fn path(ident: &VersionIdent, prefs: &Prefs) -> PathBuf {
     path
 }
 
-pub(super) fn clear(path: PathBuf) -> Result<()> {
-    if path.exists() {
-        fs::remove_dir_all(&path).context("failed to delete cache directory")?;
-        fs::create_dir_all(path).context("failed to recreate cache directory")?;
+fn usage_key(full_name: &str, version: &str) -> String {
+    format!("{}/{}", full_name, version)
+}
+
+fn read_usage(cache_dir: &Path) -> Usage {
+    let path = cache_dir.join(USAGE_FILE_NAME);
+
+    match path.exists() {
+        true => util::fs::read_json(&path).unwrap_or_else(|err| {
+            warn!("failed to read cache usage, resetting: {:#}", err);
+            Usage::new()
+        }),
+        false => Usage::new(),
     }
+}
 
-    Ok(())
+fn write_usage(usage: &Usage, cache_dir: &Path) -> Result<()> {
+    util::fs::write_json(cache_dir.join(USAGE_FILE_NAME), usage, JsonStyle::Compact)
+        .context("failed to write cache usage")
 }
 
-pub(super) fn prepare_soft_clear(app: AppHandle) -> Result<Vec<PathBuf>> {
-    let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
-    let thunderstore = app.lock_thunderstore();
+/// Extracts a package into the cache at `path` with `extract`, unless it's
+/// already there.
+///
+/// `extract` writes into a temporary directory in `staging_dir`, which is then
+/// renamed into place. An existing cache directory is never replaced, since other
+/// installs may be linking from it, and extractions into the same path are done
+/// one at a time, so a package that's installed twice at once is only extracted once.
+pub(super) fn insert<F>(path: &Path, staging_dir: &Path, extract: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    let lock = EXTRACTING
+        .lock()
+        .unwrap()
+        .entry(path.to_path_buf())
+        .or_default()
+        .clone();
+
+    let result = {
+        let _guard = lock.lock().unwrap();
+
+        match path.exists() {
+            true => Ok(()),
+            false => insert_new(path, staging_dir, extract),
+        }
+    };
 
-    let installed_mods = manager
-        .active_game()
-        .installed_mods(&thunderstore)
-        .map(|borrowed| {
-            let ident = borrowed.ident();
-            (ident.full_name(), ident.version())
+    drop(lock);
+    EXTRACTING
+        .lock()
+        .unwrap()
+        .retain(|_, lock| Arc::strong_count(lock) > 1);
+
+    result
+}
+
+fn insert_new<F>(path: &Path, staging_dir: &Path, extract: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    let temp_dir = util::fs::temp_dir_in(staging_dir)?;
+
+    extract(temp_dir.path())?;
+
+    let parent = path.parent().unwrap();
+    fs::create_dir_all(parent).fs_context("creating cache directory", parent)?;
+
+    let temp_path = temp_dir.into_path();
+    fs::rename(&temp_path, path)
+        .fs_context("moving into cache", path)
+        .inspect_err(|_| {
+            fs::remove_dir_all(&temp_path).ok();
         })
-        .collect::<HashSet<_>>();
+}
 
-    let packages = prefs
-        .cache_dir()
-        .read_dir()
-        .context("failed to read cache directory")?
-        .filter_map(Result::ok);
+/// Marks the given cached versions as used just now.
+pub(super) fn mark_used<'a>(
+    idents: impl IntoIterator<Item = &'a VersionIdent>,
+    prefs: &Prefs,
+) -> Result<()> {
+    let cache_dir = prefs.cache_dir();
+    let mut usage = read_usage(&cache_dir);
+    let now = Utc::now();
+
+    for ident in idents {
+        usage.insert(usage_key(ident.full_name(), ident.version()), now);
+    }
 
-    let mut to_remove = Vec::new();
+    write_usage(&usage, &cache_dir)
+}
 
-    for entry in packages {
-        let path = entry.path();
+struct CacheEntry {
+    key: String,
+    path: PathBuf,
+    size: u64,
+    last_used: DateTime<Utc>,
+}
 
-        if !path.is_dir() {
-            continue;
-        }
+/// Reads every cached Thunderstore version.
+fn read_entries(cache_dir: &Path, usage: &Usage) -> Result<Vec<CacheEntry>> {
+    let packages = cache_dir
+        .read_dir()
+        .context("failed to read cache directory")?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+
+    let mut entries = Vec::new();
 
+    for path in packages {
         let package_name = util::fs::file_name_owned(&path);
 
-        if thunderstore.find_package(&package_name).is_err() {
-            // package from a game other than the loaded one, skip
+        if EXTERNAL_DIRS.contains(&package_name.as_str()) {
             continue;
         }
 
         let versions = fs::read_dir(&path)
             .with_context(|| format!("failed to read cache for {}", &package_name))?
-            .filter_map(|entry| entry.ok());
+            .filter_map(Result::ok);
 
         for entry in versions {
             let path = entry.path();
-            let version = util::fs::file_name_owned(&path);
+            let key = usage_key(&package_name, &util::fs::file_name_owned(&path));
+
+            // versions cached before usage was tracked fall back to their modified time
+            let last_used = usage.get(&key).copied().unwrap_or_else(|| {
+                entry
+                    .metadata()
+                    .and_then(|meta| meta.modified())
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_default()
+            });
+
+            entries.push(CacheEntry {
+                size: util::fs::get_directory_size(&path),
+                key,
+                path,
+                last_used,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// The usage keys of all Thunderstore mods installed in any profile or used
+/// as a tool, along with the slug of the game they're installed for.
+fn installed_keys(manager: &ModManager) -> impl Iterator<Item = (String, String)> + '_ {
+    manager.games.values().flat_map(|game| {
+        game.profiles
+            .iter()
+            .flat_map(|profile| profile.thunderstore_mods())
+            .map(|(ts_mod, _)| &ts_mod.ident)
+            .chain(game.tools.iter().map(|tool| &tool.ident))
+            .map(|ident| {
+                (
+                    usage_key(ident.full_name(), ident.version()),
+                    game.game.slug.to_string(),
+                )
+            })
+    })
+}
+
+/// Evicts old versions from the cache a while after the last install,
+/// see [`evict`].
+///
+/// Further calls before then are ignored, so a batch of installs only
+/// walks the cache once.
+pub(super) fn schedule_evict(app: &AppHandle) {
+    if EVICT_SCHEDULED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let app = app.to_owned();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(EVICT_DELAY).await;
+        EVICT_SCHEDULED.store(false, Ordering::Relaxed);
+
+        let (cache_dir, max_size, installed) = {
+            let prefs = app.lock_prefs();
+            let manager = app.read_manager();
+
+            let installed = installed_keys(&manager)
+                .map(|(key, _)| key)
+                .collect::<HashSet<_>>();
+
+            (prefs.cache_dir(), prefs.max_cache_size_bytes(), installed)
+        };
+
+        let Some(max_size) = max_size else {
+            return;
+        };
+
+        let result =
+            tauri::async_runtime::spawn_blocking(move || evict(&cache_dir, max_size, &installed))
+                .await
+                .map_err(eyre::Report::from)
+                .and_then(|result| result);
+
+        if let Err(err) = result {
+            warn!("failed to evict mods from cache: {:#}", err);
+        }
+    });
+}
+
+/// Removes the least recently used versions from the cache until it fits
+/// within `max_size`.
+///
+/// Versions in `installed` are never removed, so the cache can stay over the
+/// limit if those alone exceed it. Neither are recently used ones, which an
+/// install that's still in progress might be linking from.
+fn evict(cache_dir: &Path, max_size: u64, installed: &HashSet<String>) -> Result<()> {
+    if !cache_dir.exists() {
+        return Ok(());
+    }
+
+    let mut total_size = util::fs::get_directory_size(cache_dir);
+
+    if total_size <= max_size {
+        return Ok(());
+    }
+
+    let cutoff = Utc::now() - EVICT_MIN_AGE;
+
+    let mut entries = read_entries(cache_dir, &read_usage(cache_dir))?;
+    entries.retain(|entry| !installed.contains(&entry.key) && entry.last_used < cutoff);
+
+    entries.sort_by_key(|entry| entry.last_used);
+
+    let mut evicted = Vec::new();
+
+    for entry in entries {
+        if total_size <= max_size {
+            break;
+        }
+
+        // skip the trash, otherwise the space wouldn't actually be freed
+        fs::remove_dir_all(&entry.path)
+            .with_context(|| format!("failed to remove {} from cache", entry.key))?;
+
+        total_size = total_size.saturating_sub(entry.size);
+        evicted.push(entry.key);
+    }
+
+    if total_size > max_size {
+        warn!("cache is still over the size limit, only installed mods remain");
+    }
+
+    info!("evicted {} mods from cache", evicted.len());
+
+    // read again, installs may have marked versions as used in the meantime
+    let mut usage = read_usage(cache_dir);
+    for key in &evicted {
+        usage.remove(key);
+    }
+
+    write_usage(&usage, cache_dir)
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub total_size: u64,
+    /// The size of cached versions installed in each game's profiles, keyed by slug.
+    ///
+    /// Versions shared between games count towards each of them.
+    pub game_sizes: HashMap<String, u64>,
+    /// The size of cached versions that aren't installed in any profile,
+    /// which is what a soft clear would free.
+    pub unused_size: u64,
+    pub package_count: usize,
+    pub version_count: usize,
+    /// When the least recently used version was last installed.
+    pub oldest_entry: Option<DateTime<Utc>>,
+}
 
-            if installed_mods.contains(&(&package_name, &version)) {
-                // package is installed, skip
-                continue;
+/// Computes statistics about the cache.
+///
+/// This walks the whole cache directory, so avoid calling it on the main thread.
+pub(super) fn stats(
+    cache_dir: &Path,
+    installed: HashMap<String, HashSet<String>>,
+) -> Result<CacheStats> {
+    if !cache_dir.exists() {
+        return Ok(CacheStats::default());
+    }
+
+    let entries = read_entries(cache_dir, &read_usage(cache_dir))?;
+
+    let mut stats = CacheStats {
+        total_size: util::fs::get_directory_size(cache_dir),
+        version_count: entries.len(),
+        package_count: entries
+            .iter()
+            .filter_map(|entry| entry.key.split_once('/'))
+            .map(|(full_name, _)| full_name)
+            .unique()
+            .count(),
+        oldest_entry: entries.iter().map(|entry| entry.last_used).min(),
+        ..Default::default()
+    };
+
+    for entry in &entries {
+        match installed.get(&entry.key) {
+            Some(games) => {
+                for slug in games {
+                    *stats.game_sizes.entry(slug.clone()).or_default() += entry.size;
+                }
             }
+            None => stats.unused_size += entry.size,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Maps the usage key of each installed version to the games it's installed for.
+pub(super) fn installed_by_game(manager: &ModManager) -> HashMap<String, HashSet<String>> {
+    let mut installed: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for (key, slug) in installed_keys(manager) {
+        installed.entry(key).or_default().insert(slug);
+    }
+
+    installed
+}
+
+/// Why a cached version can be removed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum GcCategory {
+    /// No version of the package is installed in any profile.
+    NotInstalled,
+    /// Another version of the package is installed, but not this one.
+    OldVersion,
+    /// The package isn't installed anywhere and belongs to another game.
+    OtherGame,
+    /// The package isn't installed anywhere, but the package index isn't loaded,
+    /// so it's unknown which game it belongs to.
+    Unknown,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GcCategoryReport {
+    pub category: GcCategory,
+    pub size: u64,
+    pub count: usize,
+}
+
+/// Everything needed to categorize the cache, collected while the state is locked.
+pub(super) struct GcInput {
+    cache_dir: PathBuf,
+    installed: HashSet<String>,
+    installed_packages: HashSet<String>,
+    /// `None` if the package index isn't loaded.
+    game_packages: Option<HashSet<String>>,
+}
+
+impl GcInput {
+    pub fn new(prefs: &Prefs, manager: &ModManager, thunderstore: &Thunderstore) -> Result<Self> {
+        let cache_dir = prefs.cache_dir();
+
+        let installed = installed_keys(manager)
+            .map(|(key, _)| key)
+            .collect::<HashSet<_>>();
+
+        let installed_packages = installed
+            .iter()
+            .filter_map(|key| key.split_once('/'))
+            .map(|(full_name, _)| full_name.to_owned())
+            .collect();
+
+        let game_packages = match (thunderstore.packages_fetched(), cache_dir.exists()) {
+            (false, _) => None,
+            (true, true) => Some(
+                cache_dir
+                    .read_dir()
+                    .context("failed to read cache directory")?
+                    .filter_map(Result::ok)
+                    .map(|entry| util::fs::file_name_owned(entry.path()))
+                    .filter(|name| thunderstore.find_package(name).is_ok())
+                    .collect(),
+            ),
+            (true, false) => Some(HashSet::new()),
+        };
+
+        Ok(Self {
+            cache_dir,
+            installed,
+            installed_packages,
+            game_packages,
+        })
+    }
+
+    fn categorize(&self, entry: &CacheEntry) -> Option<GcCategory> {
+        if self.installed.contains(&entry.key) {
+            return None;
+        }
+
+        let (full_name, _) = entry.key.split_once('/')?;
+
+        if self.installed_packages.contains(full_name) {
+            return Some(GcCategory::OldVersion);
+        }
+
+        match &self.game_packages {
+            Some(packages) if packages.contains(full_name) => Some(GcCategory::NotInstalled),
+            Some(_) => Some(GcCategory::OtherGame),
+            None => Some(GcCategory::Unknown),
+        }
+    }
+
+    fn entries(&self) -> Result<Vec<(GcCategory, CacheEntry)>> {
+        if !self.cache_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = read_entries(&self.cache_dir, &read_usage(&self.cache_dir))?
+            .into_iter()
+            .filter_map(|entry| Some((self.categorize(&entry)?, entry)))
+            .collect();
+
+        Ok(entries)
+    }
+}
 
-            to_remove.push(path);
+pub(super) async fn prepare_gc(app: &AppHandle) -> Result<GcInput> {
+    // packages are matched against the index to find ones from other games
+    if tokio::time::timeout(GC_FETCH_TIMEOUT, thunderstore::wait_for_fetch(app))
+        .await
+        .is_err()
+    {
+        warn!("package index not loaded, the game of uninstalled packages is unknown");
+    }
+
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    GcInput::new(&prefs, &manager, &thunderstore)
+}
+
+/// Reports how much space each [`GcCategory`] would free.
+///
+/// Like [`stats`], this walks the whole cache directory.
+pub(super) fn gc_report(input: GcInput) -> Result<Vec<GcCategoryReport>> {
+    let mut reports = [
+        GcCategory::NotInstalled,
+        GcCategory::OldVersion,
+        GcCategory::OtherGame,
+        GcCategory::Unknown,
+    ]
+    .map(|category| GcCategoryReport {
+        category,
+        size: 0,
+        count: 0,
+    });
+
+    for (category, entry) in input.entries()? {
+        let report = reports
+            .iter_mut()
+            .find(|report| report.category == category)
+            .expect("all categories should have a report");
+
+        report.size += entry.size;
+        report.count += 1;
+    }
+
+    Ok(reports.into())
+}
+
+/// Removes all cached versions in the given categories.
+///
+/// Returns the number of bytes freed.
+pub(super) fn gc_purge(input: GcInput, categories: &[GcCategory]) -> Result<u64> {
+    let mut usage = read_usage(&input.cache_dir);
+    let mut freed = 0;
+    let mut count = 0;
+
+    for (category, entry) in input.entries()? {
+        if !categories.contains(&category) {
+            continue;
         }
+
+        // skip the trash, otherwise the space wouldn't actually be freed
+        fs::remove_dir_all(&entry.path)
+            .with_context(|| format!("failed to remove {} from cache", entry.key))?;
+
+        freed += entry.size;
+        usage.remove(&entry.key);
+        count += 1;
+    }
+
+    info!("purged {} mods from cache", count);
+
+    if count > 0 {
+        write_usage(&usage, &input.cache_dir)?;
     }
 
-    Ok(to_remove)
+    Ok(freed)
 }
 
-pub(super) fn do_soft_clear(paths: Vec<PathBuf>) -> Result<()> {
-    let count = paths.len();
+pub(super) fn clear(path: PathBuf) -> Result<()> {
+    if path.exists() {
+        util::fs::delete_permanently(&path).context("failed to delete cache directory")?;
+        fs::create_dir_all(path).context("failed to recreate cache directory")?;
+    }
+
+    Ok(())
+}
+
+/// Collects what a soft clear needs while the state is locked.
+///
+/// Cached versions are shared between games, since a package listed in several
+/// communities has the same name and versions in each. Mods installed for any
+/// game are therefore kept, not just those of the active one.
+pub(super) fn prepare_soft_clear(
+    prefs: &Prefs,
+    manager: &ModManager,
+) -> (PathBuf, HashSet<String>) {
+    let installed = installed_keys(manager).map(|(key, _)| key).collect();
+
+    (prefs.cache_dir(), installed)
+}
 
-    for path in paths {
-        fs::remove_dir_all(path)?;
+/// Removes every cached version that isn't installed in any profile or used as a tool.
+///
+/// Returns the number of bytes freed.
+pub(super) fn soft_clear(cache_dir: &Path, installed: &HashSet<String>) -> Result<u64> {
+    if !cache_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut usage = read_usage(cache_dir);
+    let mut freed = 0;
+    let mut count = 0;
+
+    for entry in read_entries(cache_dir, &usage)? {
+        if installed.contains(&entry.key) {
+            continue;
+        }
+
+        util::fs::delete_permanently(&entry.path)?;
+
+        freed += entry.size;
+        usage.remove(&entry.key);
+        count += 1;
     }
 
     info!("cleared {} mods from cache", count);
 
-    Ok(())
+    if count > 0 {
+        write_usage(&usage, cache_dir)?;
+    }
+
+    Ok(freed)
 }
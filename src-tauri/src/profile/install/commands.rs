@@ -1,6 +1,6 @@
-use std::sync::atomic::Ordering;
-
+use eyre::OptionExt;
 use tauri::{command, AppHandle};
+use uuid::Uuid;
 
 use crate::{
     state::ManagerExt,
@@ -8,10 +8,42 @@ use crate::{
     util::{self, cmd::Result},
 };
 
-use super::{InstallOptions, ModInstall};
+use super::{
+    cache::{CacheStats, GcCategory, GcCategoryReport},
+    conflicts::{self, DependencyConflict},
+    inspect::PackageInspection,
+    overlaps::{self, FileConflict},
+    tools::{self, Tool},
+    InstallOptions, ModInstall,
+};
 
+/// Installs a mod and its dependencies.
+///
+/// Unless `allow_conflicts` is set, nothing is installed if the dependencies
+/// conflict with each other or the profile, and the conflicts are returned instead.
 #[command]
-pub async fn install_mod(mod_ref: ModId, app: AppHandle) -> Result<()> {
+pub async fn install_mod(
+    mod_ref: ModId,
+    allow_conflicts: Option<bool>,
+    app: AppHandle,
+) -> Result<Vec<DependencyConflict>> {
+    util::cancel::reset(&app);
+
+    if !allow_conflicts.unwrap_or(false) {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let conflicts = conflicts::find(
+            &[mod_ref.borrow(&thunderstore)?],
+            manager.active_profile(),
+            &thunderstore,
+        );
+
+        if !conflicts.is_empty() {
+            return Ok(conflicts);
+        }
+    }
+
     super::install_with_deps(
         vec![ModInstall::new(mod_ref)],
         InstallOptions::default(),
@@ -20,14 +52,33 @@ pub async fn install_mod(mod_ref: ModId, app: AppHandle) -> Result<()> {
     )
     .await?;
 
-    Ok(())
+    Ok(Vec::new())
+}
+
+/// Installs a newline-separated list of dependency strings and their dependencies.
+///
+/// Returns the number of mods that were installed, excluding dependencies.
+#[command]
+pub async fn install_dependency_strings(input: String, app: AppHandle) -> Result<usize> {
+    util::cancel::reset(&app);
+
+    let strings = input.lines().map(str::to_owned).collect::<Vec<_>>();
+    let count = super::install_dependency_strings(&strings, &app).await?;
+
+    Ok(count)
 }
 
 #[command]
-pub fn cancel_install(app: AppHandle) -> Result<()> {
-    app.app_state()
-        .cancel_install_flag
-        .store(true, Ordering::Relaxed);
+pub async fn install_github_mod(
+    owner: String,
+    repo: String,
+    tag: Option<String>,
+    asset: Option<String>,
+    app: AppHandle,
+) -> Result<()> {
+    util::cancel::reset(&app);
+
+    super::github::install(owner, repo, tag, asset, &app).await?;
 
     Ok(())
 }
@@ -35,11 +86,17 @@ pub fn cancel_install(app: AppHandle) -> Result<()> {
 #[command]
 pub async fn clear_download_cache(soft: bool, app: AppHandle) -> Result<u64> {
     if soft {
-        let paths = super::cache::prepare_soft_clear(app)?;
+        let (cache_dir, installed) = {
+            let prefs = app.lock_prefs();
+            let manager = app.read_manager();
 
-        let size = paths.iter().map(util::fs::get_directory_size).sum();
+            super::cache::prepare_soft_clear(&prefs, &manager)
+        };
 
-        tauri::async_runtime::spawn_blocking(|| super::cache::do_soft_clear(paths)).await??;
+        let size = tauri::async_runtime::spawn_blocking(move || {
+            super::cache::soft_clear(&cache_dir, &installed)
+        })
+        .await??;
 
         Ok(size)
     } else {
@@ -53,10 +110,47 @@ pub async fn clear_download_cache(soft: bool, app: AppHandle) -> Result<u64> {
     }
 }
 
+#[command]
+pub async fn get_cache_stats(app: AppHandle) -> Result<CacheStats> {
+    let (cache_dir, installed) = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        (prefs.cache_dir(), super::cache::installed_by_game(&manager))
+    };
+
+    let stats =
+        tauri::async_runtime::spawn_blocking(move || super::cache::stats(&cache_dir, installed))
+            .await??;
+
+    Ok(stats)
+}
+
+#[command]
+pub async fn get_cache_gc_report(app: AppHandle) -> Result<Vec<GcCategoryReport>> {
+    let input = super::cache::prepare_gc(&app).await?;
+
+    let report =
+        tauri::async_runtime::spawn_blocking(move || super::cache::gc_report(input)).await??;
+
+    Ok(report)
+}
+
+#[command]
+pub async fn purge_cache(categories: Vec<GcCategory>, app: AppHandle) -> Result<u64> {
+    let input = super::cache::prepare_gc(&app).await?;
+
+    let freed =
+        tauri::async_runtime::spawn_blocking(move || super::cache::gc_purge(input, &categories))
+            .await??;
+
+    Ok(freed)
+}
+
 #[command]
 pub fn get_download_size(mod_ref: ModId, app: AppHandle) -> Result<u64> {
     let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
     let thunderstore = app.lock_thunderstore();
 
     Ok(super::total_download_size(
@@ -66,3 +160,75 @@ pub fn get_download_size(mod_ref: ModId, app: AppHandle) -> Result<u64> {
         &thunderstore,
     ))
 }
+
+#[command]
+pub async fn inspect_package(mod_ref: ModId, app: AppHandle) -> Result<PackageInspection> {
+    let inspection = super::inspect::inspect(mod_ref, &app).await?;
+
+    Ok(inspection)
+}
+
+/// Finds files that are shipped by more than one enabled mod in the active profile.
+#[command]
+pub async fn scan_conflicts(app: AppHandle) -> Result<Vec<FileConflict>> {
+    let input = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        overlaps::ScanInput::new(manager.active_profile(), &prefs)
+    };
+
+    let conflicts = tauri::async_runtime::spawn_blocking(move || overlaps::scan(input)).await??;
+
+    Ok(conflicts)
+}
+
+#[command]
+pub fn get_tools(app: AppHandle) -> Result<Vec<Tool>> {
+    let manager = app.read_manager();
+
+    Ok(manager.active_game().tools.clone())
+}
+
+/// Adds a package to the tools of the active game, which are linked into
+/// every profile of the game when it's launched.
+#[command]
+pub fn add_tool(mod_ref: ModId, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    tools::add(mod_ref, &mut manager, &thunderstore)?;
+    manager.save_active_game(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn remove_tool(uuid: Uuid, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    manager
+        .active_game_mut()
+        .tools
+        .retain(|tool| tool.uuid() != uuid);
+    manager.save_active_game(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn set_tool_enabled(uuid: Uuid, enabled: bool, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let tool = manager
+        .active_game_mut()
+        .tools
+        .iter_mut()
+        .find(|tool| tool.uuid() == uuid)
+        .ok_or_eyre("tool not found")?;
+
+    tool.enabled = enabled;
+    manager.save_active_game(app.db())?;
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Detection of dependencies that require incompatible versions of the same package.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::{
+    profile::Profile,
+    thunderstore::{BorrowedMod, Thunderstore, VersionIdent},
+};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyConflict {
+    /// The full name of the package, without a version.
+    pub package: String,
+    /// The version of the package currently in the profile, if any.
+    pub installed: Option<String>,
+    pub requirements: Vec<Requirement>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirement {
+    pub version: String,
+    pub required_by: String,
+    /// Whether the requirement comes from the mods being installed,
+    /// rather than ones already in the profile.
+    pub is_new: bool,
+}
+
+/// Walks the dependency graph of `mods` and finds packages that would end up
+/// being required at different major versions, either by each other or by
+/// mods already in the profile.
+pub fn find(
+    mods: &[BorrowedMod<'_>],
+    profile: &Profile,
+    thunderstore: &Thunderstore,
+) -> Vec<DependencyConflict> {
+    let mut requirements: HashMap<&str, Vec<Requirement>> = HashMap::new();
+
+    for (dependant, dependency) in dependency_edges(mods, thunderstore) {
+        requirements
+            .entry(dependency.full_name())
+            .or_default()
+            .push(requirement(dependency, dependant, true));
+    }
+
+    let installed = profile
+        .mods
+        .iter()
+        .filter_map(|profile_mod| profile_mod.kind.as_thunderstore())
+        .map(|ts_mod| (ts_mod.ident.full_name(), &ts_mod.ident))
+        .collect::<HashMap<_, _>>();
+
+    for ident in installed.values() {
+        let Ok(borrowed) = thunderstore.find_ident(ident) else {
+            continue;
+        };
+
+        for dependency in borrowed.dependencies() {
+            // only consider packages that the new mods touch
+            if let Some(list) = requirements.get_mut(dependency.full_name()) {
+                list.push(requirement(dependency, ident, false));
+            }
+        }
+    }
+
+    let mut conflicts = requirements
+        .into_iter()
+        .filter_map(|(package, requirements)| {
+            let installed = installed.get(package).copied();
+
+            let majors = requirements
+                .iter()
+                .map(|req| major_version(&req.version))
+                .chain(installed.map(|ident| major_version(ident.version())))
+                .flatten()
+                .collect::<HashSet<_>>();
+
+            if majors.len() <= 1 {
+                return None;
+            }
+
+            Some(DependencyConflict {
+                package: package.to_owned(),
+                installed: installed.map(|ident| ident.version().to_owned()),
+                requirements,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    conflicts.sort_by(|a, b| a.package.cmp(&b.package));
+    conflicts
+}
+
+/// Every (dependant, dependency) pair reachable from `mods`.
+fn dependency_edges<'a>(
+    mods: &[BorrowedMod<'a>],
+    thunderstore: &'a Thunderstore,
+) -> Vec<(&'a VersionIdent, &'a VersionIdent)> {
+    let mut edges = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = mods.iter().copied().collect::<VecDeque<_>>();
+
+    while let Some(current) = queue.pop_front() {
+        if !visited.insert(current.ident().as_str()) {
+            continue;
+        }
+
+        for dependency in current.dependencies() {
+            edges.push((current.ident(), dependency));
+
+            if let Ok(borrowed) = thunderstore.find_ident(dependency) {
+                queue.push_back(borrowed);
+            }
+        }
+    }
+
+    edges
+}
+
+fn requirement(dependency: &VersionIdent, dependant: &VersionIdent, is_new: bool) -> Requirement {
+    Requirement {
+        version: dependency.version().to_owned(),
+        required_by: dependant.to_string(),
+        is_new,
+    }
+}
+
+fn major_version(version: &str) -> Option<u64> {
+    semver::Version::parse(version)
+        .ok()
+        .map(|version| version.major)
+}
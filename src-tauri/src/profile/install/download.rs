@@ -1,26 +1,27 @@
 use std::{
     fs,
     io::Cursor,
-    path::Path,
-    sync::atomic::Ordering,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
 use chrono::Utc;
 use core::str;
-use eyre::{Context, Result};
+use eyre::{eyre, Context, Result};
 use futures_util::StreamExt;
 use tauri::{AppHandle, Emitter};
 use thiserror::Error;
-use tracing::warn;
+use tracing::{info, warn};
 use zip::ZipArchive;
 
-use super::{cache, InstallOptions, InstallProgress, InstallTask, ModInstall};
+use super::{cache, fixups, InstallOptions, InstallProgress, InstallTask, ModInstall};
 use crate::{
-    profile::{ModManager, ProfileMod, ProfileModKind, ThunderstoreMod},
+    game::Game,
+    prefs::DownloadSource,
+    profile::{Profile, ProfileMod, ProfileModKind, ThunderstoreMod},
     state::ManagerExt,
-    thunderstore::Thunderstore,
-    util::error::IoResultExt,
+    thunderstore::VersionIdent,
+    util::{self, error::IoResultExt},
 };
 
 const DOWNLOAD_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
@@ -34,6 +35,15 @@ pub struct Installer<'a> {
     total_mods: usize,
     total_bytes: u64,
     completed_bytes: u64,
+    /// Cached versions used by this installation, to keep them from being evicted.
+    used: Vec<VersionIdent>,
+
+    /// The profile that mods are installed into.
+    ///
+    /// This is fixed when the installation starts, so switching profiles
+    /// in the meantime doesn't redirect the remaining mods.
+    profile_id: i64,
+    game: Game,
 
     app: &'a AppHandle,
 }
@@ -41,6 +51,7 @@ pub struct Installer<'a> {
 enum InstallMethod {
     Cached,
     Download { url: String, file_size: u64 },
+    File { path: PathBuf, file_size: u64 },
 }
 
 #[derive(Debug, Error)]
@@ -56,25 +67,28 @@ type InstallResult<T> = std::result::Result<T, InstallError>;
 
 impl<'a> Installer<'a> {
     pub fn create(options: InstallOptions, app: &'a AppHandle) -> Result<Self> {
+        let (profile_id, game) = {
+            let manager = app.read_manager();
+            (manager.active_profile().id, manager.active_game)
+        };
+
         Ok(Self {
+            profile_id,
+            game,
             options,
             index: 0,
             app,
             total_mods: 0,
             total_bytes: 0,
             completed_bytes: 0,
+            used: Vec::new(),
             current_name: String::new(),
             start_time: Instant::now(),
         })
     }
 
     fn is_cancelled(&self) -> bool {
-        self.options.can_cancel
-            && self
-                .app
-                .app_state()
-                .cancel_install_flag
-                .load(Ordering::Relaxed)
+        self.options.can_cancel && util::cancel::is_cancelled(self.app)
     }
 
     fn check_cancel(&self) -> InstallResult<()> {
@@ -106,35 +120,111 @@ impl<'a> Installer<'a> {
         }
     }
 
-    fn try_cache_install(&mut self, data: &ModInstall) -> Result<InstallMethod> {
-        let prefs = self.app.lock_prefs();
-        let mut manager = self.app.lock_manager();
-        let thunderstore = self.app.lock_thunderstore();
+    fn try_cache_install(&mut self, data: &ModInstall) -> Result<(VersionIdent, InstallMethod)> {
+        let (ident, file_size, download_url, source, cache_path) = {
+            let prefs = self.app.lock_prefs();
+            let installed = self.installed_ident(data);
+            let thunderstore = self.app.lock_thunderstore();
+
+            // packages removed from Thunderstore aren't in the index anymore, but can
+            // still be installed from a download override if they're in the profile
+            let (ident, file_size, download_url) = match data.id.borrow(&thunderstore) {
+                Ok(borrowed) => (
+                    borrowed.version.ident.clone(),
+                    borrowed.version.file_size,
+                    Some(borrowed.version.download_url()),
+                ),
+                Err(err) => match installed {
+                    Some(ident) => (ident, 0, None),
+                    None => return Err(err),
+                },
+            };
+
+            self.current_name = ident.name().to_owned();
 
-        let version = data.id.borrow(&thunderstore)?.version;
-        let cache_path = cache::path(&version.ident, &prefs);
+            let source = prefs.download_sources.get(ident.full_name()).cloned();
+            let cache_path = cache::path(&ident, &prefs);
 
-        self.current_name = version.name().to_owned();
+            (ident, file_size, download_url, source, cache_path)
+        };
 
         if cache_path.exists() {
             self.update(InstallTask::Installing);
+            self.install_from_cache(data, &ident, &cache_path)?;
+            self.completed_bytes += file_size;
+
+            return Ok((ident, InstallMethod::Cached));
+        }
+
+        let method = match source {
+            Some(DownloadSource::Url { url }) => {
+                info!("downloading {} from {}", self.current_name, url);
 
-            if let Some(callback) = &self.options.before_install {
-                callback(data, &mut manager, &thunderstore)?;
+                InstallMethod::Download {
+                    url: url.replace("{version}", ident.version()),
+                    file_size,
+                }
             }
+            Some(DownloadSource::File { path }) => {
+                info!("installing {} from {}", self.current_name, path.display());
 
-            cache_install(data, &cache_path, &mut manager, &thunderstore)?;
+                InstallMethod::File { path, file_size }
+            }
+            None => InstallMethod::Download {
+                url: download_url.ok_or_else(|| {
+                    eyre!(
+                        "{} is no longer on Thunderstore and has no download override",
+                        ident
+                    )
+                })?,
+                file_size,
+            },
+        };
 
-            self.completed_bytes += version.file_size;
-            manager.active_profile().save(self.app.db())?;
+        Ok((ident, method))
+    }
 
-            Ok(InstallMethod::Cached)
-        } else {
-            Ok(InstallMethod::Download {
-                url: version.download_url(),
-                file_size: version.file_size,
-            })
+    /// The identifier of the version being installed, if it's already in the profile.
+    fn installed_ident(&self, data: &ModInstall) -> Option<VersionIdent> {
+        let manager = self.app.read_manager();
+        let profile = manager.find_profile(self.profile_id).ok()?;
+
+        profile
+            .get_mod(data.id.package_uuid)
+            .ok()?
+            .kind
+            .as_thunderstore()
+            .filter(|ts_mod| ts_mod.id.version_uuid == data.id.version_uuid)
+            .map(|ts_mod| ts_mod.ident.clone())
+    }
+
+    /// Links a cached mod into the profile.
+    ///
+    /// This is the only step which needs to lock the mod manager,
+    /// so it's kept as short as possible.
+    fn install_from_cache(
+        &mut self,
+        data: &ModInstall,
+        ident: &VersionIdent,
+        cache_path: &Path,
+    ) -> Result<()> {
+        let mut manager = self.app.lock_manager();
+        let thunderstore = self.app.lock_thunderstore();
+
+        let profile = manager
+            .find_profile_mut(self.profile_id)
+            .context("profile was deleted during installation")?;
+
+        if let Some(callback) = &self.options.before_install {
+            callback(data, profile, &thunderstore)?;
         }
+
+        cache_install(data, ident, cache_path, self.game, profile)?;
+
+        self.used.push(ident.clone());
+        profile.save(self.app.db())?;
+
+        Ok(())
     }
 
     async fn download(&mut self, url: &str, file_size: u64) -> InstallResult<Vec<u8>> {
@@ -177,68 +267,57 @@ impl<'a> Installer<'a> {
         Ok(response)
     }
 
-    fn install_from_download(&mut self, data: Vec<u8>, install: &ModInstall) -> InstallResult<()> {
-        let prefs = self.app.lock_prefs();
-        let mut manager = self.app.lock_manager();
-        let thunderstore = self.app.lock_thunderstore();
-
-        let version = install.id.borrow(&thunderstore)?.version;
-        let cache_path = cache::path(&version.ident, &prefs);
-
-        fs::create_dir_all(&cache_path).fs_context("creating mod cache dir", &cache_path)?;
+    async fn install_from_download(
+        &mut self,
+        data: Vec<u8>,
+        install: &ModInstall,
+        ident: VersionIdent,
+    ) -> InstallResult<()> {
+        let (cache_path, staging_dir) = {
+            let prefs = self.app.lock_prefs();
+            (cache::path(&ident, &prefs), prefs.staging_dir())
+        };
 
         self.check_cancel()?;
         self.update(InstallTask::Extracting);
 
-        let mut installer = manager
-            .active_game
-            .mod_loader
-            .installer_for(version.full_name());
-
-        let archive = ZipArchive::new(Cursor::new(data)).context("failed to open archive")?;
-
-        installer
-            .extract(archive, version.full_name(), cache_path.clone())
-            .inspect_err(|_| {
-                // the cached mod is probably in an invalid state
-                fs::remove_dir_all(&cache_path).unwrap_or_else(|err| {
-                    warn!(
-                        "failed to clean up after failed extraction of {}: {:#}",
-                        self.current_name, err
-                    );
-                });
-            })
-            .context("error while extracting")?;
+        // extraction is by far the slowest step, so run it without holding any locks
+        let game = self.game;
+        let dest = cache_path.clone();
+        let extract_ident = ident.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            extract(data, &extract_ident, &dest, &staging_dir, game)
+        })
+        .await
+        .map_err(|err| InstallError::Error(err.into()))??;
 
         self.check_cancel()?;
         self.update(InstallTask::Installing);
 
-        if let Some(callback) = &self.options.before_install {
-            callback(install, &mut manager, &thunderstore)?;
-        }
-
-        cache_install(install, &cache_path, &mut manager, &thunderstore)?;
-
-        manager.active_profile().save(self.app.db())?;
+        self.install_from_cache(install, &ident, &cache_path)?;
 
         Ok(())
     }
 
     async fn install(&mut self, data: &ModInstall) -> InstallResult<()> {
-        if let InstallMethod::Download { url, file_size } = self.try_cache_install(data)? {
-            let response = self.download(&url, file_size).await?;
-            self.install_from_download(response, data)
-        } else {
-            Ok(())
+        self.current_name.clear();
+        let (ident, method) = self.try_cache_install(data)?;
+
+        match method {
+            InstallMethod::Cached => Ok(()),
+            InstallMethod::Download { url, file_size } => {
+                let response = self.download(&url, file_size).await?;
+                self.install_from_download(response, data, ident).await
+            }
+            InstallMethod::File { path, file_size } => {
+                let archive = fs::read(&path).fs_context("reading package", &path)?;
+                self.completed_bytes += file_size;
+                self.install_from_download(archive, data, ident).await
+            }
         }
     }
 
     pub async fn install_all(&mut self, mods: Vec<ModInstall>) -> Result<()> {
-        self.app
-            .app_state()
-            .cancel_install_flag
-            .store(false, Ordering::Relaxed);
-
         self.total_mods = mods.len();
         self.count_total_bytes(&mods)?;
 
@@ -253,7 +332,7 @@ impl<'a> Installer<'a> {
 
                     let mut manager = self.app.lock_manager();
 
-                    let profile = manager.active_profile_mut();
+                    let profile = manager.find_profile_mut(self.profile_id)?;
 
                     for install in mods.iter().take(i) {
                         profile
@@ -266,10 +345,10 @@ impl<'a> Installer<'a> {
                 Err(InstallError::Error(err)) => {
                     self.update(InstallTask::Error);
 
-                    let thunderstore = self.app.lock_thunderstore();
-
-                    let borrowed = data.id.borrow(&thunderstore)?;
-                    let name = &borrowed.package.ident;
+                    let name = match self.current_name.is_empty() {
+                        true => data.uuid().to_string(),
+                        false => self.current_name.clone(),
+                    };
 
                     return Err(err.wrap_err(format!("failed to install {}", name)));
                 }
@@ -278,48 +357,83 @@ impl<'a> Installer<'a> {
 
         self.update(InstallTask::Done);
 
-        let manager = self.app.lock_manager();
+        let prefs = self.app.lock_prefs();
+        let manager = self.app.read_manager();
         let thunderstore = self.app.lock_thunderstore();
 
         manager.cache_mods(&thunderstore).ok();
 
+        if let Err(err) = cache::mark_used(&self.used, &prefs) {
+            warn!("failed to record cache usage: {:#}", err);
+        }
+
+        cache::schedule_evict(self.app);
+
         Ok(())
     }
 
     fn count_total_bytes(&mut self, mods: &Vec<ModInstall>) -> Result<()> {
         let thunderstore = self.app.lock_thunderstore();
         for install in mods {
-            let borrowed = install.id.borrow(&thunderstore)?;
-            self.total_bytes += borrowed.version.file_size;
+            // packages that are no longer listed are only installable from an override,
+            // whose size isn't known in advance
+            if let Ok(borrowed) = install.id.borrow(&thunderstore) {
+                self.total_bytes += borrowed.version.file_size;
+            }
         }
 
         Ok(())
     }
 }
 
+/// Extracts a downloaded archive into the cache.
+///
+/// The archive is first extracted into `staging_dir` and moved into the cache
+/// once done, so a failed or cancelled extraction never leaves a partial mod behind.
+pub(super) fn extract(
+    data: Vec<u8>,
+    ident: &VersionIdent,
+    cache_path: &Path,
+    staging_dir: &Path,
+    game: Game,
+) -> Result<()> {
+    cache::insert(cache_path, staging_dir, |dir| {
+        let mut installer = game.mod_loader.installer_for(ident.full_name());
+
+        let archive = ZipArchive::new(Cursor::new(data)).context("failed to open archive")?;
+
+        installer
+            .extract(archive, ident.full_name(), dir.to_path_buf())
+            .context("error while extracting")?;
+
+        fixups::apply(ident, dir)
+    })
+}
+
 fn cache_install(
     data: &ModInstall,
+    ident: &VersionIdent,
     src: &Path,
-    manager: &mut ModManager,
-    thunderstore: &Thunderstore,
+    game: Game,
+    profile: &mut Profile,
 ) -> Result<()> {
-    let borrowed = data.id.borrow(thunderstore)?;
-    let package_name = borrowed.ident().full_name();
+    let package_name = ident.full_name();
 
-    let mut installer = manager.active_game.mod_loader.installer_for(package_name);
-    let profile = manager.active_profile_mut();
+    let mut installer = game.mod_loader.installer_for(package_name);
 
-    installer.install(src, package_name, profile)?;
+    let files = installer.install(src, package_name, profile)?;
 
     let install_time = data.install_time.unwrap_or_else(Utc::now);
 
     let profile_mod = ProfileMod::new_at(
         install_time,
         ProfileModKind::Thunderstore(ThunderstoreMod {
-            ident: borrowed.ident().clone(),
-            id: borrowed.into(),
+            ident: ident.clone(),
+            id: data.id.clone(),
         }),
-    );
+    )
+    .with_files(files)
+    .with_user_data(data.user_data.clone());
 
     match data.index {
         Some(index) if index < profile.mods.len() => {
@@ -331,7 +445,7 @@ fn cache_install(
     };
 
     if !data.enabled {
-        profile.force_toggle_mod(borrowed.package.uuid)?;
+        profile.force_toggle_mod(data.id.package_uuid)?;
     }
 
     Ok(())
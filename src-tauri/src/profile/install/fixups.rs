@@ -0,0 +1,157 @@
+//! Fixes for packages with known bad layouts, applied after extraction.
+//!
+//! The bundled rules live in `package_fixups.json`, which is compiled into the
+//! app and is empty until a package actually needs fixing. More can be added
+//! without a release through the `packageFixups` of the install overrides, see
+//! [`crate::game::overrides`]. Paths are relative to the extracted package, which
+//! uses the same layout as the profile (for example `BepInEx/plugins/Owner-Name`).
+
+use std::{
+    fs,
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
+
+use eyre::{bail, Context, Result};
+use semver::VersionReq;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::{thunderstore::VersionIdent, util};
+
+const FIXUPS_JSON: &str = include_str!("../../../package_fixups.json");
+
+static FIXUPS: LazyLock<Mutex<Vec<Fixup>>> = LazyLock::new(|| {
+    let fixups = serde_json::from_str(FIXUPS_JSON).unwrap_or_else(|err| {
+        warn!("failed to parse package fixups: {}", err);
+        Vec::new()
+    });
+
+    Mutex::new(fixups)
+});
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixup {
+    /// The full name of the package, like `Owner-Name`.
+    package: String,
+    /// Which versions the fixup applies to. Applies to all versions if omitted.
+    #[serde(default)]
+    versions: Option<VersionReq>,
+    actions: Vec<FixupAction>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum FixupAction {
+    /// Moves a file or directory. The contents of directories are merged into
+    /// the target, so this can also be used to flatten extra nesting.
+    Move { from: String, to: String },
+    /// Deletes a file or directory.
+    Delete { path: String },
+}
+
+impl Fixup {
+    fn matches(&self, ident: &VersionIdent) -> bool {
+        if self.package != ident.full_name() {
+            return false;
+        }
+
+        match &self.versions {
+            Some(req) => {
+                semver::Version::parse(ident.version()).is_ok_and(|version| req.matches(&version))
+            }
+            None => true,
+        }
+    }
+}
+
+/// Adds fixups after the bundled ones. Called when the install overrides are
+/// applied, which happens as the games are loaded, before anything is installed.
+pub(crate) fn extend(fixups: Vec<Fixup>) {
+    FIXUPS.lock().unwrap().extend(fixups);
+}
+
+/// Applies all matching fixups to a package extracted into `dir`.
+pub(super) fn apply(ident: &VersionIdent, dir: &Path) -> Result<()> {
+    let fixups = FIXUPS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|fixup| fixup.matches(ident))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for fixup in fixups {
+        for action in &fixup.actions {
+            debug!("applying fixup to {}: {:?}", ident, action);
+
+            action
+                .apply(dir)
+                .with_context(|| format!("failed to apply fixup to {}", ident))?;
+        }
+    }
+
+    Ok(())
+}
+
+impl FixupAction {
+    fn apply(&self, dir: &Path) -> Result<()> {
+        match self {
+            FixupAction::Move { from, to } => {
+                let from = resolve(dir, from)?;
+                let to = resolve(dir, to)?;
+
+                if from.exists() {
+                    move_merge(&from, &to)?;
+                }
+            }
+            FixupAction::Delete { path } => {
+                let path = resolve(dir, path)?;
+
+                if path.is_dir() {
+                    fs::remove_dir_all(path)?;
+                } else if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn resolve(dir: &Path, relative: &str) -> Result<std::path::PathBuf> {
+    if !util::fs::is_enclosed(relative) {
+        bail!("path {} escapes the package directory", relative);
+    }
+
+    Ok(dir.join(relative))
+}
+
+fn move_merge(from: &Path, to: &Path) -> Result<()> {
+    if from.is_file() || !(to.exists() || to.starts_with(from)) {
+        fs::create_dir_all(to.parent().unwrap())?;
+        fs::rename(from, to)?;
+        return Ok(());
+    }
+
+    // collect first, since `to` may be inside of `from`
+    let entries = fs::read_dir(from)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for path in entries {
+        if path == to {
+            continue;
+        }
+
+        move_merge(&path, &to.join(path.file_name().unwrap()))?;
+    }
+
+    if fs::read_dir(from)?.next().is_none() {
+        fs::remove_dir(from)?;
+    }
+
+    Ok(())
+}
@@ -3,10 +3,11 @@ use std::{
     fs::{self, File},
     io::{self, Read, Seek},
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use eyre::{Context, Result};
-use tracing::warn;
+use tracing::{trace, warn};
 use walkdir::WalkDir;
 use zip::ZipArchive;
 
@@ -68,11 +69,24 @@ where
     Ok(())
 }
 
+static USE_LINKS: AtomicBool = AtomicBool::new(true);
+
+/// Sets whether [`FileInstallMethod::Link`] shares files with the cache,
+/// or copies them like [`FileInstallMethod::Copy`].
+pub fn set_use_links(value: bool) {
+    USE_LINKS.store(value, Ordering::Relaxed);
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum FileInstallMethod {
-    /// Use a hard link.
+    /// Share the file with the cache, using a reflink or hard link if possible.
+    ///
+    /// Falls back to copying, for example if the profile is on another volume.
     Link,
     /// Copy the file.
+    ///
+    /// Used for files the user is expected to edit, like configs. These aren't
+    /// recorded as the mod's files, so they're left alone when it's toggled or removed.
     Copy,
 }
 
@@ -90,10 +104,19 @@ pub enum ConflictResolution {
 ///
 /// `before_install` is called each time a file is encountered,
 /// with the file's relative path and whether the target file already exists.
-pub(super) fn install<F>(src: &Path, profile: &Profile, mut before_install: F) -> Result<()>
+///
+/// Returns the paths of the installed files, relative to the profile directory,
+/// excluding those installed with [`FileInstallMethod::Copy`].
+pub(super) fn install<F>(
+    src: &Path,
+    profile: &Profile,
+    mut before_install: F,
+) -> Result<Vec<PathBuf>>
 where
     F: FnMut(&Path, bool) -> Result<(FileInstallMethod, ConflictResolution)>,
 {
+    let mut files = Vec::new();
+
     for entry in WalkDir::new(src) {
         let entry = entry?;
 
@@ -103,6 +126,8 @@ where
             .expect("WalkDir should only return full paths inside of the root");
 
         let target = profile.path.join(relative_path);
+        super::locked::forget(&target);
+
         if entry.file_type().is_dir() {
             if target.exists() {
                 continue;
@@ -124,9 +149,9 @@ where
                         );
                         continue;
                     }
-                    // fs::copy already overwrites the target, no need to remove it
-                    (ConflictResolution::Overwrite, FileInstallMethod::Copy) => (),
-                    (ConflictResolution::Overwrite, FileInstallMethod::Link) => {
+                    // the target might be linked to another package's cache,
+                    // so always unlink it instead of writing through it
+                    (ConflictResolution::Overwrite, _) => {
                         fs::remove_file(&target).with_context(|| {
                             format!(
                                 "failed to remove existing file at {}",
@@ -138,31 +163,103 @@ where
             }
 
             match method {
-                FileInstallMethod::Link => {
-                    fs::hard_link(entry.path(), target).with_context(|| {
+                FileInstallMethod::Link if USE_LINKS.load(Ordering::Relaxed) => {
+                    link_file(entry.path(), &target).with_context(|| {
                         format!("failed to link file at {}", relative_path.display())
                     })?;
                 }
-                FileInstallMethod::Copy => {
+                FileInstallMethod::Link | FileInstallMethod::Copy => {
                     fs::copy(entry.path(), target).with_context(|| {
                         format!("failed to copy file at {}", relative_path.display())
                     })?;
                 }
             }
+
+            if let FileInstallMethod::Link = method {
+                files.push(relative_path.to_path_buf());
+            }
         }
     }
 
-    Ok(())
+    Ok(files)
+}
+
+/// Links `src` to `target`, preferring copy-on-write reflinks over hard links,
+/// so that writes to the target never affect the cached original.
+pub(super) fn link_file(src: &Path, target: &Path) -> io::Result<()> {
+    if reflink_copy::reflink(src, target).is_ok() {
+        return Ok(());
+    }
+
+    match fs::hard_link(src, target) {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            trace!(
+                "failed to hard link {}: {}, copying instead",
+                src.display(),
+                err
+            );
+            fs::copy(src, target).map(|_| ())
+        }
+    }
+}
+
+/// Whether the file shares its data with another, usually its cached original.
+fn is_hard_linked(metadata: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.is_file() && metadata.nlink() > 1
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        false
+    }
 }
 
 /// Removes either a directory or file at `path`. Also accounts for any
 /// `.old` extensions that may exist.
+///
+/// Hard linked files are unlinked directly instead of being moved to the trash,
+/// so the cached originals are never touched.
+///
+/// If the files are locked, they're deleted later instead, see [`super::locked`].
+/// This is never retried on the spot, since it runs while holding the app's locks.
 pub(super) fn uninstall_any(path: impl AsRef<Path>) -> Result<()> {
-    for_any(
-        path.as_ref(),
-        |path| fs::remove_dir_all(path).map_err(|err| err.into()),
-        |path| fs::remove_file(path).map_err(|err| err.into()),
-    )
+    super::locked::delete_or_defer(path.as_ref(), uninstall_now)
+}
+
+/// Like [`uninstall_any`], but returns an error if the files are locked.
+pub(super) fn uninstall_now(path: &Path) -> Result<()> {
+    for_any(path, uninstall_dir, uninstall_file)
+}
+
+fn uninstall_file(path: &Path) -> Result<()> {
+    let metadata = path
+        .symlink_metadata()
+        .fs_context("reading metadata", path)?;
+
+    if is_hard_linked(&metadata) {
+        fs::remove_file(path).fs_context("unlinking file", path)?;
+        Ok(())
+    } else {
+        util::fs::delete_once(path)
+    }
+}
+
+fn uninstall_dir(path: &Path) -> Result<()> {
+    let linked = WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.metadata().is_ok_and(|meta| is_hard_linked(&meta)));
+
+    for entry in linked {
+        fs::remove_file(entry.path()).fs_context("unlinking file", entry.path())?;
+    }
+
+    util::fs::delete_once(path)
 }
 
 /// Toggles either a directory or file at `path`.
@@ -186,8 +283,7 @@ where
             for_file(path)
         }
     } else {
-        let mut path = path.to_path_buf();
-        path.add_ext("old");
+        let path = old_path(path);
 
         if path.exists() {
             for_file(&path)
@@ -197,6 +293,13 @@ where
     }
 }
 
+/// The path of a file when its mod is disabled.
+pub(super) fn old_path(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+    path.add_ext("old");
+    path
+}
+
 /// Toggles a file by adding/removing a `.old` extension to it.
 pub(super) fn toggle_file(path: impl AsRef<Path>, enabled: bool) -> Result<()> {
     let path = path.as_ref();
@@ -0,0 +1,428 @@
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use eyre::{bail, ensure, eyre, Context, Result};
+use itertools::Itertools;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::archive::{self, Placement};
+use crate::{
+    prefs::Prefs,
+    profile::ProfileModKind,
+    state::ManagerExt,
+    thunderstore::VersionIdent,
+    util::{self, http::RequestBuilderExt, secret::Secret},
+};
+
+const API_URL: &str = "https://api.github.com";
+
+/// A mod installed from a GitHub release asset.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubMod {
+    pub owner: String,
+    pub repo: String,
+    /// The tag of the installed release.
+    pub tag: String,
+    /// The name of the downloaded release asset.
+    pub asset: String,
+    pub description: Option<String>,
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub file_size: u64,
+    /// The newest release found during the last update check,
+    /// if it's newer than the installed one.
+    #[serde(default)]
+    pub latest_release: Option<GithubRelease>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubRelease {
+    pub tag: String,
+    pub asset: String,
+    pub url: String,
+    pub file_size: u64,
+    /// A stable id for this release, used to ignore the update.
+    pub uuid: Uuid,
+}
+
+impl GithubMod {
+    pub fn ident(&self) -> VersionIdent {
+        VersionIdent::new(
+            &util::sanitize_ident(&self.owner),
+            &self.package_name(),
+            self.tag.trim_start_matches(['v', 'V']),
+        )
+    }
+
+    /// The name used for the mod's directories in the profile.
+    pub fn package_name(&self) -> String {
+        util::sanitize_ident(&self.repo)
+    }
+
+    pub fn url(&self) -> String {
+        format!("https://github.com/{}/{}", self.owner, self.repo)
+    }
+
+    pub fn parsed_version(&self) -> Option<semver::Version> {
+        util::parse_lenient_version(&self.tag)
+    }
+
+    /// Checks whether a release with `tag` is newer than the installed one.
+    ///
+    /// Falls back to comparing the tags directly if either isn't a version.
+    fn is_outdated(&self, tag: &str) -> bool {
+        match (self.parsed_version(), util::parse_lenient_version(tag)) {
+            (Some(current), Some(latest)) => latest > current,
+            _ => tag != self.tag,
+        }
+    }
+
+    pub fn cache_path(&self, prefs: &Prefs) -> PathBuf {
+        let mut path = prefs.cache_dir();
+
+        path.push("github");
+        path.push(&self.owner);
+        path.push(&self.repo);
+        path.push(&self.tag);
+
+        path
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Release {
+    pub tag_name: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub size: u64,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// Finds the zip asset to install from the release.
+    ///
+    /// With a `preferred` name, such as the asset of the installed version, only an asset
+    /// with the same name (ignoring any version numbers in it) is picked. Otherwise the
+    /// release must have a single zip asset, or one named after the repository.
+    fn find_asset(&self, repo: &str, preferred: Option<&str>) -> Result<&ReleaseAsset> {
+        let zips = self
+            .assets
+            .iter()
+            .filter(|asset| asset.name.to_lowercase().ends_with(".zip"))
+            .collect::<Vec<_>>();
+
+        ensure!(!zips.is_empty(), "release has no zip assets");
+
+        if let Some(preferred) = preferred {
+            let preferred = asset_stem(preferred);
+
+            return zips
+                .into_iter()
+                .find(|asset| asset_stem(&asset.name) == preferred)
+                .ok_or_else(|| eyre!("release has no asset matching {}", preferred));
+        }
+
+        if let [asset] = zips.as_slice() {
+            return Ok(asset);
+        }
+
+        let repo = asset_stem(repo);
+        zips.iter()
+            .copied()
+            .find(|asset| asset_stem(&asset.name) == repo)
+            .ok_or_else(|| {
+                eyre!(
+                    "release has multiple zip assets, choose one of: {}",
+                    zips.iter().map(|asset| &asset.name).join(", ")
+                )
+            })
+    }
+}
+
+/// Normalizes an asset name for comparison, without the extension and version numbers,
+/// so `MyMod-v1.2.0.zip` and `MyMod-v1.3.0.zip` are considered the same asset.
+fn asset_stem(name: &str) -> String {
+    let name = name.to_lowercase();
+    let name = name.strip_suffix(".zip").unwrap_or(&name);
+
+    name.split(['-', '_', ' ', '+'])
+        .filter(|segment| !is_version(segment))
+        .join("-")
+}
+
+fn is_version(segment: &str) -> bool {
+    let version = segment.strip_prefix('v').unwrap_or(segment);
+
+    !version.is_empty()
+        && version
+            .chars()
+            .all(|char| char.is_ascii_digit() || char == '.')
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    description: Option<String>,
+}
+
+async fn get<T: serde::de::DeserializeOwned>(path: &str, app: &AppHandle) -> Result<T> {
+    let token = Secret::GithubToken.get().ok().flatten();
+
+    let response = app
+        .http()
+        .get(format!("{}/{}", API_URL, path))
+        .github_headers(token.as_deref())
+        .send()
+        .await?;
+
+    match response.status() {
+        StatusCode::NOT_FOUND => bail!("not found on GitHub"),
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+            bail!("GitHub rate limit reached, try again later")
+        }
+        _ => (),
+    }
+
+    let result = response.error_for_status()?.json().await?;
+    Ok(result)
+}
+
+/// Fetches a release of a repository, or the latest one if `tag` is `None`.
+pub(crate) async fn fetch_release(
+    owner: &str,
+    repo: &str,
+    tag: Option<&str>,
+    app: &AppHandle,
+) -> Result<Release> {
+    let path = match tag {
+        Some(tag) => format!("repos/{}/{}/releases/tags/{}", owner, repo, tag),
+        None => format!("repos/{}/{}/releases/latest", owner, repo),
+    };
+
+    get(&path, app).await
+}
+
+async fn download(url: &str, app: &AppHandle) -> Result<Bytes> {
+    let data = app
+        .http()
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(data)
+}
+
+/// Downloads and installs a mod from a GitHub release on the active profile.
+///
+/// If `tag` is `None`, the latest release is used. `asset` picks which zip asset
+/// to install if the release has several.
+pub async fn install(
+    owner: String,
+    repo: String,
+    tag: Option<String>,
+    asset: Option<String>,
+    app: &AppHandle,
+) -> Result<()> {
+    {
+        let manager = app.read_manager();
+        let installed = manager
+            .active_profile()
+            .github_mods()
+            .any(|(github_mod, _)| {
+                github_mod.owner.eq_ignore_ascii_case(&owner)
+                    && github_mod.repo.eq_ignore_ascii_case(&repo)
+            });
+
+        ensure!(!installed, "mod already installed");
+    }
+
+    let release = fetch_release(&owner, &repo, tag.as_deref(), app).await?;
+    let repository: Repository = get(&format!("repos/{}/{}", owner, repo), app).await?;
+
+    let asset = release.find_asset(&repo, asset.as_deref())?;
+
+    let github_mod = GithubMod {
+        tag: release.tag_name.clone(),
+        asset: asset.name.clone(),
+        file_size: asset.size,
+        description: repository.description,
+        uuid: Uuid::new_v4(),
+        latest_release: None,
+        owner,
+        repo,
+    };
+
+    install_asset(github_mod, &asset.browser_download_url, None, app).await
+}
+
+/// Checks for newer releases of the GitHub mods in the active profile.
+///
+/// Results are stored on each mod and picked up by `query_profile`.
+/// Returns the number of mods with an available update.
+pub async fn check_updates(app: &AppHandle) -> Result<usize> {
+    let mods = {
+        let manager = app.read_manager();
+        manager
+            .active_profile()
+            .github_mods()
+            .map(|(github_mod, _)| {
+                (
+                    github_mod.uuid,
+                    github_mod.owner.clone(),
+                    github_mod.repo.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if mods.is_empty() {
+        return Ok(0);
+    }
+
+    let mut results = Vec::with_capacity(mods.len());
+
+    for (uuid, owner, repo) in mods {
+        match fetch_release(&owner, &repo, None, app).await {
+            Ok(release) => results.push((uuid, release)),
+            Err(err) => warn!(
+                "failed to check for updates of {}/{}: {:#}",
+                owner, repo, err
+            ),
+        }
+    }
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let mut count = 0;
+
+    for (uuid, release) in results {
+        let Ok(profile_mod) = profile.get_mod_mut(uuid) else {
+            continue; // removed while checking
+        };
+
+        let ProfileModKind::Github(github_mod) = &mut profile_mod.kind else {
+            continue;
+        };
+
+        if !github_mod.is_outdated(&release.tag_name) {
+            github_mod.latest_release = None;
+            continue;
+        }
+
+        let asset = match release.find_asset(&github_mod.repo, Some(&github_mod.asset)) {
+            Ok(asset) => asset,
+            Err(err) => {
+                warn!(
+                    "can't update {}/{} to {}: {:#}",
+                    github_mod.owner, github_mod.repo, release.tag_name, err
+                );
+                continue;
+            }
+        };
+
+        count += 1;
+
+        // keep the id stable between checks, so ignored updates stay ignored
+        let uuid = match &github_mod.latest_release {
+            Some(latest) if latest.tag == release.tag_name => latest.uuid,
+            _ => Uuid::new_v4(),
+        };
+
+        github_mod.latest_release = Some(GithubRelease {
+            tag: release.tag_name.clone(),
+            asset: asset.name.clone(),
+            url: asset.browser_download_url.clone(),
+            file_size: asset.size,
+            uuid,
+        });
+    }
+
+    profile.save(app.db())?;
+
+    info!("found {} github mod updates", count);
+
+    Ok(count)
+}
+
+/// Updates the given GitHub mods to the releases found by [`check_updates`].
+pub async fn update(uuids: Vec<Uuid>, respect_ignored: bool, app: &AppHandle) -> Result<()> {
+    let updates = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        uuids
+            .into_iter()
+            .filter_map(|uuid| {
+                let index = profile.index_of(uuid).ok()?;
+                let profile_mod = &profile.mods[index];
+                let github_mod = profile_mod.kind.as_github()?;
+                let latest = github_mod.latest_release.as_ref()?;
+
+                if respect_ignored && profile.ignored_updates.contains(&latest.uuid) {
+                    return None;
+                }
+
+                let new_mod = GithubMod {
+                    tag: latest.tag.clone(),
+                    asset: latest.asset.clone(),
+                    file_size: latest.file_size,
+                    latest_release: None,
+                    ..github_mod.clone()
+                };
+
+                let placement = Placement {
+                    index,
+                    enabled: profile_mod.enabled,
+                    install_time: profile_mod.install_time,
+                    user_data: profile_mod.user_data(),
+                };
+
+                Some((placement, new_mod, latest.url.clone()))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (placement, github_mod, url) in updates {
+        install_asset(github_mod, &url, Some(placement), app).await?;
+    }
+
+    Ok(())
+}
+
+async fn install_asset(
+    github_mod: GithubMod,
+    url: &str,
+    placement: Option<Placement>,
+    app: &AppHandle,
+) -> Result<()> {
+    let cache_path = github_mod.cache_path(&app.lock_prefs());
+
+    if !cache_path.exists() {
+        info!(
+            "downloading {} from {}/{} ({})",
+            github_mod.asset, github_mod.owner, github_mod.repo, github_mod.tag
+        );
+
+        let data = download(url, app)
+            .await
+            .context("failed to download release asset")?;
+
+        archive::extract(data, github_mod.package_name(), cache_path.clone(), app).await?;
+    }
+
+    let kind = ProfileModKind::Github(Box::new(github_mod));
+    archive::add_to_profile(kind, &cache_path, placement, app)
+}
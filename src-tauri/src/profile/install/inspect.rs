@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use tauri::AppHandle;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use super::cache;
+use crate::{
+    state::ManagerExt,
+    thunderstore::{ModId, PackageManifest},
+};
+
+/// The contents of a package, read without installing it.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInspection {
+    pub manifest: Option<PackageManifest>,
+    pub readme: Option<String>,
+    /// Every file in the package, sorted by path.
+    pub files: Vec<PackageFile>,
+    pub from_cache: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFile {
+    /// Path relative to the package root, separated by forward slashes.
+    pub path: String,
+    pub size: u64,
+}
+
+/// Reads the manifest, README and file list of a package.
+///
+/// Uses the download cache if the package is already cached,
+/// otherwise downloads the package to memory without extracting it.
+pub async fn inspect(mod_ref: ModId, app: &AppHandle) -> Result<PackageInspection> {
+    let (cache_path, url) = {
+        let prefs = app.lock_prefs();
+        let thunderstore = app.lock_thunderstore();
+
+        let version = mod_ref.borrow(&thunderstore)?.version;
+
+        (cache::path(&version.ident, &prefs), version.download_url())
+    };
+
+    if cache_path.exists() {
+        return tauri::async_runtime::spawn_blocking(move || inspect_dir(&cache_path)).await?;
+    }
+
+    let data = app
+        .http()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context("failed to download package")?
+        .bytes()
+        .await
+        .context("failed to download package")?;
+
+    tauri::async_runtime::spawn_blocking(move || inspect_zip(Cursor::new(data))).await?
+}
+
+fn inspect_dir(root: &Path) -> Result<PackageInspection> {
+    let mut files = WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let path = entry
+                .path()
+                .strip_prefix(root)
+                .expect("walkdir should only yield children of root")
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let size = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+
+            PackageFile { path, size }
+        })
+        .collect::<Vec<_>>();
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let manifest = match fs::read_to_string(root.join("manifest.json")) {
+        Ok(str) => Some(parse_manifest(str)?),
+        Err(_) => None,
+    };
+
+    let readme = files
+        .iter()
+        .find(|file| is_readme(&file.path))
+        .and_then(|file| fs::read_to_string(root.join(&file.path)).ok());
+
+    Ok(PackageInspection {
+        manifest,
+        readme,
+        files,
+        from_cache: true,
+    })
+}
+
+fn inspect_zip(src: impl Read + Seek) -> Result<PackageInspection> {
+    let mut archive = ZipArchive::new(src).context("failed to open archive")?;
+
+    let mut result = PackageInspection::default();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+
+        if file.is_dir() {
+            continue;
+        }
+
+        let path = file.name().replace('\\', "/");
+
+        if path == "manifest.json" {
+            let mut str = String::with_capacity(file.size() as usize);
+            file.read_to_string(&mut str)
+                .context("failed to read manifest")?;
+
+            result.manifest = Some(parse_manifest(str)?);
+        } else if is_readme(&path) {
+            let mut str = String::with_capacity(file.size() as usize);
+            file.read_to_string(&mut str)
+                .context("failed to read README")?;
+
+            result.readme = Some(str);
+        }
+
+        result.files.push(PackageFile {
+            path,
+            size: file.size(),
+        });
+    }
+
+    result.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(result)
+}
+
+fn is_readme(path: &str) -> bool {
+    path.eq_ignore_ascii_case("README.md")
+}
+
+fn parse_manifest(mut str: String) -> Result<PackageManifest> {
+    // remove BOM
+    if str.starts_with("\u{feff}") {
+        str.replace_range(0..3, "");
+    }
+
+    serde_json::from_str(&str).context("failed to parse manifest")
+}
@@ -1,15 +1,17 @@
 use std::{
     borrow::Cow,
-    fs,
     path::{Path, PathBuf},
 };
 
 use eyre::Result;
 
 use super::{FileInstallMethod, PackageInstaller, PackageZip};
-use crate::profile::{
-    install::{self, fs::ConflictResolution},
-    Profile, ProfileMod,
+use crate::{
+    profile::{
+        install::{self, fs::ConflictResolution},
+        Profile, ProfileMod,
+    },
+    util,
 };
 
 pub struct BepinexInstaller;
@@ -40,7 +42,12 @@ impl PackageInstaller for BepinexInstaller {
         })
     }
 
-    fn install(&mut self, src: &Path, _package_name: &str, profile: &Profile) -> Result<()> {
+    fn install(
+        &mut self,
+        src: &Path,
+        _package_name: &str,
+        profile: &Profile,
+    ) -> Result<Vec<PathBuf>> {
         install::fs::install(src, profile, |relative_path, _| {
             if relative_path.extension().is_some_and(|ext| ext == "cfg") {
                 Ok((FileInstallMethod::Copy, ConflictResolution::Skip))
@@ -65,7 +72,7 @@ impl PackageInstaller for BepinexInstaller {
 
     fn uninstall(&mut self, _profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
         for file in scan(profile)? {
-            fs::remove_file(file)?;
+            util::fs::delete(file)?;
         }
 
         Ok(())
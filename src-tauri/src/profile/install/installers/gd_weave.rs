@@ -1,15 +1,17 @@
 use std::{
     borrow::Cow,
-    fs,
     path::{self, PathBuf},
 };
 
 use eyre::{bail, Result};
 
 use super::{PackageInstaller, PackageZip};
-use crate::profile::{
-    install::{self},
-    Profile, ProfileMod,
+use crate::{
+    profile::{
+        install::{self},
+        Profile, ProfileMod,
+    },
+    util,
 };
 
 pub struct GDWeaveModInstaller;
@@ -75,8 +77,7 @@ impl PackageInstaller for GDWeaveModInstaller {
     }
 
     fn uninstall(&mut self, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
-        fs::remove_dir_all(profile_mod_dir(&profile_mod.full_name(), profile))?;
-        Ok(())
+        util::fs::delete(profile_mod_dir(&profile_mod.full_name(), profile))
     }
 
     fn mod_dir(&self, package_name: &str, profile: &Profile) -> Option<PathBuf> {
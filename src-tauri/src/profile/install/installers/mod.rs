@@ -15,12 +15,15 @@ mod gd_weave;
 mod shimloader;
 mod subdir;
 
+#[cfg(test)]
+mod tests;
+
 pub use self::{
     bepinex::BepinexInstaller,
     extract::{ExtractInstaller, FlattenTopLevel},
     gd_weave::GDWeaveModInstaller,
     shimloader::ShimloaderInstaller,
-    subdir::{Subdir, SubdirInstaller},
+    subdir::{file_owners, tracked_files, Subdir, SubdirInstaller},
 };
 
 pub type PackageZip = ZipArchive<Cursor<Vec<u8>>>;
@@ -28,7 +31,15 @@ pub type PackageZip = ZipArchive<Cursor<Vec<u8>>>;
 pub trait PackageInstaller {
     fn extract(&mut self, archive: PackageZip, package_name: &str, dest: PathBuf) -> Result<()>;
 
-    fn install(&mut self, src: &Path, _package_name: &str, profile: &Profile) -> Result<()> {
+    /// Installs the extracted package at `src` into the profile.
+    ///
+    /// Returns the installed files, relative to the profile directory.
+    fn install(
+        &mut self,
+        src: &Path,
+        _package_name: &str,
+        profile: &Profile,
+    ) -> Result<Vec<PathBuf>> {
         super::fs::install(src, profile, |_, _| {
             Ok((FileInstallMethod::Link, ConflictResolution::Overwrite))
         })
@@ -7,9 +7,12 @@ use std::{
 use eyre::{Context, Result};
 
 use super::{PackageInstaller, PackageZip};
-use crate::profile::{
-    install::{self},
-    Profile, ProfileMod,
+use crate::{
+    profile::{
+        install::{self},
+        Profile, ProfileMod,
+    },
+    util,
 };
 
 pub struct ShimloaderInstaller;
@@ -71,7 +74,7 @@ impl PackageInstaller for ShimloaderInstaller {
 
     fn uninstall(&mut self, _profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
         for file in ["dwmapi.dll", "UE4SS.dll", "UE4SS-settings.ini"] {
-            fs::remove_file(profile.path.join(file)).ok();
+            util::fs::delete(profile.path.join(file)).ok();
         }
 
         Ok(())
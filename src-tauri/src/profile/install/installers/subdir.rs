@@ -6,7 +6,7 @@ use std::{
 };
 
 use eyre::{Context, OptionExt, Result};
-use tracing::warn;
+use tracing::{debug, warn};
 use serde::{Deserialize, Serialize};
 
 use super::{PackageInstaller, PackageZip};
@@ -49,6 +49,12 @@ pub struct Subdir<'a> {
     /// Multiple extensions are separated by a comma.
     #[serde(default)]
     pub extension: Option<&'a str>,
+    /// Whether native (unmanaged) DLLs are moved to this subdir,
+    /// regardless of where they would otherwise be installed.
+    ///
+    /// Only the first native subdir is used.
+    #[serde(default)]
+    pub native: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -75,6 +81,7 @@ impl<'a> Subdir<'a> {
             mode,
             mutable: false,
             extension: None,
+            native: false,
         }
     }
 
@@ -103,6 +110,11 @@ impl<'a> Subdir<'a> {
         self.extension = Some(ext);
         self
     }
+
+    pub const fn native(mut self) -> Self {
+        self.native = true;
+        self
+    }
 }
 
 impl<'a> SubdirInstaller<'a> {
@@ -137,6 +149,20 @@ impl<'a> SubdirInstaller<'a> {
         self.extra_subdirs.iter().chain(self.subdirs.iter())
     }
 
+    fn native_subdir(&self) -> Option<&Subdir<'_>> {
+        self.subdirs().find(|subdir| subdir.native)
+    }
+
+    /// Finds the subdir a file in the profile belongs to.
+    ///
+    /// Targets can be nested (or empty, for the profile root),
+    /// so the most specific one is picked.
+    fn subdir_of(&self, relative_path: &Path) -> Option<&Subdir<'_>> {
+        self.subdirs()
+            .filter(|subdir| relative_path.starts_with(subdir.target))
+            .min_by_key(|subdir| std::cmp::Reverse(subdir.target.len()))
+    }
+
     fn match_subdir(&self, name: &str) -> Option<&Subdir> {
         self.subdirs().find(|subdir| {
             util::cmp_ignore_case(subdir.name, name).is_eq()
@@ -268,6 +294,18 @@ fn state_file_path(name: &str, profile: &Profile) -> PathBuf {
     path
 }
 
+/// Returns the files tracked for a package, relative to the profile directory.
+///
+/// Only packages installed into [`SubdirMode::Track`] subdirs have tracked files.
+pub fn tracked_files(package_name: &str, profile: &Profile) -> Vec<PathBuf> {
+    PackageStateHandle::new(package_name, profile).state.files
+}
+
+/// Returns which package currently owns each tracked file, relative to the profile directory.
+pub fn file_owners(profile: &Profile) -> HashMap<PathBuf, String> {
+    ProfileStateHandle::new(profile).state.file_map
+}
+
 struct PackageStateHandle {
     path: PathBuf,
     state: PackageState,
@@ -335,19 +373,63 @@ impl ProfileStateHandle {
 
 impl PackageInstaller for SubdirInstaller<'_> {
     fn extract(&mut self, archive: PackageZip, package_name: &str, dest: PathBuf) -> Result<()> {
-        install::fs::extract(archive, dest, |relative_path| {
-            self.map_file(relative_path, package_name)
-        })
+        let mut dlls = Vec::new();
+
+        install::fs::extract(archive, dest.clone(), |relative_path| {
+            let target = self.map_file(relative_path, package_name)?;
+
+            if let Some(target) = &target {
+                if is_dll(target) {
+                    dlls.push(target.to_path_buf());
+                }
+            }
+
+            Ok(target)
+        })?;
+
+        if let Some(native_subdir) = self.native_subdir() {
+            for relative_path in dlls {
+                if self
+                    .subdir_of(&relative_path)
+                    .is_some_and(|subdir| subdir.native)
+                {
+                    continue;
+                }
+
+                let path = dest.join(&relative_path);
+                if !is_native_dll(&path).unwrap_or(false) {
+                    continue;
+                }
+
+                let mut target = dest.join(native_subdir.target);
+                target.push(path.file_name().unwrap());
+
+                debug!(
+                    "moving native library {} to {}",
+                    relative_path.display(),
+                    native_subdir.target
+                );
+
+                fs::create_dir_all(target.parent().unwrap())?;
+                fs::rename(&path, &target)?;
+            }
+        }
+
+        Ok(())
     }
 
-    fn install(&mut self, src: &Path, package_name: &str, profile: &Profile) -> Result<()> {
+    fn install(
+        &mut self,
+        src: &Path,
+        package_name: &str,
+        profile: &Profile,
+    ) -> Result<Vec<PathBuf>> {
         let mut state: Option<PackageStateHandle> = None;
         let mut profile_state: Option<ProfileStateHandle> = None;
 
-        install::fs::install(src, profile, |relative_path, exists| {
+        let files = install::fs::install(src, profile, |relative_path, exists| {
             let subdir = self
-                .subdirs()
-                .find(|subdir| relative_path.starts_with(subdir.target))
+                .subdir_of(relative_path)
                 .expect("file should be in a subdir");
 
             let method = if subdir.mutable {
@@ -370,7 +452,18 @@ impl PackageInstaller for SubdirInstaller<'_> {
                         profile_state.get_or_insert_with(|| ProfileStateHandle::new(profile));
 
                     if exists {
-                        if let Some(owner) = profile_state.file_map().get(relative_path) {
+                        if let Some(owner) = profile_state
+                            .file_map()
+                            .get(relative_path)
+                            .filter(|owner| *owner != package_name)
+                        {
+                            warn!(
+                                "{} overwrites {} from {}",
+                                package_name,
+                                relative_path.display(),
+                                owner
+                            );
+
                             let mut package = PackageStateHandle::new(owner, profile);
                             package.files().retain(|file| file != relative_path);
                             package.commit()?;
@@ -396,7 +489,7 @@ impl PackageInstaller for SubdirInstaller<'_> {
             state.commit().context("failed to write profile state")?;
         }
 
-        Ok(())
+        Ok(files)
     }
 
     fn toggle(&mut self, enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
@@ -444,3 +537,66 @@ impl PackageInstaller for SubdirInstaller<'_> {
         })
     }
 }
+
+fn is_dll(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+}
+
+/// Checks whether a DLL is a native library, as opposed to a .NET assembly.
+///
+/// .NET assemblies are PE files with a CLR runtime header, so this reads
+/// just enough of the PE headers to find that data directory.
+pub(super) fn is_native_dll(path: &Path) -> Result<bool> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    const CLR_DIRECTORY_INDEX: u64 = 14;
+
+    fn read_u16(file: &mut fs::File) -> Result<u16> {
+        let mut buf = [0; 2];
+        file.read_exact(&mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn read_u32(file: &mut fs::File) -> Result<u32> {
+        let mut buf = [0; 4];
+        file.read_exact(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    let mut file = fs::File::open(path)?;
+
+    if read_u16(&mut file)? != u16::from_le_bytes(*b"MZ") {
+        return Ok(false);
+    }
+
+    file.seek(SeekFrom::Start(0x3c))?;
+    let pe_offset = read_u32(&mut file)? as u64;
+
+    file.seek(SeekFrom::Start(pe_offset))?;
+    if read_u32(&mut file)? != u32::from_le_bytes(*b"PE\0\0") {
+        return Ok(false);
+    }
+
+    // the optional header comes after the 20 byte COFF header
+    let optional_header = pe_offset + 4 + 20;
+
+    file.seek(SeekFrom::Start(optional_header))?;
+    let (count_offset, directories_offset) = match read_u16(&mut file)? {
+        0x10b => (92, 96),   // PE32
+        0x20b => (108, 112), // PE32+
+        _ => return Ok(false),
+    };
+
+    file.seek(SeekFrom::Start(optional_header + count_offset))?;
+    if (read_u32(&mut file)? as u64) <= CLR_DIRECTORY_INDEX {
+        return Ok(true);
+    }
+
+    file.seek(SeekFrom::Start(
+        optional_header + directories_offset + CLR_DIRECTORY_INDEX * 8,
+    ))?;
+    let clr_rva = read_u32(&mut file)?;
+
+    Ok(clr_rva == 0)
+}
@@ -0,0 +1,104 @@
+use std::{
+    fs,
+    io::{Cursor, Write},
+    path::Path,
+};
+
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use super::{subdir::is_native_dll, PackageInstaller, Subdir, SubdirInstaller};
+
+const PE_OFFSET: usize = 0x40;
+
+/// Builds the headers of a PE file, with a CLR runtime header at `clr_rva`
+/// (0 for a native library).
+fn pe_file(pe32_plus: bool, clr_rva: u32) -> Vec<u8> {
+    let (magic, count_offset, directories_offset): (u16, usize, usize) = match pe32_plus {
+        false => (0x10b, 92, 96),
+        true => (0x20b, 108, 112),
+    };
+
+    let optional_header = PE_OFFSET + 4 + 20;
+    let mut data = vec![0; optional_header + directories_offset + 16 * 8];
+
+    data[0..2].copy_from_slice(b"MZ");
+    data[0x3c..0x40].copy_from_slice(&(PE_OFFSET as u32).to_le_bytes());
+    data[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+    data[optional_header..optional_header + 2].copy_from_slice(&magic.to_le_bytes());
+
+    let count = optional_header + count_offset;
+    data[count..count + 4].copy_from_slice(&16u32.to_le_bytes());
+
+    let clr = optional_header + directories_offset + 14 * 8;
+    data[clr..clr + 4].copy_from_slice(&clr_rva.to_le_bytes());
+
+    data
+}
+
+fn check(data: &[u8]) -> bool {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.dll");
+    fs::write(&path, data).unwrap();
+
+    is_native_dll(&path).unwrap()
+}
+
+#[test]
+fn detect_native_dll() {
+    assert!(check(&pe_file(false, 0)));
+    assert!(check(&pe_file(true, 0)));
+
+    assert!(!check(&pe_file(false, 0x2008)));
+    assert!(!check(&pe_file(true, 0x2008)));
+
+    assert!(!check(b"not a dll at all"));
+}
+
+#[test]
+fn detect_native_dll_without_clr_directory() {
+    let mut data = pe_file(false, 0x2008);
+
+    // only 14 data directories, so there's no room for a CLR header
+    let count = PE_OFFSET + 4 + 20 + 92;
+    data[count..count + 4].copy_from_slice(&14u32.to_le_bytes());
+
+    assert!(check(&data));
+}
+
+#[test]
+fn extract_moves_native_dlls() {
+    const SUBDIRS: &[Subdir] = &[
+        Subdir::flat_separated("plugins", "BepInEx/plugins"),
+        Subdir::tracked("", "").extension(".asi").native(),
+    ];
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in [
+        ("plugins/Managed.dll", pe_file(false, 0x2008)),
+        ("plugins/Native.dll", pe_file(true, 0)),
+        ("Loader.asi", b"asi".to_vec()),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    let data = zip.finish().unwrap().into_inner();
+
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().to_path_buf();
+
+    SubdirInstaller::new(SUBDIRS)
+        .with_default(0)
+        .extract(
+            ZipArchive::new(Cursor::new(data)).unwrap(),
+            "Author-Mod",
+            dest.clone(),
+        )
+        .unwrap();
+
+    let exists = |path: &str| dest.join(Path::new(path)).exists();
+
+    assert!(exists("BepInEx/plugins/Author-Mod/Managed.dll"));
+    assert!(!exists("BepInEx/plugins/Author-Mod/Native.dll"));
+    assert!(exists("Native.dll"));
+    assert!(exists("Loader.asi"));
+}
@@ -0,0 +1,160 @@
+//! Deleting mod files that are locked by another process, usually the game
+//! while it's running.
+//!
+//! Deletes run while holding the app's locks, so locked files aren't retried on
+//! the spot (unlike [`util::fs::delete`]). Instead, the delete is queued
+//! and the mod is removed from the profile as usual. The queue is saved to disk
+//! and retried in the background, so the files are cleaned up once the game or
+//! antivirus scan releases them, even if Gale is restarted in between.
+
+use std::{
+    io, mem,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use eyre::Result;
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::util::{self, fs::JsonStyle};
+
+pub const FILE_NAME: &str = "pending_deletes.json";
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+static PENDING: LazyLock<Mutex<Vec<PathBuf>>> = LazyLock::new(|| Mutex::new(read()));
+
+fn path() -> PathBuf {
+    util::path::default_app_data_dir().join(FILE_NAME)
+}
+
+fn read() -> Vec<PathBuf> {
+    let path = path();
+
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    util::fs::read_json(&path).unwrap_or_else(|err| {
+        warn!("failed to read pending deletes: {:#}", err);
+        Vec::new()
+    })
+}
+
+fn write(pending: &[PathBuf]) {
+    util::fs::write_json(path(), pending, JsonStyle::Pretty)
+        .unwrap_or_else(|err| warn!("failed to write pending deletes: {:#}", err));
+}
+
+/// Whether `err` was caused by a file being locked by another process.
+fn is_locked(err: &eyre::Report) -> bool {
+    err.chain()
+        .filter_map(|err| err.downcast_ref::<io::Error>())
+        .any(util::fs::is_locked)
+}
+
+/// Runs `delete` on `path`, queueing it for later if the file is locked.
+pub(super) fn delete_or_defer<F>(path: &Path, delete: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    match delete(path) {
+        Err(err) if is_locked(&err) => {
+            warn!(
+                "{} is in use by another program, deleting it later: {:#}",
+                path.display(),
+                err
+            );
+
+            let mut pending = PENDING.lock().unwrap();
+            if !pending.iter().any(|other| other == path) {
+                pending.push(path.to_path_buf());
+                write(&pending);
+            }
+
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Drops a queued delete of `path`, since a file is being written there again.
+///
+/// Queued directories containing `path` are split into the files left in them,
+/// so the new file is kept while the old, locked ones are still deleted.
+pub(super) fn forget(path: &Path) {
+    let mut pending = PENDING.lock().unwrap();
+
+    if !pending.iter().any(|pending| path.starts_with(pending)) {
+        return;
+    }
+
+    for queued in mem::take(&mut *pending) {
+        if !path.starts_with(&queued) {
+            pending.push(queued);
+        } else if queued.is_dir() {
+            let files = WalkDir::new(&queued)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| !entry.file_type().is_dir())
+                .map(|entry| entry.into_path())
+                .filter(|file| file != path);
+
+            pending.extend(files);
+        }
+    }
+
+    debug!("cancelled pending delete of {}", path.display());
+    write(&pending);
+}
+
+/// Retries every queued delete, keeping the ones that are still locked.
+///
+/// Returns the number of files still waiting to be deleted.
+pub fn retry_pending() -> usize {
+    let mut pending = PENDING.lock().unwrap();
+
+    if pending.is_empty() {
+        return 0;
+    }
+
+    let len = pending.len();
+
+    pending.retain(|path| {
+        if !path.exists() && !super::fs::old_path(path).exists() {
+            return false;
+        }
+
+        match super::fs::uninstall_now(path) {
+            Ok(()) => false,
+            Err(err) if is_locked(&err) => true,
+            Err(err) => {
+                warn!("failed to delete {}, giving up: {:#}", path.display(), err);
+                false
+            }
+        }
+    });
+
+    if pending.len() != len {
+        info!(
+            "finished {} pending deletes, {} remaining",
+            len - pending.len(),
+            pending.len()
+        );
+        write(&pending);
+    }
+
+    pending.len()
+}
+
+/// Retries the queued deletes periodically, for the whole lifetime of the app.
+pub async fn retry_in_background() {
+    loop {
+        if let Err(err) = tauri::async_runtime::spawn_blocking(retry_pending).await {
+            warn!("failed to retry pending deletes: {:#}", err);
+        }
+
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
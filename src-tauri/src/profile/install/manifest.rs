@@ -0,0 +1,97 @@
+//! Cleanup of the files recorded in [`ProfileMod::files`].
+//!
+//! Installers find a mod's files by its directory name, which misses files
+//! that are installed elsewhere, like native libraries or untracked subdirs.
+//! These functions run after the installer and take care of whatever is left.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+
+use crate::{
+    profile::{Profile, ProfileMod},
+    util::fs::PathExt,
+};
+
+/// Removes the files of `profile_mod` that are still in the profile.
+///
+/// Files that are also recorded for another mod are kept.
+pub(crate) fn uninstall(profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
+    let shared = shared_files(profile_mod, profile, false);
+
+    for file in managed_files(profile_mod, profile) {
+        if shared.contains(file.as_path()) {
+            continue;
+        }
+
+        super::fs::uninstall_any(profile.path.join(file))?;
+    }
+
+    Ok(())
+}
+
+/// Toggles the files of `profile_mod` that the installer didn't already.
+///
+/// Files that are also recorded for another enabled mod are left alone.
+pub(crate) fn toggle(enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
+    let shared = shared_files(profile_mod, profile, true);
+
+    for file in managed_files(profile_mod, profile) {
+        if shared.contains(file.as_path()) {
+            continue;
+        }
+
+        let path = profile.path.join(file);
+
+        if enabled {
+            if path.exists() {
+                super::fs::toggle_file(&path, true)?;
+            }
+        } else {
+            let mut old_path = path.clone();
+            old_path.add_ext("old");
+
+            if !path.exists() && old_path.exists() {
+                super::fs::toggle_file(&old_path, false)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The recorded files of `profile_mod`, except those in the loader's config directory.
+///
+/// Configs are no longer recorded, but manifests from older versions may still contain
+/// them, and the user's edits shouldn't be deleted or disabled along with the mod.
+fn managed_files<'a>(
+    profile_mod: &'a ProfileMod,
+    profile: &Profile,
+) -> impl Iterator<Item = &'a PathBuf> {
+    let config_path = profile.game.mod_loader.config_path();
+    let config_dir = (!config_path.as_os_str().is_empty()).then_some(config_path);
+
+    profile_mod.files.iter().filter(move |file| {
+        config_dir
+            .as_ref()
+            .map_or(true, |config_dir| !file.starts_with(config_dir))
+    })
+}
+
+fn shared_files<'a>(
+    profile_mod: &ProfileMod,
+    profile: &'a Profile,
+    enabled_only: bool,
+) -> HashSet<&'a Path> {
+    let uuid = profile_mod.uuid();
+
+    profile
+        .mods
+        .iter()
+        .filter(|other| other.uuid() != uuid && (!enabled_only || other.enabled))
+        .flat_map(|other| other.files.iter().map(PathBuf::as_path))
+        .collect()
+}
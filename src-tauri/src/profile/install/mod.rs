@@ -8,20 +8,34 @@ use strum_macros::Display;
 use tauri::AppHandle;
 use uuid::Uuid;
 
-use super::{ModManager, Profile};
+use super::{ModUserData, Profile};
 use crate::{
     prefs::Prefs,
     state::ManagerExt,
-    thunderstore::{BorrowedMod, ModId, Thunderstore},
+    thunderstore::{self, BorrowedMod, ModId, Thunderstore, VersionIdent},
 };
 
-mod cache;
+mod archive;
+pub(crate) mod cache;
 pub mod commands;
+mod conflicts;
 mod download;
+pub(crate) mod fixups;
 mod fs;
+pub mod github;
+mod inspect;
 mod installers;
+pub mod locked;
+pub(crate) mod manifest;
+pub mod nexus;
+mod overlaps;
+pub mod tools;
+pub use fs::set_use_links;
 pub use installers::*;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallProgress<'a> {
@@ -48,7 +62,7 @@ pub enum InstallTask {
 
 type ProgressHandler = Box<dyn Fn(&InstallProgress, &AppHandle) + 'static + Send>;
 type EventHandler =
-    Box<dyn Fn(&ModInstall, &mut ModManager, &Thunderstore) -> Result<()> + 'static + Send>;
+    Box<dyn Fn(&ModInstall, &mut Profile, &Thunderstore) -> Result<()> + 'static + Send>;
 
 pub struct InstallOptions {
     can_cancel: bool,
@@ -97,6 +111,8 @@ pub struct ModInstall {
     enabled: bool,
     index: Option<usize>,
     install_time: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    user_data: ModUserData,
 }
 
 impl ModInstall {
@@ -106,6 +122,7 @@ impl ModInstall {
             enabled: true,
             index: None,
             install_time: None,
+            user_data: ModUserData::default(),
         }
     }
 
@@ -124,6 +141,12 @@ impl ModInstall {
         self
     }
 
+    /// Keeps the tags, note and pin of the version this replaces.
+    pub fn with_user_data(mut self, data: ModUserData) -> Self {
+        self.user_data = data;
+        self
+    }
+
     /// The uuid the resulting `ProfileMod` will get after the mod is installed.
     pub fn uuid(&self) -> Uuid {
         self.id.package_uuid
@@ -164,7 +187,7 @@ pub async fn install_with_deps(
     app: &tauri::AppHandle,
 ) -> Result<()> {
     let mods = {
-        let manager = app.lock_manager();
+        let manager = app.read_manager();
         let thunderstore = app.lock_thunderstore();
         let profile = manager.active_profile();
 
@@ -196,6 +219,101 @@ pub async fn install_with_deps(
     install_mods(mods, options, app).await
 }
 
+/// Installs dependency strings and their dependencies into the active profile,
+/// see [`resolve_dependency_strings`].
+///
+/// Returns the number of mods that were installed, excluding dependencies.
+pub(crate) async fn install_dependency_strings(
+    strings: &[String],
+    app: &AppHandle,
+) -> Result<usize> {
+    thunderstore::wait_for_fetch(app).await;
+
+    let mods = {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        resolve_dependency_strings(
+            strings.iter().map(String::as_str),
+            manager.active_profile(),
+            &thunderstore,
+        )?
+    };
+
+    install_resolved(mods, app).await
+}
+
+/// Installs mods resolved from text, like a list of dependency strings, and their dependencies.
+///
+/// Returns the number of mods that were installed, excluding dependencies.
+pub(crate) async fn install_resolved(mods: Vec<ModInstall>, app: &AppHandle) -> Result<usize> {
+    let count = mods.len();
+
+    if count > 0 {
+        install_with_deps(mods, InstallOptions::default(), true, app).await?;
+    }
+
+    Ok(count)
+}
+
+/// Resolves dependency strings, such as the ones produced by
+/// `copy_dependency_strings`, into mods to install.
+///
+/// Strings without a version (`Owner-Mod`) resolve to the latest version.
+/// Mods that are already in the profile are skipped.
+pub(crate) fn resolve_dependency_strings<'a>(
+    strings: impl IntoIterator<Item = &'a str>,
+    profile: &Profile,
+    thunderstore: &Thunderstore,
+) -> Result<Vec<ModInstall>> {
+    let installs = find_dependency_strings(strings, thunderstore)?
+        .into_iter()
+        .filter(|borrowed| !profile.has_mod(borrowed.package.uuid))
+        .map(|borrowed| ModInstall::new(borrowed.into()))
+        .collect();
+
+    Ok(installs)
+}
+
+/// Finds the mods that dependency strings refer to, failing if any of them can't be found.
+fn find_dependency_strings<'a, 't>(
+    strings: impl IntoIterator<Item = &'a str>,
+    thunderstore: &'t Thunderstore,
+) -> Result<Vec<BorrowedMod<'t>>> {
+    let mut found = Vec::new();
+    let mut unknown = Vec::new();
+
+    for line in strings
+        .into_iter()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        match find_dependency_string(line, thunderstore) {
+            Ok(borrowed) => found.push(borrowed),
+            Err(_) => unknown.push(line),
+        }
+    }
+
+    if !unknown.is_empty() {
+        bail!("mods not found: {}", unknown.join(", "));
+    }
+
+    Ok(found)
+}
+
+/// Finds the mod a single dependency string refers to, see [`resolve_dependency_strings`].
+pub(crate) fn find_dependency_string<'a>(
+    string: &str,
+    thunderstore: &'a Thunderstore,
+) -> Result<BorrowedMod<'a>> {
+    match string.parse::<VersionIdent>() {
+        Ok(ident) => thunderstore.find_ident(&ident),
+        Err(_) => thunderstore
+            .find_package(string)
+            .map(|package| (package, package.latest()).into()),
+    }
+}
+
 /// Gets the number of bytes to download the given mod and its
 /// missing dependencies (ignoring already cached mods).
 fn total_download_size(
@@ -0,0 +1,315 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use eyre::{bail, ensure, eyre, Context, OptionExt, Result};
+use tauri::{AppHandle, Url};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::archive::{self, Placement};
+use crate::{
+    nexus::{
+        api::{DownloadKey, NexusApi, PremiumRequired},
+        NexusFile, NexusMod,
+    },
+    profile::ProfileModKind,
+    state::ManagerExt,
+};
+
+/// Game domain, mod id and file id.
+type FileKey = (String, u64, u64);
+
+/// Downloads that are waiting for the user to click "Mod Manager Download" on
+/// the Nexus website.
+static PENDING: LazyLock<Mutex<HashMap<FileKey, PendingDownload>>> =
+    LazyLock::new(Default::default);
+
+struct PendingDownload {
+    nexus_mod: NexusMod,
+    placement: Option<Placement>,
+}
+
+/// Downloads and installs a mod from Nexus Mods on the active profile.
+///
+/// If `file_id` is `None`, the most recent main file is used.
+pub async fn install(mod_id: u64, file_id: Option<u64>, app: &AppHandle) -> Result<()> {
+    let api = NexusApi::new(app)?;
+    install_with_key(&api, mod_id, file_id, None, app).await
+}
+
+/// Continues a download from a `nxm://` link, which the Nexus website opens
+/// when "Mod Manager Download" is clicked.
+///
+/// The link looks like `nxm://<domain>/mods/<mod id>/files/<file id>?key=..&expires=..`.
+pub async fn install_from_link(url: &str, app: &AppHandle) -> Result<()> {
+    let url = Url::parse(url).context("invalid nxm link")?;
+    let domain = url.host_str().ok_or_eyre("invalid nxm link")?.to_owned();
+
+    let segments = url
+        .path_segments()
+        .map(|segments| segments.collect::<Vec<_>>());
+    let (mod_id, file_id) = match segments.as_deref() {
+        Some(["mods", mod_id, "files", file_id]) => (mod_id.parse()?, file_id.parse()?),
+        _ => bail!("invalid nxm link"),
+    };
+
+    let query = url.query_pairs().collect::<HashMap<_, _>>();
+    let key = DownloadKey {
+        key: query
+            .get("key")
+            .ok_or_eyre("nxm link has no key")?
+            .to_string(),
+        expires: query
+            .get("expires")
+            .ok_or_eyre("nxm link has no expiry")?
+            .parse()?,
+    };
+
+    let api = NexusApi::for_domain(app, domain.clone())?;
+    let pending = PENDING.lock().unwrap().remove(&(domain, mod_id, file_id));
+
+    match pending {
+        Some(PendingDownload {
+            nexus_mod,
+            placement,
+        }) => install_file(&api, nexus_mod, placement, Some(&key), app).await,
+        None => install_with_key(&api, mod_id, Some(file_id), Some(&key), app).await,
+    }
+}
+
+async fn install_with_key(
+    api: &NexusApi,
+    mod_id: u64,
+    file_id: Option<u64>,
+    key: Option<&DownloadKey>,
+    app: &AppHandle,
+) -> Result<()> {
+    {
+        let manager = app.read_manager();
+        let installed = manager.active_profile().nexus_mods().any(|(nexus_mod, _)| {
+            nexus_mod.nexus_mod_id == mod_id && nexus_mod.game_domain == api.domain
+        });
+
+        ensure!(!installed, "mod already installed");
+    }
+
+    let info = api.mod_info(mod_id).await?;
+    let file = match file_id {
+        Some(file_id) => api
+            .files(mod_id)
+            .await?
+            .into_iter()
+            .find(|file| file.file_id == file_id)
+            .ok_or_else(|| eyre!("file {} not found", file_id))?,
+        None => api.latest_file(mod_id).await?,
+    };
+
+    let nexus_mod = NexusMod {
+        nexus_mod_id: mod_id,
+        file_id: file.file_id,
+        game_domain: api.domain.clone(),
+        name: info.name.unwrap_or(file.name.clone()),
+        author: info.author,
+        description: info.summary,
+        version: file.version.clone().or(info.version),
+        uuid: Uuid::new_v4(),
+        file_size: file.size(),
+        latest_file: None,
+    };
+
+    install_file(api, nexus_mod, None, key, app).await
+}
+
+/// Checks for newer main files of the Nexus mods in the active profile.
+///
+/// Results are stored on each mod and picked up by `query_profile`.
+/// Returns the number of mods with an available update.
+pub async fn check_updates(app: &AppHandle) -> Result<usize> {
+    let mods = {
+        let manager = app.read_manager();
+        manager
+            .active_profile()
+            .nexus_mods()
+            .map(|(nexus_mod, _)| {
+                (
+                    nexus_mod.uuid,
+                    nexus_mod.game_domain.clone(),
+                    nexus_mod.nexus_mod_id,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if mods.is_empty() {
+        return Ok(0);
+    }
+
+    let mut results = Vec::with_capacity(mods.len());
+
+    for (uuid, domain, mod_id) in mods {
+        let api = NexusApi::for_domain(app, domain)?;
+
+        match api.latest_file(mod_id).await {
+            Ok(file) => results.push((uuid, file)),
+            Err(err) => warn!(
+                "failed to check for updates of nexus mod {}: {:#}",
+                mod_id, err
+            ),
+        }
+    }
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let mut count = 0;
+
+    for (uuid, file) in results {
+        let Ok(profile_mod) = profile.get_mod_mut(uuid) else {
+            continue; // removed while checking
+        };
+
+        let ProfileModKind::Nexus(nexus_mod) = &mut profile_mod.kind else {
+            continue;
+        };
+
+        nexus_mod.latest_file = match file.file_id == nexus_mod.file_id {
+            true => None,
+            false => {
+                count += 1;
+
+                Some(NexusFile {
+                    file_id: file.file_id,
+                    version: file.version,
+                })
+            }
+        };
+    }
+
+    profile.save(app.db())?;
+
+    info!("found {} nexus mod updates", count);
+
+    Ok(count)
+}
+
+/// Updates the given Nexus mods to the files found by [`check_updates`].
+pub async fn update(uuids: Vec<Uuid>, respect_ignored: bool, app: &AppHandle) -> Result<()> {
+    let updates = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        uuids
+            .into_iter()
+            .filter_map(|uuid| {
+                let index = profile.index_of(uuid).ok()?;
+                let profile_mod = &profile.mods[index];
+                let nexus_mod = profile_mod.kind.as_nexus()?;
+                let latest = nexus_mod.latest_file.as_ref()?;
+
+                if respect_ignored
+                    && nexus_mod
+                        .latest_file_uuid()
+                        .is_some_and(|uuid| profile.ignored_updates.contains(&uuid))
+                {
+                    return None;
+                }
+
+                let new_mod = NexusMod {
+                    file_id: latest.file_id,
+                    version: latest.version.clone().or(nexus_mod.version.clone()),
+                    latest_file: None,
+                    ..nexus_mod.clone()
+                };
+
+                let placement = Placement {
+                    index,
+                    enabled: profile_mod.enabled,
+                    install_time: profile_mod.install_time,
+                    user_data: profile_mod.user_data(),
+                };
+
+                Some((placement, new_mod))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (placement, nexus_mod) in updates {
+        let api = NexusApi::for_domain(app, nexus_mod.game_domain.clone())?;
+        install_file(&api, nexus_mod, Some(placement), None, app).await?;
+    }
+
+    Ok(())
+}
+
+async fn install_file(
+    api: &NexusApi,
+    nexus_mod: NexusMod,
+    placement: Option<Placement>,
+    key: Option<&DownloadKey>,
+    app: &AppHandle,
+) -> Result<()> {
+    let cache_path = nexus_mod.cache_path(&app.lock_prefs());
+
+    if !cache_path.exists() {
+        info!(
+            "downloading nexus mod {} (file {})",
+            nexus_mod.nexus_mod_id, nexus_mod.file_id
+        );
+
+        let data = match api
+            .download(nexus_mod.nexus_mod_id, nexus_mod.file_id, key)
+            .await
+        {
+            Ok(data) => data,
+            Err(err) if key.is_none() && err.downcast_ref::<PremiumRequired>().is_some() => {
+                return Err(request_manual_download(nexus_mod, placement));
+            }
+            Err(err) => return Err(err.wrap_err("failed to download mod")),
+        };
+
+        archive::extract(data, nexus_mod.package_name(), cache_path.clone(), app).await?;
+    }
+
+    let kind = ProfileModKind::Nexus(Box::new(nexus_mod));
+    archive::add_to_profile(kind, &cache_path, placement, app)
+}
+
+/// Opens the file's page on the Nexus website, where free accounts can start the
+/// download with "Mod Manager Download". The install continues in
+/// [`install_from_link`] once the resulting `nxm://` link arrives.
+fn request_manual_download(nexus_mod: NexusMod, placement: Option<Placement>) -> eyre::Report {
+    let url = format!(
+        "https://www.nexusmods.com/{}/mods/{}?tab=files&file_id={}&nmm=1",
+        nexus_mod.game_domain, nexus_mod.nexus_mod_id, nexus_mod.file_id
+    );
+
+    if let Err(err) = open::that(&url) {
+        return eyre!(err).wrap_err("failed to open the Nexus Mods website");
+    }
+
+    info!(
+        "waiting for nxm link for nexus mod {} (file {})",
+        nexus_mod.nexus_mod_id, nexus_mod.file_id
+    );
+
+    let key = (
+        nexus_mod.game_domain.clone(),
+        nexus_mod.nexus_mod_id,
+        nexus_mod.file_id,
+    );
+
+    PENDING.lock().unwrap().insert(
+        key,
+        PendingDownload {
+            nexus_mod,
+            placement,
+        },
+    );
+
+    eyre!(
+        "Nexus Mods only allows direct downloads with a premium account. \
+        Click \"Mod Manager Download\" on the page that was opened to continue."
+    )
+}
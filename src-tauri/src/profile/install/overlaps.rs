@@ -0,0 +1,111 @@
+//! Detection of files that are shipped by more than one mod in a profile.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::{prefs::Prefs, profile::Profile};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileConflict {
+    /// The path of the file, relative to the profile directory.
+    pub path: PathBuf,
+    /// The full names of every mod that ships the file.
+    pub mods: Vec<String>,
+    /// The mod whose copy of the file is currently installed, if known.
+    pub winner: Option<String>,
+}
+
+/// Everything needed to scan a profile without holding any locks.
+pub struct ScanInput {
+    /// Pairs of package names and their cached, already mapped, files.
+    packages: Vec<(String, PathBuf)>,
+    owners: HashMap<PathBuf, String>,
+}
+
+impl ScanInput {
+    pub fn new(profile: &Profile, prefs: &Prefs) -> Self {
+        let packages = profile
+            .mods
+            .iter()
+            .filter(|profile_mod| profile_mod.enabled)
+            .map(|profile_mod| {
+                let ident = profile_mod.ident();
+                let path = super::cache::path(&ident, prefs);
+
+                (profile_mod.full_name().into_owned(), path)
+            })
+            .collect();
+
+        Self {
+            packages,
+            owners: super::file_owners(profile),
+        }
+    }
+}
+
+/// Finds paths that more than one enabled mod installs to.
+///
+/// Packages are extracted into the cache with the same layout as in the
+/// profile, so their cache directories are compared instead of the profile
+/// itself, where overwritten files can't be told apart anymore.
+pub fn scan(input: ScanInput) -> Result<Vec<FileConflict>> {
+    let mut files: HashMap<PathBuf, Vec<String>> = HashMap::new();
+
+    for (package_name, cache_path) in &input.packages {
+        if !cache_path.exists() {
+            continue;
+        }
+
+        for relative_path in package_files(cache_path)? {
+            files
+                .entry(relative_path)
+                .or_default()
+                .push(package_name.clone());
+        }
+    }
+
+    let mut conflicts = files
+        .into_iter()
+        .filter(|(_, mods)| mods.len() > 1)
+        .map(|(path, mods)| {
+            let winner = input
+                .owners
+                .get(&path)
+                .filter(|owner| mods.contains(owner))
+                .cloned();
+
+            FileConflict { path, mods, winner }
+        })
+        .collect::<Vec<_>>();
+
+    conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(conflicts)
+}
+
+fn package_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(root)
+            .expect("walkdir should only yield children of root");
+
+        files.push(relative_path.to_path_buf());
+    }
+
+    Ok(files)
+}
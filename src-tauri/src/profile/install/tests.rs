@@ -0,0 +1,89 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use super::find_dependency_strings;
+use crate::{
+    profile::export,
+    thunderstore::{PackageListing, Thunderstore},
+};
+
+fn package(full_name: &str, versions: &[&str]) -> PackageListing {
+    let versions = versions
+        .iter()
+        .map(|version| {
+            json!({
+                "full_name": format!("{}-{}", full_name, version),
+                "date_created": "2024-01-01T00:00:00Z",
+                "dependencies": [],
+                "description": "",
+                "downloads": 0,
+                "file_size": 0,
+                "is_active": true,
+                "uuid4": Uuid::new_v4(),
+                "website_url": "",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::from_value(json!({
+        "full_name": full_name,
+        "categories": [],
+        "date_created": "2024-01-01T00:00:00Z",
+        "date_updated": "2024-01-01T00:00:00Z",
+        "donation_link": null,
+        "has_nsfw_content": false,
+        "is_deprecated": false,
+        "is_pinned": false,
+        "package_url": "",
+        "rating_score": 0,
+        "uuid4": Uuid::new_v4(),
+        "versions": versions,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn dependency_strings_round_trip() {
+    let thunderstore = Thunderstore::from_packages([
+        package("BepInEx-BepInExPack", &["5.4.2100"]),
+        package("Owner-Cool_Mod", &["1.1.0", "1.0.0"]),
+    ]);
+
+    let idents = thunderstore
+        .latest()
+        .map(|borrowed| borrowed.ident().clone())
+        .chain([thunderstore
+            .find_package("Owner-Cool_Mod")
+            .unwrap()
+            .versions[1]
+            .ident
+            .clone()])
+        .collect::<Vec<_>>();
+
+    let text = export::dependency_strings(&idents);
+    let found = find_dependency_strings(text.lines(), &thunderstore).unwrap();
+
+    assert_eq!(
+        found
+            .iter()
+            .map(|borrowed| borrowed.ident().clone())
+            .collect::<Vec<_>>(),
+        idents
+    );
+}
+
+#[test]
+fn dependency_strings_report_unknown_mods() {
+    let thunderstore = Thunderstore::from_packages([package("Owner-Cool_Mod", &["1.0.0"])]);
+
+    let err = find_dependency_strings(
+        ["Owner-Cool_Mod-1.0.0", "Owner-Cool_Mod-2.0.0", "Other-Mod"],
+        &thunderstore,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "mods not found: Owner-Cool_Mod-2.0.0, Other-Mod"
+    );
+}
@@ -0,0 +1,243 @@
+//! Tools are packages, like runtime inspectors, that are kept per game instead
+//! of per profile.
+//!
+//! Enabled tools are linked into whichever profile is launched and taken out
+//! again on the next launch once they're disabled. They never become part of a
+//! profile's mod list, so they don't end up in exports or modpacks either.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::cache;
+use crate::{
+    game::Game,
+    profile::{ModManager, Profile},
+    state::ManagerExt,
+    thunderstore::{ModId, Thunderstore, VersionIdent},
+    util::{self, error::IoResultExt, fs::JsonStyle},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    #[serde(rename = "fullName")]
+    pub ident: VersionIdent,
+    #[serde(flatten)]
+    pub id: ModId,
+    pub enabled: bool,
+}
+
+impl Tool {
+    pub fn uuid(&self) -> Uuid {
+        self.id.package_uuid
+    }
+}
+
+/// Lists the files that tools were last linked to, relative to the profile.
+fn manifest_path(profile: &Profile) -> PathBuf {
+    profile.path.join("_state").join("tools.json")
+}
+
+/// The files currently linked into `profile` by tools, relative to the profile.
+pub fn linked_files(profile: &Profile) -> Vec<PathBuf> {
+    let path = manifest_path(profile);
+
+    match path.exists() {
+        true => util::fs::read_json(&path).unwrap_or_default(),
+        false => Vec::new(),
+    }
+}
+
+/// Adds a package to the active game's tools.
+pub fn add(id: ModId, manager: &mut ModManager, thunderstore: &Thunderstore) -> Result<()> {
+    let borrowed = id.borrow(thunderstore)?;
+    let full_name = borrowed.ident().full_name();
+
+    if manager.active_mod_loader().is_loader_package(full_name) {
+        bail!("the mod loader can't be used as a tool");
+    }
+
+    let game = manager.active_game_mut();
+
+    if game.tools.iter().any(|tool| tool.uuid() == id.package_uuid) {
+        bail!("{} is already a tool", full_name);
+    }
+
+    info!(
+        "adding {} as a tool for {}",
+        borrowed.ident(),
+        game.game.slug
+    );
+
+    game.tools.push(Tool {
+        ident: borrowed.ident().clone(),
+        id,
+        enabled: true,
+    });
+
+    Ok(())
+}
+
+/// Downloads the enabled tools of the active game if needed, then links
+/// them into the active profile, replacing the ones linked at the last launch.
+pub async fn prepare_launch(app: &AppHandle) -> Result<()> {
+    let (game, tools) = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let tools = manager
+            .active_game()
+            .tools
+            .iter()
+            .filter(|tool| tool.enabled)
+            .map(|tool| {
+                let url = tool
+                    .id
+                    .borrow(&thunderstore)
+                    .map(|borrowed| borrowed.version.download_url())
+                    .ok();
+
+                (tool.ident.clone(), url, cache::path(&tool.ident, &prefs))
+            })
+            .collect::<Vec<_>>();
+
+        (manager.active_game, tools)
+    };
+
+    let has_linked = !linked_files(app.read_manager().active_profile()).is_empty();
+    if tools.is_empty() && !has_linked {
+        return Ok(());
+    }
+
+    for (ident, url, cache_path) in &tools {
+        if cache_path.exists() {
+            continue;
+        }
+
+        let Some(url) = url else {
+            bail!(
+                "{} isn't cached and couldn't be found on Thunderstore",
+                ident
+            );
+        };
+
+        download(ident, url, cache_path, game, app)
+            .await
+            .with_context(|| format!("failed to download {}", ident))?;
+    }
+
+    let cache_paths = tools
+        .into_iter()
+        .map(|(_, _, path)| path)
+        .collect::<Vec<_>>();
+
+    let manager = app.read_manager();
+    link(manager.active_profile(), &cache_paths)
+}
+
+async fn download(
+    ident: &VersionIdent,
+    url: &str,
+    cache_path: &Path,
+    game: Game,
+    app: &AppHandle,
+) -> Result<()> {
+    info!("downloading tool {}", ident);
+
+    let data = app
+        .http()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?
+        .bytes()
+        .await?
+        .to_vec();
+
+    let ident = ident.clone();
+    let dest = cache_path.to_path_buf();
+    let staging_dir = app.lock_prefs().staging_dir();
+    tauri::async_runtime::spawn_blocking(move || {
+        super::download::extract(data, &ident, &dest, &staging_dir, game)
+    })
+    .await??;
+
+    Ok(())
+}
+
+/// Removes the files linked at the last launch and links the given cached packages.
+///
+/// Files that already exist in the profile are left alone, so a tool never
+/// overwrites anything installed by the profile's own mods.
+fn link(profile: &Profile, cache_paths: &[PathBuf]) -> Result<()> {
+    let mod_files = profile
+        .mods
+        .iter()
+        .flat_map(|profile_mod| &profile_mod.files)
+        .collect::<HashSet<_>>();
+
+    for relative_path in linked_files(profile) {
+        // a mod might have been installed over the tool's file since
+        if mod_files.contains(&relative_path) {
+            continue;
+        }
+
+        let path = profile.path.join(&relative_path);
+
+        if path.is_file() {
+            fs::remove_file(&path).fs_context("removing tool file", &path)?;
+        }
+    }
+
+    let mut linked = Vec::new();
+
+    for cache_path in cache_paths {
+        for entry in WalkDir::new(cache_path) {
+            let entry = entry?;
+
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative_path = entry
+                .path()
+                .strip_prefix(cache_path)
+                .expect("WalkDir should only return paths inside of the root");
+
+            let target = profile.path.join(relative_path);
+            if target.exists() {
+                warn!(
+                    "skipping tool file {} since it already exists",
+                    relative_path.display()
+                );
+                continue;
+            }
+
+            fs::create_dir_all(target.parent().unwrap())
+                .fs_context("creating directory", &target)?;
+            super::fs::link_file(entry.path(), &target).fs_context("linking tool file", &target)?;
+
+            linked.push(relative_path.to_path_buf());
+        }
+    }
+
+    info!("linked {} tool files into {}", linked.len(), profile.name);
+
+    let manifest_path = manifest_path(profile);
+    fs::create_dir_all(manifest_path.parent().unwrap())
+        .fs_context("creating state directory", &manifest_path)?;
+    util::fs::write_json(&manifest_path, &linked, JsonStyle::Compact)?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! A short hash of the mods in a profile, which players can compare to check
+//! that everyone in a lobby is running the same modpack.
+//!
+//! Only Thunderstore mods are included, since those are the only ones that can
+//! be shared through exports, so a profile hashes the same after being exported
+//! and imported again. The hash doesn't depend on the order of the mods.
+//!
+//! [`fingerprint`] hashes the mods the same way, but includes every mod and
+//! isn't shortened, for detecting whether a profile changed.
+
+use itertools::Itertools;
+
+use super::{export::R2Mod, Profile};
+
+/// The number of hex digits in the hash, split into groups of four.
+const LENGTH: usize = 12;
+
+/// Hashes the Thunderstore mods of a profile, including their versions and
+/// whether they're enabled.
+pub fn hash(profile: &Profile) -> String {
+    shorten(hash_mods(
+        profile
+            .thunderstore_mods()
+            .map(|(ts_mod, enabled)| (ts_mod.ident.to_string(), enabled)),
+    ))
+}
+
+/// Hashes every mod of a profile, including their versions and whether they're
+/// enabled. More data, like config files, can be added before finalizing.
+pub fn fingerprint(profile: &Profile) -> blake3::Hasher {
+    hash_mods(
+        profile
+            .mods
+            .iter()
+            .map(|profile_mod| (profile_mod.ident().to_string(), profile_mod.enabled)),
+    )
+}
+
+/// Hashes the mods of an exported profile, the same way as [`hash`].
+pub fn hash_manifest(mods: &[R2Mod]) -> String {
+    shorten(hash_mods(
+        mods.iter()
+            .map(|r2_mod| (r2_mod.ident().to_string(), r2_mod.enabled)),
+    ))
+}
+
+fn hash_mods(mods: impl Iterator<Item = (String, bool)>) -> blake3::Hasher {
+    let mut hasher = blake3::Hasher::new();
+
+    for (ident, enabled) in mods.sorted() {
+        hasher.update(ident.as_bytes());
+        hasher.update(&[enabled as u8]);
+    }
+
+    hasher
+}
+
+fn shorten(hasher: blake3::Hasher) -> String {
+    hasher.finalize().to_hex()[..LENGTH]
+        .chars()
+        .chunks(4)
+        .into_iter()
+        .map(|chunk| chunk.collect::<String>())
+        .join("-")
+        .to_uppercase()
+}
@@ -1,45 +1,253 @@
+use std::path::PathBuf;
+
 use eyre::Context;
 use itertools::Itertools;
+use serde::Serialize;
 use tauri::{command, AppHandle};
 
-use crate::{profile::sync, state::ManagerExt, util::cmd::Result};
+use super::{
+    crash,
+    diagnose::{self, LaunchDiagnosis},
+    game_log::{self, LogLine},
+    hooks,
+    log_analysis::{self, LogAnalysis},
+    monitor,
+    playtime::{self, GamePlaytime},
+    saves::{self, SaveBackup},
+    LaunchOverrides, LaunchTarget,
+};
+use crate::{
+    config,
+    logger::log_webview_err,
+    profile::{install, sync},
+    state::ManagerExt,
+    util::cmd::Result,
+};
 
+/// Returns a diagnosis of what went wrong if the launch failed.
 #[command]
-pub async fn launch_game(app: AppHandle) -> Result<()> {
+pub async fn launch_game(app: AppHandle) -> Result<Option<LaunchDiagnosis>> {
     if app.lock_prefs().pull_before_launch {
         sync::pull_profile(false, &app).await?;
     }
 
+    install::tools::prepare_launch(&app)
+        .await
+        .context("failed to set up tools")?;
+
+    {
+        let mut manager = app.lock_manager();
+
+        match config::shared::sync(manager.active_game()) {
+            // make sure the copies are read again, even if their modified time didn't change
+            Ok(replaced) => manager.active_profile_mut().config_cache.forget(&replaced),
+            Err(err) => log_webview_err("Failed to copy shared config", err, &app),
+        }
+    }
+
+    // copying the saves can take a while, so do it before locking for the launch
+    let backup = {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+        saves::prepare_launch_backup(manager.active_game(), &prefs)
+    };
+
+    let backup = match backup {
+        Ok(Some(backup)) => tauri::async_runtime::spawn_blocking(move || backup.run())
+            .await
+            .map_err(eyre::Error::from)
+            .and_then(|result| result),
+        Ok(None) => Ok(()),
+        Err(err) => Err(err),
+    };
+
+    // don't stop the launch, the user can still back up manually
+    if let Err(err) = backup {
+        log_webview_err("Failed to back up saves", err, &app);
+    }
+
+    // the hook can take a while or hang, so run it before locking for the launch
+    let hook = hooks::pre_launch(app.read_manager().active_profile());
+    if let Some(hook) = hook {
+        tauri::async_runtime::spawn_blocking(move || hook.run())
+            .await
+            .map_err(eyre::Error::from)
+            .and_then(|result| result)
+            .context("pre-launch hook failed")?;
+    }
+
     let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
+    let game = manager.active_game();
 
-    manager.active_game().launch(&prefs, &app)?;
+    match game.launch(&prefs, &app) {
+        Ok(()) => Ok(None),
+        Err(err) => Ok(Some(diagnose::diagnose(game, err, &prefs))),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchArgs {
+    target: LaunchTarget,
+    args: String,
+}
+
+#[command]
+pub fn is_game_running() -> bool {
+    monitor::is_running()
+}
+
+#[command]
+pub async fn stop_game() -> Result<()> {
+    monitor::stop().await?;
 
     Ok(())
 }
 
+/// The lines of the log followed since the last launch, optionally only those from `source`.
+#[command]
+pub fn get_game_log(source: Option<String>) -> Vec<LogLine> {
+    game_log::lines(source.as_deref())
+}
+
+/// Counts the errors and warnings in the active profile's log, per mod.
+#[command]
+pub fn analyze_game_log(app: AppHandle) -> Result<LogAnalysis> {
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+    let analysis = log_analysis::analyze(manager.active_profile(), &prefs)?;
+
+    Ok(analysis)
+}
+
+/// Total and recent playtime of each game and its profiles.
+#[command]
+pub fn get_playtime_stats(app: AppHandle) -> Result<Vec<GamePlaytime>> {
+    let manager = app.read_manager();
+    let stats = playtime::stats(&manager, app.db())?;
+
+    Ok(stats)
+}
+
 #[command]
-pub fn get_launch_args(app: AppHandle) -> Result<String> {
+pub fn get_launch_args(app: AppHandle) -> Result<LaunchArgs> {
     let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
+    let game = manager.active_game();
 
-    let game_dir = super::game_dir(manager.active_game, &prefs)?;
-    let (_, command) = manager.active_game().launch_command(&game_dir, &prefs)?;
-    let text = command
+    let game_dir = super::game_dir(game.game, &prefs)?;
+    let (_, command) = game.launch_command(&game_dir, &prefs)?;
+    let args = command
         .get_args()
         .map(|arg| format!("\"{}\"", arg.to_string_lossy()))
         .join(" ");
 
-    Ok(text)
+    Ok(LaunchArgs {
+        target: game.launch_target(&prefs).clone(),
+        args,
+    })
+}
+
+#[command]
+pub fn get_launch_overrides(app: AppHandle) -> Result<LaunchOverrides> {
+    let manager = app.read_manager();
+
+    Ok(manager.active_profile().launch_overrides.clone())
+}
+
+#[command]
+pub fn set_launch_overrides(overrides: LaunchOverrides, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.launch_overrides = overrides;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn open_crash_reports(app: AppHandle) -> Result<()> {
+    let manager = app.read_manager();
+
+    let path = crash::dir(manager.active_game());
+    std::fs::create_dir_all(&path)?;
+    open::that(path).context("failed to open directory")?;
+
+    Ok(())
+}
+
+#[command]
+pub fn launch_server(app: AppHandle) -> Result<()> {
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+
+    manager.active_game().launch_server(&prefs, &app)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn export_server_script(path: PathBuf, app: AppHandle) -> Result<()> {
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+
+    manager
+        .active_game()
+        .export_server_script(&path, &prefs)
+        .context("failed to export server script")?;
+
+    Ok(())
 }
 
 #[command]
 pub fn open_game_dir(app: AppHandle) -> Result<()> {
     let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
+    let manager = app.read_manager();
 
     let path = super::game_dir(manager.active_game, &prefs)?;
     open::that(path).context("failed to open directory")?;
 
     Ok(())
 }
+
+#[command]
+pub fn get_save_backups(app: AppHandle) -> Result<Vec<SaveBackup>> {
+    let manager = app.read_manager();
+    let backups = saves::list(&saves::backup_dir(manager.active_game()))?;
+
+    Ok(backups)
+}
+
+#[command]
+pub async fn backup_saves(app: AppHandle) -> Result<()> {
+    let (save_dir, backup_dir) = save_dirs(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || saves::backup(&save_dir, &backup_dir))
+        .await?
+        .context("failed to back up saves")?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn restore_save_backup(name: String, app: AppHandle) -> Result<()> {
+    let (save_dir, backup_dir) = save_dirs(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || saves::restore(&save_dir, &backup_dir, &name))
+        .await?
+        .context("failed to restore saves")?;
+
+    Ok(())
+}
+
+/// The save and backup directories of the active game, so they can be
+/// copied between without holding the locks.
+fn save_dirs(app: &AppHandle) -> eyre::Result<(PathBuf, PathBuf)> {
+    let prefs = app.lock_prefs();
+    let manager = app.read_manager();
+    let game = manager.active_game();
+
+    Ok((saves::save_dir(game, &prefs)?, saves::backup_dir(game)))
+}
@@ -0,0 +1,171 @@
+//! Collecting crash reports when the game exits with an error.
+//!
+//! A report is a zip in the game's `crash_reports` directory, with a summary,
+//! the profile's mod list, the loader's log and any config files that changed
+//! recently. A `crash-detected` event with [`CrashDetected`] is emitted once
+//! it's written, so the user can attach it when asking for help.
+
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::Local;
+use eyre::{Context, OptionExt, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    game::Game,
+    profile::{export, ManagedGame, ModManager},
+    state::ManagerExt,
+    util::error::IoResultExt,
+};
+
+/// Config files changed within this long before the crash are included.
+const RECENT_CONFIG: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashDetected {
+    pub game: String,
+    pub profile_id: i64,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub path: PathBuf,
+}
+
+pub fn dir(game: &ManagedGame) -> PathBuf {
+    game.path.join("crash_reports")
+}
+
+/// Writes a crash report for the profile in the background.
+pub fn report(
+    game: Game,
+    profile_id: i64,
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    duration_secs: u64,
+    app: &AppHandle,
+) {
+    let app = app.to_owned();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = {
+            let manager = app.read_manager();
+            write(&manager, game, profile_id, exit_code, signal, duration_secs)
+        };
+
+        match result {
+            Ok(path) => {
+                info!("wrote crash report to {}", path.display());
+
+                app.emit(
+                    "crash-detected",
+                    CrashDetected {
+                        game: game.slug.to_string(),
+                        profile_id,
+                        exit_code,
+                        signal,
+                        path,
+                    },
+                )
+                .ok();
+            }
+            Err(err) => warn!("failed to write crash report: {:#}", err),
+        }
+    });
+}
+
+fn write(
+    manager: &ModManager,
+    game: Game,
+    profile_id: i64,
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    duration_secs: u64,
+) -> Result<PathBuf> {
+    let managed_game = manager.games.get(game).ok_or_eyre("game not found")?;
+    let profile = managed_game.find_profile(profile_id)?;
+
+    let dir = dir(managed_game);
+    fs::create_dir_all(&dir).fs_context("creating crash report directory", &dir)?;
+
+    let path = dir.join(format!(
+        "crash-{}.zip",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    ));
+    let file = File::create(&path).fs_context("creating crash report", &path)?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let log = Some(game.mod_loader.log_path())
+        .filter(|log| !log.is_empty() && profile.path.join(log).exists())
+        .map(PathBuf::from);
+
+    let config = export::exported_config(profile)
+        .filter(|file| {
+            profile
+                .path
+                .join(file)
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| {
+                    modified
+                        .elapsed()
+                        .is_ok_and(|elapsed| elapsed < RECENT_CONFIG)
+                })
+        })
+        .collect::<Vec<_>>();
+
+    let enabled = profile
+        .mods
+        .iter()
+        .filter(|profile_mod| profile_mod.enabled)
+        .count();
+
+    let mut summary = String::new();
+    writeln!(summary, "Game: {}", game.name)?;
+    writeln!(summary, "Profile: {}", profile.name)?;
+    match (exit_code, signal) {
+        (Some(code), _) => writeln!(summary, "Exit code: {} ({:#x})", code, code)?,
+        (None, Some(signal)) => writeln!(summary, "Killed by signal: {}", signal)?,
+        (None, None) => writeln!(summary, "Exit code: unknown")?,
+    }
+    writeln!(summary, "Play time: {}s", duration_secs)?;
+    writeln!(
+        summary,
+        "Mods: {} ({} enabled)",
+        profile.mods.len(),
+        enabled
+    )?;
+    writeln!(summary, "Gale version: {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(summary, "OS: {}", std::env::consts::OS)?;
+
+    if !config.is_empty() {
+        writeln!(summary, "\nConfig changed in the last day:")?;
+        for file in &config {
+            writeln!(summary, "  {}", file.display())?;
+        }
+    }
+
+    zip.start_file("crash.txt", SimpleFileOptions::default())?;
+    zip.write_all(summary.as_bytes())?;
+
+    export::write_manifest(profile, game, &mut zip)?;
+    export::write_config(
+        log.iter().chain(&config),
+        &profile.path,
+        &mut zip,
+        SimpleFileOptions::default(),
+    )
+    .context("failed to write files")?;
+
+    zip.finish()?.flush()?;
+
+    Ok(path)
+}
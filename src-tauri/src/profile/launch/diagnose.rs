@@ -0,0 +1,194 @@
+//! Figuring out why a launch failed and what the user can do about it.
+
+use std::{env, path::Path};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use super::{LaunchMode, ManagedGame};
+use crate::{game::Platform, prefs::Prefs};
+
+/// Environment variables that affect how the game or its launcher is started.
+const LOGGED_ENV_VARS: &[&str] = &[
+    "PATH",
+    "STEAM_COMPAT_DATA_PATH",
+    "STEAM_COMPAT_CLIENT_INSTALL_PATH",
+    "WINEPREFIX",
+    "WINEDLLOVERRIDES",
+    "DOORSTOP_ENABLE",
+    "DOORSTOP_TARGET_ASSEMBLY",
+    "LD_PRELOAD",
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchDiagnosis {
+    /// The error that caused the launch to fail.
+    pub error: String,
+    pub issues: Vec<LaunchIssue>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchIssue {
+    pub kind: LaunchIssueKind,
+    pub message: String,
+    pub fix: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchIssueKind {
+    GameDirNotFound,
+    ExecutableNotFound,
+    SteamNotInstalled,
+    SteamNotRunning,
+    ProtonPrefixMissing,
+    InvalidSettings,
+    Unknown,
+}
+
+impl LaunchIssue {
+    fn new(kind: LaunchIssueKind, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// Checks the common causes of launch failures after `error` occurred.
+pub fn diagnose(game: &ManagedGame, error: eyre::Report, prefs: &Prefs) -> LaunchDiagnosis {
+    warn!("failed to launch {}: {:#}", game.game.slug, error);
+    log_environment();
+
+    let (launch_mode, platform, _) = game.launch_settings(prefs);
+    let mut issues = Vec::new();
+
+    if let LaunchMode::Direct { instances: 0, .. } = launch_mode {
+        issues.push(LaunchIssue::new(
+            LaunchIssueKind::InvalidSettings,
+            "The number of instances to launch is set to 0.",
+            "Set the number of instances to at least 1 in the game settings.",
+        ));
+    }
+
+    match super::game_dir(game.game, prefs) {
+        Ok(game_dir) => diagnose_game_dir(game, &game_dir, &launch_mode, platform, &mut issues),
+        Err(err) => issues.push(LaunchIssue::new(
+            LaunchIssueKind::GameDirNotFound,
+            format!("The game directory could not be found: {:#}.", err),
+            "Make sure the game is installed, or set its location manually in the game settings.",
+        )),
+    }
+
+    if issues.is_empty() {
+        issues.push(LaunchIssue::new(
+            LaunchIssueKind::Unknown,
+            "No known cause was detected.",
+            "Check the Gale log for more details, or report the issue on GitHub or Discord.",
+        ));
+    }
+
+    LaunchDiagnosis {
+        error: format!("{:#}", error),
+        issues,
+    }
+}
+
+fn diagnose_game_dir(
+    game: &ManagedGame,
+    game_dir: &Path,
+    launch_mode: &LaunchMode,
+    platform: Option<Platform>,
+    issues: &mut Vec<LaunchIssue>,
+) {
+    let uses_launcher = matches!(launch_mode, LaunchMode::Launcher)
+        && matches!(platform, Some(Platform::Steam | Platform::EpicGames));
+
+    if !uses_launcher && super::exe_path(game_dir).is_err() {
+        issues.push(LaunchIssue::new(
+            LaunchIssueKind::ExecutableNotFound,
+            format!("No game executable was found in {}.", game_dir.display()),
+            "Verify the game files through your launcher, or check the game location in the settings.",
+        ));
+    }
+
+    if uses_launcher && matches!(platform, Some(Platform::Steam)) {
+        match super::platform::find_steam_binary() {
+            Ok(_) => {
+                if steam_running() == Some(false) {
+                    issues.push(LaunchIssue::new(
+                        LaunchIssueKind::SteamNotRunning,
+                        "Steam does not appear to be running.",
+                        "Start Steam and log in, then launch the game again.",
+                    ));
+                }
+            }
+            Err(_) => issues.push(LaunchIssue::new(
+                LaunchIssueKind::SteamNotInstalled,
+                "Steam could not be found on this system.",
+                "Install Steam, or switch the launch mode to direct in the game settings.",
+            )),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    if let (Some(steam), Some(_)) = (&game.game.platforms.steam, game.game.mod_loader.proxy_dll()) {
+        if super::linux::is_proton(game_dir).unwrap_or(false)
+            && super::linux::wine_prefix(steam.id as u64, game_dir)
+                .is_some_and(|prefix| !prefix.exists())
+        {
+            issues.push(LaunchIssue::new(
+                LaunchIssueKind::ProtonPrefixMissing,
+                "The game runs through Proton, but its Wine prefix does not exist yet.",
+                "Launch the game once through Steam without mods, then try again.",
+            ));
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = game;
+}
+
+/// Whether the Steam client is currently running, if it can be determined.
+fn steam_running() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+        let key = RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(r"Software\Valve\Steam\ActiveProcess")
+            .ok()?;
+        let pid: u32 = key.get_value("pid").ok()?;
+
+        Some(pid != 0)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let pid_path = dirs_next::home_dir()?.join(".steam").join("steam.pid");
+        let pid = std::fs::read_to_string(pid_path).ok()?;
+
+        Some(Path::new("/proc").join(pid.trim()).exists())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    None
+}
+
+fn log_environment() {
+    info!(
+        "environment: {} {}, gale v{}",
+        env::consts::OS,
+        env::consts::ARCH,
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for name in LOGGED_ENV_VARS {
+        if let Some(value) = env::var_os(name) {
+            info!("{}={}", name, value.to_string_lossy());
+        }
+    }
+}
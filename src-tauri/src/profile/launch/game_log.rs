@@ -0,0 +1,283 @@
+//! Following the mod loader's log file while the game runs.
+//!
+//! New lines are parsed and emitted in batches as `game-log` events, and kept
+//! in a buffer so the log can be shown from the start when it's opened later.
+//! Only one log is followed at a time; launching again starts over.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, warn};
+
+use super::monitor;
+use crate::game::Game;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long to wait for the game to start before giving up.
+const START_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_LINES: usize = 10_000;
+/// How much of the start of the file is compared to tell when a new log was started.
+const HEAD_LEN: u64 = 256;
+
+static LINES: LazyLock<Mutex<VecDeque<LogLine>>> = LazyLock::new(Default::default);
+/// Incremented for each followed log, so the previous one stops.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Fatal,
+    Error,
+    Warning,
+    Message,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn parse(str: &str) -> Option<Self> {
+        match str.to_lowercase().as_str() {
+            "fatal" => Some(Self::Fatal),
+            "error" | "err" => Some(Self::Error),
+            "warning" | "warn" | "wrn" => Some(Self::Warning),
+            "message" | "msg" => Some(Self::Message),
+            "info" | "inf" => Some(Self::Info),
+            "debug" | "dbg" => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub level: LogLevel,
+    /// The plugin or mod that wrote the line, if the log format includes it.
+    pub source: Option<String>,
+    pub text: String,
+}
+
+impl LogLine {
+    /// Parses a line of the log, in BepInEx's `[Level : Source] text` format or
+    /// MelonLoader's `[time] [Source] text` format.
+    ///
+    /// Lines without a prefix, like stack traces, continue the `previous` line.
+    pub(super) fn parse(line: &str, previous: Option<&LogLine>) -> Self {
+        let mut groups = Vec::new();
+        let mut rest = line;
+
+        while let Some(group) = rest.strip_prefix('[').and_then(|str| str.split_once(']')) {
+            groups.push(group.0.trim());
+            rest = group.1.trim_start();
+        }
+
+        let (level, source) = match groups.as_slice() {
+            [] => match previous {
+                Some(previous) => (previous.level, previous.source.clone()),
+                None => (LogLevel::default(), None),
+            },
+            [first, ..] if first.contains(':') && !is_time(first) => {
+                let (level, source) = first.split_once(':').unwrap();
+                (
+                    LogLevel::parse(level.trim()).unwrap_or_default(),
+                    Some(source.trim().to_owned()),
+                )
+            }
+            [first, second, ..] if is_time(first) => match LogLevel::parse(second) {
+                Some(level) => (level, groups.get(2).map(|source| source.to_string())),
+                None => (LogLevel::default(), Some(second.to_string())),
+            },
+            _ => (LogLevel::default(), None),
+        };
+
+        Self {
+            level,
+            source,
+            text: line.to_owned(),
+        }
+    }
+}
+
+fn is_time(str: &str) -> bool {
+    str.chars()
+        .all(|char| char.is_ascii_digit() || matches!(char, ':' | '.'))
+}
+
+/// The lines of the last followed log, optionally only those from `source`.
+pub fn lines(source: Option<&str>) -> Vec<LogLine> {
+    LINES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|line| match source {
+            Some(source) => line.source.as_deref() == Some(source),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Follows the log at `path` until the game exits, or until another log is followed.
+///
+/// Whatever is already in the file is skipped, since it's from the last session.
+pub fn follow(path: PathBuf, app: &AppHandle) {
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    LINES.lock().unwrap().clear();
+
+    let app = app.to_owned();
+
+    tauri::async_runtime::spawn(async move {
+        let mut reader = Reader::new(path);
+        let launched = Instant::now();
+        let mut started = false;
+
+        loop {
+            if GENERATION.load(Ordering::Relaxed) != generation {
+                break;
+            }
+
+            // check before reading, so the last lines are read after the game exits
+            let running = monitor::is_running();
+            started |= running;
+
+            let lines = reader.read();
+            if !lines.is_empty() {
+                push(&lines);
+                app.emit("game-log", &lines).ok();
+            }
+
+            if (started && !running) || (!started && launched.elapsed() > START_TIMEOUT) {
+                break;
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        debug!("stopped following game log");
+    });
+}
+
+fn push(new: &[LogLine]) {
+    let mut lines = LINES.lock().unwrap();
+    lines.extend(new.iter().cloned());
+
+    let excess = lines.len().saturating_sub(MAX_LINES);
+    lines.drain(..excess);
+}
+
+pub(super) struct Reader {
+    path: PathBuf,
+    pos: u64,
+    /// The start of the file, to notice when it's replaced by a new log.
+    head: Vec<u8>,
+    /// The end of a line that hasn't been fully written yet. Kept as bytes,
+    /// since a read can end in the middle of a character.
+    partial: Vec<u8>,
+    last: Option<LogLine>,
+}
+
+impl Reader {
+    /// Starts reading at the end of the file at `path`, if it exists.
+    pub(super) fn new(path: PathBuf) -> Self {
+        let (pos, head) = File::open(&path)
+            .and_then(|mut file| Ok((file.metadata()?.len(), read_head(&mut file)?)))
+            .unwrap_or_default();
+
+        Self {
+            path,
+            pos,
+            head,
+            partial: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Reads and parses the lines completed since the last read.
+    pub(super) fn read(&mut self) -> Vec<LogLine> {
+        match self.read_new() {
+            Ok(bytes) => self.parse(&bytes),
+            Err(err) => {
+                // the file doesn't exist before the loader creates it
+                if err.kind() != io::ErrorKind::NotFound {
+                    warn!("failed to read game log: {:#}", err);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn read_new(&mut self) -> io::Result<Vec<u8>> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        let head = read_head(&mut file)?;
+
+        // the loader started a new log, either by truncating the file or by
+        // replacing it with one that may already be longer than the old one
+        if len < self.pos || !head.starts_with(&self.head) {
+            debug!("game log was replaced, reading from the start");
+
+            self.pos = 0;
+            self.partial.clear();
+            self.last = None;
+        }
+
+        self.head = head;
+
+        file.seek(SeekFrom::Start(self.pos))?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        self.pos += bytes.len() as u64;
+
+        Ok(bytes)
+    }
+
+    fn parse(&mut self, bytes: &[u8]) -> Vec<LogLine> {
+        self.partial.extend_from_slice(bytes);
+
+        let Some(end) = self.partial.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+
+        let complete = self.partial.drain(..=end).collect::<Vec<_>>();
+
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let line = LogLine::parse(line, self.last.as_ref());
+                self.last = Some(line.clone());
+                line
+            })
+            .collect()
+    }
+}
+
+fn read_head(file: &mut File) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+
+    file.seek(SeekFrom::Start(0))?;
+    file.take(HEAD_LEN).read_to_end(&mut head)?;
+
+    Ok(head)
+}
+
+/// The loader's log file in `profile_dir`, if it writes one.
+pub fn log_path(game: Game, profile_dir: &Path) -> Option<PathBuf> {
+    match game.mod_loader.log_path() {
+        "" => None,
+        path => Some(profile_dir.join(path)),
+    }
+}
@@ -0,0 +1,190 @@
+//! Scripts run before the game launches and after it exits.
+//!
+//! Hooks are set per profile in its [`LaunchOverrides`](super::LaunchOverrides).
+//! They run in the profile's directory with details about the launch in `GALE_*`
+//! environment variables, and everything they print goes to Gale's log.
+
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, ensure, Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::{game::Game, profile::Profile, state::ManagerExt};
+
+/// How long the launch waits for the pre-launch hook before giving up.
+const PRE_LAUNCH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchHook {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl LaunchHook {
+    /// Runs the hook and waits for it to exit, killing it after `timeout`.
+    fn run(
+        &self,
+        name: &'static str,
+        env: &[(&str, String)],
+        dir: &Path,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        info!("running {} hook {}", name, self.path.display());
+
+        let mut command = Command::new(&self.path);
+        command
+            .args(&self.args)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+
+            command.creation_flags(super::monitor::NO_WINDOW);
+        }
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to start {}", self.path.display()))?;
+
+        // read the output on other threads instead of waiting for the pipes to close,
+        // since anything the hook starts in the background keeps them open
+        if let Some(stdout) = child.stdout.take() {
+            forward(stdout, name, false);
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            forward(stderr, name, true);
+        }
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if timeout.is_some_and(|timeout| started.elapsed() > timeout) {
+                if let Err(err) = child.kill() {
+                    warn!("failed to kill {} hook: {:#}", name, err);
+                }
+
+                bail!(
+                    "{} hook did not exit within {}s",
+                    name,
+                    started.elapsed().as_secs()
+                );
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        ensure!(status.success(), "{} hook exited with {}", name, status);
+
+        Ok(())
+    }
+}
+
+fn forward(pipe: impl Read + Send + 'static, name: &'static str, is_stderr: bool) {
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            match is_stderr {
+                true => warn!("[{} hook] {}", name, line),
+                false => info!("[{} hook] {}", name, line),
+            }
+        }
+    });
+}
+
+fn env(profile: &Profile) -> Vec<(&'static str, String)> {
+    vec![
+        ("GALE_GAME", profile.game.slug.to_string()),
+        ("GALE_PROFILE_ID", profile.id.to_string()),
+        ("GALE_PROFILE_NAME", profile.name.clone()),
+        (
+            "GALE_PROFILE_PATH",
+            profile.path.to_string_lossy().into_owned(),
+        ),
+    ]
+}
+
+/// The pre-launch hook of a profile, ready to run without borrowing the profile.
+pub struct PreLaunch {
+    hook: LaunchHook,
+    env: Vec<(&'static str, String)>,
+    dir: PathBuf,
+}
+
+impl PreLaunch {
+    /// Runs the hook and waits for it to exit. This blocks, so don't hold any locks.
+    pub fn run(&self) -> Result<()> {
+        self.hook
+            .run("pre-launch", &self.env, &self.dir, Some(PRE_LAUNCH_TIMEOUT))
+    }
+}
+
+/// The pre-launch hook of `profile`, if it has one.
+pub fn pre_launch(profile: &Profile) -> Option<PreLaunch> {
+    profile
+        .launch_overrides
+        .pre_launch
+        .clone()
+        .map(|hook| PreLaunch {
+            hook,
+            env: env(profile),
+            dir: profile.path.clone(),
+        })
+}
+
+/// Runs the post-exit hook of the profile in the background, if it has one.
+pub fn post_exit(game: Game, profile_id: i64, exit_code: Option<i32>, app: &AppHandle) {
+    let app = app.to_owned();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let hook = {
+            let manager = app.read_manager();
+
+            manager
+                .games
+                .get(game)
+                .ok_or_eyre("game not found")
+                .and_then(|game| game.find_profile(profile_id))
+                .map(|profile| {
+                    profile.launch_overrides.post_exit.clone().map(|hook| {
+                        let mut env = env(profile);
+                        env.push((
+                            "GALE_EXIT_CODE",
+                            exit_code.map(|code| code.to_string()).unwrap_or_default(),
+                        ));
+
+                        (hook, env, profile.path.clone())
+                    })
+                })
+        };
+
+        let result = match hook {
+            Ok(Some((hook, env, dir))) => hook.run("post-exit", &env, &dir, None),
+            Ok(None) => Ok(()),
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            warn!("failed to run post-exit hook: {:#}", err);
+        }
+    });
+}
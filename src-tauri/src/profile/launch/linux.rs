@@ -1,10 +1,17 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use eyre::Result;
+use eyre::{bail, Context, OptionExt, Result};
 use itertools::Itertools;
-use tracing::{debug, info, trace};
+use steamlocate::SteamDir;
+use tracing::{debug, info, trace, warn};
+use which::which;
 
-use crate::util::error::IoResultExt;
+use super::WineRunner;
+use crate::{game::Game, prefs::Prefs, util::error::IoResultExt};
 
 pub fn is_proton(game_dir: &Path) -> Result<bool> {
     if game_dir.join(".forceproton").exists() {
@@ -18,15 +25,195 @@ pub fn is_proton(game_dir: &Path) -> Result<bool> {
         .is_some())
 }
 
+/// The Wine prefix Proton uses for the game, derived from its Steam library.
+pub fn wine_prefix(steam_id: u64, game_dir: &Path) -> Option<PathBuf> {
+    let steamapps = game_dir
+        .parent()? // common
+        .parent()?; // steamapps
+
+    Some(
+        steamapps
+            .join("compatdata")
+            .join(steam_id.to_string())
+            .join("pfx"),
+    )
+}
+
+/// Builds a command that runs the Windows executable `exe` in the game's Proton prefix.
+///
+/// Used when launching directly, since Steam sets up Proton by itself otherwise.
+pub fn wine_command(exe: &Path, game: Game, game_dir: &Path, prefs: &Prefs) -> Result<Command> {
+    let game_prefs = prefs.game_prefs.get(&*game.slug);
+    let runner = game_prefs
+        .map(|prefs| prefs.wine_runner)
+        .unwrap_or_default();
+    let proton_version = game_prefs.and_then(|prefs| prefs.proton_version.as_deref());
+
+    let steam_dir = SteamDir::locate().ok();
+    let steam_id = game.platforms.steam.as_ref().map(|steam| steam.id);
+
+    info!("launching {} with {:?}", exe.display(), runner);
+
+    let mut command = match runner {
+        WineRunner::Proton => {
+            let steam_dir = steam_dir
+                .as_ref()
+                .ok_or_eyre("failed to find steam install")?;
+            let steam_id = steam_id.ok_or_eyre("Proton can only be used for Steam games")?;
+            let proton_dir = proton_dir(proton_version, steam_id, steam_dir)?;
+            let compat_data = compat_data_dir(steam_id, game_dir)?;
+
+            let mut command = Command::new(proton_dir.join("proton"));
+            command
+                .arg("run")
+                .arg(exe)
+                .env("STEAM_COMPAT_DATA_PATH", compat_data)
+                .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_dir.path());
+            command
+        }
+        WineRunner::Umu => {
+            let mut command = Command::new(which("umu-run").context("umu-run is not installed")?);
+            command.arg(exe);
+
+            if let Some(steam_id) = steam_id {
+                let prefix = compat_data_dir(steam_id, game_dir)?.join("pfx");
+
+                command
+                    .env("GAMEID", format!("umu-{}", steam_id))
+                    .env("WINEPREFIX", prefix);
+            }
+
+            // otherwise umu downloads and uses its own build of Proton
+            if let (Some(steam_dir), Some(steam_id)) = (&steam_dir, steam_id) {
+                match proton_dir(proton_version, steam_id, steam_dir) {
+                    Ok(path) => {
+                        command.env("PROTONPATH", path);
+                    }
+                    Err(err) => debug!("using umu's default proton: {:#}", err),
+                }
+            }
+
+            command
+        }
+        WineRunner::Protontricks => {
+            let steam_id = steam_id.ok_or_eyre("protontricks can only be used for Steam games")?;
+
+            let mut command = Command::new(
+                which("protontricks-launch").context("protontricks is not installed")?,
+            );
+            command.arg("--appid").arg(steam_id.to_string()).arg(exe);
+            command
+        }
+    };
+
+    if let Some(steam_id) = steam_id {
+        command
+            .env("SteamAppId", steam_id.to_string())
+            .env("SteamGameId", steam_id.to_string());
+    }
+
+    if let Some(proxy_dll) = game.mod_loader.proxy_dll() {
+        command.env("WINEDLLOVERRIDES", format!("{}=n,b", proxy_dll));
+    }
+
+    command.current_dir(game_dir);
+
+    Ok(command)
+}
+
+/// The game's `compatdata` directory, which contains its Wine prefix.
+fn compat_data_dir(steam_id: u32, game_dir: &Path) -> Result<PathBuf> {
+    wine_prefix(steam_id as u64, game_dir)
+        .and_then(|prefix| prefix.parent().map(Path::to_path_buf))
+        .filter(|path| path.exists())
+        .ok_or_eyre(
+            "the game's Proton prefix was not found, try launching it through Steam once first",
+        )
+}
+
+/// Finds the Proton installation to use, either from `version` or the one
+/// Steam is set to use for the game.
+///
+/// `version` is either a path or the name of a directory in a Steam library's
+/// `common` directory or in `compatibilitytools.d`.
+fn proton_dir(version: Option<&str>, steam_id: u32, steam_dir: &SteamDir) -> Result<PathBuf> {
+    let mut search_dirs = vec![steam_dir.path().join("compatibilitytools.d")];
+
+    match steam_dir.library_paths() {
+        Ok(paths) => search_dirs.extend(
+            paths
+                .into_iter()
+                .map(|path| path.join("steamapps").join("common")),
+        ),
+        Err(err) => warn!("failed to read steam libraries: {:#}", err),
+    }
+
+    let find = |name: &str| {
+        search_dirs
+            .iter()
+            .map(|dir| dir.join(name))
+            .find(|path| path.join("proton").exists())
+    };
+
+    if let Some(version) = version {
+        let path = Path::new(version);
+        if path.join("proton").exists() {
+            return Ok(path.to_path_buf());
+        }
+
+        return find(version).ok_or_else(|| eyre::eyre!("Proton version {} not found", version));
+    }
+
+    let mapped = steam_dir
+        .compat_tool_mapping()
+        .ok()
+        .and_then(|mapping| mapping.get(&steam_id)?.name.clone());
+
+    if let Some(path) = mapped.as_deref().and_then(find) {
+        return Ok(path);
+    }
+
+    // fall back to the newest official version
+    let latest = search_dirs
+        .iter()
+        .filter_map(|dir| dir.read_dir().ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+
+            (name.starts_with("Proton ") && path.join("proton").exists())
+                .then(|| (proton_version(&name), path))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    match latest {
+        Some((_, path)) => Ok(path),
+        None => bail!("no Proton installation found, please install one through Steam"),
+    }
+}
+
+/// Parses the version numbers of an official Proton directory, like `Proton 9.0 (Beta)`.
+///
+/// Versions without numbers, like `Proton - Experimental`, are `None`, so they
+/// sort below any numbered version.
+fn proton_version(name: &str) -> Option<Vec<u32>> {
+    let version = name
+        .strip_prefix("Proton ")?
+        .split(|char: char| !char.is_ascii_digit() && char != '.')
+        .next()?;
+
+    version
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<_>>>()
+        .filter(|parts| !parts.is_empty())
+}
+
 pub fn ensure_wine_override(steam_id: u64, proxy_dll: &str, game_dir: &Path) -> Result<()> {
-    let wine_reg_path = game_dir
-        .parent() // common
-        .unwrap()
-        .parent() // steamapps
-        .unwrap()
-        .join("compatdata")
-        .join(steam_id.to_string())
-        .join("pfx")
+    let wine_reg_path = wine_prefix(steam_id, game_dir)
+        .ok_or_eyre("game directory is not in a steam library")?
         .join("user.reg");
 
     let text = fs::read_to_string(&wine_reg_path).fs_context("reading wine registry", &wine_reg_path)?;
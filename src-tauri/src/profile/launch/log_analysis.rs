@@ -0,0 +1,253 @@
+//! Attributing the errors and warnings in the loader's log to installed mods.
+//!
+//! Each entry is matched by its source, which BepInEx sets to the name of the
+//! plugin that logged it, and otherwise by the first frame of its stack trace
+//! that belongs to a mod. Both are compared against the package names and the
+//! assemblies each mod installed, since plugin names, namespaces and assembly
+//! names usually line up. Mods installed before their files were recorded use
+//! the assemblies of their cached package instead.
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{OptionExt, Result};
+use serde::Serialize;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::game_log::{self, LogLevel, LogLine};
+use crate::{
+    prefs::Prefs,
+    profile::{install::cache, Profile, ProfileMod, ProfileModKind},
+    util::error::IoResultExt,
+};
+
+/// How many messages to keep per mod.
+const MAX_EXAMPLES: usize = 3;
+
+/// Sources that belong to the loader or the game, rather than a mod.
+const GENERIC_SOURCES: &[&str] = &[
+    "bepinex",
+    "unitylog",
+    "unity",
+    "harmonyx",
+    "harmony",
+    "preloader",
+    "detourcontext",
+    "monomod",
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLogIssues {
+    pub uuid: Uuid,
+    pub full_name: String,
+    pub errors: usize,
+    pub warnings: usize,
+    /// The first few error messages, or warnings if there are no errors.
+    pub examples: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogAnalysis {
+    /// Mods with at least one error or warning, with the most errors first.
+    pub mods: Vec<ModLogIssues>,
+    pub unattributed_errors: usize,
+    pub unattributed_warnings: usize,
+}
+
+/// An entry of the log: a line with a prefix, and the lines following it.
+struct Entry<'a> {
+    line: LogLine,
+    continuation: Vec<&'a str>,
+}
+
+/// The names a mod's log entries can be attributed by.
+pub(super) struct ModNames {
+    pub uuid: Uuid,
+    pub full_name: String,
+    /// The package name, without the author.
+    pub name: String,
+    /// The file stems of the mod's assemblies.
+    pub assemblies: Vec<String>,
+}
+
+/// Analyzes the loader log of `profile`.
+pub fn analyze(profile: &Profile, prefs: &Prefs) -> Result<LogAnalysis> {
+    let path = game_log::log_path(profile.game, &profile.path)
+        .filter(|path| path.exists())
+        .ok_or_eyre("no log file found, launch the game first")?;
+
+    let bytes = fs::read(&path).fs_context("reading log", &path)?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let mods = profile
+        .mods
+        .iter()
+        .map(|profile_mod| mod_names(profile_mod, prefs))
+        .collect::<Vec<_>>();
+
+    Ok(analyze_text(&text, &mods))
+}
+
+fn mod_names(profile_mod: &ProfileMod, prefs: &Prefs) -> ModNames {
+    let assemblies = match profile_mod.files.is_empty() {
+        false => assemblies(profile_mod.files.iter().map(PathBuf::as_path)),
+        true => cached_assemblies(profile_mod, prefs),
+    };
+
+    ModNames {
+        uuid: profile_mod.uuid(),
+        full_name: profile_mod.full_name().into_owned(),
+        name: profile_mod.ident().name().to_owned(),
+        assemblies,
+    }
+}
+
+/// The assemblies in the cached package of a mod without a file manifest.
+fn cached_assemblies(profile_mod: &ProfileMod, prefs: &Prefs) -> Vec<String> {
+    let cache_path = match &profile_mod.kind {
+        ProfileModKind::Thunderstore(ts_mod) => cache::path(&ts_mod.ident, prefs),
+        ProfileModKind::Nexus(nexus_mod) => nexus_mod.cache_path(prefs),
+        ProfileModKind::Github(github_mod) => github_mod.cache_path(prefs),
+        ProfileModKind::Local(_) => return Vec::new(),
+    };
+
+    let files = WalkDir::new(cache_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .collect::<Vec<_>>();
+
+    assemblies(files.iter().map(PathBuf::as_path))
+}
+
+fn assemblies<'a>(files: impl Iterator<Item = &'a Path>) -> Vec<String> {
+    files
+        .filter(|file| file.extension().is_some_and(|ext| ext == "dll"))
+        .filter_map(|file| file.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .collect()
+}
+
+pub(super) fn analyze_text(text: &str, mods: &[ModNames]) -> LogAnalysis {
+    let keys = mod_keys(mods);
+    let mut counts: HashMap<usize, ModLogIssues> = HashMap::new();
+    let mut analysis = LogAnalysis::default();
+
+    for entry in entries(text) {
+        let is_error = matches!(entry.line.level, LogLevel::Error | LogLevel::Fatal);
+        if !is_error && entry.line.level != LogLevel::Warning {
+            continue;
+        }
+
+        let Some(index) = attribute(&entry, &keys) else {
+            match is_error {
+                true => analysis.unattributed_errors += 1,
+                false => analysis.unattributed_warnings += 1,
+            }
+            continue;
+        };
+
+        let issues = counts.entry(index).or_insert_with(|| ModLogIssues {
+            uuid: mods[index].uuid,
+            full_name: mods[index].full_name.clone(),
+            errors: 0,
+            warnings: 0,
+            examples: Vec::new(),
+        });
+
+        if is_error {
+            if issues.errors == 0 {
+                // errors are more useful than any warnings collected so far
+                issues.examples.clear();
+            }
+            issues.errors += 1;
+        } else {
+            issues.warnings += 1;
+        }
+
+        if issues.examples.len() < MAX_EXAMPLES && (is_error || issues.errors == 0) {
+            issues.examples.push(entry.line.text.clone());
+        }
+    }
+
+    analysis.mods = counts.into_values().collect();
+    analysis
+        .mods
+        .sort_by_key(|issues| Reverse((issues.errors, issues.warnings)));
+
+    analysis
+}
+
+fn entries(text: &str) -> Vec<Entry<'_>> {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        match entries.last_mut() {
+            Some(entry) if !line.starts_with('[') => entry.continuation.push(line),
+            _ => entries.push(Entry {
+                line: LogLine::parse(line, None),
+                continuation: Vec::new(),
+            }),
+        }
+    }
+
+    entries
+}
+
+/// Maps normalized names to the index of the mod they belong to.
+fn mod_keys(mods: &[ModNames]) -> HashMap<String, usize> {
+    let mut keys = HashMap::new();
+
+    for (index, names) in mods.iter().enumerate() {
+        keys.insert(normalize(&names.name), index);
+    }
+
+    for (index, names) in mods.iter().enumerate() {
+        for assembly in &names.assemblies {
+            keys.entry(normalize(assembly)).or_insert(index);
+        }
+    }
+
+    // a loader's own package shouldn't claim its generic messages
+    for source in GENERIC_SOURCES {
+        keys.remove(*source);
+    }
+
+    keys
+}
+
+fn attribute(entry: &Entry, keys: &HashMap<String, usize>) -> Option<usize> {
+    let from_source = entry
+        .line
+        .source
+        .as_deref()
+        .map(normalize)
+        .and_then(|source| keys.get(&source));
+
+    if let Some(index) = from_source {
+        return Some(*index);
+    }
+
+    // stack frames look like `at Namespace.Type.Method ()` or `Namespace.Type.Method ()`
+    entry.continuation.iter().find_map(|line| {
+        let frame = line.trim_start();
+        let frame = frame.strip_prefix("at ").unwrap_or(frame);
+        let namespace = frame.split(['.', ' ', ':']).next()?;
+
+        keys.get(&normalize(namespace)).copied()
+    })
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_ascii_alphanumeric())
+        .map(|char| char.to_ascii_lowercase())
+        .collect()
+}
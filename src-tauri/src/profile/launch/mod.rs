@@ -1,5 +1,6 @@
 use core::str;
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
     process::Command,
@@ -11,9 +12,9 @@ use tauri::AppHandle;
 use tokio::time::Duration;
 use tracing::{info, warn};
 
-use super::ManagedGame;
+use super::{install, ManagedGame};
 use crate::{
-    game::Game,
+    game::{Game, Platform, SteamLaunch},
     logger::log_webview_err,
     prefs::{GamePrefs, Prefs},
     util::{
@@ -24,10 +25,21 @@ use crate::{
 
 #[cfg(target_os = "linux")]
 mod linux;
-mod mod_loader;
 mod platform;
 
 pub mod commands;
+pub mod crash;
+pub mod diagnose;
+pub mod game_log;
+pub mod hooks;
+pub mod log_analysis;
+pub mod monitor;
+pub mod playtime;
+pub mod saves;
+mod server;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase", tag = "type", content = "content")]
@@ -39,53 +51,158 @@ pub enum LaunchMode {
     Direct { instances: u32, interval_secs: f32 },
 }
 
+/// What gets launched, as opposed to [`LaunchMode`], which decides how.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "content")]
+pub enum LaunchTarget {
+    /// The game, with the mod loader pointed at the active profile.
+    #[default]
+    Modded,
+    /// The game, without loading any mods.
+    Vanilla,
+    /// Another executable, like a wrapper script, with the mod loader pointed
+    /// at the active profile.
+    ///
+    /// Always launched directly, and `args` replace the game's custom arguments.
+    #[serde(rename_all = "camelCase")]
+    Custom { path: PathBuf, args: Vec<String> },
+}
+
+/// What runs Windows executables when launching directly on Linux.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WineRunner {
+    /// Proton's own launch script, with the game's Steam prefix.
+    #[default]
+    Proton,
+    /// `umu-run`, which sets up the Steam runtime like Steam would.
+    Umu,
+    /// `protontricks-launch`.
+    Protontricks,
+}
+
+/// Launch settings of a single profile, applied on top of the game's.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LaunchOverrides {
+    /// Arguments added after the game's custom arguments.
+    pub args: Vec<String>,
+    /// Ignore the game's custom arguments, only passing [`LaunchOverrides::args`].
+    pub replace_game_args: bool,
+    /// Environment variables set for the launch command.
+    ///
+    /// When launching through Steam, these only reach the game if Steam isn't
+    /// already running.
+    pub env: BTreeMap<String, String>,
+    /// Run before the game is launched. The launch is cancelled if it fails.
+    pub pre_launch: Option<hooks::LaunchHook>,
+    /// Run after the game exits, if it was launched by Gale.
+    pub post_exit: Option<hooks::LaunchHook>,
+}
+
 impl ManagedGame {
     pub fn launch(&self, prefs: &Prefs, app: &AppHandle) -> Result<()> {
+        // clean up files left behind by the last session before they're loaded again
+        install::locked::retry_pending();
+
         let game_dir = game_dir(self.game, prefs)?;
-        if let Err(err) = self.link_files(&game_dir) {
-            warn!("failed to link files: {:#}", err);
+        let target = self.launch_target(prefs);
+
+        if *target != LaunchTarget::Vanilla {
+            if let Err(err) = self.link_files(&game_dir) {
+                warn!("failed to link files: {:#}", err);
+            }
         }
 
         let (launch_mode, command) = self.launch_command(&game_dir, prefs)?;
         info!("launching {} with command {:?}", self.game.slug, command);
-        do_launch(command, app, launch_mode)?;
+        info!(
+            "launch mode: {:?}, target: {:?}, game directory: {}, working directory: {:?}, env overrides: {:?}",
+            launch_mode,
+            target,
+            game_dir.display(),
+            command.get_current_dir(),
+            command.get_envs().collect::<Vec<_>>()
+        );
+        let profile_id = self.active_profile_id;
+        do_launch(command, self.game, profile_id, &game_dir, app, launch_mode)?;
+
+        if *target != LaunchTarget::Vanilla {
+            if let Some(path) = game_log::log_path(self.game, &self.active_profile().path) {
+                game_log::follow(path, app);
+            }
+        }
 
         Ok(())
     }
 
     fn launch_command(&self, game_dir: &Path, prefs: &Prefs) -> Result<(LaunchMode, Command)> {
-        let (launch_mode, mut platform, custom_args) = prefs
-            .game_prefs
-            .get(&*self.game.slug)
-            .map(|prefs| {
-                (
-                    prefs.launch_mode.clone(),
-                    prefs.platform,
-                    prefs.custom_args.as_ref(),
-                )
-            })
-            .unwrap_or_else(|| {
-                info!("game prefs not set, using default settings");
-                Default::default()
-            });
+        let (mut launch_mode, platform, mut custom_args) = self.launch_settings(prefs);
+        let target = self.launch_target(prefs);
 
-        // if the game has a platform but the setting is unset, fill it in
-        platform = platform.or_else(|| self.game.platforms.iter().next());
+        let steam_url = !matches!(target, LaunchTarget::Custom { .. })
+            && matches!(
+                (&launch_mode, platform),
+                (LaunchMode::Launcher, Some(Platform::Steam))
+            )
+            && platform::steam_launch(self.game, prefs) == SteamLaunch::Protocol;
 
-        let mut command = match (&launch_mode, platform) {
-            (LaunchMode::Launcher, Some(platform)) => {
+        let mut command = match (&target, &launch_mode, platform) {
+            (LaunchTarget::Custom { path, args }, _, _) => {
+                ensure!(path.exists(), "{} does not exist", path.display());
+
+                // the platform launchers only know how to start the game itself
+                if let LaunchMode::Launcher = launch_mode {
+                    launch_mode = LaunchMode::Direct {
+                        instances: 1,
+                        interval_secs: 0.0,
+                    };
+                }
+
+                custom_args = Some(args);
+
+                let mut command = self.exe_command(path, game_dir, prefs)?;
+                command.current_dir(game_dir);
+                Some(Ok(command))
+            }
+            (_, LaunchMode::Launcher, Some(platform)) => {
                 platform::launch_command(game_dir, platform, self.game, prefs).transpose()
             }
             _ => None,
         }
-        .unwrap_or_else(|| exe_path(game_dir).map(Command::new))?;
+        .unwrap_or_else(|| {
+            exe_path(game_dir).and_then(|exe| self.exe_command(&exe, game_dir, prefs))
+        })?;
 
         let profile = self.active_profile();
+        let overrides = &profile.launch_overrides;
 
-        mod_loader::add_args(&mut command, &profile.path, &self.game.mod_loader)?;
+        match target {
+            LaunchTarget::Vanilla => self
+                .game
+                .mod_loader
+                .add_vanilla_args(&mut command, &profile.path)?,
+            _ => self
+                .game
+                .mod_loader
+                .add_launch_args(&mut command, &profile.path)?,
+        }
 
         if let Some(custom_args) = custom_args {
-            command.args(custom_args);
+            if !overrides.replace_game_args {
+                command.args(custom_args);
+            }
+        }
+
+        command.args(&overrides.args);
+        command.envs(&overrides.env);
+
+        if steam_url {
+            if !overrides.env.is_empty() {
+                warn!("environment variables are not passed when launching through a Steam URL");
+            }
+
+            command = platform::steam_url_command(&command)?;
         }
 
         /*
@@ -97,6 +214,53 @@ impl ManagedGame {
         Ok((launch_mode, command))
     }
 
+    /// Builds a command running `exe` directly, through Proton if it's a
+    /// Windows executable on Linux.
+    #[allow(unused_variables)] // allow unused game_dir and prefs on other platforms
+    fn exe_command(&self, exe: &Path, game_dir: &Path, prefs: &Prefs) -> Result<Command> {
+        #[cfg(target_os = "linux")]
+        if exe.extension().is_some_and(|ext| ext == "exe") {
+            return linux::wine_command(exe, self.game, game_dir, prefs);
+        }
+
+        Ok(Command::new(exe))
+    }
+
+    fn launch_target<'a>(&self, prefs: &'a Prefs) -> &'a LaunchTarget {
+        static DEFAULT: LaunchTarget = LaunchTarget::Modded;
+
+        prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .map(|prefs| &prefs.launch_target)
+            .unwrap_or(&DEFAULT)
+    }
+
+    fn launch_settings<'a>(
+        &self,
+        prefs: &'a Prefs,
+    ) -> (LaunchMode, Option<Platform>, Option<&'a Vec<String>>) {
+        let (launch_mode, platform, custom_args) = prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .map(|prefs| {
+                (
+                    prefs.launch_mode.clone(),
+                    prefs.platform,
+                    prefs.custom_args.as_ref(),
+                )
+            })
+            .unwrap_or_else(|| {
+                info!("game prefs not set, using default settings");
+                Default::default()
+            });
+
+        // if the game has a platform but the setting is unset, fill it in
+        let platform = platform.or_else(|| self.game.platforms.iter().next());
+
+        (launch_mode, platform, custom_args)
+    }
+
     fn link_files(&self, game_dir: &Path) -> Result<()> {
         const EXCLUDES: [&str; 2] = ["profile.json", "mods.yml"];
 
@@ -136,10 +300,31 @@ impl ManagedGame {
     }
 }
 
-fn do_launch(mut command: Command, app: &AppHandle, mode: LaunchMode) -> Result<()> {
+fn do_launch(
+    mut command: Command,
+    game: Game,
+    profile_id: i64,
+    game_dir: &Path,
+    app: &AppHandle,
+    mode: LaunchMode,
+) -> Result<()> {
     match mode {
-        LaunchMode::Launcher | LaunchMode::Direct { instances: 1, .. } => {
+        LaunchMode::Launcher => {
             command.spawn()?;
+
+            // the launcher starts the game by itself, so it has to be found afterwards
+            match exe_path(game_dir) {
+                Ok(path) => monitor::watch_by_name(
+                    path.file_name().unwrap().to_string_lossy().into_owned(),
+                    game,
+                    profile_id,
+                    app,
+                ),
+                Err(err) => warn!("not monitoring game: {:#}", err),
+            }
+        }
+        LaunchMode::Direct { instances: 1, .. } => {
+            monitor::watch_child(command.spawn()?, game, profile_id, app);
         }
         LaunchMode::Direct { instances: 0, .. } => bail!("instances must be greater than 0"),
         LaunchMode::Direct {
@@ -149,12 +334,13 @@ fn do_launch(mut command: Command, app: &AppHandle, mode: LaunchMode) -> Result<
             let app = app.clone();
             tauri::async_runtime::spawn(async move {
                 for i in 0..instances {
-                    if let Err(err) = command.spawn() {
-                        log_webview_err(
+                    match command.spawn() {
+                        Ok(child) => monitor::watch_child(child, game, profile_id, &app),
+                        Err(err) => log_webview_err(
                             "Failed to launch game",
                             eyre!("Launch command {} failed: {}.", i, err),
                             &app,
-                        );
+                        ),
                     }
                     tokio::time::sleep(Duration::from_secs_f32(interval_secs)).await;
                 }
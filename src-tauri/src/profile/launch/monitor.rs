@@ -0,0 +1,324 @@
+//! Keeping track of the game after it's launched.
+//!
+//! When the game is spawned directly, its process is watched until it exits.
+//! Launchers like Steam start the game themselves, so the process is looked up
+//! by the name of its executable instead, which means the exit code isn't known.
+//!
+//! Emits `game-started` and `game-exited` events with [`GameStarted`] and
+//! [`GameExited`] payloads. Each session is recorded with [`playtime`], and
+//! if the game exits with an error a crash report is collected, see [`crash`].
+
+use std::{
+    process::{Child, Command},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, ensure, Context, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use super::{crash, hooks, playtime};
+use crate::game::Game;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long to look for the game process after launching through a launcher.
+const FIND_TIMEOUT: Duration = Duration::from_secs(120);
+/// How long the game gets to close by itself before it's killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(windows)]
+pub(super) const NO_WINDOW: u32 = 0x08000000;
+
+/// The pids of the running game processes.
+static TRACKED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+/// The pids of processes closed by [`stop`], which aren't reported as crashes.
+static STOPPED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameStarted<'a> {
+    pub game: &'a str,
+    pub pid: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameExited<'a> {
+    pub game: &'a str,
+    /// Only known if the game was spawned directly.
+    pub exit_code: Option<i32>,
+    /// The signal that killed the game, on Unix. Only known if it was spawned directly.
+    pub signal: Option<i32>,
+    pub duration_secs: u64,
+}
+
+/// Whether any instance of a game launched by Gale is still running.
+pub fn is_running() -> bool {
+    !TRACKED.lock().unwrap().is_empty()
+}
+
+/// Asks every running game process to close, then kills the ones that are
+/// still running after [`STOP_TIMEOUT`].
+pub async fn stop() -> Result<()> {
+    let pids = TRACKED.lock().unwrap().clone();
+
+    if pids.is_empty() {
+        bail!("the game is not running");
+    }
+
+    STOPPED.lock().unwrap().extend(&pids);
+
+    for pid in &pids {
+        info!("asking process {} to close", pid);
+
+        if let Err(err) = terminate(*pid, false) {
+            warn!("failed to close process {}: {:#}", pid, err);
+        }
+    }
+
+    let asked = Instant::now();
+    let mut remaining = pids;
+
+    while !remaining.is_empty() && asked.elapsed() < STOP_TIMEOUT {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        let tracked = TRACKED.lock().unwrap();
+        remaining.retain(|pid| tracked.contains(pid));
+    }
+
+    for pid in remaining {
+        warn!("process {} did not close in time, killing it", pid);
+        terminate(pid, true).with_context(|| format!("failed to kill process {}", pid))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32, force: bool) -> Result<()> {
+    let signal = if force { "-KILL" } else { "-TERM" };
+    let status = Command::new("kill")
+        .arg(signal)
+        .arg(pid.to_string())
+        .status()?;
+
+    ensure!(status.success(), "kill exited with {}", status);
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn terminate(pid: u32, force: bool) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let mut command = Command::new("taskkill");
+    command
+        .creation_flags(NO_WINDOW)
+        .arg("/PID")
+        .arg(pid.to_string());
+
+    if force {
+        command.args(["/T", "/F"]);
+    }
+
+    let status = command.status()?;
+    ensure!(status.success(), "taskkill exited with {}", status);
+
+    Ok(())
+}
+
+/// Watches a game process that was spawned directly.
+pub fn watch_child(mut child: Child, game: Game, profile_id: i64, app: &AppHandle) {
+    let app = app.to_owned();
+
+    tauri::async_runtime::spawn(async move {
+        let pid = child.id();
+        let started = start(game, pid, &app);
+
+        let (exit_code, signal) = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break (status.code(), exit_signal(&status)),
+                Ok(None) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(err) => {
+                    warn!("failed to wait for game process: {:#}", err);
+                    break (None, None);
+                }
+            }
+        };
+
+        exit(game, profile_id, pid, exit_code, signal, started, &app);
+    });
+}
+
+/// Looks for a process running `exe_name` and watches it, for when the game
+/// was started by a launcher.
+pub fn watch_by_name(exe_name: String, game: Game, profile_id: i64, app: &AppHandle) {
+    let app = app.to_owned();
+
+    tauri::async_runtime::spawn(async move {
+        let searched = Instant::now();
+
+        let pid = loop {
+            if let Some(pid) = find_process(&exe_name) {
+                break pid;
+            }
+
+            if searched.elapsed() > FIND_TIMEOUT {
+                warn!("game process {} was not found, not monitoring it", exe_name);
+                return;
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+
+        let started = start(game, pid, &app);
+
+        while is_alive(pid) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        exit(game, profile_id, pid, None, None, started, &app);
+    });
+}
+
+/// The signal that killed a process, since it has no exit code then.
+#[cfg(unix)]
+fn exit_signal(status: &std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+fn start(game: Game, pid: u32, app: &AppHandle) -> Instant {
+    info!("{} started with pid {}", game.slug, pid);
+
+    TRACKED.lock().unwrap().push(pid);
+    app.emit(
+        "game-started",
+        GameStarted {
+            game: &game.slug,
+            pid,
+        },
+    )
+    .ok();
+
+    Instant::now()
+}
+
+fn exit(
+    game: Game,
+    profile_id: i64,
+    pid: u32,
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    started: Instant,
+    app: &AppHandle,
+) {
+    let duration = started.elapsed();
+    let duration_secs = duration.as_secs();
+    info!(
+        "{} exited with code {:?} (signal {:?}) after {}s",
+        game.slug, exit_code, signal, duration_secs
+    );
+
+    TRACKED.lock().unwrap().retain(|other| *other != pid);
+    app.emit(
+        "game-exited",
+        GameExited {
+            game: &game.slug,
+            exit_code,
+            signal,
+            duration_secs,
+        },
+    )
+    .ok();
+
+    playtime::record(game, profile_id, duration, app);
+    hooks::post_exit(game, profile_id, exit_code, app);
+
+    let stopped = {
+        let mut stopped = STOPPED.lock().unwrap();
+        let len = stopped.len();
+        stopped.retain(|other| *other != pid);
+        stopped.len() != len
+    };
+
+    // signals like SIGSEGV and SIGABRT are crashes too, unless the game was stopped
+    if (exit_code.is_some_and(|code| code != 0) || signal.is_some()) && !stopped {
+        crash::report(game, profile_id, exit_code, signal, duration_secs, app);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn find_process(exe_name: &str) -> Option<u32> {
+    use std::fs;
+
+    fs::read_dir("/proc")
+        .ok()?
+        .filter_map(Result::ok)
+        .find_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            let cmdline = fs::read(entry.path().join("cmdline")).ok()?;
+
+            // also check the first argument, for scripts and games running under wine
+            cmdline
+                .split(|byte| *byte == 0)
+                .take(2)
+                .any(|arg| {
+                    String::from_utf8_lossy(arg)
+                        .rsplit(['/', '\\'])
+                        .next()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(exe_name))
+                })
+                .then_some(pid)
+        })
+}
+
+#[cfg(target_os = "linux")]
+fn is_alive(pid: u32) -> bool {
+    std::path::Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(windows)]
+fn find_process(exe_name: &str) -> Option<u32> {
+    tasklist(&format!("IMAGENAME eq {}", exe_name))
+}
+
+#[cfg(windows)]
+fn is_alive(pid: u32) -> bool {
+    tasklist(&format!("PID eq {}", pid)).is_some()
+}
+
+/// Returns the pid of the first process matching `filter`.
+#[cfg(windows)]
+fn tasklist(filter: &str) -> Option<u32> {
+    use std::os::windows::process::CommandExt;
+
+    let output = Command::new("tasklist")
+        .creation_flags(NO_WINDOW)
+        .args(["/FI", filter, "/NH", "/FO", "CSV"])
+        .output()
+        .ok()?;
+
+    // "Game.exe","1234","Console","1","100,000 K"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.split("\",\"").nth(1)?.parse().ok())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn find_process(_exe_name: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
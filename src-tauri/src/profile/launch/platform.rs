@@ -4,11 +4,12 @@ use std::{
 };
 
 use eyre::{bail, ensure, Context, OptionExt, Result};
+use itertools::Itertools;
 use tracing::{info, warn};
 use which::which;
 
 use crate::{
-    game::{Game, Platform},
+    game::{Game, Platform, SteamLaunch},
     prefs::Prefs,
 };
 
@@ -54,7 +55,61 @@ fn steam_command(game_dir: &Path, game: Game, prefs: &Prefs) -> Result<Command>
     Ok(command)
 }
 
-fn find_steam_binary() -> Result<PathBuf> {
+/// How `game` is started through Steam, either from the prefs or `games.json`.
+pub fn steam_launch(game: Game, prefs: &Prefs) -> SteamLaunch {
+    prefs
+        .game_prefs
+        .get(&*game.slug)
+        .and_then(|prefs| prefs.steam_launch)
+        .or_else(|| game.platforms.steam.as_ref().map(|steam| steam.launch))
+        .unwrap_or_default()
+}
+
+/// Turns a `steam -applaunch` command into one that opens the equivalent
+/// `steam://run` URL, with the rest of the arguments encoded into it.
+pub fn steam_url_command(command: &Command) -> Result<Command> {
+    let mut args = command.get_args().map(|arg| arg.to_string_lossy());
+
+    ensure!(
+        args.next().as_deref() == Some("-applaunch"),
+        "expected a Steam applaunch command"
+    );
+    let app_id = args
+        .next()
+        .ok_or_eyre("Steam command is missing the app id")?;
+
+    let url = format!(
+        "steam://run/{}//{}/",
+        app_id,
+        args.map(|arg| encode_steam_arg(&arg)).join("%20")
+    );
+
+    info!("launching from Steam with URL {}", url);
+
+    open::commands(url)
+        .into_iter()
+        .next()
+        .ok_or_eyre("open returned no commands to try")
+}
+
+/// Percent-encodes `arg` for a `steam://run` URL, quoting it if it contains spaces.
+fn encode_steam_arg(arg: &str) -> String {
+    let arg = match arg.contains(' ') {
+        true => format!("\"{}\"", arg),
+        false => arg.to_owned(),
+    };
+
+    arg.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+pub fn find_steam_binary() -> Result<PathBuf> {
     let path = which("steam").unwrap_or_else(|_| {
         #[cfg(target_os = "windows")]
         match read_steam_registry() {
@@ -132,9 +187,14 @@ fn steam_game_dir(game: Game) -> Result<PathBuf> {
         bail!("{} is not available on Steam", game.slug);
     };
 
+    steam_app_dir(steam.id)
+}
+
+/// Finds where the Steam app with `id` is installed.
+pub fn steam_app_dir(id: u32) -> Result<PathBuf> {
     let steam_dir = steamlocate::SteamDir::locate().context("failed to find steam install")?;
     let (app, lib) = steam_dir
-        .find_app(steam.id)?
+        .find_app(id)?
         .ok_or_eyre("failed to find app in steam registry")?;
 
     Ok(lib.resolve_app_dir(&app))
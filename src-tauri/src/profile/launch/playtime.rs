@@ -0,0 +1,107 @@
+//! Recording how long the game runs with each profile.
+//!
+//! A session is saved every time a game launched by Gale exits, and deleted
+//! together with its profile.
+
+use std::{cmp::Reverse, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::warn;
+
+use crate::{db::Db, game::Game, profile::ModManager, state::ManagerExt};
+
+/// Sessions started within this long count as recent.
+const RECENT: TimeDelta = TimeDelta::days(14);
+
+/// The sum of a profile's sessions, as stored in the database.
+pub struct PlaytimeTotals {
+    pub game_slug: String,
+    pub profile_id: i64,
+    pub total_secs: u64,
+    pub recent_secs: u64,
+    pub sessions: u64,
+    pub last_played: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePlaytime {
+    pub id: i64,
+    pub name: String,
+    pub total_secs: u64,
+    pub recent_secs: u64,
+    pub sessions: u64,
+    pub last_played: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlaytime {
+    pub game: String,
+    pub total_secs: u64,
+    pub recent_secs: u64,
+    /// The existing profiles that have been played, most played first.
+    pub profiles: Vec<ProfilePlaytime>,
+}
+
+/// Saves a finished session of `profile_id`.
+pub fn record(game: Game, profile_id: i64, duration: Duration, app: &AppHandle) {
+    let started_at = Utc::now() - duration;
+
+    let result =
+        app.db()
+            .insert_play_session(profile_id, &game.slug, started_at, duration.as_secs());
+
+    if let Err(err) = result {
+        warn!("failed to record play session: {:#}", err);
+    }
+}
+
+/// The playtime of every game with at least one session, most played first.
+pub fn stats(manager: &ModManager, db: &Db) -> Result<Vec<GamePlaytime>> {
+    let totals = db.get_playtime_totals(Utc::now() - RECENT)?;
+
+    let mut games = totals
+        .into_iter()
+        .into_group_map_by(|totals| totals.game_slug.clone())
+        .into_iter()
+        .map(|(game, totals)| {
+            let mut profiles = totals
+                .iter()
+                .filter_map(|totals| {
+                    let profile = manager
+                        .games
+                        .values()
+                        .flat_map(|game| &game.profiles)
+                        .find(|profile| profile.id == totals.profile_id)?;
+
+                    Some(ProfilePlaytime {
+                        id: profile.id,
+                        name: profile.name.clone(),
+                        total_secs: totals.total_secs,
+                        recent_secs: totals.recent_secs,
+                        sessions: totals.sessions,
+                        last_played: totals.last_played,
+                    })
+                })
+                .collect_vec();
+
+            profiles.sort_by_key(|profile| Reverse(profile.total_secs));
+
+            GamePlaytime {
+                game,
+                total_secs: totals.iter().map(|totals| totals.total_secs).sum(),
+                recent_secs: totals.iter().map(|totals| totals.recent_secs).sum(),
+                profiles,
+            }
+        })
+        .collect_vec();
+
+    games.sort_by_key(|game| Reverse(game.total_secs));
+
+    Ok(games)
+}
@@ -0,0 +1,262 @@
+//! Backups of the game's save files, taken before launching with a different
+//! set of mods than last time, since that's when saves usually get corrupted.
+//!
+//! Backups are copies of the save directory, stored in the `save_backups`
+//! directory of the game, since every profile shares the same saves. For the
+//! same reason, the mods of the last launch are fingerprinted per game.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use eyre::{bail, ensure, OptionExt, Result};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    prefs::Prefs,
+    profile::{integrity, ManagedGame},
+    util::{
+        self,
+        error::IoResultExt,
+        fs::{Overwrite, UseLinks},
+    },
+};
+
+/// The number of backups kept per game, the oldest ones are removed first.
+const MAX_BACKUPS: usize = 5;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S%.3f";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveBackup {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+pub fn backup_dir(game: &ManagedGame) -> PathBuf {
+    game.path.join("save_backups")
+}
+
+fn fingerprint_path(game: &ManagedGame) -> PathBuf {
+    game.path.join("last_launch.hash")
+}
+
+/// Finds the directory the game keeps its saves in.
+///
+/// This is either set in the game prefs, or derived from the `savePath` in
+/// `games.json`, which is relative to the Windows user directory.
+pub fn save_dir(game: &ManagedGame, prefs: &Prefs) -> Result<PathBuf> {
+    let game_prefs = prefs.game_prefs.get(&*game.game.slug);
+
+    if let Some(path) = game_prefs.and_then(|prefs| prefs.save_dir_override.as_ref()) {
+        return Ok(path.clone());
+    }
+
+    let Some(save_path) = game.game.save_path else {
+        bail!(
+            "the save location of {} is unknown, please set it in the settings",
+            game.game.name
+        );
+    };
+
+    user_dir(game, prefs).map(|dir| dir.join(save_path))
+}
+
+#[cfg(target_os = "windows")]
+fn user_dir(_game: &ManagedGame, _prefs: &Prefs) -> Result<PathBuf> {
+    dirs_next::home_dir().ok_or_eyre("could not find home directory")
+}
+
+#[cfg(target_os = "linux")]
+fn user_dir(game: &ManagedGame, prefs: &Prefs) -> Result<PathBuf> {
+    let steam = game
+        .game
+        .platforms
+        .steam
+        .as_ref()
+        .ok_or_eyre("saves can only be found for Steam games running through Proton")?;
+
+    let game_dir = super::game_dir(game.game, prefs)?;
+    let prefix = super::linux::wine_prefix(steam.id as u64, &game_dir)
+        .ok_or_eyre("game directory is not in a steam library")?;
+
+    Ok(prefix.join("drive_c").join("users").join("steamuser"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn user_dir(game: &ManagedGame, _prefs: &Prefs) -> Result<PathBuf> {
+    bail!(
+        "the save location of {} is unknown on this platform, please set it in the settings",
+        game.game.name
+    )
+}
+
+/// A save backup to take before launching, see [`prepare_launch_backup`].
+pub struct LaunchBackup {
+    save_dir: PathBuf,
+    backup_dir: PathBuf,
+    fingerprint_path: PathBuf,
+    fingerprint: blake3::Hash,
+}
+
+/// Checks whether the saves should be backed up before launching, which is
+/// when it's enabled in the game prefs and the active profile's mods are
+/// different from the last launch.
+///
+/// This only reads the state, so the copy can be made with
+/// [`LaunchBackup::run`] after the locks are released.
+pub fn prepare_launch_backup(game: &ManagedGame, prefs: &Prefs) -> Result<Option<LaunchBackup>> {
+    let enabled = prefs
+        .game_prefs
+        .get(&*game.game.slug)
+        .is_some_and(|prefs| prefs.backup_saves);
+
+    if !enabled {
+        return Ok(None);
+    }
+
+    let fingerprint = integrity::fingerprint(game.active_profile()).finalize();
+    let fingerprint_path = fingerprint_path(game);
+
+    if fs::read_to_string(&fingerprint_path).ok().as_deref() == Some(fingerprint.to_hex().as_str())
+    {
+        info!("mods are unchanged since the last launch, skipping save backup");
+        return Ok(None);
+    }
+
+    Ok(Some(LaunchBackup {
+        save_dir: save_dir(game, prefs)?,
+        backup_dir: backup_dir(game),
+        fingerprint_path,
+        fingerprint,
+    }))
+}
+
+impl LaunchBackup {
+    pub fn run(self) -> Result<()> {
+        if self.save_dir.exists() {
+            backup(&self.save_dir, &self.backup_dir)?;
+        } else {
+            info!("save directory does not exist yet, skipping backup");
+        }
+
+        fs::write(&self.fingerprint_path, self.fingerprint.to_hex().as_str())
+            .fs_context("writing mod fingerprint", &self.fingerprint_path)?;
+
+        Ok(())
+    }
+}
+
+/// Copies the contents of `save_dir` into a new backup in `backup_dir`, then
+/// removes the oldest backups past [`MAX_BACKUPS`].
+pub fn backup(save_dir: &Path, backup_dir: &Path) -> Result<()> {
+    ensure!(
+        save_dir.exists(),
+        "save directory does not exist (expected at {})",
+        save_dir.display()
+    );
+
+    let dest = backup_dir.join(Utc::now().format(TIMESTAMP_FORMAT).to_string());
+
+    let temp_dir = util::fs::temp_dir_in(backup_dir)?;
+    util::fs::copy_contents(save_dir, temp_dir.path(), Overwrite::Yes, UseLinks::No)?;
+    util::fs::persist_dir(temp_dir, &dest)?;
+
+    info!("backed up saves from {}", save_dir.display());
+
+    if let Err(err) = prune(backup_dir) {
+        warn!("failed to remove old save backups: {:#}", err);
+    }
+
+    Ok(())
+}
+
+/// Lists the save backups in `backup_dir`, newest first.
+pub fn list(backup_dir: &Path) -> Result<Vec<SaveBackup>> {
+    let mut backups = find(backup_dir)?
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_owned();
+            let created_at = NaiveDateTime::parse_from_str(&name, TIMESTAMP_FORMAT)
+                .ok()?
+                .and_utc();
+
+            Some(SaveBackup {
+                size: util::fs::get_directory_size(&path),
+                name,
+                created_at,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    backups.reverse();
+
+    Ok(backups)
+}
+
+/// Replaces the contents of `save_dir` with a backup.
+///
+/// The current saves are backed up first, so the restore can be undone the same way.
+pub fn restore(save_dir: &Path, backup_dir: &Path, name: &str) -> Result<()> {
+    let source = backup_dir.join(name);
+    ensure!(
+        NaiveDateTime::parse_from_str(name, TIMESTAMP_FORMAT).is_ok() && source.is_dir(),
+        "save backup {} not found",
+        name
+    );
+
+    // copy first, since taking the new backup might prune this one
+    let temp_dir = util::fs::temp_dir_for(save_dir)?;
+    util::fs::copy_contents(&source, temp_dir.path(), Overwrite::Yes, UseLinks::No)?;
+
+    if save_dir.exists() {
+        backup(save_dir, backup_dir)?;
+    }
+
+    util::fs::persist_dir(temp_dir, save_dir)?;
+
+    info!("restored saves from backup {}", name);
+
+    Ok(())
+}
+
+/// Lists the backup directories in `dir`, oldest first.
+fn find(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = dir
+        .read_dir()
+        .fs_context("reading save backups", dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_dir()))
+        .map(|entry| entry.path())
+        // skip leftovers from interrupted backups
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+
+    // directory names are timestamps, so they sort chronologically
+    paths.sort();
+
+    Ok(paths)
+}
+
+fn prune(dir: &Path) -> Result<()> {
+    let paths = find(dir)?;
+    let excess = paths.len().saturating_sub(MAX_BACKUPS);
+
+    for path in &paths[..excess] {
+        fs::remove_dir_all(path).fs_context("removing save backup", path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! Running a game's dedicated server with a profile's mods.
+//!
+//! Only games with a [`DedicatedServer`] in `games.json` have one. The server
+//! is started like a direct launch of the game, with the mod loader pointed at
+//! the profile, and is watched by [`monitor`] like the game.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::{ensure, eyre, Context, Result};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use super::{monitor, platform};
+use crate::{
+    game::{DedicatedServer, Game},
+    prefs::Prefs,
+    profile::{install, ManagedGame},
+    util::error::IoResultExt,
+};
+
+impl ManagedGame {
+    pub fn launch_server(&self, prefs: &Prefs, app: &AppHandle) -> Result<()> {
+        install::locked::retry_pending();
+
+        let mut command = self.server_command(prefs)?;
+        info!(
+            "launching {} server with command {:?}",
+            self.game.slug, command
+        );
+
+        let child = command.spawn().context("failed to start server")?;
+        monitor::watch_child(child, self.game, self.active_profile_id, app);
+
+        Ok(())
+    }
+
+    /// Writes a script to `path` that starts the server with the active profile,
+    /// for hosts that run it without Gale.
+    pub fn export_server_script(&self, path: &Path, prefs: &Prefs) -> Result<()> {
+        let command = self.server_command(prefs)?;
+
+        fs::write(path, script(&command)).fs_context("writing server script", path)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+                .fs_context("making server script executable", path)?;
+        }
+
+        Ok(())
+    }
+
+    fn server_command(&self, prefs: &Prefs) -> Result<Command> {
+        let server = self
+            .game
+            .dedicated_server
+            .as_ref()
+            .ok_or_else(|| eyre!("{} has no dedicated server", self.game.name))?;
+
+        let server_dir = server_dir(self.game, server, prefs)?;
+        let exe = server
+            .exe_names
+            .iter()
+            .map(|name| server_dir.join(name))
+            .find(|path| path.exists())
+            .ok_or_else(|| eyre!("server executable not found in {}", server_dir.display()))?;
+
+        if let Err(err) = self.link_files(&server_dir) {
+            warn!("failed to link files: {:#}", err);
+        }
+
+        let profile = self.active_profile();
+
+        let mut command = self.exe_command(&exe, &server_dir, prefs)?;
+        command.current_dir(&server_dir);
+
+        self.game
+            .mod_loader
+            .add_launch_args(&mut command, &profile.path)?;
+
+        command.args(&server.args);
+
+        let server_args = prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .and_then(|prefs| prefs.server_args.as_ref());
+
+        if let Some(args) = server_args {
+            command.args(args);
+        }
+
+        // servers of Steam games need to know which game they belong to
+        if let Some(steam) = &self.game.platforms.steam {
+            command.env("SteamAppId", steam.id.to_string());
+        }
+
+        command.envs(&profile.launch_overrides.env);
+
+        Ok(command)
+    }
+}
+
+fn server_dir(game: Game, server: &DedicatedServer, prefs: &Prefs) -> Result<PathBuf> {
+    let dir_override = prefs
+        .game_prefs
+        .get(&*game.slug)
+        .and_then(|prefs| prefs.server_dir.as_ref());
+
+    let path = match (dir_override, server.steam_id) {
+        (Some(path), _) => path.clone(),
+        (None, Some(id)) => platform::steam_app_dir(id)
+            .context("failed to find the server, please set its location in the settings")?,
+        (None, None) => super::game_dir(game, prefs)?,
+    };
+
+    ensure!(
+        path.exists(),
+        "server directory does not exist, please check your settings (expected at {})",
+        path.display()
+    );
+
+    Ok(path)
+}
+
+#[cfg(windows)]
+fn script(command: &Command) -> String {
+    let quote = |str: &str| format!("\"{}\"", str.replace('"', "\"\""));
+
+    let mut script = String::from("@echo off\r\n");
+
+    if let Some(dir) = command.get_current_dir() {
+        write!(script, "cd /d {}\r\n", quote(&dir.to_string_lossy())).unwrap();
+    }
+
+    for (key, value) in command.get_envs() {
+        if let Some(value) = value {
+            let var = format!("{}={}", key.to_string_lossy(), value.to_string_lossy());
+            write!(script, "set {}\r\n", quote(&var)).unwrap();
+        }
+    }
+
+    script.push_str(&quote(&command.get_program().to_string_lossy()));
+    for arg in command.get_args() {
+        write!(script, " {}", quote(&arg.to_string_lossy())).unwrap();
+    }
+    script.push_str("\r\n");
+
+    script
+}
+
+#[cfg(not(windows))]
+fn script(command: &Command) -> String {
+    let quote = |str: &str| format!("'{}'", str.replace('\'', "'\\''"));
+
+    let mut script = String::from("#!/bin/sh\n");
+
+    if let Some(dir) = command.get_current_dir() {
+        writeln!(script, "cd {}", quote(&dir.to_string_lossy())).unwrap();
+    }
+
+    for (key, value) in command.get_envs() {
+        if let Some(value) = value {
+            writeln!(
+                script,
+                "export {}={}",
+                key.to_string_lossy(),
+                quote(&value.to_string_lossy())
+            )
+            .unwrap();
+        }
+    }
+
+    script.push_str("exec ");
+    script.push_str(&quote(&command.get_program().to_string_lossy()));
+    for arg in command.get_args() {
+        write!(script, " {}", quote(&arg.to_string_lossy())).unwrap();
+    }
+    script.push('\n');
+
+    script
+}
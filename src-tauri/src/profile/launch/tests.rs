@@ -0,0 +1,141 @@
+use std::{fs, io::Write};
+
+use uuid::Uuid;
+
+use super::{
+    game_log::{LogLevel, LogLine, Reader},
+    log_analysis::{analyze_text, ModNames},
+};
+
+#[test]
+fn parse_bepinex_line() {
+    let line = LogLine::parse("[Error  : MyMod] Something failed", None);
+
+    assert_eq!(line.level, LogLevel::Error);
+    assert_eq!(line.source.as_deref(), Some("MyMod"));
+    assert_eq!(line.text, "[Error  : MyMod] Something failed");
+}
+
+#[test]
+fn parse_melon_loader_line() {
+    let line = LogLine::parse("[12:34:56.789] [MyMod] Loaded", None);
+    assert_eq!(line.level, LogLevel::Info);
+    assert_eq!(line.source.as_deref(), Some("MyMod"));
+
+    let line = LogLine::parse("[12:34:56.789] [Warning] [MyMod] Careful", None);
+    assert_eq!(line.level, LogLevel::Warning);
+    assert_eq!(line.source.as_deref(), Some("MyMod"));
+}
+
+#[test]
+fn parse_continues_previous_line() {
+    let previous = LogLine::parse("[Error  : MyMod] NullReferenceException", None);
+
+    let line = LogLine::parse("  at MyMod.Plugin.Awake ()", Some(&previous));
+    assert_eq!(line.level, LogLevel::Error);
+    assert_eq!(line.source.as_deref(), Some("MyMod"));
+
+    let line = LogLine::parse("  at MyMod.Plugin.Awake ()", None);
+    assert_eq!(line.level, LogLevel::default());
+    assert_eq!(line.source, None);
+}
+
+fn texts(lines: Vec<LogLine>) -> Vec<String> {
+    lines.into_iter().map(|line| line.text).collect()
+}
+
+#[test]
+fn reader_skips_old_log_and_waits_for_full_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("LogOutput.log");
+    fs::write(&path, "[Info   : Old] last session\n").unwrap();
+
+    let mut reader = Reader::new(path.clone());
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+
+    // ends in the middle of the two bytes of 'é'
+    file.write_all(b"[Info   : New] caf\xC3").unwrap();
+    assert!(reader.read().is_empty());
+
+    file.write_all(b"\xA9\n[Info   : New] sec").unwrap();
+    assert_eq!(texts(reader.read()), ["[Info   : New] café"]);
+
+    file.write_all(b"ond\n").unwrap();
+    assert_eq!(texts(reader.read()), ["[Info   : New] second"]);
+}
+
+#[test]
+fn reader_restarts_on_new_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("LogOutput.log");
+    fs::write(&path, "[Info   : BepInEx] started at 10:00\n").unwrap();
+
+    let mut reader = Reader::new(path.clone());
+
+    // truncated
+    fs::write(&path, "[Info   : A] 1\n").unwrap();
+    assert_eq!(texts(reader.read()), ["[Info   : A] 1"]);
+
+    // replaced by a longer log before the next read
+    fs::write(&path, "[Info   : B] started at 10:05\n[Info   : B] 2\n").unwrap();
+    assert_eq!(
+        texts(reader.read()),
+        ["[Info   : B] started at 10:05", "[Info   : B] 2"]
+    );
+}
+
+fn mod_names(full_name: &str, assemblies: &[&str]) -> ModNames {
+    ModNames {
+        uuid: Uuid::new_v4(),
+        full_name: full_name.to_owned(),
+        name: full_name.split('-').nth(1).unwrap().to_owned(),
+        assemblies: assemblies.iter().map(|name| name.to_string()).collect(),
+    }
+}
+
+#[test]
+fn analysis_attributes_by_source_and_stack_trace() {
+    let mods = [
+        mod_names("Author-Cool_Mod", &[]),
+        mod_names("Other-Utils", &["OtherLib"]),
+        mod_names("BepInEx-BepInExPack", &["BepInEx"]),
+    ];
+
+    let log = "\
+[Warning: Cool Mod] first warning
+[Error  : Cool Mod] first error
+[Warning: Cool Mod] warning after an error
+[Error  : Unity Log] NullReferenceException
+  at OtherLib.Patches.Prefix () [0x00000]
+[Error  : BepInEx] generic failure
+[Info   : Cool Mod] not an issue
+";
+
+    let analysis = analyze_text(log, &mods);
+
+    assert_eq!(analysis.mods.len(), 2);
+    assert_eq!(analysis.unattributed_errors, 1);
+    assert_eq!(analysis.unattributed_warnings, 0);
+
+    let cool_mod = &analysis.mods[0];
+    assert_eq!(cool_mod.full_name, "Author-Cool_Mod");
+    assert_eq!((cool_mod.errors, cool_mod.warnings), (1, 2));
+    assert_eq!(cool_mod.examples, ["[Error  : Cool Mod] first error"]);
+
+    let utils = &analysis.mods[1];
+    assert_eq!(utils.full_name, "Other-Utils");
+    assert_eq!((utils.errors, utils.warnings), (1, 0));
+}
+
+#[test]
+fn analysis_prefers_package_names_over_assemblies() {
+    let mods = [
+        mod_names("Author-Library", &["Helper"]),
+        mod_names("Other-Helper", &[]),
+    ];
+
+    let analysis = analyze_text("[Error  : Helper] failed\n", &mods);
+
+    assert_eq!(analysis.mods.len(), 1);
+    assert_eq!(analysis.mods[0].full_name, "Other-Helper");
+}
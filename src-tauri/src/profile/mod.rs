@@ -7,6 +7,7 @@ use std::{
 use chrono::{DateTime, Utc};
 use export::modpack::ModpackArgs;
 use eyre::{anyhow, ensure, eyre, Context, ContextCompat, OptionExt, Result};
+use install::github::GithubMod;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
@@ -16,23 +17,36 @@ use uuid::Uuid;
 use crate::{
     config::ConfigCache,
     db::{self, Db},
-    game::{self, Game, ModLoader},
+    game::{self, Game, GameModLoader},
+    nexus::NexusMod,
     prefs::Prefs,
     state::ManagerExt,
     thunderstore::{self, BorrowedMod, ModId, Thunderstore, VersionIdent},
     util::fs::PathExt,
 };
 
+pub mod auto_snapshot;
 pub mod commands;
+pub mod disk_usage;
 pub mod export;
+pub mod health;
 pub mod import;
 pub mod install;
+pub mod integrity;
 pub mod launch;
+pub mod orphans;
+pub mod reconcile;
 pub mod sync;
+pub mod template;
+pub mod undo;
 pub mod update;
+pub mod verify;
 
 mod actions;
 mod query;
+mod runtime_data;
+
+pub use runtime_data::RuntimeDataOverrides;
 
 pub fn setup(data: db::SaveData, prefs: &Prefs, db: &Db, app: &AppHandle) -> Result<ModManager> {
     actions::setup(app)?;
@@ -60,6 +74,10 @@ pub struct ManagedGame {
     pub profiles: Vec<Profile>,
     pub favorite: bool,
     pub active_profile_id: i64,
+    /// Packages linked into whichever profile is launched, see [`install::tools`].
+    pub tools: Vec<install::tools::Tool>,
+    /// Names of the groups profiles can be sorted into, in the order they're shown.
+    pub profile_groups: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -74,6 +92,10 @@ pub struct Profile {
     pub linked_config: HashMap<Uuid, PathBuf>,
     pub modpack: Option<ModpackArgs>,
     pub sync_profile: Option<sync::SyncProfileData>,
+    pub runtime_data: RuntimeDataOverrides,
+    /// The name of the group the profile is in, one of [`ManagedGame::profile_groups`].
+    pub group: Option<String>,
+    pub launch_overrides: launch::LaunchOverrides,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -84,6 +106,24 @@ pub struct ProfileMod {
     #[serde(default = "Utc::now")]
     pub install_time: DateTime<Utc>,
 
+    /// User-defined tags, used to group and toggle mods together.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// A free-text note written by the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    /// Whether the mod is excluded from updates, until unpinned.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+
+    /// The files installed by the mod, relative to the profile directory.
+    ///
+    /// Empty for mods installed before these were recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
+
     #[serde(flatten)]
     pub kind: ProfileModKind,
 }
@@ -92,6 +132,9 @@ pub struct ProfileMod {
 #[serde(rename_all = "camelCase", untagged)]
 pub enum ProfileModKind {
     Thunderstore(ThunderstoreMod),
+    // must come before Local, since untagged deserialization picks the first match
+    Nexus(Box<NexusMod>),
+    Github(Box<GithubMod>),
     // Box to decrease size of enum, since this variant is rare and much larger
     Local(Box<LocalMod>),
 }
@@ -106,12 +149,25 @@ pub struct ThunderstoreMod {
     pub id: ModId,
 }
 
+/// What the user attached to a mod, which is carried over when it's
+/// replaced by another version.
+#[derive(Debug, Clone, Default)]
+pub struct ModUserData {
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+}
+
 impl ProfileMod {
     fn new(kind: ProfileModKind) -> Self {
         Self {
             kind,
             install_time: Utc::now(),
             enabled: true,
+            tags: Vec::new(),
+            note: None,
+            pinned: false,
+            files: Vec::new(),
         }
     }
 
@@ -126,6 +182,26 @@ impl ProfileMod {
         Self::new(ProfileModKind::Local(Box::new(local_mod)))
     }
 
+    fn with_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = files;
+        self
+    }
+
+    fn with_user_data(mut self, data: ModUserData) -> Self {
+        self.tags = data.tags;
+        self.note = data.note;
+        self.pinned = data.pinned;
+        self
+    }
+
+    pub fn user_data(&self) -> ModUserData {
+        ModUserData {
+            tags: self.tags.clone(),
+            note: self.note.clone(),
+            pinned: self.pinned,
+        }
+    }
+
     /// See [`ProfileModKind::uuid`]
     pub fn uuid(&self) -> Uuid {
         self.kind.uuid()
@@ -149,6 +225,18 @@ impl ProfileMod {
         self.kind.as_local().map(|local| (local, self.enabled))
     }
 
+    fn as_nexus(&self) -> Option<(&NexusMod, bool)> {
+        self.kind
+            .as_nexus()
+            .map(|nexus_mod| (nexus_mod, self.enabled))
+    }
+
+    fn as_github(&self) -> Option<(&GithubMod, bool)> {
+        self.kind
+            .as_github()
+            .map(|github_mod| (github_mod, self.enabled))
+    }
+
     /// Finds all dependencies of this mod.
     ///
     /// See [`Thunderstore::dependencies`] for more information.
@@ -165,6 +253,8 @@ impl ProfileModKind {
     pub fn uuid(&self) -> Uuid {
         match self {
             ProfileModKind::Local(local_mod) => local_mod.uuid,
+            ProfileModKind::Nexus(nexus_mod) => nexus_mod.uuid,
+            ProfileModKind::Github(github_mod) => github_mod.uuid,
             ProfileModKind::Thunderstore(ts_mod) => ts_mod.id.package_uuid,
         }
     }
@@ -173,6 +263,8 @@ impl ProfileModKind {
         match self {
             ProfileModKind::Thunderstore(ts_mod) => Cow::Borrowed(&ts_mod.ident),
             ProfileModKind::Local(local_mod) => Cow::Owned(local_mod.ident()),
+            ProfileModKind::Nexus(nexus_mod) => Cow::Owned(nexus_mod.ident()),
+            ProfileModKind::Github(github_mod) => Cow::Owned(github_mod.ident()),
         }
     }
 
@@ -197,6 +289,20 @@ impl ProfileModKind {
         }
     }
 
+    pub fn as_nexus(&self) -> Option<&NexusMod> {
+        match self {
+            ProfileModKind::Nexus(nexus_mod) => Some(nexus_mod),
+            _ => None,
+        }
+    }
+
+    pub fn as_github(&self) -> Option<&GithubMod> {
+        match self {
+            ProfileModKind::Github(github_mod) => Some(github_mod),
+            _ => None,
+        }
+    }
+
     /// Finds all dependencies of this mod.
     ///
     /// See [`Thunderstore::dependencies`] for more information.
@@ -206,6 +312,7 @@ impl ProfileModKind {
     ) -> impl Iterator<Item = BorrowedMod<'a>> {
         let idents = match self {
             ProfileModKind::Local(local_mod) => local_mod.dependencies.as_ref(),
+            ProfileModKind::Nexus(_) | ProfileModKind::Github(_) => None,
             ProfileModKind::Thunderstore(ts_mod) => ts_mod
                 .id
                 .borrow(thunderstore)
@@ -261,6 +368,14 @@ impl Profile {
         self.mods.iter().filter_map(ProfileMod::as_local)
     }
 
+    fn nexus_mods(&self) -> impl Iterator<Item = (&NexusMod, bool)> {
+        self.mods.iter().filter_map(ProfileMod::as_nexus)
+    }
+
+    fn github_mods(&self) -> impl Iterator<Item = (&GithubMod, bool)> {
+        self.mods.iter().filter_map(ProfileMod::as_github)
+    }
+
     /// Finds all the dependants of a mod in this profile.
     ///
     /// This includes both direct and indirect dependencies.
@@ -310,11 +425,20 @@ pub struct LocalMod {
     pub icon: Option<PathBuf>,
     pub author: Option<String>,
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
     pub version: Option<semver::Version>,
     pub dependencies: Option<Vec<VersionIdent>>,
     pub uuid: Uuid,
     #[serde(default)]
     pub file_size: u64,
+    /// Where to check for new versions, if set by the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<update::local::LocalModSource>,
+    /// The newest version found during the last update check,
+    /// if it's newer than the installed one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_update: Option<update::local::LocalModUpdate>,
 }
 
 impl LocalMod {
@@ -368,21 +492,21 @@ impl ManagedGame {
             .ok_or_else(|| anyhow!("profile index {} is out of bounds", index))
     }
 
-    fn find_profile(&self, id: i64) -> Result<&Profile> {
+    pub fn find_profile(&self, id: i64) -> Result<&Profile> {
         self.profiles
             .iter()
             .find(|profile| profile.id == id)
             .with_context(|| format!("profile with id {} not found", id))
     }
 
-    fn find_profile_mut(&mut self, id: i64) -> Result<&mut Profile> {
+    pub fn find_profile_mut(&mut self, id: i64) -> Result<&mut Profile> {
         self.profiles
             .iter_mut()
             .find(|profile| profile.id == id)
             .with_context(|| format!("profile with id {} not found", id))
     }
 
-    fn active_profile(&self) -> &Profile {
+    pub fn active_profile(&self) -> &Profile {
         self.find_profile(self.active_profile_id).unwrap()
     }
 
@@ -495,6 +619,9 @@ impl ModManager {
                 config_cache: ConfigCache::default(),
                 linked_config: HashMap::new(),
                 sync_profile: saved_profile.sync_data,
+                runtime_data: saved_profile.runtime_data.unwrap_or_default(),
+                group: saved_profile.group,
+                launch_overrides: saved_profile.launch_overrides.unwrap_or_default(),
             };
 
             manager
@@ -509,7 +636,7 @@ impl ModManager {
         Ok(manager)
     }
 
-    pub fn active_mod_loader(&self) -> &'static ModLoader<'static> {
+    pub fn active_mod_loader(&self) -> &'static GameModLoader<'static> {
         &self.active_game.mod_loader
     }
 
@@ -533,6 +660,27 @@ impl ModManager {
         self.active_game_mut().active_profile_mut()
     }
 
+    /// Finds a profile by id across all games.
+    pub fn find_profile(&self, id: i64) -> Result<&Profile> {
+        self.games
+            .values()
+            .flat_map(|game| game.profiles.iter())
+            .find(|profile| profile.id == id)
+            .with_context(|| format!("profile with id {} not found", id))
+    }
+
+    /// Finds a profile by id across all games.
+    ///
+    /// Prefer this over [`ModManager::active_profile_mut`] for long-running tasks,
+    /// since the user is free to switch profiles while they're in progress.
+    pub fn find_profile_mut(&mut self, id: i64) -> Result<&mut Profile> {
+        self.games
+            .values_mut()
+            .flat_map(|game| game.profiles.iter_mut())
+            .find(|profile| profile.id == id)
+            .with_context(|| format!("profile with id {} not found", id))
+    }
+
     pub fn set_active_game(&mut self, game: Game, app: &AppHandle) -> Result<&ManagedGame> {
         self.ensure_game(game, true, &app.lock_prefs(), app.db())?;
 
@@ -590,6 +738,8 @@ impl ModManager {
             profiles: Vec::new(),
             favorite: false,
             active_profile_id: 0,
+            tools: Vec::new(),
+            profile_groups: Vec::new(),
         };
 
         if let Err(err) = managed.create_default_profile(db) {
@@ -623,6 +773,8 @@ impl ModManager {
             favorite: saved_game.favorite,
             active_profile_id: saved_game.active_profile_id,
             path: base_path.join(&*game.slug),
+            tools: saved_game.tools.unwrap_or_default(),
+            profile_groups: saved_game.profile_groups.unwrap_or_default(),
         };
 
         self.games.insert(game, managed_game);
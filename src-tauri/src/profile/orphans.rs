@@ -0,0 +1,210 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use super::{
+    export,
+    install::{cache, tools},
+    ModManager, ProfileModKind, RuntimeDataOverrides,
+};
+use crate::{prefs::Prefs, util};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedFile {
+    /// The path of the file, relative to the profile directory.
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Which files in a profile are accounted for, collected while the manager is locked.
+pub struct OrphanTargets {
+    root: PathBuf,
+    config_dir: Option<PathBuf>,
+    runtime_data: RuntimeDataOverrides,
+    /// Files recorded for mods when they were installed.
+    files: HashSet<PathBuf>,
+    /// Cached packages, which have the same layout as the profile.
+    cache_paths: Vec<PathBuf>,
+    /// Directories named after mods, relative to the profile.
+    mod_dirs: Vec<PathBuf>,
+}
+
+impl OrphanTargets {
+    pub fn for_active_profile(prefs: &Prefs, manager: &ModManager) -> Self {
+        let mod_loader = manager.active_mod_loader();
+        let profile = manager.active_profile();
+
+        let files = profile
+            .mods
+            .iter()
+            .flat_map(|profile_mod| profile_mod.files.iter().cloned())
+            .chain(tools::linked_files(profile))
+            .collect();
+
+        // mods installed before their files were recorded have an empty manifest,
+        // so fall back to the layout of their cached archive instead
+        let archive_paths = profile
+            .mods
+            .iter()
+            .filter(|profile_mod| profile_mod.files.is_empty())
+            .filter_map(|profile_mod| match &profile_mod.kind {
+                ProfileModKind::Nexus(nexus_mod) => Some(nexus_mod.cache_path(prefs)),
+                ProfileModKind::Github(github_mod) => Some(github_mod.cache_path(prefs)),
+                _ => None,
+            });
+
+        let cache_paths = profile
+            .thunderstore_mods()
+            .map(|(ts_mod, _)| cache::path(&ts_mod.ident, prefs))
+            .chain(archive_paths)
+            .collect();
+
+        let mod_dirs = profile
+            .mods
+            .iter()
+            .filter_map(|profile_mod| {
+                let full_name = profile_mod.full_name();
+
+                mod_loader
+                    .installer_for(&full_name)
+                    .mod_dir(&full_name, profile)
+            })
+            .filter_map(|dir| dir.strip_prefix(&profile.path).ok().map(Path::to_path_buf))
+            .collect();
+
+        let config_path = mod_loader.config_path();
+
+        Self {
+            root: profile.path.clone(),
+            // some loaders keep their config in the profile root
+            config_dir: (!config_path.as_os_str().is_empty()).then_some(config_path),
+            runtime_data: profile.runtime_data.clone(),
+            files,
+            cache_paths,
+            mod_dirs,
+        }
+    }
+
+    /// Whether a file, relative to the profile, belongs to a mod or is otherwise expected.
+    fn is_claimed(&self, relative_path: &Path, cached: &HashSet<PathBuf>) -> bool {
+        let enabled_path = strip_old_ext(relative_path);
+
+        if self.files.contains(&enabled_path) || cached.contains(&enabled_path) {
+            return true;
+        }
+
+        if self
+            .mod_dirs
+            .iter()
+            .any(|dir| relative_path.starts_with(dir))
+        {
+            return true;
+        }
+
+        let is_config = match &self.config_dir {
+            Some(dir) => relative_path.starts_with(dir),
+            None => export::has_config_extension(relative_path),
+        };
+
+        is_config || export::is_generated(relative_path) || self.runtime_data.matches(relative_path)
+    }
+}
+
+/// Finds files in the profile that no mod installed and that aren't config,
+/// runtime data or generated by Gale.
+///
+/// This reads through the cache, so avoid calling it on the main thread.
+pub fn find(targets: &OrphanTargets) -> Result<Vec<OrphanedFile>> {
+    let mut cached = HashSet::new();
+
+    for cache_path in &targets.cache_paths {
+        if !cache_path.exists() {
+            continue;
+        }
+
+        for entry in WalkDir::new(cache_path) {
+            let entry = entry?;
+
+            if entry.file_type().is_file() {
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(cache_path)
+                    .expect("WalkDir should only return paths inside of the root");
+
+                cached.insert(relative_path.to_path_buf());
+            }
+        }
+    }
+
+    let mut orphans = Vec::new();
+
+    for entry in WalkDir::new(&targets.root) {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(&targets.root)
+            .expect("WalkDir should only return paths inside of the root");
+
+        if targets.is_claimed(relative_path, &cached) {
+            continue;
+        }
+
+        orphans.push(OrphanedFile {
+            path: relative_path.to_path_buf(),
+            size: entry.metadata()?.len(),
+        });
+    }
+
+    info!("found {} orphaned files", orphans.len());
+
+    Ok(orphans)
+}
+
+/// Moves the given files to the trash (unless permanent deletion is enabled),
+/// as long as they're still orphaned.
+///
+/// Returns the number of removed files.
+pub fn remove(paths: &[PathBuf], targets: &OrphanTargets) -> Result<usize> {
+    let orphans = find(targets)?
+        .into_iter()
+        .map(|orphan| orphan.path)
+        .collect::<HashSet<_>>();
+
+    let mut removed = 0;
+
+    for path in paths {
+        if !orphans.contains(path) {
+            warn!("{} is no longer orphaned, skipping", path.display());
+            continue;
+        }
+
+        util::fs::delete(targets.root.join(path))?;
+        removed += 1;
+    }
+
+    info!("removed {} orphaned files", removed);
+
+    Ok(removed)
+}
+
+fn strip_old_ext(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+
+    while path.extension().is_some_and(|ext| ext == "old") {
+        path.set_extension("");
+    }
+
+    path
+}
@@ -4,15 +4,24 @@ use chrono::{DateTime, Utc};
 use eyre::Result;
 use tracing::warn;
 
-use super::{Dependant, LocalMod, Profile, ProfileMod, ProfileModKind};
-use crate::thunderstore::{
-    self,
-    query::{QueryModsArgs, Queryable, SortBy, SortOrder},
-    BorrowedMod, FrontendProfileMod, IntoFrontendMod, Thunderstore,
+use super::{
+    install::github::GithubMod, Dependant, LocalMod, Profile, ProfileMod, ProfileModKind,
+    ThunderstoreMod,
+};
+use crate::{
+    nexus::NexusMod,
+    thunderstore::{
+        self,
+        query::{QueryModsArgs, Queryable, SortBy, SortOrder},
+        BorrowedMod, FrontendProfileMod, IntoFrontendMod, Thunderstore,
+    },
 };
 
 struct QueryableProfileMod<'a> {
     enabled: bool,
+    tags: &'a [String],
+    note: Option<&'a str>,
+    pinned: bool,
     install_time: DateTime<Utc>,
     kind: QueryableProfileModKind<'a>,
     index: usize,
@@ -20,7 +29,11 @@ struct QueryableProfileMod<'a> {
 
 enum QueryableProfileModKind<'a> {
     Local(&'a LocalMod),
+    Nexus(&'a NexusMod),
+    Github(&'a GithubMod),
     Thunderstore(BorrowedMod<'a>),
+    /// A Thunderstore mod that is no longer listed, likely because it was removed.
+    Orphaned(&'a ThunderstoreMod),
 }
 
 impl<'a> QueryableProfileMod<'a> {
@@ -31,14 +44,23 @@ impl<'a> QueryableProfileMod<'a> {
     ) -> Result<QueryableProfileMod<'a>> {
         let kind = match &profile_mod.kind {
             ProfileModKind::Local(local) => QueryableProfileModKind::Local(local),
-            ProfileModKind::Thunderstore(ts_mod) => {
-                let borrow = ts_mod.id.borrow(thunderstore)?;
-                QueryableProfileModKind::Thunderstore(borrow)
-            }
+            ProfileModKind::Nexus(nexus_mod) => QueryableProfileModKind::Nexus(nexus_mod),
+            ProfileModKind::Github(github_mod) => QueryableProfileModKind::Github(github_mod),
+            ProfileModKind::Thunderstore(ts_mod) => match ts_mod.id.borrow(thunderstore) {
+                Ok(borrow) => QueryableProfileModKind::Thunderstore(borrow),
+                // until the packages are fetched, any mod could be missing
+                Err(_) if thunderstore.packages_fetched() => {
+                    QueryableProfileModKind::Orphaned(ts_mod)
+                }
+                Err(err) => return Err(err),
+            },
         };
 
         Ok(QueryableProfileMod {
             enabled: profile_mod.enabled,
+            tags: &profile_mod.tags,
+            note: profile_mod.note.as_deref(),
+            pinned: profile_mod.pinned,
             install_time: profile_mod.install_time,
             kind,
             index,
@@ -52,7 +74,10 @@ impl Queryable for QueryableProfileMod<'_> {
 
         match &self.kind {
             Kind::Local(local) => &local.name,
+            Kind::Nexus(nexus_mod) => &nexus_mod.name,
+            Kind::Github(github_mod) => &github_mod.repo,
             Kind::Thunderstore(remote) => remote.package.ident.as_str(),
+            Kind::Orphaned(ts_mod) => ts_mod.ident.full_name(),
         }
     }
 
@@ -69,7 +94,10 @@ impl Queryable for QueryableProfileMod<'_> {
 
         match &self.kind {
             Kind::Local(local) => local.matches(args),
+            Kind::Nexus(nexus_mod) => nexus_mod.matches(args),
+            Kind::Github(github_mod) => github_mod.matches(args),
             Kind::Thunderstore(remote) => remote.matches(args),
+            Kind::Orphaned(ts_mod) => ts_mod.matches(args),
         }
     }
 
@@ -92,8 +120,17 @@ impl Queryable for QueryableProfileMod<'_> {
         match (&self.kind, &other.kind) {
             (Kind::Thunderstore(a), Kind::Thunderstore(b)) => a.cmp(b, args),
             (Kind::Local(a), Kind::Local(b)) => a.cmp(b, args),
+            (Kind::Nexus(a), Kind::Nexus(b)) => a.cmp(b, args),
+            (Kind::Github(a), Kind::Github(b)) => a.cmp(b, args),
+            (Kind::Orphaned(a), Kind::Orphaned(b)) => a.cmp(b, args),
             (Kind::Local(_), _) => Ordering::Less,
             (_, Kind::Local(_)) => Ordering::Greater,
+            (Kind::Nexus(_), _) => Ordering::Less,
+            (_, Kind::Nexus(_)) => Ordering::Greater,
+            (Kind::Github(_), _) => Ordering::Less,
+            (_, Kind::Github(_)) => Ordering::Greater,
+            (Kind::Orphaned(_), _) => Ordering::Less,
+            (_, Kind::Orphaned(_)) => Ordering::Greater,
         }
     }
 }
@@ -127,17 +164,32 @@ impl Profile {
 
         let found = thunderstore::query::query_mods(args, mods)
             .map(|queryable| {
+                let orphaned = matches!(queryable.kind, QueryableProfileModKind::Orphaned(_));
+
                 let (data, uuid) = match queryable.kind {
                     QueryableProfileModKind::Local(local) => (local.clone().into(), local.uuid),
+                    QueryableProfileModKind::Nexus(nexus_mod) => {
+                        (nexus_mod.clone().into(), nexus_mod.uuid)
+                    }
+                    QueryableProfileModKind::Github(github_mod) => {
+                        (github_mod.clone().into(), github_mod.uuid)
+                    }
                     QueryableProfileModKind::Thunderstore(remote) => {
                         (remote.into_frontend(Some(self)), remote.package.uuid)
                     }
+                    QueryableProfileModKind::Orphaned(ts_mod) => {
+                        (ts_mod.clone().into(), ts_mod.id.package_uuid)
+                    }
                 };
 
                 FrontendProfileMod {
                     data,
                     enabled: queryable.enabled,
                     config_file: self.linked_config.get(&uuid).cloned(),
+                    tags: queryable.tags.to_vec(),
+                    note: queryable.note.map(str::to_owned),
+                    pinned: queryable.pinned,
+                    orphaned,
                 }
             })
             .collect();
@@ -177,3 +229,80 @@ impl Queryable for LocalMod {
         }
     }
 }
+
+impl Queryable for ThunderstoreMod {
+    fn full_name(&self) -> &str {
+        self.ident.full_name()
+    }
+
+    fn matches(&self, _args: &QueryModsArgs) -> bool {
+        true
+    }
+
+    fn cmp(&self, other: &Self, args: &QueryModsArgs) -> Ordering {
+        let order = match args.sort_by {
+            SortBy::Name => other.ident.name().cmp(self.ident.name()),
+            SortBy::Author => other.ident.owner().cmp(self.ident.owner()),
+            _ => Ordering::Equal,
+        };
+
+        match args.sort_order {
+            SortOrder::Ascending => order,
+            SortOrder::Descending => order.reverse(),
+        }
+    }
+}
+
+impl Queryable for NexusMod {
+    fn full_name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn matches(&self, _args: &QueryModsArgs) -> bool {
+        true
+    }
+
+    fn cmp(&self, other: &Self, args: &QueryModsArgs) -> Ordering {
+        let order = match args.sort_by {
+            SortBy::Name => other.name.cmp(&self.name),
+            SortBy::Author => other.author.cmp(&self.author),
+            _ => Ordering::Equal,
+        };
+
+        match args.sort_order {
+            SortOrder::Ascending => order,
+            SortOrder::Descending => order.reverse(),
+        }
+    }
+}
+
+impl Queryable for GithubMod {
+    fn full_name(&self) -> &str {
+        &self.repo
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn matches(&self, _args: &QueryModsArgs) -> bool {
+        true
+    }
+
+    fn cmp(&self, other: &Self, args: &QueryModsArgs) -> Ordering {
+        let order = match args.sort_by {
+            SortBy::Name => other.repo.cmp(&self.repo),
+            SortBy::Author => other.owner.cmp(&self.owner),
+            _ => Ordering::Equal,
+        };
+
+        match args.sort_order {
+            SortOrder::Ascending => order,
+            SortOrder::Descending => order.reverse(),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Bringing a profile in line with a lockfile: the exact mod versions it should
+//! have, in the format of an exported profile's manifest.
+//!
+//! Nothing here depends on Tauri: planning only looks at the profile and the
+//! Thunderstore data, and applying the plan goes through a [`Host`], which
+//! provides the app state and installs the mods. That way, reconciling can be
+//! driven headlessly, like from the CLI, a server or tests.
+//!
+//! Only Thunderstore mods are reconciled, since those are the only ones a
+//! lockfile can describe. Local and other mods are left as they are.
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    future::Future,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use eyre::{Context, Result};
+use tracing::info;
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use super::{
+    export::{ProfileManifest, R2Mod},
+    install::ModInstall,
+    Profile,
+};
+use crate::{thunderstore::Thunderstore, util::error::IoResultExt};
+
+/// The app state a plan is made from and applied to.
+pub trait Host {
+    /// Calls `f` with the active profile and the Thunderstore index, once it's loaded.
+    fn read<R>(&self, f: impl FnOnce(&Profile, &Thunderstore) -> R) -> impl Future<Output = R>;
+
+    /// Installs mods into the active profile, replacing any other version of them.
+    fn install(&self, mods: Vec<ModInstall>) -> impl Future<Output = Result<()>>;
+
+    /// Calls `f` with the active profile, then saves it.
+    fn update(&self, f: impl FnOnce(&mut Profile) -> Result<()>) -> Result<()>;
+}
+
+#[derive(Debug, Default)]
+pub struct Plan {
+    /// Mods to install, or to change to a different version.
+    pub install: Vec<ModInstall>,
+    /// Installed mods that aren't in the lockfile.
+    pub remove: Vec<Uuid>,
+    /// Installed mods with the right version, but the wrong enabled state.
+    pub toggle: Vec<Uuid>,
+    /// Entries of the lockfile that couldn't be found on Thunderstore.
+    /// Installed versions of them are kept.
+    pub missing: Vec<String>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.install.is_empty() && self.remove.is_empty() && self.toggle.is_empty()
+    }
+}
+
+/// Reads the mods of a lockfile, either a manifest or an exported profile containing one.
+pub fn read_lockfile(path: &Path) -> Result<Vec<R2Mod>> {
+    let is_archive = path
+        .extension()
+        .is_some_and(|ext| ext == "r2z" || ext == "zip");
+
+    let file = File::open(path).fs_context("opening lockfile", path)?;
+
+    let manifest: ProfileManifest = if is_archive {
+        let mut archive =
+            ZipArchive::new(BufReader::new(file)).context("failed to read archive")?;
+
+        let mut yaml = String::new();
+        archive
+            .by_name("export.r2x")
+            .context("archive has no profile manifest")?
+            .read_to_string(&mut yaml)?;
+
+        serde_yaml::from_str(&yaml)
+    } else {
+        serde_yaml::from_reader(BufReader::new(file))
+    }
+    .context("failed to parse lockfile")?;
+
+    Ok(manifest.mods)
+}
+
+/// Works out what needs to change for `profile` to match `mods`.
+pub fn plan(profile: &Profile, mods: &[R2Mod], thunderstore: &Thunderstore) -> Plan {
+    let mut plan = Plan::default();
+    let mut wanted = HashSet::new();
+    // packages of unresolved entries, matched by name since their uuid is unknown
+    let mut wanted_names = HashSet::new();
+
+    for r2_mod in mods {
+        let Ok(id) = r2_mod.resolve(thunderstore) else {
+            plan.missing.push(r2_mod.ident().to_string());
+            wanted_names.insert(r2_mod.full_name.as_str());
+            continue;
+        };
+
+        wanted.insert(id.package_uuid);
+
+        let current = profile
+            .mods
+            .iter()
+            .enumerate()
+            .find(|(_, profile_mod)| profile_mod.uuid() == id.package_uuid);
+
+        let Some((index, profile_mod)) = current else {
+            plan.install
+                .push(ModInstall::new(id).with_state(r2_mod.enabled));
+            continue;
+        };
+
+        match profile_mod.as_thunderstore() {
+            Some((ts_mod, enabled)) if ts_mod.id.version_uuid == id.version_uuid => {
+                if enabled != r2_mod.enabled {
+                    plan.toggle.push(id.package_uuid);
+                }
+            }
+            _ => plan.install.push(
+                ModInstall::new(id)
+                    .with_state(r2_mod.enabled)
+                    .with_index(index)
+                    .with_time(profile_mod.install_time)
+                    .with_user_data(profile_mod.user_data()),
+            ),
+        }
+    }
+
+    plan.remove = profile
+        .thunderstore_mods()
+        .filter(|(ts_mod, _)| {
+            !wanted.contains(&ts_mod.id.package_uuid)
+                && !wanted_names.contains(ts_mod.ident.full_name())
+        })
+        .map(|(ts_mod, _)| ts_mod.id.package_uuid)
+        .collect();
+
+    plan
+}
+
+/// Plans and applies the changes needed for the active profile to match `mods`.
+///
+/// Returns the applied plan, with its installs already consumed.
+pub async fn reconcile(mods: &[R2Mod], host: &impl Host) -> Result<Plan> {
+    let mut plan = host
+        .read(|profile, thunderstore| plan(profile, mods, thunderstore))
+        .await;
+
+    if plan.is_empty() {
+        info!("profile already matches the lockfile");
+        return Ok(plan);
+    }
+
+    info!(
+        "reconciling profile: {} to install, {} to remove, {} to toggle, {} missing",
+        plan.install.len(),
+        plan.remove.len(),
+        plan.toggle.len(),
+        plan.missing.len()
+    );
+
+    // installed first, so the indices of changed versions stay correct
+    host.install(std::mem::take(&mut plan.install)).await?;
+
+    host.update(|profile| {
+        for uuid in &plan.remove {
+            profile.force_remove_mod(*uuid)?;
+        }
+
+        for uuid in &plan.toggle {
+            profile.force_toggle_mod(*uuid)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(plan)
+}
@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Paths, relative to the profile, that mod loaders and mods write to while the game runs.
+///
+/// These are regenerated on launch, so they're left out of exports and conflict checks.
+const DEFAULT_PATHS: &[&str] = &[
+    "BepInEx/cache",
+    "BepInEx/DumpedAssemblies",
+    "BepInEx/interop",
+    "BepInEx/unity-libs",
+    "BepInEx/LogOutput.log",
+    "MelonLoader/Il2CppAssemblies",
+    "MelonLoader/Logs",
+    "MelonLoader/Latest.log",
+];
+
+/// Per-profile changes to which paths count as runtime data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuntimeDataOverrides {
+    /// Additional paths to treat as runtime data.
+    pub extra: Vec<PathBuf>,
+    /// Default paths to treat as regular files instead.
+    pub keep: Vec<PathBuf>,
+}
+
+impl RuntimeDataOverrides {
+    /// Checks whether a path, relative to the profile, is runtime data.
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        if self.keep.iter().any(|path| relative_path.starts_with(path)) {
+            return false;
+        }
+
+        DEFAULT_PATHS
+            .iter()
+            .any(|path| relative_path.starts_with(path))
+            || self
+                .extra
+                .iter()
+                .any(|path| relative_path.starts_with(path))
+    }
+}
@@ -1,6 +1,9 @@
 use tauri::{command, AppHandle};
 
-use crate::{state::ManagerExt, util::cmd::Result};
+use crate::{
+    state::ManagerExt,
+    util::{self, cmd::Result},
+};
 
 use super::{auth, ListedSyncProfile, SyncProfileMetadata};
 
@@ -13,6 +16,8 @@ pub async fn read_sync_profile(id: String, app: AppHandle) -> Result<SyncProfile
 
 #[command]
 pub async fn create_sync_profile(app: AppHandle) -> Result<String> {
+    util::cancel::reset(&app);
+
     let id = super::create_profile(&app).await?;
 
     Ok(id)
@@ -20,6 +25,8 @@ pub async fn create_sync_profile(app: AppHandle) -> Result<String> {
 
 #[command]
 pub async fn push_sync_profile(app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     super::push_profile(&app).await?;
 
     Ok(())
@@ -27,6 +34,8 @@ pub async fn push_sync_profile(app: AppHandle) -> Result<()> {
 
 #[command]
 pub async fn clone_sync_profile(id: String, name: String, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     super::clone_profile(&id, name, &app).await?;
 
     Ok(())
@@ -48,6 +57,8 @@ pub async fn delete_sync_profile(id: String, app: AppHandle) -> Result<()> {
 
 #[command]
 pub async fn pull_sync_profile(app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     super::pull_profile(false, &app).await?;
 
     Ok(())
@@ -2,11 +2,12 @@ use std::{fmt::Display, io::Cursor};
 
 use chrono::{DateTime, Utc};
 use eyre::{bail, eyre, Context, OptionExt, Result};
-use reqwest::{Method, StatusCode};
+use reqwest::{header::CONTENT_LENGTH, Body, Method, StatusCode};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
+use tempfile::NamedTempFile;
 
-use crate::{profile::install::InstallOptions, state::ManagerExt};
+use crate::{profile::install::InstallOptions, state::ManagerExt, util};
 
 pub mod auth;
 pub mod commands;
@@ -84,26 +85,34 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
         bail!("not logged in");
     };
 
-    let bytes = {
-        let manager = app.lock_manager();
+    let temp_dir = app.lock_prefs().temp_dir();
+
+    let file = {
+        let manager = app.read_manager();
         let game = manager.active_game();
         let profile = game.active_profile();
 
-        let mut bytes = Cursor::new(Vec::new());
-        super::export::export_zip(profile, &mut bytes, game.game)
-            .context("failed to export profile")?;
-
-        bytes.into_inner()
+        super::export::export_zip_to_temp(profile, game.game, &temp_dir)
+            .context("failed to export profile")?
     };
 
-    let response: CreateSyncProfileResponse = request(Method::POST, "/profile", app)
-        .await
-        .body(bytes)
-        .send()
-        .await?
-        .error_for_status()?
-        .json()
-        .await?;
+    let (body, len) = file_body(&file).await?;
+
+    let response: CreateSyncProfileResponse = util::cancel::abortable(
+        async {
+            request(Method::POST, "/profile", app)
+                .await
+                .header(CONTENT_LENGTH, len)
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        },
+        app,
+    )
+    .await?;
 
     let id = response.id.clone();
 
@@ -125,8 +134,10 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
 }
 
 async fn push_profile(app: &AppHandle) -> Result<()> {
-    let (id, bytes) = {
-        let manager = app.lock_manager();
+    let temp_dir = app.lock_prefs().temp_dir();
+
+    let (id, file) = {
+        let manager = app.read_manager();
         let game = manager.active_game();
         let profile = game.active_profile();
 
@@ -136,21 +147,29 @@ async fn push_profile(app: &AppHandle) -> Result<()> {
             .map(|data| data.id.clone())
             .ok_or_eyre("profile is not synced")?;
 
-        let mut bytes = Cursor::new(Vec::new());
-        super::export::export_zip(profile, &mut bytes, game.game)
+        let file = super::export::export_zip_to_temp(profile, game.game, &temp_dir)
             .context("failed to export profile")?;
 
-        (id, bytes.into_inner())
+        (id, file)
     };
 
-    let response: CreateSyncProfileResponse = request(Method::PUT, format!("/profile/{id}"), app)
-        .await
-        .body(bytes)
-        .send()
-        .await?
-        .error_for_status()?
-        .json()
-        .await?;
+    let (body, len) = file_body(&file).await?;
+
+    let response: CreateSyncProfileResponse = util::cancel::abortable(
+        async {
+            request(Method::PUT, format!("/profile/{id}"), app)
+                .await
+                .header(CONTENT_LENGTH, len)
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        },
+        app,
+    )
+    .await?;
 
     {
         let mut manager = app.lock_manager();
@@ -166,6 +185,14 @@ async fn push_profile(app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Streams an exported profile from disk, instead of reading it into memory.
+async fn file_body(file: &NamedTempFile) -> Result<(Body, u64)> {
+    let file = tokio::fs::File::from_std(file.reopen().context("failed to open exported file")?);
+    let len = file.metadata().await?.len();
+
+    Ok((Body::from(file), len))
+}
+
 async fn disconnect_profile(delete: bool, app: &AppHandle) -> Result<()> {
     let (id, is_owner) = {
         let mut manager = app.lock_manager();
@@ -251,22 +278,34 @@ async fn download_and_import_file(
     app: &AppHandle,
 ) -> Result<()> {
     let path = format!("/profile/{}", sync_profile.id);
-    let bytes = request(Method::GET, path, app)
-        .await
-        .send()
-        .await?
-        .error_for_status()?
-        .bytes()
-        .await?;
+    let bytes = util::cancel::abortable(
+        async {
+            request(Method::GET, path, app)
+                .await
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await
+        },
+        app,
+    )
+    .await?;
 
     let mut data =
-        super::import::read_file(Cursor::new(bytes)).context("failed to import profile")?;
+        super::import::read_file(Cursor::new(bytes), app).context("failed to import profile")?;
 
     data.manifest.name = name.clone();
 
-    let index = super::import::import_profile(data, InstallOptions::default(), false, app)
-        .await
-        .context("failed to import profile")?;
+    let index = super::import::import_profile(
+        data,
+        InstallOptions::default(),
+        super::import::ImportOptions::default(),
+        app,
+    )
+    .await
+    .context("failed to import profile")?
+    .index;
 
     {
         let mut manager = app.lock_manager();
@@ -0,0 +1,131 @@
+//! Templates for creating near-identical profiles over and over, like for
+//! running several servers of the same modpack.
+//!
+//! A template is an exported profile stored in the database, with or without
+//! its config files. Creating a profile from one goes through the regular
+//! import flow.
+
+use std::{io::Cursor, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use super::{
+    export,
+    import::{self, ImportOptions},
+    install::InstallOptions,
+};
+use crate::state::ManagerExt;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileTemplate {
+    pub id: i64,
+    pub name: String,
+    pub include_config: bool,
+    /// Only Thunderstore mods are saved in a template, like in other exports.
+    pub thunderstore_mod_count: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Saves the active profile as a new template of the active game.
+pub fn save(name: String, include_config: bool, app: &AppHandle) -> Result<ProfileTemplate> {
+    if include_config {
+        app.lock_manager().active_profile_mut().refresh_config();
+    }
+
+    let (mut template, data, game, profile_name) = {
+        let manager = app.read_manager();
+
+        let game = manager.active_game().game;
+        let profile = manager.active_profile();
+
+        let mut data = Cursor::new(Vec::new());
+        match include_config {
+            true => export::export_zip(profile, &mut data, game)?,
+            false => export::export_mods_zip(profile, &mut data, game)?,
+        }
+
+        let template = ProfileTemplate {
+            id: 0,
+            name,
+            include_config,
+            thunderstore_mod_count: profile.thunderstore_mods().count(),
+            created_at: Utc::now(),
+        };
+
+        (template, data.into_inner(), game, profile.name.clone())
+    };
+
+    template.id = app.db().insert_template(&template, &game.slug, &data)?;
+
+    info!("saved {} as template {}", profile_name, template.name);
+
+    Ok(template)
+}
+
+/// Lists the templates of the active game.
+pub fn list(app: &AppHandle) -> Result<Vec<ProfileTemplate>> {
+    let game = app.read_manager().active_game().game;
+
+    app.db().get_templates(&game.slug)
+}
+
+/// Creates a new profile in the active game and installs the mods of a template into it.
+///
+/// If the import fails or is cancelled, the new profile is deleted again.
+pub async fn create_profile(
+    id: i64,
+    name: String,
+    override_path: Option<PathBuf>,
+    app: &AppHandle,
+) -> Result<()> {
+    let bytes = app.db().get_template_data(id)?;
+
+    let mut data = import::read_file(Cursor::new(bytes), app)?;
+    data.manifest.name.clone_from(&name);
+
+    let profile_id = {
+        let mut manager = app.lock_manager();
+        let game = manager.active_game_mut();
+
+        let profile = game.create_profile(name, override_path, app.db())?;
+        profile.save(app.db())?;
+        let id = profile.id;
+        game.save(app.db())?;
+
+        id
+    };
+
+    let result = import::import_profile(
+        data,
+        InstallOptions::default(),
+        ImportOptions::default(),
+        app,
+    )
+    .await;
+
+    if result.is_err() {
+        let mut manager = app.lock_manager();
+        let game = manager.active_game_mut();
+
+        // the import doesn't clean up after itself, since the profile already existed
+        if let Some(index) = game
+            .profiles
+            .iter()
+            .position(|profile| profile.id == profile_id)
+        {
+            game.delete_profile(index, true, app.db())
+                .unwrap_or_else(|err| {
+                    warn!("failed to delete profile of failed template: {:#}", err)
+                });
+        }
+    }
+
+    result.context("failed to install template")?;
+
+    Ok(())
+}
@@ -0,0 +1,287 @@
+//! Undo support for destructive profile actions, like removing mods or deleting config files.
+//!
+//! The files of removed mods are kept in a temporary directory next to the profile
+//! until the action is undone or pushed off the stack. Nothing is kept across restarts.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, OptionExt, Result};
+use tempfile::TempDir;
+use tracing::{debug, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{install, ModManager, Profile, ProfileMod};
+use crate::{
+    db::Db,
+    util::{self, error::IoResultExt, fs::PathExt},
+};
+
+/// How many actions are kept before the oldest ones are dropped.
+const MAX_DEPTH: usize = 10;
+
+#[derive(Default)]
+pub struct UndoStack(Vec<UndoEntry>);
+
+pub struct UndoEntry {
+    profile_id: i64,
+    description: String,
+    action: UndoAction,
+}
+
+enum UndoAction {
+    RemoveMods {
+        /// The removed mods along with their original index, in ascending order.
+        mods: Vec<(usize, ProfileMod)>,
+        /// The files that were removed, relative to the profile.
+        files: TempDir,
+    },
+    DeleteConfigFile {
+        relative_path: PathBuf,
+        contents: Vec<u8>,
+    },
+}
+
+impl UndoStack {
+    pub fn push(&mut self, entry: UndoEntry) {
+        debug!("pushing undo entry: {}", entry.description);
+
+        self.0.push(entry);
+
+        if self.0.len() > MAX_DEPTH {
+            self.0.remove(0);
+        }
+    }
+
+    /// Undoes the most recent action and returns its description.
+    pub fn undo(&mut self, manager: &mut ModManager, db: &Db) -> Result<String> {
+        let entry = self.0.pop().ok_or_eyre("nothing to undo")?;
+
+        let profile = manager
+            .find_profile_mut(entry.profile_id)
+            .context("the profile has since been deleted")?;
+
+        entry.action.undo(profile)?;
+        profile.save(db)?;
+
+        Ok(entry.description)
+    }
+}
+
+impl UndoAction {
+    fn undo(self, profile: &mut Profile) -> Result<()> {
+        match self {
+            UndoAction::RemoveMods { mods, files } => {
+                restore_files(files.path(), &profile.path)?;
+
+                for (index, profile_mod) in mods {
+                    // the mod could have been reinstalled since
+                    if profile.has_mod(profile_mod.uuid()) {
+                        continue;
+                    }
+
+                    let index = index.min(profile.mods.len());
+                    profile.mods.insert(index, profile_mod);
+                }
+            }
+            UndoAction::DeleteConfigFile {
+                relative_path,
+                contents,
+            } => {
+                let path = profile.path.join(relative_path);
+
+                fs::create_dir_all(path.parent().unwrap())?;
+                fs::write(&path, contents).fs_context("restoring config file", &path)?;
+
+                profile.refresh_config();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs an action that may remove the mods in `uuids` from `profile`, and records
+/// how to undo it.
+///
+/// Only the files of those mods are backed up beforehand.
+/// Returns `None` instead of an entry if no mods were removed.
+pub fn record_removal<T, F>(
+    profile: &mut Profile,
+    uuids: &[Uuid],
+    description: impl Into<String>,
+    action: F,
+) -> Result<(T, Option<UndoEntry>)>
+where
+    F: FnOnce(&mut Profile) -> Result<T>,
+{
+    let backup = util::fs::temp_dir_for(&profile.path)?;
+
+    for profile_mod in &profile.mods {
+        if uuids.contains(&profile_mod.uuid()) {
+            let files = mod_files(profile_mod, profile);
+            link_files(&files, &profile.path, backup.path())?;
+        }
+    }
+
+    let before = profile.mods.iter().cloned().enumerate().collect::<Vec<_>>();
+
+    let result = action(profile)?;
+
+    let removed = before
+        .into_iter()
+        .filter(|(_, profile_mod)| !profile.has_mod(profile_mod.uuid()))
+        .collect::<Vec<_>>();
+
+    if removed.is_empty() {
+        return Ok((result, None));
+    }
+
+    prune_unchanged(backup.path(), &profile.path)?;
+
+    let entry = UndoEntry {
+        profile_id: profile.id,
+        description: description.into(),
+        action: UndoAction::RemoveMods {
+            mods: removed,
+            files: backup,
+        },
+    };
+
+    Ok((result, Some(entry)))
+}
+
+/// Reads a config file before it's deleted, so the deletion can be undone.
+pub fn record_config_deletion(profile: &Profile, relative_path: &Path) -> Result<UndoEntry> {
+    let path = profile.path.join(relative_path);
+    let contents = fs::read(&path).fs_context("reading config file", &path)?;
+
+    Ok(UndoEntry {
+        profile_id: profile.id,
+        description: format!("delete {}", relative_path.display()),
+        action: UndoAction::DeleteConfigFile {
+            relative_path: relative_path.to_owned(),
+            contents,
+        },
+    })
+}
+
+/// The files that removing a mod may delete, relative to the profile.
+///
+/// Mods installed before their files were recorded in [`ProfileMod::files`] fall
+/// back to the files the installer knows about.
+fn mod_files(profile_mod: &ProfileMod, profile: &Profile) -> Vec<PathBuf> {
+    let package_name = profile_mod.full_name();
+
+    let mut files = profile_mod.files.clone();
+    files.extend(install::tracked_files(&package_name, profile));
+
+    let mod_dir = profile
+        .game
+        .mod_loader
+        .installer_for(&package_name)
+        .mod_dir(&package_name, profile);
+
+    if let Some(dir) = mod_dir {
+        files.extend(
+            WalkDir::new(&dir)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .filter_map(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(&profile.path)
+                        .ok()
+                        .map(Path::to_path_buf)
+                }),
+        );
+    }
+
+    // disabled files have an extra extension
+    let disabled = files
+        .iter()
+        .map(|file| {
+            let mut file = file.clone();
+            file.add_ext("old");
+            file
+        })
+        .collect::<Vec<_>>();
+
+    files.extend(disabled);
+    files
+}
+
+/// Hard links the given files from `src` into `dest`, which is cheap and keeps
+/// their contents around once the originals are deleted.
+fn link_files(files: &[PathBuf], src: &Path, dest: &Path) -> Result<()> {
+    for relative_path in files {
+        let path = src.join(relative_path);
+        let target = dest.join(relative_path);
+
+        if !path.is_file() || target.exists() {
+            continue;
+        }
+
+        fs::create_dir_all(target.parent().unwrap()).fs_context("creating directory", &target)?;
+
+        if fs::hard_link(&path, &target).is_err() {
+            fs::copy(&path, &target).fs_context("copying file", &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes files from the backup that still exist in the profile.
+fn prune_unchanged(backup: &Path, profile: &Path) -> Result<()> {
+    for entry in WalkDir::new(backup).contents_first(true).min_depth(1) {
+        let entry = entry?;
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(backup)
+            .expect("WalkDir should only return paths inside of the root");
+
+        if entry.file_type().is_dir() {
+            // only succeeds once the directory is empty
+            fs::remove_dir(entry.path()).ok();
+        } else if profile.join(relative_path).exists() {
+            fs::remove_file(entry.path()).fs_context("pruning backup", entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn restore_files(backup: &Path, profile: &Path) -> Result<()> {
+    for entry in WalkDir::new(backup).min_depth(1) {
+        let entry = entry?;
+
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(backup)
+            .expect("WalkDir should only return paths inside of the root");
+        let target = profile.join(relative_path);
+
+        if target.exists() {
+            warn!(
+                "not restoring {}, since it already exists",
+                relative_path.display()
+            );
+            continue;
+        }
+
+        fs::create_dir_all(target.parent().unwrap())?;
+        fs::rename(entry.path(), &target).fs_context("restoring file", &target)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Update checks for every profile of the active game, which run after each
+//! successful fetch of the Thunderstore package index.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::debug;
+
+use crate::{profile::ModManager, state::ManagerExt, thunderstore::Thunderstore};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileUpdateCount {
+    pub profile_id: i64,
+    pub count: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCounts {
+    pub game: String,
+    pub profiles: Vec<ProfileUpdateCount>,
+    pub total: usize,
+}
+
+/// Emits an `updates-available` event with the current [`UpdateCounts`].
+pub fn emit_counts(app: &AppHandle) {
+    let counts = {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        count_updates(&manager, &thunderstore)
+    };
+
+    debug!("{} updates available across all profiles", counts.total);
+    app.emit("updates-available", counts).ok();
+}
+
+/// Counts the available updates of each profile of the active game,
+/// excluding ignored updates and pinned mods.
+pub fn count_updates(manager: &ModManager, thunderstore: &Thunderstore) -> UpdateCounts {
+    let game = manager.active_game();
+
+    let profiles = game
+        .profiles
+        .iter()
+        .map(|profile| {
+            let count = profile
+                .mods
+                .iter()
+                .filter(|profile_mod| {
+                    profile
+                        .check_update(profile_mod.uuid(), true, thunderstore)
+                        .is_ok_and(|update| update.is_some())
+                })
+                .count();
+
+            ProfileUpdateCount {
+                profile_id: profile.id,
+                count,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    UpdateCounts {
+        game: game.game.slug.to_string(),
+        total: profiles.iter().map(|profile| profile.count).sum(),
+        profiles,
+    }
+}
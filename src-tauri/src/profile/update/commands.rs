@@ -1,22 +1,122 @@
+use chrono::{DateTime, Utc};
+use eyre::eyre;
 use tauri::{command, AppHandle};
 use uuid::Uuid;
 
-use crate::{state::ManagerExt, thunderstore::ModId, util::cmd::Result};
+use super::{background::UpdateCounts, local::LocalModSource, RollbackSummary};
+use crate::{
+    profile::{
+        auto_snapshot::{self, SnapshotReason},
+        install::github,
+        ProfileModKind,
+    },
+    state::ManagerExt,
+    thunderstore::ModId,
+    util::{self, cmd::Result},
+};
 
 #[command]
 pub async fn change_mod_version(mod_ref: ModId, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
     super::change_version(mod_ref, &app).await?;
 
     Ok(())
 }
 
+#[command]
+pub fn get_update_counts(app: AppHandle) -> UpdateCounts {
+    let manager = app.read_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    super::background::count_updates(&manager, &thunderstore)
+}
+
 #[command]
 pub async fn update_mods(uuids: Vec<Uuid>, respect_ignored: bool, app: AppHandle) -> Result<()> {
+    util::cancel::reset(&app);
+
+    {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let profile = manager.active_profile();
+        if !uuids
+            .iter()
+            .any(|uuid| profile.has_update(*uuid, respect_ignored, &thunderstore))
+        {
+            return Ok(());
+        }
+
+        auto_snapshot::capture(
+            manager.active_profile(),
+            SnapshotReason::Update,
+            &prefs,
+            &app,
+        )?;
+    }
+
     super::update_mods(uuids, respect_ignored, &app).await?;
 
     Ok(())
 }
 
+#[command]
+pub async fn roll_back_to_date(date: DateTime<Utc>, app: AppHandle) -> Result<RollbackSummary> {
+    util::cancel::reset(&app);
+
+    {
+        let prefs = app.lock_prefs();
+        let manager = app.read_manager();
+
+        auto_snapshot::capture(
+            manager.active_profile(),
+            SnapshotReason::Update,
+            &prefs,
+            &app,
+        )?;
+    }
+
+    let summary = super::roll_back_to_date(date, &app).await?;
+
+    Ok(summary)
+}
+
+#[command]
+pub async fn check_github_updates(app: AppHandle) -> Result<usize> {
+    let count = github::check_updates(&app).await?;
+
+    Ok(count)
+}
+
+#[command]
+pub async fn check_local_updates(app: AppHandle) -> Result<usize> {
+    let count = super::local::check_updates(&app).await?;
+
+    Ok(count)
+}
+
+#[command]
+pub fn set_local_mod_source(
+    uuid: Uuid,
+    source: Option<LocalModSource>,
+    app: AppHandle,
+) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    let ProfileModKind::Local(local_mod) = &mut profile.get_mod_mut(uuid)?.kind else {
+        return Err(eyre!("only local mods can have an update source").into());
+    };
+
+    local_mod.source = source;
+    local_mod.latest_update = None;
+    profile.save(app.db())?;
+
+    Ok(())
+}
+
 #[command]
 pub fn ignore_update(version_uuid: Uuid, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
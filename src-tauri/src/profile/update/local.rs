@@ -0,0 +1,246 @@
+//! Update checks for local mods, which have no index to check against.
+//!
+//! Instead, the user can point a local mod at a folder or URL where new versions
+//! of its zip are published. Only zips with a `manifest.json` are considered,
+//! since the version number is read from there.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tempfile::NamedTempFile;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    profile::{
+        import::{self, LocalModKind},
+        install::InstallOptions,
+        ProfileModKind,
+    },
+    state::ManagerExt,
+    thunderstore::PackageManifest,
+};
+
+/// Where to look for new versions of a local mod.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum LocalModSource {
+    /// A folder with one or more versions of the mod's zip.
+    Folder { path: PathBuf },
+    /// A URL which always serves the latest version of the mod's zip.
+    Url { url: String },
+}
+
+/// A newer version of a local mod, found by [`check_updates`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModUpdate {
+    pub version: semver::Version,
+    /// The path or URL of the new zip.
+    pub location: String,
+    /// A stable id for this version, used to ignore the update.
+    pub uuid: Uuid,
+}
+
+/// Checks the sources of the local mods in the active profile for newer versions.
+///
+/// Results are stored on each mod and picked up by `query_profile`.
+/// Returns the number of mods with an available update.
+pub async fn check_updates(app: &AppHandle) -> Result<usize> {
+    let mods = {
+        let manager = app.read_manager();
+        manager
+            .active_profile()
+            .local_mods()
+            .filter_map(|(local_mod, _)| {
+                let source = local_mod.source.clone()?;
+                Some((local_mod.uuid, local_mod.name.clone(), source))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if mods.is_empty() {
+        return Ok(0);
+    }
+
+    let mut results = Vec::with_capacity(mods.len());
+
+    for (uuid, name, source) in mods {
+        let result = match source {
+            LocalModSource::Folder { path } => {
+                let name = name.clone();
+                tauri::async_runtime::spawn_blocking(move || newest_in_folder(&path, &name))
+                    .await
+                    .map_err(eyre::Error::from)
+                    .and_then(|result| result)
+            }
+            LocalModSource::Url { url } => check_url(&url, app).await,
+        };
+
+        match result {
+            Ok(Some(found)) => results.push((uuid, found)),
+            Ok(None) => (),
+            Err(err) => warn!("failed to check for updates of {}: {:#}", name, err),
+        }
+    }
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let mut count = 0;
+
+    for (uuid, (version, location)) in results {
+        let Ok(profile_mod) = profile.get_mod_mut(uuid) else {
+            continue; // removed while checking
+        };
+
+        let ProfileModKind::Local(local_mod) = &mut profile_mod.kind else {
+            continue;
+        };
+
+        let is_newer = local_mod
+            .version
+            .as_ref()
+            .map_or(true, |current| version > *current);
+
+        if !is_newer {
+            local_mod.latest_update = None;
+            continue;
+        }
+
+        count += 1;
+
+        // keep the id stable between checks, so ignored updates stay ignored
+        let uuid = match &local_mod.latest_update {
+            Some(latest) if latest.version == version => latest.uuid,
+            _ => Uuid::new_v4(),
+        };
+
+        local_mod.latest_update = Some(LocalModUpdate {
+            version,
+            location,
+            uuid,
+        });
+    }
+
+    profile.save(app.db())?;
+
+    info!("found {} local mod updates", count);
+
+    Ok(count)
+}
+
+/// Updates the given local mods to the versions found by [`check_updates`].
+pub async fn update(uuids: Vec<Uuid>, respect_ignored: bool, app: &AppHandle) -> Result<()> {
+    let updates = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        uuids
+            .into_iter()
+            .filter_map(|uuid| {
+                let local_mod = profile.get_mod(uuid).ok()?.kind.as_local()?;
+                let latest = local_mod.latest_update.as_ref()?;
+
+                if respect_ignored && profile.ignored_updates.contains(&latest.uuid) {
+                    return None;
+                }
+
+                Some(latest.location.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for location in updates {
+        // re-importing replaces the existing version, keeping its place in the profile
+        let file = fetch_zip(&location, app).await?;
+
+        import::import_local_mod(
+            file.path().to_owned(),
+            Some(LocalModKind::Zip),
+            app,
+            InstallOptions::default().can_cancel(false),
+        )
+        .await
+        .with_context(|| format!("failed to install update from {}", location))?;
+    }
+
+    Ok(())
+}
+
+/// Finds the newest version of the mod `name` in `dir`.
+fn newest_in_folder(dir: &Path, name: &str) -> Result<Option<(semver::Version, String)>> {
+    let mut newest: Option<(semver::Version, String)> = None;
+
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+
+        if path.extension().map_or(true, |ext| ext != "zip") {
+            continue;
+        }
+
+        let manifest = match import::read_zip_manifest(&path) {
+            Ok(Some(manifest)) => manifest,
+            Ok(None) => continue,
+            Err(err) => {
+                warn!("failed to read manifest of {}: {:#}", path.display(), err);
+                continue;
+            }
+        };
+
+        if manifest.name != name {
+            continue;
+        }
+
+        let is_newer = newest
+            .as_ref()
+            .map_or(true, |(version, _)| manifest.version_number > *version);
+
+        if is_newer {
+            let location = path.to_str().ok_or_eyre("path is not valid UTF-8")?;
+            newest = Some((manifest.version_number, location.to_owned()));
+        }
+    }
+
+    Ok(newest)
+}
+
+async fn check_url(url: &str, app: &AppHandle) -> Result<Option<(semver::Version, String)>> {
+    let file = fetch_zip(url, app).await?;
+
+    let manifest: Option<PackageManifest> = import::read_zip_manifest(file.path())?;
+    let manifest = manifest.ok_or_eyre("zip has no manifest")?;
+
+    Ok(Some((manifest.version_number, url.to_owned())))
+}
+
+/// Copies or downloads a zip to a temporary file.
+async fn fetch_zip(location: &str, app: &AppHandle) -> Result<NamedTempFile> {
+    let temp_dir = app.lock_prefs().temp_dir();
+    fs::create_dir_all(&temp_dir).context("failed to create temp directory")?;
+
+    let mut file = NamedTempFile::new_in(temp_dir).context("failed to create temp file")?;
+
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let data = app
+            .http()
+            .get(location)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        file.write_all(&data).context("failed to write temp file")?;
+    } else {
+        fs::copy(location, file.path()).context("failed to copy zip")?;
+    }
+
+    Ok(file)
+}
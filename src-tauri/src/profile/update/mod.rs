@@ -1,16 +1,19 @@
 use chrono::{DateTime, Utc};
 use eyre::Context;
 use itertools::Itertools;
+use serde::Serialize;
 use uuid::Uuid;
 
 use super::install::{InstallOptions, ModInstall};
 use crate::{
-    profile::{install, Profile, Result},
+    profile::{install, ModUserData, Profile, ProfileModKind, Result},
     state::ManagerExt,
     thunderstore::{ModId, PackageListing, PackageVersion, Thunderstore},
 };
 
+pub mod background;
 pub mod commands;
+pub mod local;
 
 pub struct AvailableUpdate<'a> {
     pub enabled: bool,
@@ -19,6 +22,7 @@ pub struct AvailableUpdate<'a> {
     pub package: &'a PackageListing,
     pub current: &'a PackageVersion,
     pub latest: &'a PackageVersion,
+    pub user_data: ModUserData,
 }
 
 impl From<AvailableUpdate<'_>> for ModInstall {
@@ -32,6 +36,7 @@ impl From<AvailableUpdate<'_>> for ModInstall {
             .with_state(value.enabled)
             .with_index(value.index)
             .with_time(value.install_time)
+            .with_user_data(value.user_data)
     }
 }
 
@@ -45,6 +50,10 @@ impl Profile {
         let index = self.index_of(uuid)?;
         let profile_mod = &self.mods[index];
 
+        if profile_mod.pinned {
+            return Ok(None);
+        }
+
         let Some((ts_mod, _)) = profile_mod.as_thunderstore() else {
             return Ok(None); // local mods can't be updated
         };
@@ -74,41 +83,189 @@ impl Profile {
             latest: package.latest(),
             enabled: profile_mod.enabled,
             install_time: profile_mod.install_time,
+            user_data: profile_mod.user_data(),
         }))
     }
+
+    /// Whether [`update_mods`] would install a new version of the mod.
+    pub fn has_update(
+        &self,
+        uuid: Uuid,
+        respect_ignored: bool,
+        thunderstore: &Thunderstore,
+    ) -> bool {
+        let Ok(profile_mod) = self.get_mod(uuid) else {
+            return false;
+        };
+
+        if profile_mod.pinned {
+            return false;
+        }
+
+        let latest_uuid = match &profile_mod.kind {
+            ProfileModKind::Nexus(nexus_mod) => nexus_mod.latest_file_uuid(),
+            ProfileModKind::Github(github_mod) => {
+                github_mod.latest_release.as_ref().map(|latest| latest.uuid)
+            }
+            ProfileModKind::Local(local_mod) => {
+                local_mod.latest_update.as_ref().map(|latest| latest.uuid)
+            }
+            _ => {
+                return self
+                    .check_update(uuid, respect_ignored, thunderstore)
+                    .is_ok_and(|update| update.is_some())
+            }
+        };
+
+        latest_uuid.is_some_and(|uuid| !respect_ignored || !self.ignored_updates.contains(&uuid))
+    }
 }
 
 pub async fn change_version(mod_ref: ModId, app: &tauri::AppHandle) -> Result<()> {
     let install = {
-        let manager = app.lock_manager();
+        let manager = app.read_manager();
 
         let profile = manager.active_profile();
 
         let index = profile.index_of(mod_ref.package_uuid)?;
-        let enabled = profile.mods[index].enabled;
-        let install_time = profile.mods[index].install_time;
+        let profile_mod = &profile.mods[index];
 
         ModInstall::new(mod_ref)
-            .with_state(enabled)
+            .with_state(profile_mod.enabled)
             .with_index(index)
-            .with_time(install_time)
+            .with_time(profile_mod.install_time)
+            .with_user_data(profile_mod.user_data())
     };
 
     _update_mods(vec![install], app).await
 }
 
+/// The result of [`roll_back_to_date`].
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackSummary {
+    /// The number of mods whose version was changed.
+    pub changed: usize,
+    /// Mods without any version published before the date, which were left as is.
+    pub missing: Vec<String>,
+}
+
+/// Changes every Thunderstore mod in the active profile to the newest
+/// version that was published before `date`. Pinned mods are left untouched.
+pub async fn roll_back_to_date(
+    date: DateTime<Utc>,
+    app: &tauri::AppHandle,
+) -> Result<RollbackSummary> {
+    let mut summary = RollbackSummary::default();
+
+    let installs = {
+        let manager = app.read_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let profile = manager.active_profile();
+        let mut installs = Vec::new();
+
+        for (index, profile_mod) in profile.mods.iter().enumerate() {
+            if profile_mod.pinned {
+                continue;
+            }
+
+            let Some((ts_mod, enabled)) = profile_mod.as_thunderstore() else {
+                continue;
+            };
+
+            let Ok(package) = thunderstore.get_package(ts_mod.id.package_uuid) else {
+                continue; // ignore missing mods
+            };
+
+            let Some(version) = package
+                .versions
+                .iter()
+                .filter(|version| version.date_created <= date)
+                .max_by_key(|version| version.date_created)
+            else {
+                summary.missing.push(package.ident.to_string());
+                continue;
+            };
+
+            if version.uuid == ts_mod.id.version_uuid {
+                continue;
+            }
+
+            let id = ModId {
+                package_uuid: package.uuid,
+                version_uuid: version.uuid,
+            };
+
+            installs.push(
+                ModInstall::new(id)
+                    .with_state(enabled)
+                    .with_index(index)
+                    .with_time(profile_mod.install_time)
+                    .with_user_data(profile_mod.user_data()),
+            );
+        }
+
+        installs
+    };
+
+    summary.changed = installs.len();
+
+    _update_mods(installs, app).await?;
+
+    Ok(summary)
+}
+
 pub async fn update_mods(
     uuids: Vec<Uuid>,
     respect_ignored: bool,
     app: &tauri::AppHandle,
 ) -> Result<()> {
+    let mut nexus_uuids = Vec::new();
+    let mut github_uuids = Vec::new();
+    let mut local_uuids = Vec::new();
+    let mut thunderstore_uuids = Vec::new();
+
+    {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        for uuid in uuids {
+            if profile
+                .get_mod(uuid)
+                .is_ok_and(|profile_mod| profile_mod.pinned)
+            {
+                continue;
+            }
+
+            match profile.get_mod(uuid).map(|profile_mod| &profile_mod.kind) {
+                Ok(ProfileModKind::Nexus(_)) => nexus_uuids.push(uuid),
+                Ok(ProfileModKind::Github(_)) => github_uuids.push(uuid),
+                Ok(ProfileModKind::Local(_)) => local_uuids.push(uuid),
+                _ => thunderstore_uuids.push(uuid),
+            }
+        }
+    }
+
+    if !nexus_uuids.is_empty() {
+        install::nexus::update(nexus_uuids, respect_ignored, app).await?;
+    }
+
+    if !github_uuids.is_empty() {
+        install::github::update(github_uuids, respect_ignored, app).await?;
+    }
+
+    if !local_uuids.is_empty() {
+        local::update(local_uuids, respect_ignored, app).await?;
+    }
+
     let installs = {
         let mut manager = app.lock_manager();
         let thunderstore = app.lock_thunderstore();
 
         let profile = manager.active_profile_mut();
 
-        uuids
+        thunderstore_uuids
             .into_iter()
             .filter_map(|uuid| {
                 profile
@@ -125,10 +282,8 @@ pub async fn update_mods(
 async fn _update_mods(installs: Vec<ModInstall>, app: &tauri::AppHandle) -> Result<()> {
     install::install_with_deps(
         installs,
-        InstallOptions::default().before_install(Box::new(|install, manager, _| {
+        InstallOptions::default().before_install(Box::new(|install, profile, _| {
             // remove the old version
-            let profile = manager.active_profile_mut();
-
             // check since it could be a new dependency being installed, not an update itself
             if profile.has_mod(install.uuid()) {
                 profile
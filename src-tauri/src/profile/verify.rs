@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Context, Result};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::info;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{
+    install::{self, cache, InstallOptions, ModInstall},
+    ModManager,
+};
+use crate::{prefs::Prefs, state::ManagerExt, util::fs::PathExt};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenMod {
+    pub uuid: Uuid,
+    pub full_name: String,
+    /// Files of the cached package that are missing from the profile.
+    ///
+    /// Empty if the package isn't cached and the mod's directory is missing entirely.
+    pub missing_files: Vec<PathBuf>,
+    /// Whether the mod can be reinstalled without downloading it again.
+    pub cached: bool,
+}
+
+struct ModTarget {
+    uuid: Uuid,
+    full_name: String,
+    cache_path: PathBuf,
+    mod_dir: Option<PathBuf>,
+}
+
+/// What to check in a profile, collected while the manager is locked.
+pub struct VerifyTargets {
+    root: PathBuf,
+    config_dir: Option<PathBuf>,
+    mods: Vec<ModTarget>,
+}
+
+impl VerifyTargets {
+    pub fn for_active_profile(prefs: &Prefs, manager: &ModManager) -> Self {
+        let mod_loader = manager.active_mod_loader();
+        let profile = manager.active_profile();
+
+        let mods = profile
+            .thunderstore_mods()
+            .map(|(ts_mod, _)| {
+                let full_name = ts_mod.ident.full_name();
+
+                ModTarget {
+                    uuid: ts_mod.id.package_uuid,
+                    full_name: full_name.to_owned(),
+                    cache_path: cache::path(&ts_mod.ident, prefs),
+                    mod_dir: mod_loader
+                        .installer_for(full_name)
+                        .mod_dir(full_name, profile),
+                }
+            })
+            .collect();
+
+        let config_path = mod_loader.config_path();
+
+        Self {
+            root: profile.path.clone(),
+            // some loaders keep their config in the profile root
+            config_dir: (!config_path.as_os_str().is_empty()).then_some(config_path),
+            mods,
+        }
+    }
+}
+
+/// Checks the files of each Thunderstore mod against its cached package.
+///
+/// Config files are skipped, since they're expected to be changed or removed.
+/// Mods that aren't cached can only be checked for a missing directory.
+///
+/// This reads through the cache, so avoid calling it on the main thread.
+pub fn verify(targets: VerifyTargets) -> Result<Vec<BrokenMod>> {
+    let mut broken = Vec::new();
+
+    for target in targets.mods {
+        let cached = target.cache_path.exists();
+
+        let missing_files = if cached {
+            missing_files(
+                &target.cache_path,
+                &targets.root,
+                targets.config_dir.as_deref(),
+            )?
+        } else {
+            Vec::new()
+        };
+
+        let dir_missing = !cached && target.mod_dir.as_ref().is_some_and(|dir| !dir.exists());
+
+        if !missing_files.is_empty() || dir_missing {
+            broken.push(BrokenMod {
+                uuid: target.uuid,
+                full_name: target.full_name,
+                missing_files,
+                cached,
+            });
+        }
+    }
+
+    info!("found {} broken mods", broken.len());
+
+    Ok(broken)
+}
+
+fn missing_files(
+    cache_path: &Path,
+    root: &Path,
+    config_dir: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
+    let mut missing = Vec::new();
+
+    for entry in WalkDir::new(cache_path) {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(cache_path)
+            .expect("WalkDir should only return paths inside of the root");
+
+        if config_dir.is_some_and(|dir| relative_path.starts_with(dir)) {
+            continue;
+        }
+
+        if !exists_or_disabled(root.join(relative_path)) {
+            missing.push(relative_path.to_owned());
+        }
+    }
+
+    Ok(missing)
+}
+
+/// Checks if the file exists, either as is or with the `.old` extension of disabled mods.
+fn exists_or_disabled(mut path: PathBuf) -> bool {
+    if path.exists() {
+        return true;
+    }
+
+    path.add_ext("old");
+    path.exists()
+}
+
+/// Reinstalls the given mods in the active profile, keeping their position and state.
+///
+/// Uses the cache if possible, otherwise the mods are downloaded again.
+pub async fn repair(uuids: Vec<Uuid>, app: &AppHandle) -> Result<()> {
+    let installs = {
+        let manager = app.read_manager();
+        let profile = manager.active_profile();
+
+        uuids
+            .into_iter()
+            .map(|uuid| {
+                let index = profile.index_of(uuid)?;
+                let profile_mod = &profile.mods[index];
+                let ts_mod = profile_mod
+                    .kind
+                    .as_thunderstore()
+                    .ok_or_else(|| eyre!("{} is not a thunderstore mod", uuid))?;
+
+                Ok(ModInstall::new(ts_mod.id.clone())
+                    .with_state(profile_mod.enabled)
+                    .with_index(index)
+                    .with_time(profile_mod.install_time)
+                    .with_user_data(profile_mod.user_data()))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    install::install_mods(
+        installs,
+        InstallOptions::default().before_install(Box::new(|install, profile, _| {
+            // remove what's left of the broken install
+            profile
+                .force_remove_mod(install.uuid())
+                .context("failed to remove broken mod")
+        })),
+        app,
+    )
+    .await
+}
@@ -1,27 +1,31 @@
-use std::sync::{atomic::AtomicBool, Mutex, MutexGuard};
+use std::sync::{atomic::AtomicBool, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use eyre::{Context, Result};
 use tauri::{command, AppHandle, Manager};
 use tokio::sync::broadcast;
 
 use crate::{
-    db::{self, Db},
+    db::{self, Db, Recovery},
     prefs::Prefs,
     profile::sync::auth::AuthState,
-    profile::{self, ModManager},
+    profile::{self, undo::UndoStack, ModManager},
     thunderstore::{self, Thunderstore},
+    util,
 };
 
 pub struct AppState {
     pub http: reqwest::Client,
     pub prefs: Mutex<Prefs>,
-    pub manager: Mutex<ModManager>,
+    /// Read-only commands only take a read lock, so they don't block each other.
+    pub manager: RwLock<ModManager>,
     pub thunderstore: Mutex<Thunderstore>,
+    pub undo_stack: Mutex<UndoStack>,
     pub db: Db,
     pub auth: Mutex<Option<AuthState>>,
     pub auth_callback_channel: broadcast::Sender<String>,
-    pub cancel_install_flag: AtomicBool,
+    pub cancel_flag: AtomicBool,
     pub is_first_run: bool,
+    pub recovery: Option<Recovery>,
 }
 
 impl AppState {
@@ -29,8 +33,12 @@ impl AppState {
         self.prefs.lock().unwrap()
     }
 
-    pub fn lock_manager(&self) -> MutexGuard<'_, ModManager> {
-        self.manager.lock().unwrap()
+    pub fn lock_manager(&self) -> RwLockWriteGuard<'_, ModManager> {
+        self.manager.write().unwrap()
+    }
+
+    pub fn read_manager(&self) -> RwLockReadGuard<'_, ModManager> {
+        self.manager.read().unwrap()
     }
 
     pub fn lock_thunderstore(&self) -> MutexGuard<'_, Thunderstore> {
@@ -40,20 +48,27 @@ impl AppState {
     pub fn lock_auth(&self) -> MutexGuard<'_, Option<AuthState>> {
         self.auth.lock().unwrap()
     }
+
+    pub fn lock_undo_stack(&self) -> MutexGuard<'_, UndoStack> {
+        self.undo_stack.lock().unwrap()
+    }
 }
 
 pub fn setup(app: &AppHandle) -> Result<()> {
-    let http = reqwest::Client::builder()
-        .user_agent("Kesomannen-gale")
-        .build()
-        .context("failed to init http client")?;
-
-    let (db, db_existed) = db::init().context("failed to init database")?;
-
-    let (data, mut prefs, auth, migrated) = db.read()?;
+    let db::LoadedData {
+        db,
+        existed: db_existed,
+        data,
+        mut prefs,
+        auth,
+        migrated,
+        recovery,
+    } = db::load()?;
 
     prefs.init(&db, app).context("failed to init prefs")?;
 
+    let http = util::http::client(&util::http::user_agent(prefs.user_agent.as_deref()))?;
+
     let manager = profile::setup(data, &prefs, &db, app).context("failed to init profiles")?;
     let thunderstore = Thunderstore::default();
 
@@ -61,12 +76,14 @@ pub fn setup(app: &AppHandle) -> Result<()> {
         db,
         http,
         prefs: Mutex::new(prefs),
-        manager: Mutex::new(manager),
+        manager: RwLock::new(manager),
         thunderstore: Mutex::new(thunderstore),
+        undo_stack: Mutex::default(),
         auth: Mutex::new(auth),
         auth_callback_channel: broadcast::channel(1).0,
-        cancel_install_flag: AtomicBool::new(false),
+        cancel_flag: AtomicBool::new(false),
         is_first_run: !db_existed && !migrated,
+        recovery,
     };
 
     app.manage(state);
@@ -91,10 +108,14 @@ pub trait ManagerExt<R> {
         self.app_state().lock_prefs()
     }
 
-    fn lock_manager(&self) -> MutexGuard<'_, ModManager> {
+    fn lock_manager(&self) -> RwLockWriteGuard<'_, ModManager> {
         self.app_state().lock_manager()
     }
 
+    fn read_manager(&self) -> RwLockReadGuard<'_, ModManager> {
+        self.app_state().read_manager()
+    }
+
     fn lock_thunderstore(&self) -> MutexGuard<'_, Thunderstore> {
         self.app_state().lock_thunderstore()
     }
@@ -103,6 +124,10 @@ pub trait ManagerExt<R> {
         self.app_state().lock_auth()
     }
 
+    fn lock_undo_stack(&self) -> MutexGuard<'_, UndoStack> {
+        self.app_state().lock_undo_stack()
+    }
+
     fn db(&self) -> &Db {
         &self.app_state().db
     }
@@ -122,3 +147,13 @@ where
 pub fn is_first_run(app: AppHandle) -> bool {
     app.app_state().is_first_run
 }
+
+#[command]
+pub fn get_recovery(app: AppHandle) -> Option<Recovery> {
+    app.app_state().recovery.clone()
+}
+
+#[command]
+pub fn cancel_operation(app: AppHandle) {
+    util::cancel::cancel(&app);
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use eyre::{bail, Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{debug, warn};
+
+use crate::state::ManagerExt;
+
+const MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24); // 1 day
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackageCategory {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Deserialize)]
+struct CategoriesResponse {
+    results: Vec<PackageCategory>,
+}
+
+/// Gets the valid package categories of a community, identified by its slug.
+///
+/// The list is cached for a day. If fetching a new one fails,
+/// an outdated cached list is used instead, if there is one.
+pub async fn get(community: &str, app: &AppHandle) -> Result<Vec<PackageCategory>> {
+    if let Some(categories) = read_cached(community, Some(MAX_AGE), app)? {
+        return Ok(categories);
+    }
+
+    match fetch(community, app).await {
+        Ok(categories) => {
+            let json = serde_json::to_string(&categories)?;
+            app.db().insert_cached_categories(community, &json)?;
+
+            Ok(categories)
+        }
+        Err(err) => match read_cached(community, None, app)? {
+            Some(categories) => {
+                warn!(
+                    "failed to fetch categories, using outdated cache: {:#}",
+                    err
+                );
+                Ok(categories)
+            }
+            None => Err(err),
+        },
+    }
+}
+
+fn read_cached(
+    community: &str,
+    max_age: Option<Duration>,
+    app: &AppHandle,
+) -> Result<Option<Vec<PackageCategory>>> {
+    let Some(json) = app.db().get_cached_categories(community, max_age)? else {
+        return Ok(None);
+    };
+
+    let categories = serde_json::from_str(&json).context("failed to parse cached categories")?;
+    Ok(Some(categories))
+}
+
+async fn fetch(community: &str, app: &AppHandle) -> Result<Vec<PackageCategory>> {
+    debug!("fetching categories for {}", community);
+
+    let url = format!(
+        "https://thunderstore.io/api/experimental/community/{}/category/",
+        community
+    );
+
+    let response: CategoriesResponse = app
+        .http()
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("failed to fetch categories")?;
+
+    Ok(response.results)
+}
+
+/// Checks that all of `selected` are slugs of valid categories in `community`.
+pub fn validate(community: &str, selected: &[String], valid: &[PackageCategory]) -> Result<()> {
+    let invalid = selected
+        .iter()
+        .filter(|slug| !valid.iter().any(|category| &category.slug == *slug))
+        .collect_vec();
+
+    if !invalid.is_empty() {
+        bail!(
+            "invalid categories for {}: {}",
+            community,
+            invalid.iter().join(", ")
+        );
+    }
+
+    Ok(())
+}
@@ -1,24 +1,51 @@
 use eyre::anyhow;
+use itertools::Itertools;
 use tauri::{command, AppHandle};
 
 use super::{
     cache::MarkdownCache,
-    models::FrontendMod,
+    categories::PackageCategory,
+    credits::PackageCredits,
+    icons,
+    models::{FrontendMod, IntoFrontendMod},
     query::{self, QueryModsArgs},
     ModId,
 };
-use crate::{logger, state::ManagerExt, util::cmd::Result};
+use crate::{logger, profile::install, state::ManagerExt, util::cmd::Result};
 
 #[command]
 pub fn query_thunderstore(args: QueryModsArgs, app: AppHandle) -> Vec<FrontendMod> {
-    let manager = app.lock_manager();
-    let mut thunderstore = app.lock_thunderstore();
+    let (result, prefetch) = {
+        let manager = app.read_manager();
+        let mut thunderstore = app.lock_thunderstore();
+
+        // query the next page as well, so its icons are ready by the time it's scrolled to
+        let prefetch_args = QueryModsArgs {
+            max_count: args.max_count * 2,
+            ..args.clone()
+        };
+
+        let mods = query::query_mods(&prefetch_args, thunderstore.latest()).collect_vec();
+
+        let prefetch = mods
+            .iter()
+            .map(|borrowed| borrowed.version.ident.clone())
+            .collect_vec();
+
+        let result = mods
+            .into_iter()
+            .take(args.max_count)
+            .map(|borrowed| borrowed.into_frontend(Some(manager.active_profile())))
+            .collect_vec();
+
+        if !thunderstore.packages_fetched {
+            thunderstore.current_query = Some(args);
+        }
 
-    let result = query::query_frontend_mods(&args, thunderstore.latest(), manager.active_profile());
+        (result, prefetch)
+    };
 
-    if !thunderstore.packages_fetched {
-        thunderstore.current_query = Some(args);
-    }
+    icons::prefetch(prefetch, &app);
 
     result
 }
@@ -40,10 +67,10 @@ pub fn trigger_mod_fetch(app: AppHandle) -> Result<()> {
         !state.packages_fetched
     };
 
-    let game = app.lock_manager().active_game;
+    let game = app.read_manager().active_game;
 
     tauri::async_runtime::spawn(async move {
-        if let Err(err) = super::fetch::fetch_packages(game, write_directly, &app).await {
+        if let Some(Err(err)) = super::fetch::guarded_fetch(game, write_directly, &app).await {
             logger::log_webview_err("error while fetching mods from Thunderstore", err, &app);
         }
     });
@@ -61,6 +88,39 @@ pub async fn get_markdown(
     Ok(content)
 }
 
+#[command]
+pub async fn get_categories(
+    community: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<PackageCategory>> {
+    let community = community.unwrap_or_else(|| app.read_manager().active_game.slug.to_string());
+    let categories = super::categories::get(&community, &app).await?;
+
+    Ok(categories)
+}
+
+#[command]
+pub async fn get_package_credits(mod_ref: ModId, app: AppHandle) -> Result<PackageCredits> {
+    {
+        let prefs = app.lock_prefs();
+        let thunderstore = app.lock_thunderstore();
+
+        let version = mod_ref.borrow(&thunderstore)?.version;
+        let cache_path = install::cache::path(&version.ident, &prefs);
+
+        if cache_path.exists() {
+            return Ok(PackageCredits::from_dir(&cache_path, version));
+        }
+    }
+
+    let readme = super::cache::get_markdown(MarkdownCache::Readme, mod_ref.clone(), &app).await?;
+
+    let thunderstore = app.lock_thunderstore();
+    let version = mod_ref.borrow(&thunderstore)?.version;
+
+    Ok(PackageCredits::from_readme(version, readme.as_deref()))
+}
+
 #[command]
 pub fn set_thunderstore_token(token: &str) -> Result<()> {
     super::token::set(token)?;
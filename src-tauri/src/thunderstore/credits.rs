@@ -0,0 +1,181 @@
+use std::{fs, path::Path};
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use super::PackageVersion;
+
+/// Attribution info for a package, gathered from its files and metadata.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCredits {
+    /// The detected license as an SPDX id, e.g. `MIT` or `GPL-3.0-only`.
+    ///
+    /// `Custom` if the package ships a license file we couldn't identify.
+    pub license: Option<String>,
+    /// A link to the package's source code repository.
+    pub source_url: Option<String>,
+}
+
+const SOURCE_HOSTS: &[&str] = &[
+    "https://github.com/",
+    "https://gitlab.com/",
+    "https://codeberg.org/",
+];
+
+impl PackageCredits {
+    /// Reads credits from an extracted package, such as one in the download cache.
+    pub fn from_dir(dir: &Path, version: &PackageVersion) -> Self {
+        let mut license = None;
+        let mut readme = None;
+
+        let files = WalkDir::new(dir)
+            .max_depth(3)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file());
+
+        for entry in files {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+
+            if license.is_none() && (name.starts_with("license") || name.starts_with("licence")) {
+                license = fs::read_to_string(entry.path())
+                    .ok()
+                    .map(|text| detect_license(&text));
+            } else if readme.is_none() && name == "readme.md" {
+                readme = fs::read_to_string(entry.path()).ok();
+            }
+        }
+
+        Self {
+            license,
+            source_url: find_source_url(version, readme.as_deref()),
+        }
+    }
+
+    /// Reads credits from a package's README, without access to its files.
+    pub fn from_readme(version: &PackageVersion, readme: Option<&str>) -> Self {
+        Self {
+            license: None,
+            source_url: find_source_url(version, readme),
+        }
+    }
+}
+
+fn detect_license(text: &str) -> String {
+    const KNOWN: &[(&str, &str)] = &[
+        ("mit license", "MIT"),
+        ("permission is hereby granted, free of charge", "MIT"),
+        ("apache license", "Apache-2.0"),
+        ("mozilla public license", "MPL-2.0"),
+        ("the unlicense", "Unlicense"),
+        ("this is free and unencumbered software", "Unlicense"),
+        ("bsd 3-clause", "BSD-3-Clause"),
+        ("bsd 2-clause", "BSD-2-Clause"),
+    ];
+
+    // only check the start of the file, since licenses often mention each other further down
+    let head = text.chars().take(500).collect::<String>().to_lowercase();
+
+    if let Some(license) = detect_gnu_license(&head).or_else(|| detect_cc_license(&head)) {
+        return license;
+    }
+
+    KNOWN
+        .iter()
+        .find(|(pattern, _)| head.contains(pattern))
+        .map(|(_, name)| *name)
+        .unwrap_or("Custom")
+        .to_owned()
+}
+
+/// Maps the GNU licenses to their SPDX ids, which include the version and
+/// whether later versions are allowed.
+fn detect_gnu_license(head: &str) -> Option<String> {
+    // the LGPL mentions the GPL in its preamble, so go by whichever title comes first
+    let (index, family) = [
+        ("gnu lesser general public license", "LGPL"),
+        ("gnu library general public license", "LGPL"),
+        ("gnu affero general public license", "AGPL"),
+        ("gnu general public license", "GPL"),
+    ]
+    .into_iter()
+    .filter_map(|(title, family)| head.find(title).map(|index| (index, family)))
+    .min_by_key(|(index, _)| *index)?;
+
+    let rest = &head[index..];
+    let version = match family {
+        "AGPL" => "3.0",
+        "LGPL" if rest.contains("version 2.1") => "2.1",
+        "LGPL" if rest.contains("version 2") => "2.0",
+        "GPL" if rest.contains("version 2") => "2.0",
+        _ => "3.0",
+    };
+
+    let suffix = match rest.contains("any later version") {
+        true => "or-later",
+        false => "only",
+    };
+
+    Some(format!("{}-{}-{}", family, version, suffix))
+}
+
+/// Maps Creative Commons licenses to their SPDX ids, like `CC-BY-NC-SA-4.0`.
+fn detect_cc_license(head: &str) -> Option<String> {
+    if head.contains("cc0") || head.contains("creative commons zero") {
+        return Some("CC0-1.0".to_owned());
+    }
+
+    if !head.contains("creative commons") {
+        return None;
+    }
+
+    let version = ["4.0", "3.0", "2.5", "2.0", "1.0"]
+        .into_iter()
+        .find(|version| head.contains(version))?;
+
+    let mut id = String::from("CC-BY");
+
+    if head.contains("noncommercial") {
+        id.push_str("-NC");
+    }
+
+    if head.contains("noderivatives") || head.contains("noderivs") {
+        id.push_str("-ND");
+    } else if head.contains("sharealike") {
+        id.push_str("-SA");
+    }
+
+    Some(format!("{}-{}", id, version))
+}
+
+fn find_source_url(version: &PackageVersion, readme: Option<&str>) -> Option<String> {
+    if SOURCE_HOSTS
+        .iter()
+        .any(|host| version.website_url.starts_with(host))
+    {
+        return Some(version.website_url.trim_end_matches('/').to_owned());
+    }
+
+    readme.and_then(|readme| {
+        SOURCE_HOSTS
+            .iter()
+            .filter_map(|host| find_repo_link(readme, host))
+            .next()
+    })
+}
+
+/// Finds the first link to a repository (`<host>/<owner>/<repo>`) in `text`.
+fn find_repo_link(text: &str, host: &str) -> Option<String> {
+    text.match_indices(host).find_map(|(index, _)| {
+        let path = text[index + host.len()..]
+            .split(|c: char| c.is_whitespace() || matches!(c, ')' | ']' | '"' | '\'' | '>' | '<'))
+            .next()?;
+
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+        let owner = segments.next()?;
+        let repo = segments.next()?.trim_end_matches(".git");
+
+        Some(format!("{}{}/{}", host, owner, repo))
+    })
+}
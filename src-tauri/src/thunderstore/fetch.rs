@@ -10,7 +10,7 @@ use tauri::{AppHandle, Emitter};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::{game::Game, logger, state::ManagerExt, thunderstore::PackageListing};
+use crate::{game::Game, logger, profile::update, state::ManagerExt, thunderstore::PackageListing};
 
 pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
     const FETCH_INTERVAL: Duration = Duration::from_secs(60 * 15);
@@ -36,22 +36,51 @@ pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
     }
 
     async fn loop_iter(game: Game, is_first: &mut bool, app: &AppHandle) -> Result<()> {
-        if app.lock_thunderstore().is_fetching {
+        let Some(result) = guarded_fetch(game, *is_first, app).await else {
             warn!("automatic fetch cancelled due to ongoing fetch");
             return Ok(());
+        };
+
+        *is_first &= result.is_err();
+
+        result
+    }
+}
+
+/// Fetches packages unless another fetch is already running, in which case
+/// `None` is returned.
+///
+/// Once the fetch succeeds, the update counts of the active game's profiles are sent
+/// to the frontend.
+pub(super) async fn guarded_fetch(
+    game: Game,
+    write_directly: bool,
+    app: &AppHandle,
+) -> Option<Result<()>> {
+    {
+        let mut state = app.lock_thunderstore();
+
+        if state.is_fetching {
+            return None;
         }
 
-        let result = fetch_packages(game, *is_first, app).await;
+        state.is_fetching = true;
+    }
+
+    let result = fetch_packages(game, write_directly, app).await;
 
+    {
         let mut state = app.lock_thunderstore();
 
         state.is_fetching = false;
         state.packages_fetched |= result.is_ok();
+    }
 
-        *is_first &= result.is_err();
-
-        result
+    if result.is_ok() {
+        update::background::emit_counts(app);
     }
+
+    Some(result)
 }
 
 fn read_and_insert_cache(app: &AppHandle) {
@@ -77,11 +106,7 @@ static EXCLUDED_PACKAGES: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
         .collect()
 });
 
-pub(super) async fn fetch_packages(
-    game: Game,
-    write_directly: bool,
-    app: &AppHandle,
-) -> Result<()> {
+async fn fetch_packages(game: Game, write_directly: bool, app: &AppHandle) -> Result<()> {
     const UPDATE_INTERVAL: Duration = Duration::from_millis(250);
     const INSERT_EVERY: usize = 1000;
 
@@ -193,6 +218,25 @@ fn insert_package(count: &mut usize, buffer: &mut IndexMap<Uuid, PackageListing>
     }
 }
 
+/// Fetches the package index of the active game again, replacing the current one.
+///
+/// Used when a package can't be found, since it may have been published after the last fetch.
+/// If a fetch is already running, this waits for it to finish instead of starting another.
+pub async fn refetch(app: &AppHandle) -> Result<()> {
+    let game = app.read_manager().active_game;
+
+    match guarded_fetch(game, false, app).await {
+        Some(result) => result,
+        None => {
+            while app.lock_thunderstore().is_fetching {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+
+            Ok(())
+        }
+    }
+}
+
 pub async fn wait_for_fetch(app: &AppHandle) {
     loop {
         if app.lock_thunderstore().packages_fetched() {
@@ -0,0 +1,153 @@
+//! Prefetching of package icons, so they're already on disk once they scroll into view.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use eyre::Result;
+use futures_util::{future, stream, StreamExt};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, warn};
+
+use super::VersionIdent;
+use crate::{prefs::Prefs, state::ManagerExt, util::error::IoResultExt};
+
+/// How many icons are downloaded at once.
+const CONCURRENCY: usize = 8;
+
+/// How many icons are kept on disk before the oldest ones are deleted.
+const MAX_ICONS: usize = 2000;
+
+/// Icons which are currently being downloaded, to avoid fetching them twice
+/// when results come in faster than the downloads finish.
+static IN_FLIGHT: LazyLock<Mutex<HashSet<VersionIdent>>> = LazyLock::new(Default::default);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IconAvailable {
+    full_name: String,
+    path: PathBuf,
+}
+
+fn dir(prefs: &Prefs) -> PathBuf {
+    prefs.cache_dir().join("icons").join("thunderstore")
+}
+
+fn url(ident: &VersionIdent) -> String {
+    format!(
+        "https://gcdn.thunderstore.io/live/repository/icons/{}.png",
+        ident
+    )
+}
+
+/// Makes sure the icons of `idents` are cached, downloading missing ones in the background.
+///
+/// An `icon_available` event is emitted for each icon once it's on disk,
+/// including the ones which were already cached.
+pub fn prefetch(idents: Vec<VersionIdent>, app: &AppHandle) {
+    let dir = dir(&app.lock_prefs());
+
+    let mut missing = Vec::new();
+    {
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+
+        for ident in idents {
+            let path = dir.join(format!("{}.png", ident));
+
+            if path.exists() {
+                emit(&ident, path, app);
+            } else if in_flight.insert(ident.clone()) {
+                missing.push(ident);
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        stream::iter(missing)
+            .map(|ident| fetch(ident, &dir, &app))
+            .buffer_unordered(CONCURRENCY)
+            .for_each(|_| future::ready(()))
+            .await;
+
+        let result = tauri::async_runtime::spawn_blocking(move || prune(&dir)).await;
+        if let Ok(Err(err)) = result {
+            warn!("failed to prune icon cache: {:#}", err);
+        }
+    });
+}
+
+/// Deletes the least recently downloaded icons past [`MAX_ICONS`].
+fn prune(dir: &Path) -> Result<()> {
+    let mut icons = fs::read_dir(dir)
+        .fs_context("reading icon cache", dir)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect::<Vec<_>>();
+
+    if icons.len() <= MAX_ICONS {
+        return Ok(());
+    }
+
+    icons.sort_by_key(|(_, modified)| *modified);
+
+    let count = icons.len() - MAX_ICONS;
+    for (path, _) in icons.into_iter().take(count) {
+        fs::remove_file(&path).fs_context("deleting icon", &path)?;
+    }
+
+    debug!("pruned {} icons from cache", count);
+
+    Ok(())
+}
+
+async fn fetch(ident: VersionIdent, dir: &Path, app: &AppHandle) {
+    let path = dir.join(format!("{}.png", ident));
+
+    match download(&ident, &path, app).await {
+        Ok(()) => emit(&ident, path, app),
+        Err(err) => debug!("failed to prefetch icon for {}: {:#}", ident, err),
+    }
+
+    IN_FLIGHT.lock().unwrap().remove(&ident);
+}
+
+async fn download(ident: &VersionIdent, path: &Path, app: &AppHandle) -> Result<()> {
+    let bytes = app
+        .http()
+        .get(url(ident))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    fs::create_dir_all(path.parent().unwrap())?;
+
+    // write to a temporary file first, so a half-written icon is never picked up
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &bytes).fs_context("writing icon", &temp_path)?;
+    fs::rename(&temp_path, path).fs_context("moving icon", path)?;
+
+    Ok(())
+}
+
+fn emit(ident: &VersionIdent, path: PathBuf, app: &AppHandle) {
+    let payload = IconAvailable {
+        full_name: ident.to_string(),
+        path,
+    };
+
+    app.emit("icon_available", payload).ok();
+}
@@ -14,12 +14,15 @@ use uuid::Uuid;
 use crate::{game::Game, state::ManagerExt};
 
 pub mod cache;
+pub mod categories;
 pub mod commands;
+pub mod credits;
+pub mod icons;
 pub mod query;
 pub mod token;
 
 mod fetch;
-pub use fetch::wait_for_fetch;
+pub use fetch::{refetch, wait_for_fetch};
 
 mod models;
 pub use models::*;
@@ -111,6 +114,21 @@ pub struct Thunderstore {
     current_query: Option<QueryModsArgs>,
 }
 
+#[cfg(test)]
+impl Thunderstore {
+    /// A registry of the given packages, as if they had been fetched.
+    pub(crate) fn from_packages(packages: impl IntoIterator<Item = PackageListing>) -> Self {
+        Self {
+            packages_fetched: true,
+            packages: packages
+                .into_iter()
+                .map(|package| (package.uuid, package))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
 impl Thunderstore {
     /// Whether packages have been succesfully fetched at least one since
     /// the last call to [`Thunderstore::switch_game`].
@@ -207,7 +207,7 @@ pub enum UserMediaStatus {
     UploadAborted,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UploadPartUrl {
     pub part_number: u32,
     pub url: String,
@@ -220,7 +220,7 @@ pub struct UserMediaFinishUploadParams {
     pub parts: Vec<CompletedPart>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CompletedPart {
     #[serde(rename = "ETag")]
     pub tag: String,
@@ -242,6 +242,8 @@ pub struct PackageSubmissionMetadata {
 #[serde(rename_all = "camelCase")]
 pub enum FrontendModKind {
     Local,
+    Nexus,
+    Github,
     #[default]
     Remote,
 }
@@ -285,6 +287,11 @@ pub struct FrontendVersion {
 pub struct FrontendProfileMod {
     pub enabled: bool,
     pub config_file: Option<PathBuf>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    /// Whether the mod has been removed from Thunderstore.
+    pub orphaned: bool,
     #[serde(flatten)]
     pub data: FrontendMod,
 }
@@ -11,7 +11,8 @@ use super::{
     BorrowedMod,
 };
 use crate::{
-    profile::{LocalMod, Profile},
+    nexus::NexusMod,
+    profile::{install::github::GithubMod, LocalMod, Profile, ThunderstoreMod},
     state::ManagerExt,
     util,
 };
@@ -64,7 +65,7 @@ pub async fn query_loop(app: AppHandle) -> Result<()> {
             let mut thunderstore = app.lock_thunderstore();
 
             if let Some(args) = &thunderstore.current_query {
-                let manager = app.lock_manager();
+                let manager = app.read_manager();
 
                 let mods =
                     query_frontend_mods(args, thunderstore.latest(), manager.active_profile());
@@ -202,7 +203,9 @@ impl From<LocalMod> for FrontendMod {
     fn from(value: LocalMod) -> Self {
         let LocalMod {
             name,
+            author,
             description,
+            website_url,
             version,
             file_size,
             uuid,
@@ -213,7 +216,9 @@ impl From<LocalMod> for FrontendMod {
 
         FrontendMod {
             name,
+            author,
             description,
+            website_url,
             version,
             file_size,
             uuid,
@@ -225,6 +230,80 @@ impl From<LocalMod> for FrontendMod {
     }
 }
 
+impl From<NexusMod> for FrontendMod {
+    fn from(value: NexusMod) -> Self {
+        let website_url = Some(value.url());
+        let version = value.parsed_version();
+
+        let NexusMod {
+            name,
+            author,
+            description,
+            file_size,
+            uuid,
+            ..
+        } = value;
+
+        FrontendMod {
+            name,
+            author,
+            description,
+            version,
+            file_size,
+            uuid,
+            website_url,
+            kind: FrontendModKind::Nexus,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<GithubMod> for FrontendMod {
+    fn from(value: GithubMod) -> Self {
+        let website_url = Some(value.url());
+        let version = value.parsed_version();
+
+        let GithubMod {
+            owner,
+            repo,
+            description,
+            file_size,
+            uuid,
+            ..
+        } = value;
+
+        FrontendMod {
+            name: repo,
+            author: Some(owner),
+            description,
+            version,
+            file_size,
+            uuid,
+            website_url,
+            kind: FrontendModKind::Github,
+            ..Default::default()
+        }
+    }
+}
+
+/// Builds what's known about a mod that is no longer on Thunderstore.
+impl From<ThunderstoreMod> for FrontendMod {
+    fn from(value: ThunderstoreMod) -> Self {
+        let ThunderstoreMod { ident, id } = value;
+
+        FrontendMod {
+            name: ident.name().to_owned(),
+            author: Some(ident.owner().to_owned()),
+            version: ident.version().parse().ok(),
+            uuid: id.package_uuid,
+            version_uuid: id.version_uuid,
+            is_installed: true,
+            kind: FrontendModKind::Remote,
+            ..Default::default()
+        }
+    }
+}
+
 /// Sorts and filters `mods` according to `args` and converts the
 /// results to [`FrontendMod`].
 pub fn query_frontend_mods<T, I>(
@@ -0,0 +1,112 @@
+//! Cooperative cancellation of long operations, like installs, imports and uploads.
+//!
+//! There's only ever one such operation running at a time, so they share a
+//! single flag which is set by the `cancel_operation` command.
+
+use std::{
+    future::Future,
+    io::{self, Seek, SeekFrom, Write},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use eyre::Result;
+use tauri::AppHandle;
+
+use crate::state::ManagerExt;
+
+/// How often [`abortable`] checks whether the operation has been cancelled.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(thiserror::Error, Debug)]
+#[error("cancelled by the user")]
+pub struct Cancelled;
+
+/// Clears a previous cancellation.
+///
+/// Call this only at the start of a command, since operations may run as part of
+/// bigger ones (like installs during an import) whose cancellation shouldn't be cleared.
+pub fn reset(app: &AppHandle) {
+    app.app_state().cancel_flag.store(false, Ordering::Relaxed);
+}
+
+pub fn cancel(app: &AppHandle) {
+    app.app_state().cancel_flag.store(true, Ordering::Relaxed);
+}
+
+pub fn is_cancelled(app: &AppHandle) -> bool {
+    app.app_state().cancel_flag.load(Ordering::Relaxed)
+}
+
+/// Returns a [`Cancelled`] error if the current operation has been cancelled.
+pub fn check(app: &AppHandle) -> Result<()> {
+    match is_cancelled(app) {
+        true => Err(Cancelled.into()),
+        false => Ok(()),
+    }
+}
+
+/// Whether an error was caused by the user cancelling the operation.
+pub fn is_cancel_error(err: &eyre::Error) -> bool {
+    err.chain().any(|cause| {
+        cause.is::<Cancelled>()
+            || cause
+                .downcast_ref::<io::Error>()
+                .and_then(io::Error::get_ref)
+                .is_some_and(|inner| inner.is::<Cancelled>())
+    })
+}
+
+/// Wraps a writer to fail with [`Cancelled`] once the operation has been cancelled,
+/// for example to abort writing a zip archive.
+pub struct Writer<'a, W> {
+    inner: W,
+    app: &'a AppHandle,
+}
+
+impl<'a, W> Writer<'a, W> {
+    pub fn new(inner: W, app: &'a AppHandle) -> Self {
+        Self { inner, app }
+    }
+}
+
+impl<W: Write> Write for Writer<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if is_cancelled(self.app) {
+            return Err(io::Error::other(Cancelled));
+        }
+
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for Writer<'_, W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// Runs `future` until it completes or the operation is cancelled, in which
+/// case the future is dropped, aborting any request it was making.
+pub async fn abortable<T, E>(
+    future: impl Future<Output = Result<T, E>>,
+    app: &AppHandle,
+) -> Result<T>
+where
+    E: Into<eyre::Report>,
+{
+    let cancelled = async {
+        while !is_cancelled(app) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    };
+
+    tokio::select! {
+        result = future => result.map_err(Into::into),
+        _ = cancelled => Err(Cancelled.into()),
+    }
+}
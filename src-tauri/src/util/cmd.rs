@@ -5,6 +5,9 @@ use std::{
 
 use serde::Serialize;
 
+/// Returned in place of the error message when a command is cancelled.
+pub const CANCELLED: &str = "cancelled";
+
 #[derive(Debug)]
 pub struct CommandError(eyre::Error);
 
@@ -19,6 +22,11 @@ impl Serialize for CommandError {
     where
         S: serde::Serializer,
     {
+        // the frontend doesn't show an error for operations the user cancelled
+        if super::cancel::is_cancel_error(&self.0) {
+            return serializer.serialize_str(CANCELLED);
+        }
+
         serializer.serialize_str(&self.to_string())
     }
 }
@@ -1,12 +1,19 @@
 use std::{
     ffi::OsStr,
     fs::{self, File},
-    io::{BufReader, BufWriter},
+    io::{self, BufReader, BufWriter},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    thread,
+    time::Duration,
 };
 
 use tracing::warn;
 use serde::{de::DeserializeOwned, Serialize};
+use tempfile::{NamedTempFile, TempDir};
 use walkdir::WalkDir;
 use zip::ZipArchive;
 
@@ -130,6 +137,162 @@ pub fn write_json<T: Serialize + ?Sized>(
     Ok(())
 }
 
+static USE_TRASH: AtomicBool = AtomicBool::new(true);
+
+type TrashFallbackHandler = Box<dyn Fn(&Path) + Send + Sync>;
+
+static ON_TRASH_FALLBACK: OnceLock<TrashFallbackHandler> = OnceLock::new();
+
+/// Sets whether [`delete`] moves files to the trash or deletes them permanently.
+pub fn set_use_trash(value: bool) {
+    USE_TRASH.store(value, Ordering::Relaxed);
+}
+
+/// Sets a callback for when [`delete`] couldn't move a path to the trash,
+/// and deleted it permanently instead.
+pub fn on_trash_fallback(handler: impl Fn(&Path) + Send + Sync + 'static) {
+    ON_TRASH_FALLBACK.set(Box::new(handler)).ok();
+}
+
+/// Deletes a file or directory at `path`.
+///
+/// Unless permanent deletion is preferred, this moves the path to the OS trash,
+/// falling back to deleting it permanently if that fails (e.g. on filesystems
+/// without trash support). The fallback is reported to [`on_trash_fallback`].
+pub fn delete(path: impl AsRef<Path>) -> eyre::Result<()> {
+    delete_with(path.as_ref(), LOCK_RETRY_DELAYS_MS)
+}
+
+/// Like [`delete`], but fails right away if the path is locked instead of
+/// retrying, for callers that hold locks and can retry later themselves.
+pub fn delete_once(path: impl AsRef<Path>) -> eyre::Result<()> {
+    delete_with(path.as_ref(), &[])
+}
+
+fn delete_with(path: &Path, retry_delays: &[u64]) -> eyre::Result<()> {
+    if USE_TRASH.load(Ordering::Relaxed) {
+        match trash::delete(path) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                warn!(
+                    "failed to move {} to trash, deleting permanently: {}",
+                    path.display(),
+                    err
+                );
+
+                if let Some(handler) = ON_TRASH_FALLBACK.get() {
+                    handler(path);
+                }
+            }
+        }
+    }
+
+    remove_permanently(path, retry_delays)
+}
+
+/// Deletes a file or directory at `path`, without going through the trash.
+///
+/// Use this for data that can be recreated, like the download cache, since
+/// moving it to the trash wouldn't free up any space.
+pub fn delete_permanently(path: impl AsRef<Path>) -> eyre::Result<()> {
+    remove_permanently(path.as_ref(), LOCK_RETRY_DELAYS_MS)
+}
+
+fn remove_permanently(path: &Path, retry_delays: &[u64]) -> eyre::Result<()> {
+    if path.is_dir() {
+        retry_locked(retry_delays, || fs::remove_dir_all(path))
+            .fs_context("deleting directory", path)
+    } else {
+        retry_locked(retry_delays, || fs::remove_file(path)).fs_context("deleting file", path)
+    }
+}
+
+/// Whether an error was caused by the file being in use by another process,
+/// such as a running game or an antivirus scanning it.
+///
+/// Only Windows locks files like this.
+pub fn is_locked(err: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32 | 33))
+}
+
+const LOCK_RETRY_DELAYS_MS: &[u64] = &[50, 150, 400, 1000];
+
+/// Runs `op`, retrying after each of `delays` while the file is locked.
+///
+/// Antivirus scans usually release the file within a second, while a running
+/// game keeps it locked and the last error is returned.
+fn retry_locked<T>(delays: &[u64], mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    for delay in delays {
+        match op() {
+            Err(err) if is_locked(&err) => thread::sleep(Duration::from_millis(*delay)),
+            result => return result,
+        }
+    }
+
+    op()
+}
+
+/// Creates a temporary directory inside of `root`.
+///
+/// Prefer this over [`tempfile::tempdir`], since the OS temp directory may be on
+/// a different (and small) drive, which makes moving the results slow or
+/// impossible, and breaks hard links.
+pub fn temp_dir_in(root: &Path) -> eyre::Result<TempDir> {
+    fs::create_dir_all(root).fs_context("creating temporary directory", root)?;
+
+    tempfile::Builder::new()
+        .prefix(".tmp")
+        .tempdir_in(root)
+        .fs_context("creating temporary directory", root)
+}
+
+/// Creates a temporary directory next to `dest`, which can later be
+/// moved there in a single rename with [`persist_dir`].
+pub fn temp_dir_for(dest: &Path) -> eyre::Result<TempDir> {
+    temp_dir_in(dest.parent().unwrap_or(dest))
+}
+
+/// Moves a temporary directory to `dest`, replacing anything already there.
+///
+/// An existing directory is renamed out of the way first instead of being deleted,
+/// so `dest` is only missing between the two renames, and is put back if the move fails.
+pub fn persist_dir(temp: TempDir, dest: &Path) -> eyre::Result<()> {
+    let path = temp.into_path();
+
+    // deleted along with the old contents when dropped
+    let aside = temp_dir_for(dest)?;
+    let old = aside.path().join("old");
+
+    let has_old = dest.exists();
+    if has_old {
+        fs::rename(dest, &old).fs_context("moving existing directory", dest)?;
+    }
+
+    if let Err(err) = fs::rename(&path, dest) {
+        fs::remove_dir_all(&path).ok();
+
+        if has_old {
+            fs::rename(&old, dest).ok();
+        }
+
+        return Err(err).fs_context("moving temporary directory", dest);
+    }
+
+    Ok(())
+}
+
+/// Creates a temporary file next to `dest`. Once it's written, it can be
+/// moved into place with [`NamedTempFile::persist`], so `dest` is never left half-written.
+pub fn temp_file_for(dest: &Path) -> eyre::Result<NamedTempFile> {
+    let dir = dest.parent().unwrap_or(dest);
+
+    tempfile::Builder::new()
+        .prefix(".tmp")
+        .tempfile_in(dir)
+        .fs_context("creating temporary file", dir)
+}
+
 pub fn file_name_owned(path: impl AsRef<Path>) -> String {
     path.as_ref()
         .file_name()
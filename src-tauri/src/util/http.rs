@@ -0,0 +1,60 @@
+//! Shared setup for HTTP requests, so every integration identifies itself the
+//! same way and sends the headers third-party APIs ask for.
+
+use eyre::{Context, Result};
+use reqwest::RequestBuilder;
+
+const DEFAULT_USER_AGENT: &str = "Kesomannen-gale";
+
+const APP_NAME: &str = "Gale";
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The version of the GitHub REST API the requests are written against.
+const GITHUB_API_VERSION: &str = "2022-11-28";
+
+/// Builds the user agent, for example `Kesomannen-gale/1.5.0 (windows)`.
+///
+/// `custom` replaces the name, but the version and platform are always appended.
+pub fn user_agent(custom: Option<&str>) -> String {
+    let name = custom
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_USER_AGENT);
+
+    format!("{}/{} ({})", name, APP_VERSION, std::env::consts::OS)
+}
+
+pub fn client(user_agent: &str) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .build()
+        .context("failed to init http client")
+}
+
+pub trait RequestBuilderExt {
+    /// Adds the headers GitHub asks API clients to send, and the token if there is one.
+    fn github_headers(self, token: Option<&str>) -> Self;
+
+    /// Adds the API key and the application headers Nexus Mods requires.
+    fn nexus_headers(self, api_key: &str) -> Self;
+}
+
+impl RequestBuilderExt for RequestBuilder {
+    fn github_headers(self, token: Option<&str>) -> Self {
+        let request = self
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION);
+
+        match token.filter(|token| !token.is_empty()) {
+            // raises the rate limit from 60 to 5000 requests per hour
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    fn nexus_headers(self, api_key: &str) -> Self {
+        self.header("apikey", api_key)
+            .header("Application-Name", APP_NAME)
+            .header("Application-Version", APP_VERSION)
+    }
+}
@@ -2,10 +2,13 @@ use std::cmp::Ordering;
 
 use itertools::Itertools;
 
+pub mod cancel;
 pub mod cmd;
 pub mod error;
 pub mod fs;
+pub mod http;
 pub mod path;
+pub mod secret;
 pub mod window;
 pub mod zip;
 
@@ -22,3 +25,32 @@ pub fn cmp_ignore_case(a: impl AsRef<str>, b: impl AsRef<str>) -> Ordering {
         .find(|&ordering| ordering != Ordering::Equal)
         .unwrap_or(Ordering::Equal)
 }
+
+/// Parses versions that don't strictly follow semver, such as `v1.2` or `1.0.0b`.
+///
+/// Only the leading numeric components are considered.
+pub fn parse_lenient_version(str: &str) -> Option<semver::Version> {
+    let str = str.trim().trim_start_matches(['v', 'V']);
+
+    let mut parts = str
+        .split(|c: char| !c.is_ascii_digit())
+        .take_while(|part| !part.is_empty())
+        .take(3)
+        .map(|part| part.parse::<u64>());
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().transpose().ok()?.unwrap_or(0);
+    let patch = parts.next().transpose().ok()?.unwrap_or(0);
+
+    Some(semver::Version::new(major, minor, patch))
+}
+
+/// Replaces characters that aren't allowed in package identifiers with underscores.
+pub fn sanitize_ident(str: &str) -> String {
+    str.chars()
+        .map(|c| match c.is_alphanumeric() || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! API credentials for other sites, stored in the OS keyring like the
+//! Thunderstore token (see [`crate::thunderstore::token`]).
+
+use eyre::Result;
+use keyring::Entry;
+use serde::Deserialize;
+use tracing::info;
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum Secret {
+    NexusApiKey,
+    GithubToken,
+}
+
+impl Secret {
+    fn entry(self) -> Result<Entry> {
+        let (service, user) = match self {
+            Secret::NexusApiKey => ("nexusmods", "api_key"),
+            Secret::GithubToken => ("github", "token"),
+        };
+
+        Ok(Entry::new(service, user)?)
+    }
+
+    pub fn get(self) -> Result<Option<String>> {
+        match self.entry()?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn is_set(self) -> bool {
+        self.get().is_ok_and(|value| value.is_some())
+    }
+
+    pub fn set(self, value: &str) -> Result<()> {
+        info!("setting {:?}", self);
+        self.entry()?.set_password(value)?;
+        Ok(())
+    }
+
+    pub fn clear(self) -> Result<()> {
+        info!("clearing {:?}", self);
+        match self.entry()?.delete_credential() {
+            Ok(()) => Ok(()),
+            Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Moves a value that used to be stored in plain text into the keyring.
+    ///
+    /// The value is taken out of `legacy` either way, so it's never saved in plain text again.
+    pub fn migrate(self, legacy: &mut Option<String>) -> Result<()> {
+        if let Some(value) = legacy.take().filter(|value| !value.is_empty()) {
+            self.set(&value)?;
+        }
+
+        Ok(())
+    }
+}
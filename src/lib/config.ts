@@ -5,15 +5,20 @@ export function isNum(value: ConfigValue) {
 	return value.type === 'int' || value.type === 'float';
 }
 
+/**
+ * Returns the value that was actually written, which may have been clamped
+ * into the entry's acceptable range.
+ */
 export async function setConfigEntry(id: ConfigEntryId, value: ConfigValue) {
-	if (isNum(value) && value.content.value === null) return;
+	if (isNum(value) && value.content.value === null) return null;
 
-	await invokeCommand('set_config_entry', {
+	let result = await invokeCommand<ConfigValue>('set_config_entry', {
 		file: id.file.relativePath,
 		section: id.section.name,
 		entry: id.entry.name,
 		value
 	});
 
-	id.entry.value = value;
+	id.entry.value = result;
+	return result;
 }
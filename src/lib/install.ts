@@ -0,0 +1,49 @@
+import { confirm } from '@tauri-apps/plugin-dialog';
+import { invokeCommand } from './invoke';
+import type { DependencyConflict } from './models';
+
+type ModRef = {
+	packageUuid: string;
+	versionUuid: string;
+};
+
+/**
+ * Installs a mod and its dependencies, asking the user to confirm first
+ * if they require conflicting versions of a package.
+ *
+ * Returns whether the mod was installed.
+ */
+export async function installMod(modRef: ModRef): Promise<boolean> {
+	let conflicts = await invokeCommand<DependencyConflict[]>('install_mod', { modRef });
+	if (conflicts.length === 0) return true;
+
+	let confirmed = await confirm(conflictMessage(conflicts), {
+		title: 'Dependency conflicts',
+		kind: 'warning'
+	});
+
+	if (!confirmed) return false;
+
+	await invokeCommand('install_mod', { modRef, allowConflicts: true });
+	return true;
+}
+
+function conflictMessage(conflicts: DependencyConflict[]) {
+	let lines = conflicts.map((conflict) => {
+		let versions = conflict.requirements.map(
+			(requirement) => `${requirement.version} (required by ${requirement.requiredBy})`
+		);
+
+		if (conflict.installed !== null) {
+			versions.unshift(`${conflict.installed} (installed)`);
+		}
+
+		return `${conflict.package}: ${[...new Set(versions)].join(', ')}`;
+	});
+
+	return (
+		'Some dependencies require incompatible versions of the same mod:\n\n' +
+		lines.join('\n') +
+		'\n\nInstalling anyway may break these mods. Do you want to continue?'
+	);
+}
@@ -20,6 +20,10 @@ export async function invokeCommand<T>(cmd: string, args?: any): Promise<T> {
 		return await invoke<T>(cmd, args);
 	} catch (error: any) {
 		let errStr = error as string;
+
+		// the user cancelled the operation, so there's nothing to report
+		if (errStr === 'cancelled') throw error;
+
 		let name = `Failed to ${sentenceCase(cmd).toLowerCase()}`;
 		let message = errStr[0].toUpperCase() + errStr.slice(1);
 
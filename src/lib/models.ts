@@ -50,6 +50,125 @@ export type ConfigRange = {
 	end: number;
 };
 
+export type Tool = {
+	fullName: string;
+	packageUuid: string;
+	versionUuid: string;
+	enabled: boolean;
+};
+
+export type ExportCode = {
+	code: string;
+	createdAt: string;
+	profileName: string;
+	outdated: boolean;
+};
+
+export type LaunchOverrides = {
+	args: string[];
+	replaceGameArgs: boolean;
+	env: Record<string, string>;
+	preLaunch: LaunchHook | null;
+	postExit: LaunchHook | null;
+};
+
+export type ModListFormat = 'markdown' | 'html';
+
+export type ModListPreview = {
+	mods: ResolvedLine[];
+	unresolved: string[];
+};
+
+export type ResolvedLine = {
+	line: string;
+	fullName: string;
+	fuzzy: boolean;
+	installed: boolean;
+};
+
+export type ModListOptions = {
+	format: ModListFormat;
+	includeDisabled: boolean;
+	includeDescriptions: boolean;
+};
+
+export type LaunchHook = {
+	path: string;
+	args: string[];
+};
+
+export type ProfileTemplate = {
+	id: number;
+	name: string;
+	includeConfig: boolean;
+	thunderstoreModCount: number;
+	createdAt: string;
+};
+
+export type SaveBackup = {
+	name: string;
+	createdAt: string;
+	size: number;
+};
+
+export type ModpackStats = {
+	package: string;
+	points: {
+		recordedAt: string;
+		downloads: number;
+		ratingScore: number;
+	}[];
+};
+
+export type PackIssue = {
+	severity: 'error' | 'warning';
+	field: string;
+	message: string;
+};
+
+export type UploadProgress = {
+	partNumber: number;
+	state: { kind: 'uploading' } | { kind: 'retrying'; attempt: number } | { kind: 'done' };
+	completedParts: number;
+	totalParts: number;
+	uploadedBytes: number;
+	attemptBytes: number;
+	totalBytes: number;
+	durationSecs: number;
+};
+
+export type ConfigVersion = {
+	id: number;
+	savedAt: string;
+	size: number;
+};
+
+export type CopyConfigResult =
+	| { type: 'conflicts'; files: string[] }
+	| { type: 'done'; copied: number };
+
+export type ConfigDiffEntry = {
+	relativePath: string;
+	section: string;
+	entry: string;
+	valueA: string | null;
+	valueB: string | null;
+};
+
+export type ConfigDiff = {
+	onlyInA: ConfigDiffEntry[];
+	onlyInB: ConfigDiffEntry[];
+	different: ConfigDiffEntry[];
+};
+
+export type ConfigMatch = {
+	relativePath: string;
+	displayName: string;
+	section: string;
+	entry: string | null;
+	field: 'name' | 'section' | 'description' | 'value';
+};
+
 export type ConfigFile = { relativePath: string; displayName: string | null } & (
 	| ({ type: 'ok' } & ConfigFileData)
 	| { type: 'unsupported' }
@@ -64,6 +183,57 @@ export type ProfileInfo = {
 	name: string;
 	modCount: number;
 	sync: SyncProfileInfo | null;
+	group: string | null;
+};
+
+export type FileConflict = {
+	path: string;
+	mods: string[];
+	winner: string | null;
+};
+
+export type OrphanedFile = {
+	path: string;
+	size: number;
+};
+
+export type DependencyConflict = {
+	package: string;
+	installed: string | null;
+	requirements: {
+		version: string;
+		requiredBy: string;
+		isNew: boolean;
+	}[];
+};
+
+export type LaunchIssue = {
+	kind:
+		| 'gameDirNotFound'
+		| 'executableNotFound'
+		| 'steamNotInstalled'
+		| 'steamNotRunning'
+		| 'protonPrefixMissing'
+		| 'invalidSettings'
+		| 'unknown';
+	message: string;
+	fix: string;
+};
+
+export type LaunchDiagnosis = {
+	error: string;
+	issues: LaunchIssue[];
+};
+
+export type RollbackSummary = {
+	changed: number;
+	missing: string[];
+};
+
+export type UpdateCounts = {
+	game: string;
+	profiles: { profileId: number; count: number }[];
+	total: number;
 };
 
 export type SyncProfileInfo = {
@@ -99,6 +269,7 @@ export type SyncImportData = {
 export type ProfilesInfo = {
 	profiles: ProfileInfo[];
 	activeId: number;
+	groups: string[];
 };
 
 export type GameInfo = {
@@ -134,10 +305,16 @@ export type Mod = {
 	enabled?: boolean | null;
 	icon: string | null;
 	configFile: string | null;
+	tags?: string[];
+	note?: string | null;
+	pinned?: boolean;
+	orphaned?: boolean;
 };
 
 export enum ModType {
 	Local = 'local',
+	Nexus = 'nexus',
+	Github = 'github',
 	Remote = 'remote'
 }
 
@@ -208,6 +385,32 @@ export type InstallProgress = {
 	task: InstallTask;
 };
 
+export type PackageManifest = {
+	name: string;
+	author?: string;
+	description: string;
+	version_number: string;
+	dependencies: string[];
+	website_url: string;
+};
+
+export type PackageFile = {
+	path: string;
+	size: number;
+};
+
+export type PackageInspection = {
+	manifest: PackageManifest | null;
+	readme: string | null;
+	files: PackageFile[];
+	fromCache: boolean;
+};
+
+export type PackageCredits = {
+	license: string | null;
+	sourceUrl: string | null;
+};
+
 export type ModpackArgs = {
 	name: string;
 	description: string;
@@ -221,6 +424,7 @@ export type ModpackArgs = {
 	websiteUrl: string;
 	includeDisabled: boolean;
 	includeFileMap: Map<string, boolean>;
+	additionalCommunities: Record<string, string[]>;
 };
 
 export type Game = {
@@ -230,6 +434,7 @@ export type Game = {
 	favorite: boolean;
 	modLoader: ModLoader;
 	popular: boolean;
+	dedicatedServer: boolean;
 };
 
 export enum ModLoader {
@@ -245,14 +450,19 @@ export type PackageCategory = {
 	slug: string;
 };
 
-export type FiltersResponse = {
-	results: PackageCategory[];
-};
-
 export type LaunchMode =
 	| { type: 'launcher'; content?: undefined }
 	| { type: 'direct'; content: { instances: number; intervalSecs: number } };
 
+export type LaunchTarget =
+	| { type: 'modded' | 'vanilla'; content?: undefined }
+	| { type: 'custom'; content: { path: string; args: string[] } };
+
+export type LaunchArgs = {
+	target: LaunchTarget;
+	args: string;
+};
+
 export type AvailableUpdate = {
 	fullName: string;
 	ignore: boolean;
@@ -262,9 +472,12 @@ export type AvailableUpdate = {
 	new: string;
 };
 
+export type LocalModSource = { type: 'folder'; path: string } | { type: 'url'; url: string };
+
 export type ProfileQuery = {
 	mods: Mod[];
 	totalModCount: number;
+	tags: string[];
 	unknownMods: Dependant[];
 	updates: AvailableUpdate[];
 };
@@ -277,11 +490,139 @@ export type ImportData = {
 	deleteAfterImport: boolean;
 };
 
+export type RuntimeDataOverrides = {
+	extra: string[];
+	keep: string[];
+};
+
+export type BrokenMod = {
+	uuid: string;
+	fullName: string;
+	missingFiles: string[];
+	cached: boolean;
+};
+
+export type HealthIssue = {
+	kind:
+		| 'missingDependency'
+		| 'disabledDependency'
+		| 'outdatedDependency'
+		| 'outdatedLoader'
+		| 'brokenLink'
+		| 'emptyCache';
+	message: string;
+	fix: HealthFix;
+};
+
+export type HealthFix =
+	| { type: 'installMods'; mods: { packageUuid: string; versionUuid: string }[] }
+	| { type: 'enableMods'; uuids: string[] }
+	| { type: 'updateMods'; uuids: string[] }
+	| { type: 'removeLinks'; paths: string[] }
+	| { type: 'redownload'; uuids: string[] };
+
+export type AutoSnapshot = {
+	profileId: number;
+	profileName: string;
+	reason: 'update' | 'import' | 'migration';
+	createdAt: string;
+};
+
+export type GameStarted = {
+	game: string;
+	pid: number;
+};
+
+export type GameExited = {
+	game: string;
+	exitCode: number | null;
+	signal: number | null;
+	durationSecs: number;
+};
+
+export type CrashDetected = {
+	game: string;
+	profileId: number;
+	exitCode: number | null;
+	signal: number | null;
+	path: string;
+};
+
+export type LogLevel = 'fatal' | 'error' | 'warning' | 'message' | 'info' | 'debug';
+
+export type LogLine = {
+	level: LogLevel;
+	source: string | null;
+	text: string;
+};
+
+export type ModLogIssues = {
+	uuid: string;
+	fullName: string;
+	errors: number;
+	warnings: number;
+	examples: string[];
+};
+
+export type LogAnalysis = {
+	mods: ModLogIssues[];
+	unattributedErrors: number;
+	unattributedWarnings: number;
+};
+
+export type ProfilePlaytime = {
+	id: number;
+	name: string;
+	totalSecs: number;
+	recentSecs: number;
+	sessions: number;
+	lastPlayed: string;
+};
+
+export type GamePlaytime = {
+	game: string;
+	totalSecs: number;
+	recentSecs: number;
+	profiles: ProfilePlaytime[];
+};
+
+export type IconAvailable = {
+	fullName: string;
+	path: string;
+};
+
+export type ImportOptions = {
+	importAll: boolean;
+	merge: boolean;
+	overwriteConfig: boolean;
+};
+
+export type ImportConflict =
+	| { type: 'modVersion'; name: string; existing: string; imported: string }
+	| { type: 'config'; path: string; overwritten: boolean };
+
+export type VersionChange = {
+	name: string;
+	from: string;
+	to: string;
+};
+
+export type ImportPreview = {
+	profileExists: boolean;
+	added: string[];
+	removed: string[];
+	upgraded: VersionChange[];
+	downgraded: VersionChange[];
+	configOverwritten: string[];
+	configRemoved: string[];
+};
+
 type ProfileManifest = {
 	profileName: string;
 	mods: ProfileManifestMod[];
 	community: string | null;
 	ignoredUpdates: string[];
+	hash?: string;
 };
 
 type ProfileManifestMod = {
@@ -300,23 +641,83 @@ export type R2ImportData = {
 	include: boolean[];
 };
 
+export type ExternalManagerInfo = {
+	manager: 'r2modman' | 'thunderstoreModManager';
+	label: string;
+	path: string;
+	profiles: string[];
+};
+
+export type DiskUsage = {
+	total: number;
+	config: number;
+	other: number;
+	mods: { uuid: string; fullName: string; size: number }[];
+};
+
+export type CacheStats = {
+	totalSize: number;
+	gameSizes: Record<string, number>;
+	unusedSize: number;
+	packageCount: number;
+	versionCount: number;
+	oldestEntry: string | null;
+};
+
+export type GcCategory = 'notInstalled' | 'oldVersion' | 'otherGame' | 'unknown';
+
+export type GcCategoryReport = {
+	category: GcCategory;
+	size: number;
+	count: number;
+};
+
+export type Recovery = {
+	kind: 'restoredBackup' | 'reset';
+	error: string;
+	corruptPath: string;
+};
+
 export type Prefs = {
 	dataDir: string;
 	cacheDir: string;
 	sendTelemetry: boolean;
 	fetchModsAutomatically: boolean;
 	pullBeforeLaunch: boolean;
+	userAgent: string | null;
+	permanentDelete: boolean;
+	maxCacheSize: number | null;
+	linkFiles: boolean;
+	autoSnapshots: boolean;
+	snapshotRetention: number;
+	clampConfigValues: boolean;
+	installOverridesUrl: string | null;
+	downloadSources: Record<string, DownloadSource>;
 	zoomFactor: number;
 	gamePrefs: Map<string, GamePrefs>;
 };
 
+export type DownloadSource = { type: 'url'; url: string } | { type: 'file'; path: string };
+
 export type GamePrefs = {
 	dirOverride: string | null;
 	customArgs: string[] | null;
 	launchMode: LaunchMode;
+	launchTarget: LaunchTarget;
 	platform: Platform | null;
+	steamLaunch: SteamLaunch | null;
+	wineRunner: WineRunner;
+	protonVersion: string | null;
+	backupSaves: boolean;
+	saveDirOverride: string | null;
+	serverDir: string | null;
+	serverArgs: string[] | null;
 };
 
+export type SteamLaunch = 'applaunch' | 'protocol';
+
+export type WineRunner = 'proton' | 'umu' | 'protontricks';
+
 export enum Platform {
 	Steam = 'steam',
 	EpicGames = 'epicGames',
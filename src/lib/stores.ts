@@ -1,18 +1,21 @@
 import { derived, get, writable } from 'svelte/store';
 import { invokeCommand } from './invoke';
+import { listen } from '@tauri-apps/api/event';
 import {
 	SortBy,
 	SortOrder,
-	type FiltersResponse,
 	type Game,
+	type GameExited,
 	type GameInfo,
+	type GameStarted,
+	type IconAvailable,
 	type PackageCategory,
 	type ProfileInfo,
 	type ProfilesInfo,
 	type QueryModsArgs,
-	type SyncUser
+	type SyncUser,
+	type UpdateCounts
 } from './models';
-import { fetch } from '@tauri-apps/plugin-http';
 
 export let games: Game[] = [];
 export let categories = writable<PackageCategory[]>([]);
@@ -20,10 +23,20 @@ export let activeGame = writable<Game | null>(null);
 
 export let activeProfileId: number = 0;
 export let profiles: ProfileInfo[] = [];
+export let profileGroups: string[] = [];
 export let activeProfile = writable<ProfileInfo | null>(null);
 
 export let user = writable<SyncUser | null>(null);
 
+/** Maps profile ids of the active game to their number of available updates. */
+export let updateCounts = writable<Record<number, number>>({});
+
+/** Whether a game launched by Gale is still running. */
+export let gameRunning = writable(false);
+
+/** Maps full version names of Thunderstore packages to their locally cached icons. */
+export let cachedIcons = writable<Record<string, string>>({});
+
 export let activeProfileLocked = derived([activeProfile, user], ([activeProfile, user]) => {
 	if (activeProfile === null) return false;
 	if (activeProfile.sync === null) return false;
@@ -77,6 +90,27 @@ activeGame.subscribe((value) => {
 
 refreshGames();
 refreshUser();
+refreshGameRunning();
+
+listen<UpdateCounts>('updates-available', ({ payload }) => setUpdateCounts(payload));
+
+listen<GameStarted>('game-started', () => gameRunning.set(true));
+// other instances might still be running
+listen<GameExited>('game-exited', () => refreshGameRunning());
+
+/** How many icons are kept in {@link cachedIcons} before the oldest are forgotten. */
+const MAX_CACHED_ICONS = 500;
+
+listen<IconAvailable>('icon_available', ({ payload }) => {
+	cachedIcons.update((icons) => {
+		// keys keep their insertion order, so the first ones are the oldest
+		let entries = Object.entries(icons).filter(([fullName]) => fullName !== payload.fullName);
+		entries = entries.slice(Math.max(0, entries.length - MAX_CACHED_ICONS + 1));
+		entries.push([payload.fullName, payload.path]);
+
+		return Object.fromEntries(entries);
+	});
+});
 
 function loadQuery(key: string, getDefault: () => QueryModsArgs) {
 	let json = localStorage.getItem(key);
@@ -121,24 +155,7 @@ export async function setActiveGame(slug: string) {
 }
 
 export async function refreshCategories() {
-	let gameId = get(activeGame)?.slug;
-	if (!gameId) return;
-
-	try {
-		let response = await fetch(
-			`https://thunderstore.io/api/experimental/community/${gameId}/category/`
-		);
-
-		if (!response.ok) {
-			console.error('Failed to fetch categories:', response);
-			return;
-		}
-
-		let data = (await response.json()) as FiltersResponse;
-		categories.set(data.results);
-	} catch (e) {
-		console.error('Failed to fetch categories:', e);
-	}
+	categories.set(await invokeCommand<PackageCategory[]>('get_categories'));
 }
 
 export async function refreshProfiles() {
@@ -146,7 +163,18 @@ export async function refreshProfiles() {
 
 	activeProfileId = info.activeId;
 	profiles = info.profiles;
+	profileGroups = info.groups;
 	activeProfile.set(profiles.find((profile) => profile.id === activeProfileId) ?? null);
+
+	setUpdateCounts(await invokeCommand<UpdateCounts>('get_update_counts'));
+}
+
+function setUpdateCounts(counts: UpdateCounts) {
+	if (counts.game !== get(activeGame)?.slug) return;
+
+	updateCounts.set(
+		Object.fromEntries(counts.profiles.map(({ profileId, count }) => [profileId, count]))
+	);
 }
 
 export async function setActiveProfile(index: number) {
@@ -165,6 +193,10 @@ export async function refreshUser() {
 	user.set(info);
 }
 
+export async function refreshGameRunning() {
+	gameRunning.set(await invokeCommand<boolean>('is_game_running'));
+}
+
 export async function login() {
 	let info = await invokeCommand<SyncUser>('login');
 	user.set(info);
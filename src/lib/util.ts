@@ -1,6 +1,6 @@
 import { get } from 'svelte/store';
 import type { Mod, ConfigEntry, Dependant, SyncUser } from './models';
-import { activeGame } from './stores';
+import { activeGame, cachedIcons } from './stores';
 import { convertFileSrc } from '@tauri-apps/api/core';
 
 export function shortenFileSize(size: number): string {
@@ -90,7 +90,8 @@ export function communityUrl(path: string) {
 export function iconSrc(mod: Mod) {
 	if (mod.type === 'remote') {
 		let fullName = `${mod.author}-${mod.name}-${mod.version}`;
-		return thunderstoreIconUrl(fullName);
+		let cached = get(cachedIcons)[fullName];
+		return cached ? convertFileSrc(cached) : thunderstoreIconUrl(fullName);
 	} else if (mod.icon !== null) {
 		return convertFileSrc(mod.icon);
 	} else {